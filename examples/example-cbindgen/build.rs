@@ -115,6 +115,7 @@ fn generate_ffi_bindings() -> PathBuf {
         pq!(calculator_get_value),
         pq!(calculator_get_count),
         pq!(calculator_is),
+        pq!(Calculator_is_zero),
         pq!(calculator_to_string),
         pq!(calculator_get_history),
         pq!(calculator_for_each),
@@ -257,6 +257,20 @@ pub(crate) fn __cbg_result_Result___Calculator___Error__() {}
 pub(crate) fn __cbg_result_Result___f64___Error__() {}
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
+pub unsafe extern "C" fn Calculator_is_zero(this: *const calculator_t) -> bool {
+    let this = match __cbg_in___Calculator(this) {
+        ::core::result::Result::Ok(__v) => __v,
+        ::core::result::Result::Err(__msg) => {
+            panic!("{}", __msg);
+        }
+    };
+    let __v = example_flat::Calculator_is_zero(this);
+    let __ret: bool;
+    __ret = __cbg_out_bool(__v);
+    __ret
+}
+#[no_mangle]
+#[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_apply(
     c: *mut calculator_t,
     op: operation_t,
@@ -478,6 +492,10 @@ pub unsafe extern "C" fn inside_foo_value(x: inside_foo_t) -> i32 {
     __ret = __cbg_out_i32(__v);
     __ret
 }
+/// Printable symbol of each [`Operation`], indexed by its discriminant — a
+/// plain data table the binding layer exports as a C global.
+#[unsafe(no_mangle)]
+pub static OPERATION_SYMBOLS: [u8; 4] = example_flat::OPERATION_SYMBOLS;
 const _: () = {
     konst::assertc_eq!(
         example_flat::FEATURES, "",
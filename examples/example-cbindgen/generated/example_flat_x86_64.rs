@@ -47,13 +47,22 @@ pub unsafe extern "C" fn calculator_drop(this_: *mut calculator_t) {
         drop(::std::boxed::Box::from_raw(this_ as *mut example_flat::Calculator));
     }
 }
+/// A by-value data struct whose **field set varies by target architecture and by
+/// feature**. `#[prebindgen]` records every `cfg`-gated field; the binding crate
+/// keeps only those matching the build target, so the generated `#[repr(C)] foo_t`
+/// differs per target. (`lang::Cbindgen` `.data_struct`.)
 #[repr(C)]
 #[allow(non_camel_case_types)]
 pub struct foo_t {
+    /// Always present.
     pub id: u64,
     pub x86_64_field: u64,
     pub stable_field: u64,
 }
+/// A fieldless enum whose **discriminants differ by target architecture**. The two
+/// definitions are mutually exclusive — the `#[prebindgen(cfg = ...)]` macro emits a
+/// matching real `#[cfg]`, so each target compiles exactly one and the generated C
+/// enum carries that target's values. (`lang::Cbindgen` `.enum_type`.)
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[allow(non_camel_case_types)]
@@ -61,6 +70,18 @@ pub enum inside_foo_t {
     DouddleDee = 42,
     DouddleDum = 24,
 }
+const _: () = {
+    assert!(
+        inside_foo_t::DouddleDee as i64 == example_flat::InsideFoo::DouddleDee as i64,
+        "enum_type: `InsideFoo::DouddleDee` discriminant differs from the source enum"
+    );
+    assert!(
+        inside_foo_t::DouddleDum as i64 == example_flat::InsideFoo::DouddleDum as i64,
+        "enum_type: `InsideFoo::DouddleDum` discriminant differs from the source enum"
+    );
+};
+/// Arithmetic operation selector — a primitive-repr enum (like zenoh-flat's
+/// `Priority`); the adapter lowers it to a C enum.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[allow(non_camel_case_types)]
@@ -70,6 +91,24 @@ pub enum operation_t {
     Mul = 2,
     Div = 3,
 }
+const _: () = {
+    assert!(
+        operation_t::Add as i64 == example_flat::Operation::Add as i64,
+        "enum_type: `Operation::Add` discriminant differs from the source enum"
+    );
+    assert!(
+        operation_t::Sub as i64 == example_flat::Operation::Sub as i64,
+        "enum_type: `Operation::Sub` discriminant differs from the source enum"
+    );
+    assert!(
+        operation_t::Mul as i64 == example_flat::Operation::Mul as i64,
+        "enum_type: `Operation::Mul` discriminant differs from the source enum"
+    );
+    assert!(
+        operation_t::Div as i64 == example_flat::Operation::Div as i64,
+        "enum_type: `Operation::Div` discriminant differs from the source enum"
+    );
+};
 #[repr(C)]
 #[allow(non_camel_case_types)]
 pub struct closure_value_t {
@@ -255,6 +294,24 @@ pub(crate) fn __cbg_outmark_vec_f64() {}
 pub(crate) fn __cbg_result_Result___Calculator___Error__() {}
 #[allow(non_snake_case, dead_code, unused)]
 pub(crate) fn __cbg_result_Result___f64___Error__() {}
+/// Whether the accumulator is back at zero.
+#[no_mangle]
+#[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
+pub unsafe extern "C" fn Calculator_is_zero(this: *const calculator_t) -> bool {
+    let this = match __cbg_in___Calculator(this) {
+        ::core::result::Result::Ok(__v) => __v,
+        ::core::result::Result::Err(__msg) => {
+            panic!("{}", __msg);
+        }
+    };
+    let __v = example_flat::Calculator_is_zero(this);
+    let __ret: bool;
+    __ret = __cbg_out_bool(__v);
+    __ret
+}
+/// Apply `op` with `operand`, updating the accumulator and returning the new
+/// value. Division by zero returns an error (its fallible `&mut` input routes
+/// through the error channel of the `Result`).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_apply(
@@ -292,6 +349,8 @@ pub unsafe extern "C" fn calculator_apply(
         }
     }
 }
+/// Invoke `f` once per recorded value in application order — replays the history
+/// into a C closure (demonstrates callback / closure-struct generation).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_for_each(
@@ -307,6 +366,7 @@ pub unsafe extern "C" fn calculator_for_each(
     let f = __cbg_in_closure_value_t(f);
     example_flat::calculator_for_each(c, f);
 }
+/// How many operations have been applied so far.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_get_count(c: *const calculator_t) -> u64 {
@@ -321,6 +381,7 @@ pub unsafe extern "C" fn calculator_get_count(c: *const calculator_t) -> u64 {
     __ret = __cbg_out_u64(__v);
     __ret
 }
+/// Copy the recorded history out as an array.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_get_history(
@@ -341,6 +402,7 @@ pub unsafe extern "C" fn calculator_get_history(
     *len = __n;
     __ret
 }
+/// The current accumulator value.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_get_value(c: *const calculator_t) -> f64 {
@@ -355,6 +417,7 @@ pub unsafe extern "C" fn calculator_get_value(c: *const calculator_t) -> f64 {
     __ret = __cbg_out_f64(__v);
     __ret
 }
+/// Whether the accumulator currently holds exactly `value`.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_is(c: *const calculator_t, value: f64) -> bool {
@@ -370,6 +433,7 @@ pub unsafe extern "C" fn calculator_is(c: *const calculator_t, value: f64) -> bo
     __ret = __cbg_out_bool(__v);
     __ret
 }
+/// Build a fresh accumulator initialized to zero.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_new() -> *mut calculator_t {
@@ -378,6 +442,8 @@ pub unsafe extern "C" fn calculator_new() -> *mut calculator_t {
     __ret = __cbg_out_Calculator(__v);
     __ret
 }
+/// Clone an accumulator handle. Use before passing one to a consuming call when
+/// the caller needs to keep the original.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_new_clone(
@@ -394,6 +460,8 @@ pub unsafe extern "C" fn calculator_new_clone(
     __ret = __cbg_out_Calculator(__v);
     __ret
 }
+/// Parse an initial value from a string, returning an error on bad input
+/// (demonstrates a `&str` input plus `Result` error routing).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_new_from_str(
@@ -427,6 +495,8 @@ pub unsafe extern "C" fn calculator_new_from_str(
         }
     }
 }
+/// Render the accumulator as an owned string (`char*` to C, freed by the
+/// adapter's `example_free`).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn calculator_to_string(
@@ -443,6 +513,7 @@ pub unsafe extern "C" fn calculator_to_string(
     __ret = __cbg_out_String(__v);
     __ret
 }
+/// Read a `Foo`'s always-present field (consumes the value-struct by value).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn foo_get_id(f: foo_t) -> u64 {
@@ -452,6 +523,7 @@ pub unsafe extern "C" fn foo_get_id(f: foo_t) -> u64 {
     __ret = __cbg_out_u64(__v);
     __ret
 }
+/// Construct a `Foo` (the target-specific fields default to zero).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn foo_new(id: u64) -> foo_t {
@@ -461,6 +533,7 @@ pub unsafe extern "C" fn foo_new(id: u64) -> foo_t {
     __ret = __cbg_out_Foo(__v);
     __ret
 }
+/// The default `InsideFoo` variant (its numeric value is target-specific).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn inside_foo_default() -> inside_foo_t {
@@ -469,6 +542,7 @@ pub unsafe extern "C" fn inside_foo_default() -> inside_foo_t {
     __ret = __cbg_out_InsideFoo(__v);
     __ret
 }
+/// The numeric value of an `InsideFoo` (consumes the enum by value).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn inside_foo_value(x: inside_foo_t) -> i32 {
@@ -478,6 +552,10 @@ pub unsafe extern "C" fn inside_foo_value(x: inside_foo_t) -> i32 {
     __ret = __cbg_out_i32(__v);
     __ret
 }
+/// Printable symbol of each [`Operation`], indexed by its discriminant — a
+/// plain data table the binding layer exports as a C global.
+#[no_mangle]
+pub static OPERATION_SYMBOLS: [u8; 4] = example_flat::OPERATION_SYMBOLS;
 const _: () = {
     konst::assertc_eq!(
         example_flat::FEATURES, "",
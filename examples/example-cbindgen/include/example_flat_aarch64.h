@@ -40,6 +40,12 @@ typedef struct foo_t {
   uint64_t stable_field;
 } foo_t;
 
+/**
+ * Printable symbol of each [`Operation`], indexed by its discriminant — a
+ * plain data table the binding layer exports as a C global.
+ */
+extern const uint8_t OPERATION_SYMBOLS[4];
+
 extern void *malloc(uintptr_t size);
 
 extern void free(void *ptr);
@@ -48,6 +54,8 @@ void example_free(void *p);
 
 void calculator_drop(struct calculator_t *this_);
 
+bool Calculator_is_zero(const struct calculator_t *this_);
+
 bool calculator_apply(struct calculator_t *c,
                       enum operation_t op,
                       double operand,
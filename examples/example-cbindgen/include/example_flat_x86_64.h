@@ -12,6 +12,10 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * Arithmetic operation selector — a primitive-repr enum (like zenoh-flat's
+ * `Priority`); the adapter lowers it to a C enum.
+ */
 typedef enum operation_t {
   Add = 0,
   Sub = 1,
@@ -19,6 +23,12 @@ typedef enum operation_t {
   Div = 3,
 } operation_t;
 
+/**
+ * A fieldless enum whose **discriminants differ by target architecture**. The two
+ * definitions are mutually exclusive — the `#[prebindgen(cfg = ...)]` macro emits a
+ * matching real `#[cfg]`, so each target compiles exactly one and the generated C
+ * enum carries that target's values. (`lang::Cbindgen` `.enum_type`.)
+ */
 typedef enum inside_foo_t {
   DouddleDee = 42,
   DouddleDum = 24,
@@ -34,12 +44,27 @@ typedef struct closure_value_t {
   void (*drop)(void*);
 } closure_value_t;
 
+/**
+ * A by-value data struct whose **field set varies by target architecture and by
+ * feature**. `#[prebindgen]` records every `cfg`-gated field; the binding crate
+ * keeps only those matching the build target, so the generated `#[repr(C)] foo_t`
+ * differs per target. (`lang::Cbindgen` `.data_struct`.)
+ */
 typedef struct foo_t {
+  /**
+   * Always present.
+   */
   uint64_t id;
   uint64_t x86_64_field;
   uint64_t stable_field;
 } foo_t;
 
+/**
+ * Printable symbol of each [`Operation`], indexed by its discriminant — a
+ * plain data table the binding layer exports as a C global.
+ */
+extern const uint8_t OPERATION_SYMBOLS[4];
+
 extern void *malloc(uintptr_t size);
 
 extern void free(void *ptr);
@@ -48,36 +73,89 @@ void example_free(void *p);
 
 void calculator_drop(struct calculator_t *this_);
 
+/**
+ * Whether the accumulator is back at zero.
+ */
+bool Calculator_is_zero(const struct calculator_t *this_);
+
+/**
+ * Apply `op` with `operand`, updating the accumulator and returning the new
+ * value. Division by zero returns an error (its fallible `&mut` input routes
+ * through the error channel of the `Result`).
+ */
 bool calculator_apply(struct calculator_t *c,
                       enum operation_t op,
                       double operand,
                       double *out,
                       char **e);
 
+/**
+ * Invoke `f` once per recorded value in application order — replays the history
+ * into a C closure (demonstrates callback / closure-struct generation).
+ */
 void calculator_for_each(const struct calculator_t *c, struct closure_value_t f);
 
+/**
+ * How many operations have been applied so far.
+ */
 uint64_t calculator_get_count(const struct calculator_t *c);
 
+/**
+ * Copy the recorded history out as an array.
+ */
 double *calculator_get_history(const struct calculator_t *c, uintptr_t *len);
 
+/**
+ * The current accumulator value.
+ */
 double calculator_get_value(const struct calculator_t *c);
 
+/**
+ * Whether the accumulator currently holds exactly `value`.
+ */
 bool calculator_is(const struct calculator_t *c, double value);
 
+/**
+ * Build a fresh accumulator initialized to zero.
+ */
 struct calculator_t *calculator_new(void);
 
+/**
+ * Clone an accumulator handle. Use before passing one to a consuming call when
+ * the caller needs to keep the original.
+ */
 struct calculator_t *calculator_new_clone(const struct calculator_t *c);
 
+/**
+ * Parse an initial value from a string, returning an error on bad input
+ * (demonstrates a `&str` input plus `Result` error routing).
+ */
 struct calculator_t *calculator_new_from_str(const char *s, char **e);
 
+/**
+ * Render the accumulator as an owned string (`char*` to C, freed by the
+ * adapter's `example_free`).
+ */
 char *calculator_to_string(const struct calculator_t *c);
 
+/**
+ * Read a `Foo`'s always-present field (consumes the value-struct by value).
+ */
 uint64_t foo_get_id(struct foo_t f);
 
+/**
+ * Construct a `Foo` (the target-specific fields default to zero).
+ */
 struct foo_t foo_new(uint64_t id);
 
+/**
+ * The default `InsideFoo` variant (its numeric value is target-specific).
+ */
 enum inside_foo_t inside_foo_default(void);
 
+/**
+ * The numeric value of an `InsideFoo` (consumes the enum by value).
+ */
 int32_t inside_foo_value(enum inside_foo_t x);
 
 #endif  /* EXAMPLE_FLAT_H */
@@ -52,6 +52,11 @@ pub enum Operation {
     Div = 3,
 }
 
+/// Printable symbol of each [`Operation`], indexed by its discriminant — a
+/// plain data table the binding layer exports as a C global.
+#[prebindgen]
+pub static OPERATION_SYMBOLS: [u8; 4] = *b"+-*/";
+
 /// A stateful accumulator. This is a plain Rust type used as an opaque handle:
 /// the binding holds it behind a pointer and frees it with `calculator_drop`.
 pub struct Calculator {
@@ -128,6 +133,16 @@ pub fn calculator_is(c: &Calculator, value: f64) -> bool {
     c.value == value
 }
 
+/// Methods are exported too: `#[prebindgen]` on the impl captures each `pub`
+/// method as a free `Calculator_<method>` function taking the receiver as `this`.
+#[prebindgen]
+impl Calculator {
+    /// Whether the accumulator is back at zero.
+    pub fn is_zero(&self) -> bool {
+        self.value == 0.0
+    }
+}
+
 /// Render the accumulator as an owned string (`char*` to C, freed by the
 /// adapter's `example_free`).
 #[prebindgen]
@@ -85,6 +85,10 @@ pub unsafe extern "C" fn string_drop(this_: *mut string_t) {
         drop(::std::boxed::Box::from_raw(this_ as *mut ::std::string::String));
     }
 }
+/// A by-value, FFI-safe payload. Scalars cross the C ABI as themselves; the
+/// `label` string crosses as an opaque pointer (`Option<Box<String>>` ⇒ a nullable
+/// `string_t *`). Being `#[repr(C)]`, the whole struct is passed by direct
+/// reinterpret (zero-copy) — see `perftest-c`'s `.repr_c_struct(Payload)`.
 #[repr(C)]
 #[allow(non_camel_case_types)]
 pub struct payload_t {
@@ -469,6 +473,11 @@ pub(crate) fn __cbg_outmark_option_Vec___Payload__() {}
 pub(crate) fn __cbg_outmark_slice_Payload() {}
 #[allow(non_snake_case, dead_code, unused)]
 pub(crate) fn __cbg_outmark_vec_Payload() {}
+/// Prepare a reusable [`PayloadHandler`] from a callback `f`. The (foreign) closure
+/// is decoded into the handler **once** here — reuse the handler across many
+/// [`storage_callback`] calls instead of passing a fresh callback each time. This
+/// is the "declare the subscriber once" step (its trampoline + per-call setup are
+/// built here, amortized over every later delivery).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn payload_handler_new(
@@ -480,6 +489,9 @@ pub unsafe extern "C" fn payload_handler_new(
     __ret = __cbg_out_PayloadHandler(__v);
     __ret
 }
+/// Prepare a reusable [`PayloadVecHandler`] from a whole-batch callback `f`. Like
+/// [`payload_handler_new`], the foreign closure is decoded **once** here; reuse the
+/// handler across many [`storage_callback_vec`] calls.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn payload_vec_handler_new(
@@ -491,6 +503,14 @@ pub unsafe extern "C" fn payload_vec_handler_new(
     __ret = __cbg_out_PayloadVecHandler(__v);
     __ret
 }
+/// Invoke the prepared `handler` once **per stored payload** with a borrow of each
+/// — reuses the handler's already-built foreign trampoline, so there is **no
+/// per-call callback decoding** (only firing). After a single-payload put this
+/// fires exactly once; after a [`storage_put_slice`] it fires once per slice
+/// element. In C the closure receives a `const payload_t *` (zero-copy); in Kotlin
+/// the borrowed `Payload` is delivered whole to the handler's
+/// `PayloadCallback.run(Payload)` (its fields cross as decoupled leaves and are
+/// reassembled on the Kotlin side — see `prebindgen::lang::JniGen`).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_callback(
@@ -511,6 +531,11 @@ pub unsafe extern "C" fn storage_callback(
     };
     perftest_flat::storage_callback(s, handler);
 }
+/// Invoke the prepared `handler` **once** with the whole stored batch as a slice
+/// (the dual of [`storage_callback`], which fires once per element). In C the closure
+/// receives the slice **by reference** — `const payload_t *` + `size_t`, zero-copy, no
+/// per-element materialization; in Kotlin the batch is delivered as a `List<Payload>`
+/// to the handler's `PayloadVecCallback.run(List<Payload>)`.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_callback_vec(
@@ -531,6 +556,11 @@ pub unsafe extern "C" fn storage_callback_vec(
     };
     perftest_flat::storage_callback_vec(s, handler);
 }
+/// Return a clone of the **first** stored payload, or `None` if the storage is empty
+/// (by value; crosses by reinterpret, the `label` becoming a fresh owned `string_t *`
+/// the C caller must drop). Across the C ABI an `Option<Payload>` lowers to
+/// `bool storage_get(const storage_t *, payload_t *out)` (true + writes `*out` if
+/// present); in Kotlin it surfaces as a nullable `Payload?`.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_get(s: *const storage_t, out: *mut payload_t) -> bool {
@@ -553,6 +583,11 @@ pub unsafe extern "C" fn storage_get(s: *const storage_t, out: *mut payload_t) -
     }
     __ret
 }
+/// Write the first stored payload into the caller's **already-initialized** `payload`
+/// slot and return `true`; return `false` (leaving the slot untouched) if the storage
+/// is empty. When it does write, the assignment drops the old value first (freeing its
+/// old `label`) — so the slot must hold a valid payload (use [`storage_get_into_uninit`]
+/// for raw memory). The `bool` is the C function's return; `payload` is the out-param.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_get_into_init(
@@ -576,6 +611,10 @@ pub unsafe extern "C" fn storage_get_into_init(
     __ret = __cbg_out_bool(__v);
     __ret
 }
+/// Write the first stored payload into the caller's **uninitialized** `payload` slot
+/// (without dropping whatever bytes were there) and return `true`; return `false`
+/// (leaving the slot uninitialized — the caller must not read it) if the storage is
+/// empty (`&mut MaybeUninit<Payload>` → `payload_t *`).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_get_into_uninit(
@@ -599,6 +638,12 @@ pub unsafe extern "C" fn storage_get_into_uninit(
     __ret = __cbg_out_bool(__v);
     __ret
 }
+/// Return a clone of the **whole** stored batch, or `None` if the storage is empty
+/// (each `label` becoming a fresh owned `string_t *` the C caller must drop). A
+/// returned `Some` is always non-empty (empty storage is `None`). [`storage_get`] is
+/// the first-element case of this. Across the C ABI `Option<Vec<Payload>>` lowers to
+/// `bool storage_get_vec(const storage_t *, payload_t **out, size_t *out_len)`; in
+/// Kotlin it surfaces as a nullable `List<Payload>?`.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_get_vec(
@@ -631,6 +676,7 @@ pub unsafe extern "C" fn storage_get_vec(
     }
     __ret
 }
+/// Create a new, empty storage handle.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_new() -> *mut storage_t {
@@ -639,6 +685,8 @@ pub unsafe extern "C" fn storage_new() -> *mut storage_t {
     __ret = __cbg_out_Storage(__v);
     __ret
 }
+/// Store a clone of `payload`, read through a shared borrow (`const payload_t *`).
+/// The caller's payload is left untouched.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_put_by_read(
@@ -659,6 +707,8 @@ pub unsafe extern "C" fn storage_put_by_read(
     };
     perftest_flat::storage_put_by_read(s, payload);
 }
+/// Store a clone of `payload`, then **update the caller's payload in place** by
+/// bumping its `seq` counter (a `&mut Payload` read/write borrow → `payload_t *`).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_put_by_read_and_update(
@@ -679,6 +729,10 @@ pub unsafe extern "C" fn storage_put_by_read_and_update(
     };
     perftest_flat::storage_put_by_read_and_update(s, payload);
 }
+/// Move `payload` into the storage. Taken **by value**: across the C ABI this is a
+/// consume — Rust reads the `payload_t` out through a `*mut` and writes a gravestone
+/// back (nulling the owned `label` pointer) so the caller's later free is a no-op
+/// (see `perftest-c`'s `.repr_c_struct(Payload)` — owned-ness is inferred from `label`).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_put_by_take(
@@ -699,6 +753,8 @@ pub unsafe extern "C" fn storage_put_by_take(
     };
     perftest_flat::storage_put_by_take(s, payload);
 }
+/// Replace the stored batch with a clone of `payloads`. The single-payload puts
+/// are the array-of-one case of this.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn storage_put_slice(
@@ -722,6 +778,8 @@ pub unsafe extern "C" fn storage_put_slice(
     };
     perftest_flat::storage_put_slice(s, payloads);
 }
+/// Byte length of an opaque string — lets the C benchmark read it through the
+/// `string_t *` handle.
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn string_len(s: *const string_t) -> usize {
@@ -736,6 +794,8 @@ pub unsafe extern "C" fn string_len(s: *const string_t) -> usize {
     __ret = __cbg_out_usize(__v);
     __ret
 }
+/// Build the opaque string the C side stores in [`Payload::label`]. To C this
+/// returns a `string_t *` (since `String` is declared `opaque_ptr`).
 #[no_mangle]
 #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
 pub unsafe extern "C" fn string_new(s: *const ::core::ffi::c_char) -> *mut string_t {
@@ -751,7 +811,11 @@ pub unsafe extern "C" fn string_new(s: *const ::core::ffi::c_char) -> *mut strin
     __ret
 }
 /// The storage capacity limit advertised to bindings (a primitive const).
-pub const COVER_MAGIC: i64 = perftest_flat::COVER_MAGIC;
+pub const COVER_MAGIC: i64 = 0xC0FFEE;
+const _: () = assert!(
+    COVER_MAGIC == perftest_flat::COVER_MAGIC,
+    "const `COVER_MAGIC` differs from the source const"
+);
 /// The coverage surface's tag string (a string const).
 pub const COVER_TAG: &str = perftest_flat::COVER_TAG;
 const _: () = {
@@ -12,7 +12,10 @@
 #include <stdint.h>
 #include <stdlib.h>
 
-
+/**
+ * The storage capacity limit advertised to bindings (a primitive const).
+ */
+#define COVER_MAGIC 12648430
 
 typedef struct payload_handler_t {
   uint8_t _private[0];
@@ -30,6 +33,12 @@ typedef struct string_t {
   uint8_t _private[0];
 } string_t;
 
+/**
+ * A by-value, FFI-safe payload. Scalars cross the C ABI as themselves; the
+ * `label` string crosses as an opaque pointer (`Option<Box<String>>` ⇒ a nullable
+ * `string_t *`). Being `#[repr(C)]`, the whole struct is passed by direct
+ * reinterpret (zero-copy) — see `perftest-c`'s `.repr_c_struct(Payload)`.
+ */
 typedef struct payload_t {
   int64_t id;
   int32_t seq;
@@ -64,36 +73,122 @@ void string_drop(struct string_t *this_);
 
 void payload_drop(struct payload_t *this_);
 
+/**
+ * Prepare a reusable [`PayloadHandler`] from a callback `f`. The (foreign) closure
+ * is decoded into the handler **once** here — reuse the handler across many
+ * [`storage_callback`] calls instead of passing a fresh callback each time. This
+ * is the "declare the subscriber once" step (its trampoline + per-call setup are
+ * built here, amortized over every later delivery).
+ */
 struct payload_handler_t *payload_handler_new(struct closure_payload_t f);
 
+/**
+ * Prepare a reusable [`PayloadVecHandler`] from a whole-batch callback `f`. Like
+ * [`payload_handler_new`], the foreign closure is decoded **once** here; reuse the
+ * handler across many [`storage_callback_vec`] calls.
+ */
 struct payload_vec_handler_t *payload_vec_handler_new(struct closure_payload_vec_t f);
 
+/**
+ * Invoke the prepared `handler` once **per stored payload** with a borrow of each
+ * — reuses the handler's already-built foreign trampoline, so there is **no
+ * per-call callback decoding** (only firing). After a single-payload put this
+ * fires exactly once; after a [`storage_put_slice`] it fires once per slice
+ * element. In C the closure receives a `const payload_t *` (zero-copy); in Kotlin
+ * the borrowed `Payload` is delivered whole to the handler's
+ * `PayloadCallback.run(Payload)` (its fields cross as decoupled leaves and are
+ * reassembled on the Kotlin side — see `prebindgen::lang::JniGen`).
+ */
 void storage_callback(const struct storage_t *s, const struct payload_handler_t *handler);
 
+/**
+ * Invoke the prepared `handler` **once** with the whole stored batch as a slice
+ * (the dual of [`storage_callback`], which fires once per element). In C the closure
+ * receives the slice **by reference** — `const payload_t *` + `size_t`, zero-copy, no
+ * per-element materialization; in Kotlin the batch is delivered as a `List<Payload>`
+ * to the handler's `PayloadVecCallback.run(List<Payload>)`.
+ */
 void storage_callback_vec(const struct storage_t *s, const struct payload_vec_handler_t *handler);
 
+/**
+ * Return a clone of the **first** stored payload, or `None` if the storage is empty
+ * (by value; crosses by reinterpret, the `label` becoming a fresh owned `string_t *`
+ * the C caller must drop). Across the C ABI an `Option<Payload>` lowers to
+ * `bool storage_get(const storage_t *, payload_t *out)` (true + writes `*out` if
+ * present); in Kotlin it surfaces as a nullable `Payload?`.
+ */
 bool storage_get(const struct storage_t *s, struct payload_t *out);
 
+/**
+ * Write the first stored payload into the caller's **already-initialized** `payload`
+ * slot and return `true`; return `false` (leaving the slot untouched) if the storage
+ * is empty. When it does write, the assignment drops the old value first (freeing its
+ * old `label`) — so the slot must hold a valid payload (use [`storage_get_into_uninit`]
+ * for raw memory). The `bool` is the C function's return; `payload` is the out-param.
+ */
 bool storage_get_into_init(const struct storage_t *s, struct payload_t *payload);
 
+/**
+ * Write the first stored payload into the caller's **uninitialized** `payload` slot
+ * (without dropping whatever bytes were there) and return `true`; return `false`
+ * (leaving the slot uninitialized — the caller must not read it) if the storage is
+ * empty (`&mut MaybeUninit<Payload>` → `payload_t *`).
+ */
 bool storage_get_into_uninit(const struct storage_t *s, struct payload_t *payload);
 
+/**
+ * Return a clone of the **whole** stored batch, or `None` if the storage is empty
+ * (each `label` becoming a fresh owned `string_t *` the C caller must drop). A
+ * returned `Some` is always non-empty (empty storage is `None`). [`storage_get`] is
+ * the first-element case of this. Across the C ABI `Option<Vec<Payload>>` lowers to
+ * `bool storage_get_vec(const storage_t *, payload_t **out, size_t *out_len)`; in
+ * Kotlin it surfaces as a nullable `List<Payload>?`.
+ */
 bool storage_get_vec(const struct storage_t *s, struct payload_t **out, uintptr_t *out_len);
 
+/**
+ * Create a new, empty storage handle.
+ */
 struct storage_t *storage_new(void);
 
+/**
+ * Store a clone of `payload`, read through a shared borrow (`const payload_t *`).
+ * The caller's payload is left untouched.
+ */
 void storage_put_by_read(struct storage_t *s, const struct payload_t *payload);
 
+/**
+ * Store a clone of `payload`, then **update the caller's payload in place** by
+ * bumping its `seq` counter (a `&mut Payload` read/write borrow → `payload_t *`).
+ */
 void storage_put_by_read_and_update(struct storage_t *s, struct payload_t *payload);
 
+/**
+ * Move `payload` into the storage. Taken **by value**: across the C ABI this is a
+ * consume — Rust reads the `payload_t` out through a `*mut` and writes a gravestone
+ * back (nulling the owned `label` pointer) so the caller's later free is a no-op
+ * (see `perftest-c`'s `.repr_c_struct(Payload)` — owned-ness is inferred from `label`).
+ */
 void storage_put_by_take(struct storage_t *s, struct payload_t *payload);
 
+/**
+ * Replace the stored batch with a clone of `payloads`. The single-payload puts
+ * are the array-of-one case of this.
+ */
 void storage_put_slice(struct storage_t *s,
                        const struct payload_t *payloads,
                        uintptr_t payloads_len);
 
+/**
+ * Byte length of an opaque string — lets the C benchmark read it through the
+ * `string_t *` handle.
+ */
 uintptr_t string_len(const struct string_t *s);
 
+/**
+ * Build the opaque string the C side stores in [`Payload::label`]. To C this
+ * returns a `string_t *` (since `String` is declared `opaque_ptr`).
+ */
 struct string_t *string_new(const char *s);
 
 #endif  /* PERFTEST_H */
@@ -36,15 +36,17 @@
 //!
 //! See also: [`prebindgen`](https://docs.rs/prebindgen) for the main processing library.
 //!
-use std::{collections::HashMap, fs::OpenOptions};
+use std::{collections::HashMap, ffi::OsStr, fs::OpenOptions};
 
 use prebindgen::{get_prebindgen_out_dir, Record, RecordKind, SourceLocation, DEFAULT_GROUP_NAME};
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
     parse::{Parse, ParseStream},
     spanned::Spanned,
-    DeriveInput, Ident, ItemConst, ItemFn, ItemType, LitStr, Result, Token,
+    visit_mut::VisitMut,
+    DeriveInput, Ident, ItemConst, ItemFn, ItemImpl, ItemMod, ItemStatic, ItemType, LitStr, Result,
+    Token,
 };
 
 /// Helper function to generate consistent error messages for unsupported or unparseable items.
@@ -52,10 +54,7 @@ fn unsupported_item_error(item: Option<syn::Item>) -> TokenStream {
     match item {
         Some(item) => {
             let item_type = match &item {
-                syn::Item::Static(_) => "Static items",
-                syn::Item::Mod(_) => "Modules",
                 syn::Item::Trait(_) => "Traits",
-                syn::Item::Impl(_) => "Impl blocks",
                 syn::Item::Use(_) => "Use statements",
                 syn::Item::ExternCrate(_) => "Extern crate declarations",
                 syn::Item::Macro(_) => "Macro definitions",
@@ -82,27 +81,75 @@ fn unsupported_item_error(item: Option<syn::Item>) -> TokenStream {
     }
 }
 
+/// A failure of the macro, reported as a compiler error at the offending
+/// span rather than as a panic ("proc macro panicked") or an unattributed
+/// message. Stable proc macros can't emit warnings or notes, so it renders as
+/// a `compile_error!`.
+struct Diagnostic {
+    span: proc_macro2::Span,
+    message: String,
+}
+
+impl Diagnostic {
+    fn error(span: proc_macro2::Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+        }
+    }
+
+    /// The error followed by the unchanged `item`, so that the rest of the
+    /// crate still sees the item and reports nothing but this error.
+    fn emit(self, item: TokenStream) -> TokenStream {
+        let error = syn::Error::new(self.span, self.message).to_compile_error();
+        let item: proc_macro2::TokenStream = item.into();
+        quote! {
+            #error
+            #item
+        }
+        .into()
+    }
+}
+
+impl From<syn::Error> for Diagnostic {
+    fn from(e: syn::Error) -> Self {
+        Self::error(e.span(), e.to_string())
+    }
+}
+
 /// Arguments for the prebindgen macro
 struct PrebindgenArgs {
-    group: String,
+    /// Groups the item is recorded into, in argument order (deduplicated)
+    groups: Vec<String>,
     cfg: Option<String>,
+    /// Exported name of the item, in place of its Rust name
+    rename: Option<String>,
 }
 
 impl Parse for PrebindgenArgs {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut group = DEFAULT_GROUP_NAME.to_string();
+        let mut groups: Vec<String> = Vec::new();
         let mut cfg = None;
-
-        if input.is_empty() {
-            return Ok(PrebindgenArgs { group, cfg });
-        }
+        let mut rename = None;
 
         // Parse arguments in any order
         while !input.is_empty() {
             if input.peek(LitStr) {
-                // String literal - could be group name
+                // String literal - a group name; several may be given. It
+                // starts the data file names, `{group}_{pid}_{thread}.jsonl`,
+                // which are split at the first `_`
                 let lit: LitStr = input.parse()?;
-                group = lit.value();
+                let group = lit.value();
+                if group.is_empty() || !group.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                {
+                    return Err(syn::Error::new_spanned(
+                        &lit,
+                        "group name must be non-empty ASCII letters, digits or '-'",
+                    ));
+                }
+                if !groups.contains(&group) {
+                    groups.push(group);
+                }
             } else if input.peek(Ident) {
                 let ident: Ident = input.parse()?;
                 input.parse::<Token![=]>()?;
@@ -110,10 +157,26 @@ impl Parse for PrebindgenArgs {
                 match ident.to_string().as_str() {
                     "cfg" => {
                         let cfg_lit: LitStr = input.parse()?;
+                        if let Err(e) = syn::parse_str::<syn::Meta>(&cfg_lit.value()) {
+                            return Err(syn::Error::new_spanned(
+                                &cfg_lit,
+                                format!("invalid cfg condition `{}`: {e}", cfg_lit.value()),
+                            ));
+                        }
                         cfg = Some(cfg_lit.value());
                     }
+                    "rename" => {
+                        let rename_lit: LitStr = input.parse()?;
+                        if syn::parse_str::<Ident>(&rename_lit.value()).is_err() {
+                            return Err(syn::Error::new_spanned(
+                                rename_lit,
+                                "'rename' must be an identifier",
+                            ));
+                        }
+                        rename = Some(rename_lit.value());
+                    }
                     _ => {
-                        return Err(syn::Error::new_spanned(ident, "Expected 'cfg'"));
+                        return Err(syn::Error::new_spanned(ident, "Expected 'cfg' or 'rename'"));
                     }
                 }
             } else {
@@ -131,7 +194,14 @@ impl Parse for PrebindgenArgs {
             }
         }
 
-        Ok(PrebindgenArgs { group, cfg })
+        if groups.is_empty() {
+            groups.push(DEFAULT_GROUP_NAME.to_string());
+        }
+        Ok(PrebindgenArgs {
+            groups,
+            cfg,
+            rename,
+        })
     }
 }
 
@@ -193,7 +263,7 @@ fn get_prebindgen_jsonl_path(group: &str) -> std::path::PathBuf {
 ///
 /// ```rust,ignore
 /// // Use with explicit group name
-/// #[prebindgen("group_name")]
+/// #[prebindgen("shapes")]
 /// #[repr(C)]
 /// pub struct Point {
 ///     pub x: f64,
@@ -217,13 +287,56 @@ fn get_prebindgen_jsonl_path(group: &str) -> std::path::PathBuf {
 /// pub fn another_function() -> i32 {
 ///     42
 /// }
+///
+/// // Export under another name; binding adapters declare and name the
+/// // generated function `point_new` while still calling `Point_new`
+/// #[prebindgen("functions", rename = "point_new", cfg = "unix")]
+/// pub fn Point_new(x: f64, y: f64) -> Point {
+///     Point { x, y }
+/// }
+///
+/// // Record the item into several groups
+/// #[prebindgen("structs", "all")]
+/// #[repr(C)]
+/// pub struct Size {
+///     pub w: f64,
+///     pub h: f64,
+/// }
+///
+/// // Export the `pub` methods of an inherent impl; each one is captured as
+/// // a free function `Type_method` taking the receiver as `this`
+/// // (here `pub fn Point_norm(this: &Point) -> f64`)
+/// #[prebindgen]
+/// impl Point {
+///     pub fn norm(&self) -> f64 {
+///         (self.x * self.x + self.y * self.y).sqrt()
+///     }
+/// }
+///
+/// // Share a (non-`mut`) static; the binding crate re-exports it by value
+/// // under `#[no_mangle]`, so leave the source one unmangled
+/// #[prebindgen]
+/// pub static SQUARES: [u8; 4] = [0, 1, 4, 9];
+///
+/// // Record an inline module with its items; the binding crate refers to
+/// // them through the module (`my_crate::geometry::Size`)
+/// #[prebindgen]
+/// pub mod geometry {
+///     pub struct Size {
+///         pub w: u32,
+///         pub h: u32,
+///     }
+/// }
 /// ```
 ///
 /// # Requirements
 ///
 /// - Must call `prebindgen::init_prebindgen_out_dir()` in your crate's `build.rs`
-/// - Optionally takes a string literal group name for organization (defaults to "default")
+/// - Optionally takes one or more string literal group names for organization
+///   (defaults to "default"); a group name is made of ASCII letters, digits and `-`
 /// - Optionally takes `cfg = "condition"` to add `#[cfg(condition)]` to generated code
+/// - Optionally takes `rename = "name"` to record the name binding adapters export
+///   a function under (functions only)
 ///
 /// # The `inline` feature
 ///
@@ -235,9 +348,12 @@ pub fn prebindgen(args: TokenStream, input: TokenStream) -> TokenStream {
     let input_clone = input.clone();
 
     // Parse arguments
-    let parsed_args = syn::parse::<PrebindgenArgs>(args).expect("Invalid #[prebindgen] arguments");
+    let parsed_args = match syn::parse::<PrebindgenArgs>(args) {
+        Ok(parsed_args) => parsed_args,
+        Err(e) => return Diagnostic::from(e).emit(input),
+    };
 
-    let group = parsed_args.group;
+    let groups = parsed_args.groups;
 
     // Try to parse as different item types
     let (kind, name, content, span) = if let Ok(parsed) = syn::parse::<DeriveInput>(input.clone()) {
@@ -268,6 +384,9 @@ pub fn prebindgen(args: TokenStream, input: TokenStream) -> TokenStream {
         )
     } else if let Ok(parsed) = syn::parse::<ItemType>(input.clone()) {
         // Handle type alias
+        if let Err(diagnostic) = check_type_alias(&parsed) {
+            return diagnostic.emit(input);
+        }
         let tokens = quote! { #parsed };
         (
             RecordKind::TypeAlias,
@@ -284,12 +403,71 @@ pub fn prebindgen(args: TokenStream, input: TokenStream) -> TokenStream {
             tokens.to_string(),
             parsed.ident.span(),
         )
+    } else if let Ok(parsed) = syn::parse::<ItemStatic>(input.clone()) {
+        // Handle static. The generated layer re-exports it by value, which a
+        // `static mut` can't be read for in a static initializer.
+        if !matches!(parsed.mutability, syn::StaticMutability::None) {
+            return syn::Error::new_spanned(
+                &parsed.mutability,
+                "Mutable statics are not supported by #[prebindgen]",
+            )
+            .to_compile_error()
+            .into();
+        }
+        let tokens = quote! { #parsed };
+        (
+            RecordKind::Static,
+            parsed.ident.to_string(),
+            tokens.to_string(),
+            parsed.ident.span(),
+        )
+    } else if let Ok(parsed) = syn::parse::<ItemMod>(input.clone()) {
+        // Handle inline module: recorded whole, with its functions'
+        // bodies replaced like a function record's
+        if parsed_args.rename.is_some() {
+            return syn::Error::new_spanned(
+                &parsed.ident,
+                "'rename' is not supported on modules; rename the items inside instead",
+            )
+            .to_compile_error()
+            .into();
+        }
+        let recorded = match module_record(&parsed) {
+            Ok(recorded) => recorded,
+            Err(diagnostic) => return diagnostic.emit(input),
+        };
+        let tokens = quote! { #recorded };
+        (
+            RecordKind::Mod,
+            parsed.ident.to_string(),
+            tokens.to_string(),
+            parsed.ident.span(),
+        )
+    } else if let Ok(parsed) = syn::parse::<ItemImpl>(input.clone()) {
+        // Handle inherent impl: each `pub` method is exported through a free
+        // shim function, recorded as an ordinary function
+        if parsed_args.rename.is_some() {
+            return syn::Error::new_spanned(
+                parsed.self_ty,
+                "'rename' is not supported on impl blocks; rename the methods' shims instead",
+            )
+            .to_compile_error()
+            .into();
+        }
+        return prebindgen_impl(&groups, parsed_args.cfg.as_deref(), parsed);
     } else {
         // Try to parse as any item to provide better error messages
         let item = syn::parse::<syn::Item>(input.clone()).ok();
         return unsupported_item_error(item);
     };
 
+    // Only a function has a symbol of its own to export under another name
+    if parsed_args.rename.is_some() && !matches!(kind, RecordKind::Function) {
+        return syn::Error::new(span, format!("'rename' is not supported on {kind} items"))
+            .to_compile_error()
+            .into();
+    }
+
     // The `inline` feature adds `#[inline]` to function wrappers only (not to
     // structs/enums/types/consts). Captured here before `kind` is moved below.
     let is_function = matches!(kind, RecordKind::Function);
@@ -304,43 +482,332 @@ pub fn prebindgen(args: TokenStream, input: TokenStream) -> TokenStream {
         content,
         source_location,
         parsed_args.cfg.clone(),
-    );
+    )
+    .with_rename(parsed_args.rename.clone());
 
-    // Get the full path to the JSONL file
-    let file_path = get_prebindgen_jsonl_path(&group);
-    if prebindgen::utils::write_to_jsonl_file(&file_path, &[&new_record]).is_err() {
-        return TokenStream::from(quote! {
-            compile_error!("Failed to write prebindgen record");
-        });
-    }
+    let out_dir = std::env::var_os("OUT_DIR");
+    let notice = match write_records(out_dir.as_deref(), &groups, &[new_record]) {
+        Ok(notice) => notice,
+        Err(e) => return write_failure(span, e).emit(input_clone),
+    };
 
     // Re-emit the original item, optionally prepending `#[cfg(...)]` (from the
     // macro argument) and `#[inline]` (when the `inline` feature is on, functions
-    // only). When neither applies, the original tokens are returned unchanged.
+    // only) and followed by the unset-`OUT_DIR` notice. When none applies, the
+    // original tokens are returned unchanged.
     let add_inline = cfg!(feature = "inline") && is_function;
-    if parsed_args.cfg.is_none() && !add_inline {
+    if parsed_args.cfg.is_none() && !add_inline && notice.is_empty() {
         return input_clone;
     }
-    let inline_attr = if add_inline {
+    let inline_attr = inline_attr(add_inline);
+    let cfg_attr = cfg_attr(parsed_args.cfg.as_deref());
+    let original_tokens: proc_macro2::TokenStream = input_clone.into();
+    quote! {
+        #cfg_attr
+        #inline_attr
+        #original_tokens
+        #notice
+    }
+    .into()
+}
+
+/// Append `records` to the JSONL file of each of `groups`, given the
+/// `out_dir` (`OUT_DIR`, as read by the caller) of the crate being compiled.
+///
+/// Returns the tokens to emit next to the item: nothing once the records are
+/// written, or — when `OUT_DIR` is unset, as under IDE macro expansion, where
+/// there is no build script output to record into — a use of a deprecated
+/// marker that surfaces as a warning instead of a hard error.
+fn write_records(
+    out_dir: Option<&OsStr>,
+    groups: &[String],
+    records: &[Record],
+) -> std::result::Result<proc_macro2::TokenStream, Box<dyn std::error::Error>> {
+    if out_dir.is_none() {
+        return Ok(out_dir_unset_notice());
+    }
+    for group in groups {
+        prebindgen::utils::write_to_jsonl_file(get_prebindgen_jsonl_path(group), records)?;
+    }
+    Ok(quote! {})
+}
+
+/// Reject a generic type alias: C has no generics, and the alias would only
+/// fail later, in the binding crate, far from its definition.
+fn check_type_alias(item: &ItemType) -> std::result::Result<(), Diagnostic> {
+    if item.generics.params.is_empty() {
+        return Ok(());
+    }
+    let ident = &item.ident;
+    let generics = &item.generics;
+    Err(Diagnostic::error(
+        generics.span(),
+        format!(
+            "generic type alias `{}` can't be exported by #[prebindgen]; \
+             mark a concrete instantiation instead, e.g. `pub type {ident}I32 = {ident}<i32>;`",
+            quote!(#ident #generics).to_string().replace(' ', ""),
+        ),
+    ))
+}
+
+/// The error reported at `span` when the records of its item can't be written.
+fn write_failure(span: proc_macro2::Span, e: Box<dyn std::error::Error>) -> Diagnostic {
+    Diagnostic::error(span, format!("failed to write the prebindgen record: {e}"))
+}
+
+/// The non-fatal diagnostic emitted in place of the records when `OUT_DIR`
+/// is unset.
+fn out_dir_unset_notice() -> proc_macro2::TokenStream {
+    quote! {
+        const _: () = {
+            #[deprecated(
+                note = "OUT_DIR is not set: #[prebindgen] records nothing (expected during IDE \
+                        expansion; in a build, check that the crate has a build.rs calling \
+                        prebindgen::init_prebindgen_out_dir())"
+            )]
+            struct PrebindgenOutDirUnset;
+            let _ = PrebindgenOutDirUnset;
+        };
+    }
+}
+
+fn inline_attr(add_inline: bool) -> proc_macro2::TokenStream {
+    if add_inline {
         quote! { #[inline] }
     } else {
         quote! {}
-    };
-    let cfg_attr = if let Some(cfg_value) = &parsed_args.cfg {
+    }
+}
+
+fn cfg_attr(cfg: Option<&str>) -> proc_macro2::TokenStream {
+    if let Some(cfg_value) = cfg {
+        // Validated when parsing the arguments
         let cfg_tokens: proc_macro2::TokenStream = cfg_value
             .parse()
-            .unwrap_or_else(|_| panic!("Invalid cfg condition: {}", cfg_value));
+            .expect("cfg condition checked by PrebindgenArgs");
         quote! { #[cfg(#cfg_tokens)] }
     } else {
         quote! {}
+    }
+}
+
+/// `#[prebindgen]` on an inherent `impl Type { .. }` block: every `pub` method
+/// gets a free shim `pub fn Type_method(..)` emitted next to the impl, and the
+/// shim (not the method) is recorded, so the binding layer sees an ordinary
+/// function. A receiver becomes the leading `this` parameter with the same
+/// borrow (`&self` → `this: &Type`, `self` → `this: Type`).
+fn prebindgen_impl(groups: &[String], cfg: Option<&str>, item_impl: ItemImpl) -> TokenStream {
+    match impl_shims(&item_impl) {
+        Ok(shims) => {
+            let records = shims.iter().map(|shim| {
+                let mut fn_sig = shim.clone();
+                fn_sig.block = syn::parse_quote! {{ /* placeholder */ }};
+                Record::new(
+                    RecordKind::Function,
+                    shim.sig.ident.to_string(),
+                    quote! { #fn_sig }.to_string(),
+                    SourceLocation::from_span(&shim.sig.ident.span()),
+                    cfg.map(str::to_string),
+                )
+            });
+            let records: Vec<Record> = records.collect();
+            let out_dir = std::env::var_os("OUT_DIR");
+            let notice = match write_records(out_dir.as_deref(), groups, &records) {
+                Ok(notice) => notice,
+                Err(e) => {
+                    return write_failure(item_impl.self_ty.span(), e)
+                        .emit(quote!(#item_impl).into())
+                }
+            };
+            let cfg_attr = cfg_attr(cfg);
+            let inline_attr = inline_attr(cfg!(feature = "inline"));
+            quote! {
+                #cfg_attr
+                #item_impl
+                #(
+                    #cfg_attr
+                    #inline_attr
+                    #shims
+                )*
+                #notice
+            }
+            .into()
+        }
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// The recorded form of a `#[prebindgen]` module: a `pub` inline module
+/// holding only items a `#[prebindgen]` item may be (structs, enums, unions,
+/// functions, type aliases, consts and statics, or such modules), with every
+/// function body replaced by a placeholder.
+fn module_record(module: &ItemMod) -> std::result::Result<ItemMod, Diagnostic> {
+    if !matches!(module.vis, syn::Visibility::Public(_)) {
+        return Err(Diagnostic::error(
+            module.ident.span(),
+            "#[prebindgen] modules must be `pub`",
+        ));
+    }
+    let Some((brace, items)) = &module.content else {
+        return Err(Diagnostic::error(
+            module.span(),
+            "only inline modules (`pub mod foo { ... }`) are supported by #[prebindgen]",
+        ));
     };
-    let original_tokens: proc_macro2::TokenStream = input_clone.into();
-    quote! {
-        #cfg_attr
-        #inline_attr
-        #original_tokens
+    let mut children = Vec::with_capacity(items.len());
+    for item in items {
+        let vis = match item {
+            syn::Item::Fn(i) => Some(&i.vis),
+            syn::Item::Type(i) => Some(&i.vis),
+            syn::Item::Struct(i) => Some(&i.vis),
+            syn::Item::Enum(i) => Some(&i.vis),
+            syn::Item::Union(i) => Some(&i.vis),
+            syn::Item::Const(i) => Some(&i.vis),
+            syn::Item::Static(i) => Some(&i.vis),
+            _ => None,
+        };
+        if vis.is_some_and(|vis| !matches!(vis, syn::Visibility::Public(_))) {
+            return Err(Diagnostic::error(
+                item.span(),
+                "items inside a #[prebindgen] module must be `pub`",
+            ));
+        }
+        children.push(match item {
+            syn::Item::Fn(f) => {
+                let mut fn_sig = f.clone();
+                fn_sig.block = syn::parse_quote! {{ /* placeholder */ }};
+                syn::Item::Fn(fn_sig)
+            }
+            syn::Item::Type(t) => {
+                check_type_alias(t)?;
+                item.clone()
+            }
+            syn::Item::Mod(m) => syn::Item::Mod(module_record(m)?),
+            syn::Item::Struct(_)
+            | syn::Item::Enum(_)
+            | syn::Item::Union(_)
+            | syn::Item::Const(_)
+            | syn::Item::Static(_) => item.clone(),
+            other => {
+                return Err(Diagnostic::error(
+                    other.span(),
+                    "only structs, enums, unions, functions, type aliases, consts, statics \
+                     and inline modules are supported inside a #[prebindgen] module",
+                ))
+            }
+        });
+    }
+    let mut recorded = module.clone();
+    recorded.content = Some((*brace, children));
+    Ok(recorded)
+}
+
+/// Build the free shim functions forwarding to the `pub` methods of `item_impl`.
+fn impl_shims(item_impl: &ItemImpl) -> Result<Vec<ItemFn>> {
+    if let Some((_, path, _)) = &item_impl.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "Trait impl blocks are not supported by #[prebindgen]; only inherent impls are",
+        ));
+    }
+    if !item_impl.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_impl.generics,
+            "Generic impl blocks are not supported by #[prebindgen]",
+        ));
+    }
+    let self_ty = &item_impl.self_ty;
+    let type_name = match self_ty.as_ref() {
+        syn::Type::Path(tp) if tp.qself.is_none() => tp
+            .path
+            .segments
+            .last()
+            .filter(|seg| seg.arguments.is_none())
+            .map(|seg| seg.ident.clone()),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        syn::Error::new_spanned(
+            self_ty,
+            "#[prebindgen] impl blocks must be for a plain named type",
+        )
+    })?;
+
+    let mut shims = Vec::new();
+    for method in item_impl.items.iter().filter_map(|item| match item {
+        syn::ImplItem::Fn(method) if matches!(method.vis, syn::Visibility::Public(_)) => {
+            Some(method)
+        }
+        _ => None,
+    }) {
+        let mut sig = method.sig.clone();
+        let method_ident = &method.sig.ident;
+        sig.ident = format_ident!("{}_{}", type_name, method_ident, span = method_ident.span());
+        let mut call_args = Vec::new();
+        for input in sig.inputs.iter_mut() {
+            match input {
+                syn::FnArg::Receiver(receiver) => {
+                    let ty = &receiver.ty;
+                    *input = syn::parse_quote_spanned! { receiver.self_token.span => this: #ty };
+                    call_args.push(quote! { this });
+                }
+                syn::FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                    syn::Pat::Ident(pat_ident) => {
+                        let ident = &pat_ident.ident;
+                        call_args.push(quote! { #ident });
+                    }
+                    pat => {
+                        return Err(syn::Error::new_spanned(
+                            pat,
+                            "#[prebindgen] methods must name each parameter with a plain identifier",
+                        ));
+                    }
+                },
+            }
+        }
+        ReplaceSelf(self_ty).visit_signature_mut(&mut sig);
+
+        let mut call = quote! { <#self_ty>::#method_ident(#(#call_args),*) };
+        if sig.asyncness.is_some() {
+            call = quote! { #call.await };
+        }
+        if sig.unsafety.is_some() {
+            call = quote! { unsafe { #call } };
+        }
+        let attrs = method
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("doc") || a.path().is_ident("cfg"));
+        shims.push(syn::parse_quote! {
+            #(#attrs)*
+            #[allow(non_snake_case)]
+            pub #sig {
+                #call
+            }
+        });
+    }
+    if shims.is_empty() {
+        return Err(syn::Error::new_spanned(
+            self_ty,
+            "#[prebindgen] impl block has no `pub` methods to export",
+        ));
+    }
+    Ok(shims)
+}
+
+/// Replaces `Self` with the impl's self type, for use outside the impl.
+struct ReplaceSelf<'a>(&'a syn::Type);
+
+impl VisitMut for ReplaceSelf<'_> {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        if let syn::Type::Path(tp) = ty {
+            if tp.qself.is_none() && tp.path.is_ident("Self") {
+                *ty = self.0.clone();
+                return;
+            }
+        }
+        syn::visit_mut::visit_type_mut(self, ty);
     }
-    .into()
 }
 
 /// Proc macro that returns the prebindgen output directory path as a string literal.
@@ -438,3 +905,140 @@ pub fn manifest_dir(_input: TokenStream) -> TokenStream {
     let lit = syn::LitStr::new(&dir, proc_macro2::Span::call_site());
     TokenStream::from(quote! { #lit })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rename_composes_with_groups_and_cfg() {
+        let args: PrebindgenArgs =
+            syn::parse_str(r#"rename = "point_new", "functions", cfg = "unix""#).unwrap();
+        assert_eq!(args.rename.as_deref(), Some("point_new"));
+        assert_eq!(args.groups, ["functions"]);
+        assert_eq!(args.cfg.as_deref(), Some("unix"));
+
+        let args: PrebindgenArgs = syn::parse_str(r#""functions""#).unwrap();
+        assert_eq!(args.rename, None);
+
+        assert!(syn::parse_str::<PrebindgenArgs>(r#"rename = "not an ident""#).is_err());
+        assert!(syn::parse_str::<PrebindgenArgs>(r#"alias = "x""#).is_err());
+    }
+
+    #[test]
+    fn group_names_cannot_contain_underscores() {
+        let args: PrebindgenArgs = syn::parse_str(r#""core-types", "v2""#).unwrap();
+        assert_eq!(args.groups, ["core-types", "v2"]);
+
+        assert!(syn::parse_str::<PrebindgenArgs>(r#""core_types""#).is_err());
+        assert!(syn::parse_str::<PrebindgenArgs>(r#""""#).is_err());
+        assert!(syn::parse_str::<PrebindgenArgs>(r#""a/b""#).is_err());
+    }
+
+    #[test]
+    fn module_items_must_be_pub() {
+        let module: ItemMod = syn::parse_str("pub mod shapes { pub struct Point; }").unwrap();
+        assert!(module_record(&module).is_ok());
+
+        let module: ItemMod = syn::parse_str("pub mod shapes { fn hidden() {} }").unwrap();
+        assert!(module_record(&module).is_err());
+        let module: ItemMod =
+            syn::parse_str("pub mod shapes { pub mod inner { struct Hidden; } }").unwrap();
+        assert!(module_record(&module).is_err());
+    }
+
+    #[test]
+    fn unset_out_dir_writes_nothing_and_warns() {
+        let record = Record::new(
+            RecordKind::Function,
+            "origin".to_string(),
+            "pub fn origin() -> i32 { 0 }".to_string(),
+            SourceLocation::default(),
+            None,
+        );
+        let notice = write_records(None, &["default".to_string()], &[record])
+            .expect("a missing OUT_DIR is not an error");
+        let notice: syn::ItemConst = syn::parse2(notice).expect("notice is a const item");
+        let notice = quote!(#notice).to_string();
+        assert!(notice.contains("deprecated"), "{notice}");
+        assert!(notice.contains("OUT_DIR is not set"), "{notice}");
+    }
+
+    #[test]
+    fn invalid_cfg_is_reported_at_the_argument() {
+        let Err(e) = syn::parse_str::<PrebindgenArgs>(r#""functions", cfg = "all(unix""#) else {
+            panic!("an unbalanced cfg must be rejected");
+        };
+        let message = Diagnostic::from(e).message;
+        assert!(
+            message.starts_with("invalid cfg condition `all(unix`"),
+            "{message}"
+        );
+        assert!(syn::parse_str::<PrebindgenArgs>(r#"cfg = "a b""#).is_err());
+        assert!(syn::parse_str::<PrebindgenArgs>(r#"cfg = "all(unix, feature = \"x\")""#).is_ok());
+    }
+
+    #[test]
+    fn generic_type_alias_is_rejected() {
+        let alias: ItemType = syn::parse_quote!(
+            pub type Pair<T> = (T, T);
+        );
+        let Err(diagnostic) = check_type_alias(&alias) else {
+            panic!("a generic alias must be rejected");
+        };
+        assert!(
+            diagnostic
+                .message
+                .starts_with("generic type alias `Pair<T>` can't be exported"),
+            "{}",
+            diagnostic.message
+        );
+        assert!(diagnostic.message.contains("pub type PairI32 = Pair<i32>;"));
+
+        let alias: ItemType = syn::parse_quote!(
+            pub type Handle = Box<Inner>;
+        );
+        assert!(check_type_alias(&alias).is_ok());
+    }
+
+    #[test]
+    fn inline_module_is_recorded_with_stubbed_functions() {
+        let module: ItemMod = syn::parse_quote!(
+            pub mod foo {
+                pub struct Point {
+                    pub x: i32,
+                }
+                pub fn origin() -> Point {
+                    Point { x: 0 }
+                }
+                pub mod nested {
+                    pub const LIMIT: u32 = 8;
+                }
+            }
+        );
+        let Ok(recorded) = module_record(&module) else {
+            panic!("a simple inline module is accepted");
+        };
+        let recorded = quote!(#recorded).to_string();
+        assert!(
+            recorded.contains("pub fn origin () -> Point { }"),
+            "{recorded}"
+        );
+        assert!(!recorded.contains("Point { x : 0 }"), "{recorded}");
+        assert!(recorded.contains("pub mod nested { pub const LIMIT : u32 = 8 ; }"));
+
+        let private: ItemMod = syn::parse_quote!(
+            mod foo {}
+        );
+        assert!(module_record(&private).is_err());
+        let with_impl: ItemMod = syn::parse_quote!(
+            pub mod foo {
+                impl Point {}
+            }
+        );
+        let Err(diagnostic) = module_record(&with_impl) else {
+            panic!("an impl block inside the module must be rejected");
+        };
+        assert!(diagnostic.message.starts_with("only structs, enums"));
+    }
+}
@@ -1,7 +1,7 @@
 use roxygen::roxygen;
 
 use crate::{
-    api::record::SourceLocation,
+    api::{record::SourceLocation, source::Source},
     codegen::{process_features::process_item_features, CfgExprRules},
 };
 
@@ -81,6 +81,103 @@ impl Builder {
         self
     }
 
+    /// Enable a specific target pointer width. All other widths are treated as disabled.
+    ///
+    /// Only one width can be enabled. Calling this again overwrites the previous choice.
+    #[roxygen]
+    pub fn enable_target_pointer_width<S: Into<String>>(
+        mut self,
+        /// The target pointer width to enable (e.g., "32", "64")
+        width: S,
+    ) -> Self {
+        self.rules.enabled_target_pointer_width = Some(width.into());
+        self
+    }
+
+    /// Enable a target family; `unix` and `windows` shorthands map to it. Families
+    /// not enabled are treated as disabled.
+    ///
+    /// A target may belong to several families, so calls accumulate.
+    #[roxygen]
+    pub fn enable_target_family<S: Into<String>>(
+        mut self,
+        /// The target family to enable (e.g., "unix", "windows", "wasm")
+        family: S,
+    ) -> Self {
+        self.rules.enabled_target_families.insert(family.into());
+        self
+    }
+
+    /// Choose how features that no rule covers are handled (default `true`)
+    ///
+    /// When `true`, each unmapped feature is recorded with its source location,
+    /// reported as a `cargo:warning`, and the code it guards is dropped. When
+    /// `false`, unmapped features pass through unchanged in the `#[cfg(...)]`.
+    #[roxygen]
+    pub fn error_on_unmapped(
+        mut self,
+        /// Whether unmapped features are reported instead of passed through
+        enable: bool,
+    ) -> Self {
+        self.rules.keep_unmapped_features = !enable;
+        self
+    }
+
+    /// Keep `#[cfg(feature = "...")]` predicates on enabled features (default `false`)
+    ///
+    /// By default an enabled feature evaluates to true and its cfg is removed.
+    /// When `true`, the predicate stays in place, so the generated file still
+    /// tracks it when `include!`d under several configurations. Disabled and
+    /// mapped features are handled as usual.
+    #[roxygen]
+    pub fn keep_enabled_cfg(
+        mut self,
+        /// Whether cfgs on enabled features are kept
+        keep: bool,
+    ) -> Self {
+        self.rules.keep_enabled_features = keep;
+        self
+    }
+
+    /// Treat the bare cfg predicate `name` as always false
+    ///
+    /// Code guarded by `#[cfg(test)]` or `#[cfg(debug_assertions)]` should never
+    /// reach the generated bindings: with `forbid_cfg("test")` an item under
+    /// `#[cfg(test)]` (or `all(test, ...)`) is dropped, and `not(test)` holds.
+    /// Calls accumulate.
+    #[roxygen]
+    pub fn forbid_cfg(
+        mut self,
+        /// The cfg predicate name (e.g., "test")
+        name: &str,
+    ) -> Self {
+        self.rules.forbidden_cfgs.insert(name.to_string());
+        self
+    }
+
+    /// Rename several source crate features to one binding crate feature
+    ///
+    /// Each `#[cfg(feature = "<from>")]` predicate becomes
+    /// `#[cfg(feature = "<to>")]`, e.g. `tokio`, `async-std` and `smol` all
+    /// mapped to `async`. Calls accumulate; a feature mapped again takes the
+    /// latest target. Features the filter already knows as enabled or disabled
+    /// are resolved as such instead, and [`predefined_features`](Self::predefined_features)
+    /// clears the mappings, so call this after it.
+    #[roxygen]
+    pub fn map_many<S: AsRef<str>>(
+        mut self,
+        /// Source crate feature names
+        from: &[S],
+        /// Binding crate feature they are renamed to
+        to: &str,
+    ) -> Self {
+        self.rules.feature_mappings.extend(
+            from.iter()
+                .map(|feature| (feature.as_ref().to_string(), to.to_string())),
+        );
+        self
+    }
+
     /// Automatically filter features according to provided list
     /// In the beginning put assert that list matches the actual features list of imported source crate
     /// The feature list comes directly from the prebindgen output directory, so it should match the
@@ -124,6 +221,33 @@ impl Builder {
         self
     }
 
+    /// Enable the features the source crate was built with
+    ///
+    /// Reads [`Source::features`] (the `features.txt` written by
+    /// `init_prebindgen_out_dir`) and enables each of them, so their cfgs are
+    /// resolved without listing them by hand or asserting a features constant.
+    /// Features absent from that list were off in the source crate and are
+    /// treated as disabled. Calls accumulate with other enabled features.
+    #[roxygen]
+    pub fn enable_from_source(
+        mut self,
+        /// Source whose recorded features are enabled
+        source: &Source,
+    ) -> Self {
+        self.rules.enabled_features.extend(source.features());
+        self.rules.disable_unknown_features = true;
+        self
+    }
+
+    /// Also record the unknown features that
+    /// [`predefined_features`](Self::predefined_features) or
+    /// [`enable_from_source`](Self::enable_from_source) silently disable as
+    /// unmapped
+    pub(crate) fn report_unknown_features(mut self) -> Self {
+        self.rules.report_unknown_features = true;
+        self
+    }
+
     /// Build the CfgFilter instance with the configured options
     pub fn build(self) -> CfgFilter {
         // Determine if this filter is active (i.e., not pass-through)
@@ -160,6 +284,7 @@ impl Builder {
             prelude_item,
             prelude_emitted: false,
             active,
+            unmapped: Vec::new(),
         }
     }
 }
@@ -182,6 +307,8 @@ pub struct CfgFilter {
     prelude_item: Option<(syn::Item, SourceLocation)>,
     prelude_emitted: bool,
     active: bool,
+    /// Unmapped features met so far, with the location of the guarded code
+    unmapped: Vec<(String, SourceLocation)>,
 }
 
 impl CfgFilter {
@@ -193,7 +320,29 @@ impl CfgFilter {
     ///
     /// Consumes items until it finds one that should be kept according to feature flags.
     /// Returns that item, possibly with adjusted attributes. Otherwise `None` at end.
+    /// Each unmapped feature met on the way is reported as a `cargo:warning`.
     pub fn call<I>(&mut self, iter: &mut I) -> Option<(syn::Item, SourceLocation)>
+    where
+        I: Iterator<Item = (syn::Item, SourceLocation)>,
+    {
+        self.next_kept(iter, true)
+    }
+
+    /// Unmapped features met so far, each with the location of the code it guards
+    ///
+    /// Collected while [`Builder::error_on_unmapped`] is on, in addition to the
+    /// `cargo:warning` printed for each.
+    pub fn unmapped(&self) -> &[(String, SourceLocation)] {
+        &self.unmapped
+    }
+
+    /// Internal: the next kept item, printing the newly met unmapped
+    /// features as warnings if `report` is set
+    pub(crate) fn next_kept<I>(
+        &mut self,
+        iter: &mut I,
+        report: bool,
+    ) -> Option<(syn::Item, SourceLocation)>
     where
         I: Iterator<Item = (syn::Item, SourceLocation)>,
     {
@@ -210,10 +359,28 @@ impl CfgFilter {
             return iter.next();
         }
         for (mut item, source_location) in iter {
-            if process_item_features(&mut item, &self.builder.rules, &source_location) {
+            let reported = self.unmapped.len();
+            let keep = process_item_features(
+                &mut item,
+                &self.builder.rules,
+                &source_location,
+                &mut self.unmapped,
+            );
+            if report {
+                for (name, location) in &self.unmapped[reported..] {
+                    println!(
+                        "cargo:warning=prebindgen: unmapped feature `{name}` at {location}; \
+                         the code it guards is skipped"
+                    );
+                }
+            }
+            if keep {
                 return Some((item, source_location));
             }
         }
         None
     }
 }
+
+#[cfg(test)]
+mod tests;
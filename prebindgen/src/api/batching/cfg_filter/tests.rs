@@ -0,0 +1,349 @@
+use quote::ToTokens;
+
+use super::*;
+
+fn located(line: usize) -> SourceLocation {
+    SourceLocation {
+        file: "src/lib.rs".to_string(),
+        line,
+        column: 1,
+        crate_name: None,
+        group: None,
+        module_path: None,
+        source_name: None,
+    }
+}
+
+fn run(filter: &mut CfgFilter, items: Vec<(syn::Item, SourceLocation)>) -> Vec<String> {
+    let mut iter = items.into_iter();
+    std::iter::from_fn(|| filter.call(&mut iter))
+        .map(|(item, _)| item.to_token_stream().to_string())
+        .collect()
+}
+
+fn items() -> Vec<(syn::Item, SourceLocation)> {
+    vec![
+        (
+            syn::parse_quote! {
+                #[cfg(feature = "extra")]
+                pub fn extra() {}
+            },
+            located(3),
+        ),
+        (
+            syn::parse_quote! {
+                #[cfg(target_arch = "x86_64")]
+                pub fn native() {}
+            },
+            located(7),
+        ),
+    ]
+}
+
+#[test]
+fn unmapped_features_are_collected_with_location() {
+    let mut filter = CfgFilter::builder().enable_target_arch("x86_64").build();
+    let kept = run(&mut filter, items());
+
+    assert_eq!(kept, vec!["pub fn native () { }"]);
+    assert_eq!(filter.unmapped(), vec![("extra".to_string(), located(3))]);
+}
+
+#[test]
+fn unmapped_features_pass_through_when_not_errors() {
+    let mut filter = CfgFilter::builder()
+        .enable_target_arch("x86_64")
+        .error_on_unmapped(false)
+        .build();
+    let kept = run(&mut filter, items());
+
+    assert_eq!(
+        kept,
+        vec![
+            "# [cfg (feature = \"extra\")] pub fn extra () { }",
+            "pub fn native () { }"
+        ]
+    );
+    assert!(filter.unmapped().is_empty());
+}
+
+/// A Linux-only binding collapses `target_os` cfgs: fields and items for
+/// other systems are dropped, the cfg on matching ones is removed, and
+/// predicates on dimensions left unselected are kept.
+#[test]
+fn target_os_collapses_struct_fields_and_items() {
+    let mut filter = CfgFilter::builder()
+        .enable_target_os("linux")
+        .error_on_unmapped(false)
+        .build();
+    let kept = run(
+        &mut filter,
+        vec![
+            (
+                syn::parse_quote! {
+                    pub struct Foo {
+                        #[cfg(target_os = "linux")]
+                        pub epoll_fd: i32,
+                        #[cfg(target_os = "macos")]
+                        pub kqueue_fd: i32,
+                        #[cfg(any(target_os = "linux", target_os = "android"))]
+                        pub eventfd: i32,
+                        #[cfg(all(target_os = "linux", target_env = "musl"))]
+                        pub musl_only: u8,
+                        pub common: u64,
+                    }
+                },
+                located(1),
+            ),
+            (
+                syn::parse_quote! {
+                    #[cfg(target_os = "windows")]
+                    pub fn iocp() {}
+                },
+                located(12),
+            ),
+            (
+                syn::parse_quote! {
+                    #[cfg(not(target_os = "windows"))]
+                    pub fn posix() {}
+                },
+                located(15),
+            ),
+        ],
+    );
+
+    assert_eq!(
+        kept,
+        vec![
+            "pub struct Foo { pub epoll_fd : i32 , pub eventfd : i32 , \
+             # [cfg (target_env = \"musl\")] pub musl_only : u8 , pub common : u64 }",
+            "pub fn posix () { }",
+        ]
+    );
+}
+
+/// All four target dimensions together select a single triple.
+#[test]
+fn full_target_selection_drops_other_triples() {
+    let mut filter = CfgFilter::builder()
+        .enable_target_arch("aarch64")
+        .enable_target_vendor("apple")
+        .enable_target_os("macos")
+        .enable_target_env("")
+        .build();
+    let kept = run(
+        &mut filter,
+        vec![
+            (
+                syn::parse_quote! {
+                    #[cfg(all(target_arch = "aarch64", target_vendor = "apple", target_os = "macos"))]
+                    pub fn apple_silicon() {}
+                },
+                located(1),
+            ),
+            (
+                syn::parse_quote! {
+                    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+                    pub fn linux_gnu() {}
+                },
+                located(4),
+            ),
+        ],
+    );
+
+    assert_eq!(kept, vec!["pub fn apple_silicon () { }"]);
+}
+
+/// `unix`/`windows` shorthands resolve through the selected target family.
+#[test]
+fn family_shorthands_and_pointer_width() {
+    let mut filter = CfgFilter::builder()
+        .enable_target_family("unix")
+        .enable_target_pointer_width("64")
+        .build();
+    let kept = run(
+        &mut filter,
+        vec![
+            (
+                syn::parse_quote! {
+                    #[cfg(all(unix, target_pointer_width = "64"))]
+                    pub fn unix64() {}
+                },
+                located(1),
+            ),
+            (
+                syn::parse_quote! {
+                    #[cfg(windows)]
+                    pub fn win() {}
+                },
+                located(4),
+            ),
+            (
+                syn::parse_quote! {
+                    #[cfg(target_pointer_width = "32")]
+                    pub fn narrow() {}
+                },
+                located(7),
+            ),
+        ],
+    );
+
+    assert_eq!(kept, vec!["pub fn unix64 () { }"]);
+}
+
+/// `keep_enabled_cfg` leaves the cfg of an enabled feature in place, while a
+/// disabled feature still drops its item.
+#[test]
+fn keep_enabled_cfg_preserves_enabled_feature_attribute() {
+    let mut filter = CfgFilter::builder()
+        .predefined_features("FEATURES", "source/extra")
+        .keep_enabled_cfg(true)
+        .build();
+    let kept = run(
+        &mut filter,
+        vec![
+            (
+                syn::parse_quote! {
+                    #[cfg(feature = "extra")]
+                    pub fn extra() {}
+                },
+                located(1),
+            ),
+            (
+                syn::parse_quote! {
+                    #[cfg(feature = "other")]
+                    pub fn other() {}
+                },
+                located(4),
+            ),
+        ],
+    );
+
+    assert!(
+        kept.contains(&"# [cfg (feature = \"extra\")] pub fn extra () { }".to_string()),
+        "{kept:?}"
+    );
+    assert!(
+        !kept.iter().any(|item| item.contains("fn other")),
+        "{kept:?}"
+    );
+}
+
+#[test]
+fn map_many_renames_every_alias_to_one_feature() {
+    let mut filter = CfgFilter::builder()
+        .map_many(&["tokio", "async-std", "smol"], "async")
+        .build();
+    let items = ["tokio", "async-std", "smol"]
+        .into_iter()
+        .enumerate()
+        .map(|(line, feature)| {
+            let item = syn::parse_quote! {
+                #[cfg(feature = #feature)]
+                pub fn spawn() {}
+            };
+            (item, located(line))
+        })
+        .collect();
+    let kept = run(&mut filter, items);
+
+    assert_eq!(
+        kept,
+        vec!["# [cfg (feature = \"async\")] pub fn spawn () { }"; 3]
+    );
+    assert!(filter.unmapped().is_empty());
+}
+
+#[test]
+fn forbid_cfg_drops_test_only_items() {
+    let mut filter = CfgFilter::builder()
+        .forbid_cfg("test")
+        .forbid_cfg("debug_assertions")
+        .build();
+    let items = vec![
+        (
+            syn::parse_quote! {
+                #[cfg(test)]
+                pub fn test_helper() {}
+            },
+            located(1),
+        ),
+        (
+            syn::parse_quote! {
+                #[cfg(all(debug_assertions, unix))]
+                pub fn debug_dump() {}
+            },
+            located(2),
+        ),
+        (
+            syn::parse_quote! {
+                #[cfg(not(test))]
+                pub fn open() {}
+            },
+            located(3),
+        ),
+        (
+            syn::parse_quote! {
+                #[cfg(miri)]
+                pub fn close() {}
+            },
+            located(4),
+        ),
+    ];
+    let kept = run(&mut filter, items);
+
+    assert_eq!(
+        kept,
+        vec![
+            "pub fn open () { }".to_string(),
+            "# [cfg (miri)] pub fn close () { }".to_string(),
+        ]
+    );
+}
+
+/// `enable_from_source` enables the features listed in the source's
+/// `features.txt`, stripping their cfgs; an unlisted feature is off.
+#[test]
+fn enable_from_source_enables_recorded_features() {
+    let dir = crate::api::test_util::unique_test_dir("cfg_filter_from_source");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(crate::CRATE_NAME_FILE), "source_ffi").unwrap();
+    std::fs::write(dir.join(crate::FEATURES_FILE), "extra\nstd\n").unwrap();
+    let record = crate::Record::new(
+        crate::RecordKind::Function,
+        "a".to_string(),
+        "pub fn a() {}".to_string(),
+        SourceLocation::default(),
+        None,
+    );
+    crate::api::utils::jsonl::write_to_jsonl_file(dir.join("default_1_1.jsonl"), &[record])
+        .unwrap();
+    let source = Source::builder(&dir)
+        .enable_feature_filtering(None::<&str>)
+        .enable_target_filtering(None::<&str>)
+        .build();
+
+    let mut filter = CfgFilter::builder().enable_from_source(&source).build();
+    let kept = run(
+        &mut filter,
+        vec![
+            (
+                syn::parse_quote! {
+                    #[cfg(all(feature = "extra", feature = "std"))]
+                    pub fn extra() {}
+                },
+                located(1),
+            ),
+            (
+                syn::parse_quote! {
+                    #[cfg(feature = "other")]
+                    pub fn other() {}
+                },
+                located(4),
+            ),
+        ],
+    );
+
+    assert_eq!(kept, vec!["pub fn extra () { }"]);
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -1,6 +1,6 @@
 use std::{collections::BTreeSet, env, fs, path::Path};
 
-use crate::{CRATE_NAME_FILE, FEATURES_FILE};
+use crate::{CRATE_NAME_FILE, CRATE_VERSION_FILE, FEATURES_FILE};
 
 /// Initialize the prebindgen output directory for the current crate
 ///
@@ -62,6 +62,19 @@ pub fn init_prebindgen_out_dir() {
         );
     });
 
+    // Store the crate version next to it
+    let crate_version = env::var("CARGO_PKG_VERSION").expect(
+        "CARGO_PKG_VERSION environment variable not set. This function should be called from build.rs.",
+    );
+    let crate_version_path = prebindgen_dir.join(CRATE_VERSION_FILE);
+    fs::write(&crate_version_path, &crate_version).unwrap_or_else(|e| {
+        panic!(
+            "Failed to write crate version to {}: {}",
+            crate_version_path.display(),
+            e
+        );
+    });
+
     let features = get_enabled_features();
 
     // Save features list to features.txt (one per line)
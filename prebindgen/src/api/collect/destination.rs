@@ -1,8 +1,14 @@
-use std::{env, fs, path::Path};
+use std::{
+    collections::{BTreeMap, HashSet},
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
 
-use crate::SourceLocation;
+use crate::{api::utils::edition::RustEdition, Source, SourceLocation};
 
-/// Internal collector that accumulates `syn::Item` objects (Rust AST items)
+/// Collector that accumulates `syn::Item` objects (Rust AST items)
 /// and writes them as `prettyplease`-formatted Rust source code to a file.
 ///
 /// This is the final step of `Registry::write_rust`, where the generated FFI
@@ -11,40 +17,234 @@ use crate::SourceLocation;
 /// The [`write`](Self::write) method handles path resolution automatically:
 /// - Relative paths are resolved relative to the `OUT_DIR` environment variable
 /// - Absolute paths are used as-is
+///
+/// Items collected together with their `SourceLocation` keep the group they
+/// were captured into, so [`write_per_group`](Self::write_per_group) can split
+/// them back into one file per group.
+///
+/// A [`with_banner`](Self::with_banner) comment opens every written file, a
+/// [`with_prologue`](Self::with_prologue) block follows it, [`with_source_comments`](Self::with_source_comments)
+/// marks each item with the source location it was captured at,
+/// [`with_unsafe_comments`](Self::with_unsafe_comments) explains each
+/// `unsafe` block, and [`with_allowed_lints`](Self::with_allowed_lints) silences lints the
+/// generated code is known to trip.
 pub struct Destination {
     file: syn::File,
+    // Location of each item in `file.items`, index-aligned; `None` for items
+    // collected without a location (generated code).
+    locations: Vec<Option<SourceLocation>>,
+    // The anonymous `const _` items among `file.items`, to drop duplicates.
+    assertions: HashSet<syn::Item>,
+    // Leading inner attributes and items emitted before `file`.
+    prologue: syn::File,
+    source_comments: bool,
+    unsafe_comments: bool,
+    // Lints allowed on every collected item.
+    allowed_lints: Vec<syn::Path>,
+    // Comment lines opening the written file.
+    banner: Option<String>,
 }
 
 impl FromIterator<syn::Item> for Destination {
-    /// Creates a `Destination` from an iterator of `syn::Item` objects.
+    /// Creates a `Destination` from an iterator of `syn::Item` objects; see
+    /// [`append`](Self::append) for how assertions are deduplicated.
     fn from_iter<T: IntoIterator<Item = syn::Item>>(iter: T) -> Self {
-        Self {
-            file: syn::File {
-                shebang: None,
-                attrs: vec![],
-                items: iter.into_iter().collect(),
-            },
-        }
+        let mut destination = Self::empty();
+        destination.extend(iter);
+        destination
     }
 }
 
 impl FromIterator<(syn::Item, SourceLocation)> for Destination {
     /// Creates a `Destination` from an iterator of `(syn::Item, SourceLocation)` tuples.
     ///
-    /// The source location information is discarded during collection, keeping only
-    /// the `syn::Item` objects for code generation.
+    /// The source locations are kept for
+    /// [`write_per_group`](Self::write_per_group) and
+    /// [`with_source_comments`](Self::with_source_comments); see
+    /// [`append`](Self::append) for how assertions are deduplicated.
     fn from_iter<T: IntoIterator<Item = (syn::Item, SourceLocation)>>(iter: T) -> Self {
-        Self {
-            file: syn::File {
-                shebang: None,
-                attrs: vec![],
-                items: iter.into_iter().map(|(item, _)| item).collect(),
-            },
+        let mut destination = Self::empty();
+        destination.extend(iter);
+        destination
+    }
+}
+
+impl Extend<syn::Item> for Destination {
+    /// Appends items collected without a location; see
+    /// [`append`](Self::append) for how assertions are deduplicated.
+    fn extend<T: IntoIterator<Item = syn::Item>>(&mut self, iter: T) {
+        for item in iter {
+            self.push(item, None);
+        }
+    }
+}
+
+impl Extend<(syn::Item, SourceLocation)> for Destination {
+    /// Appends items with their locations; see [`append`](Self::append) for
+    /// how assertions are deduplicated.
+    fn extend<T: IntoIterator<Item = (syn::Item, SourceLocation)>>(&mut self, iter: T) {
+        for (item, location) in iter {
+            self.push(item, Some(location));
         }
     }
 }
 
 impl Destination {
+    /// Lints C-style generated names commonly trip: a reasonable argument for
+    /// [`with_allowed_lints`](Self::with_allowed_lints).
+    pub const DEFAULT_ALLOWED_LINTS: &'static [&'static str] =
+        &["non_camel_case_types", "non_snake_case"];
+
+    /// Opens the generated file with
+    /// `// @generated by prebindgen from <crate> <version>`, naming the
+    /// crate and version `source` was captured from.
+    ///
+    /// The `@generated` marker tells editors, review tools and linters that
+    /// the file is not to be edited by hand. See [`with_banner`](Self::with_banner)
+    /// for custom text.
+    pub fn with_generated_banner(self, source: &Source) -> Self {
+        let mut banner = format!("@generated by prebindgen from {}", source.crate_name());
+        if let Some(version) = source.crate_version() {
+            banner.push(' ');
+            banner.push_str(&version);
+        }
+        self.with_banner(banner)
+    }
+
+    /// Opens the generated file with `text`, each line written as a `//`
+    /// comment, above the prologue. Replaces a previously set banner.
+    ///
+    /// An inner `//!` doc comment would be the natural form, but the file is
+    /// usually `include!`d, where inner doc comments are rejected like inner
+    /// attributes, so plain comments are emitted instead.
+    pub fn with_banner(mut self, text: impl Into<String>) -> Self {
+        self.banner = Some(text.into());
+        self
+    }
+
+    /// Emits `prologue` at the top of the generated file, above every
+    /// collected item.
+    ///
+    /// The prologue may hold inner attributes (`#![allow(non_camel_case_types)]`)
+    /// and any items (`use` imports, `include!`s); they are kept as given.
+    /// Calling this again appends to the existing prologue.
+    ///
+    /// # Panics
+    ///
+    /// If `prologue` does not parse as the contents of a Rust file.
+    pub fn with_prologue(mut self, prologue: proc_macro2::TokenStream) -> Self {
+        let parsed: syn::File = syn::parse2(prologue).unwrap_or_else(|e| {
+            panic!("Destination::with_prologue: prologue is not valid Rust: {e}");
+        });
+        self.prologue.attrs.extend(parsed.attrs);
+        self.prologue.items.extend(parsed.items);
+        self
+    }
+
+    /// Emits a `// <file>:<line>:<column>` comment before each item that was
+    /// collected with its `SourceLocation`.
+    ///
+    /// Makes it possible to trace an error in the generated file (a failing
+    /// layout assertion, say) back to the `#[prebindgen]` item it came from.
+    /// Items collected without a location, or with a default one that names
+    /// no file (generated code), get no comment.
+    pub fn with_source_comments(mut self, enabled: bool) -> Self {
+        self.source_comments = enabled;
+        self
+    }
+
+    /// Emits a `// SAFETY:` comment before each `unsafe { ... }` block of the
+    /// collected items.
+    ///
+    /// A block calling `transmute::<A, B>` is described as reinterpreting
+    /// `A` as `B`; the comment of an item collected with its `SourceLocation`
+    /// names it, so a committed bindings file can be audited block by block
+    /// against the `#[prebindgen]` item it came from; any other block is
+    /// marked as having no generated justification. Blocks inside macro
+    /// invocations are not seen and are left uncommented.
+    pub fn with_unsafe_comments(mut self, enabled: bool) -> Self {
+        self.unsafe_comments = enabled;
+        self
+    }
+
+    /// Allows `lints` (e.g. `non_camel_case_types`,
+    /// `clippy::missing_safety_doc`) on the generated code, so the including
+    /// crate doesn't have to.
+    ///
+    /// The generated file is usually `include!`d, where inner `#![allow]`
+    /// attributes are rejected, so the lints are emitted as an outer
+    /// `#[allow(...)]` on each collected item instead (prologue items are
+    /// left alone). Calling this again adds to the set.
+    /// [`DEFAULT_ALLOWED_LINTS`](Self::DEFAULT_ALLOWED_LINTS) covers C-style
+    /// type and function names.
+    ///
+    /// # Panics
+    ///
+    /// If a lint is not a valid path.
+    pub fn with_allowed_lints<I, S>(mut self, lints: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for lint in lints {
+            let lint = lint.as_ref();
+            let path = syn::parse_str(lint).unwrap_or_else(|e| {
+                panic!("Destination::with_allowed_lints: `{lint}` is not a lint name: {e}");
+            });
+            if !self.allowed_lints.contains(&path) {
+                self.allowed_lints.push(path);
+            }
+        }
+        self
+    }
+
+    /// Merges `other` into this destination, e.g. to write the output of
+    /// two separately mapped item streams into one file.
+    ///
+    /// The items of `other` follow this destination's, keeping their order
+    /// and locations; its prologue is appended to this one and its allowed
+    /// lints are added. An anonymous `const _` item (a layout or value
+    /// assertion) identical to one already collected is dropped, so
+    /// assertions both streams produced are checked once. This destination's
+    /// source-comments setting and banner are kept.
+    pub fn append(&mut self, other: Destination) {
+        self.prologue.attrs.extend(other.prologue.attrs);
+        self.prologue.items.extend(other.prologue.items);
+        for lint in other.allowed_lints {
+            if !self.allowed_lints.contains(&lint) {
+                self.allowed_lints.push(lint);
+            }
+        }
+        for (item, location) in other.file.items.into_iter().zip(other.locations) {
+            self.push(item, location);
+        }
+    }
+
+    /// A destination with no items and default settings.
+    fn empty() -> Self {
+        Self {
+            file: empty_file(),
+            locations: vec![],
+            assertions: HashSet::new(),
+            prologue: empty_file(),
+            source_comments: false,
+            unsafe_comments: false,
+            allowed_lints: vec![],
+            banner: None,
+        }
+    }
+
+    /// Collect `item`, unless it is an anonymous `const _` identical to an
+    /// already collected one.
+    fn push(&mut self, item: syn::Item, location: Option<SourceLocation>) {
+        let is_assertion = matches!(&item, syn::Item::Const(c) if c.ident == "_");
+        if is_assertion && !self.assertions.insert(item.clone()) {
+            return;
+        }
+        self.file.items.push(item);
+        self.locations.push(location);
+    }
+
     /// Writes the collected Rust items to a file and returns the absolute path.
     ///
     /// This method formats the collected `syn::Item` objects into valid Rust source code
@@ -67,25 +267,549 @@ impl Destination {
     ///
     /// - If the `OUT_DIR` environment variable is not set (when using relative paths)
     /// - If the file cannot be written (e.g., permission denied, disk full)
-    pub fn write<P: AsRef<Path>>(self, filename: P) -> std::path::PathBuf {
-        let file_path = if filename.as_ref().is_relative() {
-            let out_dir = env::var("OUT_DIR").expect("OUT_DIR environment variable not set");
-            std::path::PathBuf::from(out_dir).join(filename)
-        } else {
-            filename.as_ref().to_path_buf()
+    pub fn write<P: AsRef<Path>>(&self, filename: P) -> PathBuf {
+        let file_path = resolve_out_path(filename.as_ref());
+        fs::File::create(&file_path)
+            .and_then(|mut file| self.write_to(&mut file))
+            .unwrap_or_else(|e| {
+                panic!("Failed to write file {}: {}", file_path.display(), e);
+            });
+        file_path
+    }
+
+    /// The collected items with their source locations, in collection order;
+    /// `None` for items collected without a location (generated code).
+    ///
+    /// The prologue and allowed lints are not part of the items: they are
+    /// applied only when the destination is written.
+    pub fn items(&self) -> impl Iterator<Item = (&syn::Item, Option<&SourceLocation>)> {
+        self.file
+            .items
+            .iter()
+            .zip(self.locations.iter().map(Option::as_ref))
+    }
+
+    /// Takes the collected items out of the destination, e.g. to run them
+    /// through a custom `syn` visitor and collect the result into a new
+    /// `Destination`.
+    ///
+    /// Items collected without a location get `SourceLocation::default()`
+    /// (no file, no group). The prologue, source comments and allowed lints
+    /// are dropped; set them again on the re-collected destination.
+    pub fn into_items(self) -> Vec<(syn::Item, SourceLocation)> {
+        self.file
+            .items
+            .into_iter()
+            .zip(self.locations)
+            .map(|(item, location)| (item, location.unwrap_or_default()))
+            .collect()
+    }
+
+    /// A digest of the FFI surface of the collected items, as 16 hex digits.
+    ///
+    /// It covers the signatures of `extern` functions (defined or declared in
+    /// an `extern` block) and the definitions of `#[repr(..)]` structs, enums
+    /// and unions; function bodies, docs and all other items are left out, so
+    /// it changes only when the exported ABI does. Snapshot it in a test to
+    /// make unexpected ABI drift fail CI.
+    ///
+    /// The digest is stable across runs, platforms and Rust versions: the
+    /// items are normalized through `prettyplease` and sorted before hashing
+    /// with 64-bit FNV-1a.
+    pub fn abi_digest(&self) -> String {
+        let mut surface: Vec<String> = self
+            .file
+            .items
+            .iter()
+            .flat_map(abi_items)
+            .map(|item| {
+                prettyplease::unparse(&syn::File {
+                    items: vec![item],
+                    ..empty_file()
+                })
+            })
+            .collect();
+        surface.sort();
+        format!("{:016x}", fnv1a64(surface.join("\n").as_bytes()))
+    }
+
+    /// Writes the collected Rust items, formatted exactly as [`write`](Self::write)
+    /// does, into any `io::Write` sink.
+    ///
+    /// Use this (or `to_string()`, via `Display`) to post-process the generated
+    /// source — prepend a license header, run a custom formatter — before
+    /// writing it out yourself.
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(self.render().as_bytes())
+    }
+
+    fn render(&self) -> String {
+        let mut out = self.render_banner();
+        out.push_str(&self.render_body());
+        out
+    }
+
+    /// The [`banner`](Self::with_banner) as `//` lines, empty if unset.
+    fn render_banner(&self) -> String {
+        self.banner
+            .iter()
+            .flat_map(|banner| banner.lines())
+            .map(|line| match line {
+                "" => "//\n".to_string(),
+                line => format!("// {line}\n"),
+            })
+            .collect()
+    }
+
+    fn render_body(&self) -> String {
+        let items = self.items_with_allows();
+        if !self.source_comments && !self.unsafe_comments {
+            let file = syn::File {
+                shebang: None,
+                attrs: [&self.prologue.attrs[..], &self.file.attrs[..]].concat(),
+                items: [&self.prologue.items[..], &items[..]].concat(),
+            };
+            return prettyplease::unparse(&file);
+        }
+        // prettyplease drops comments, so each item is unparsed on its own
+        // and its comments are spliced into the text.
+        let head = syn::File {
+            shebang: None,
+            attrs: [&self.prologue.attrs[..], &self.file.attrs[..]].concat(),
+            items: self.prologue.items.clone(),
         };
+        let mut out = prettyplease::unparse(&head);
+        for (item, location) in items.iter().zip(&self.locations) {
+            match location {
+                Some(location) if self.source_comments && !location.file.is_empty() => {
+                    out.push_str(&format!("// {location}\n"));
+                }
+                _ => {}
+            }
+            if self.unsafe_comments {
+                out.push_str(&comment_unsafe_blocks(item, location.as_ref()));
+            } else {
+                out.push_str(&prettyplease::unparse(&syn::File {
+                    items: vec![item.clone()],
+                    ..empty_file()
+                }));
+            }
+        }
+        out
+    }
+
+    /// The collected items, each carrying the [`allowed_lints`](Self::with_allowed_lints).
+    fn items_with_allows(&self) -> Vec<syn::Item> {
+        let mut items = self.file.items.clone();
+        if self.allowed_lints.is_empty() {
+            return items;
+        }
+        let lints = &self.allowed_lints;
+        let allow: syn::Attribute = syn::parse_quote!(#[allow(#(#lints),*)]);
+        for attrs in items.iter_mut().filter_map(item_attrs_mut) {
+            attrs.push(allow.clone());
+        }
+        items
+    }
 
-        let content = prettyplease::unparse(&self.file);
+    /// Writes the collected Rust items to a file formatted by `rustfmt` and
+    /// returns the absolute path.
+    ///
+    /// Same as [`write`](Self::write), but the `prettyplease` output is piped
+    /// through the `rustfmt` binary so a committed bindings file follows the
+    /// project's own style. `rustfmt` runs in `CARGO_MANIFEST_DIR`, so the
+    /// crate's `rustfmt.toml` is picked up.
+    ///
+    /// If `rustfmt` is not installed the `prettyplease` output is written
+    /// as-is. If it fails, its stderr is reported as `cargo:warning` lines
+    /// and the `prettyplease` output is written as well.
+    ///
+    /// # Panics
+    ///
+    /// - If the `OUT_DIR` environment variable is not set (when using relative paths)
+    /// - If the file cannot be written
+    pub fn write_formatted<P: AsRef<Path>>(&self, filename: P) -> PathBuf {
+        let file_path = resolve_out_path(filename.as_ref());
+        let source = self.render();
+        let content = run_rustfmt("rustfmt", &source).unwrap_or(source);
         fs::write(&file_path, content).unwrap_or_else(|e| {
             panic!("Failed to write file {}: {}", file_path.display(), e);
         });
+        file_path
+    }
 
+    /// Writes the collected Rust items wrapped in `pub mod <name> { ... }` to
+    /// a file and returns the absolute path.
+    ///
+    /// `include!`ing the plain output dumps every generated item into the
+    /// including module, where it can clash with the crate's own names; the
+    /// wrapped output keeps them in their own module. The prologue (inner
+    /// attributes included) goes inside the module too, so its `use`s and
+    /// assertions see the generated items. `#[no_mangle]` functions keep
+    /// their exported symbols, which don't depend on the module path.
+    ///
+    /// `file` is resolved like the [`write`](Self::write) path.
+    ///
+    /// # Panics
+    ///
+    /// - If `name` is not a valid identifier
+    /// - If the `OUT_DIR` environment variable is not set (when using relative paths)
+    /// - If the file cannot be written
+    pub fn write_as_module<P: AsRef<Path>>(&self, name: &str, file: P) -> PathBuf {
+        let ident: syn::Ident = syn::parse_str(name).unwrap_or_else(|e| {
+            panic!("Destination::write_as_module: `{name}` is not a module name: {e}");
+        });
+        let file_path = resolve_out_path(file.as_ref());
+        fs::write(&file_path, self.render_module(&ident)).unwrap_or_else(|e| {
+            panic!("Failed to write file {}: {}", file_path.display(), e);
+        });
         file_path
     }
+
+    /// [`render`](Self::render) inside `pub mod <name> { ... }`.
+    fn render_module(&self, name: &syn::Ident) -> String {
+        if self.source_comments || self.unsafe_comments {
+            // The comments are spliced into the text, so the module is too.
+            return format!(
+                "{}pub mod {name} {{\n{}}}\n",
+                self.render_banner(),
+                self.render_body()
+            );
+        }
+        let attrs = self.prologue.attrs.iter().chain(&self.file.attrs);
+        let items = self
+            .prologue
+            .items
+            .iter()
+            .cloned()
+            .chain(self.items_with_allows());
+        let module: syn::Item = syn::parse_quote! {
+            pub mod #name {
+                #(#attrs)*
+                #(#items)*
+            }
+        };
+        let mut out = self.render_banner();
+        out.push_str(&prettyplease::unparse(&syn::File {
+            items: vec![module],
+            ..empty_file()
+        }));
+        out
+    }
+
+    /// Writes one file per group into `dir` and returns the written paths.
+    ///
+    /// Each group `name` goes to `{dir}/{name}.rs`, in group-name order, and
+    /// keeps the relative order of its items. Items collected without a
+    /// group (e.g. generated code) are written to `default.rs`, the group
+    /// `#[prebindgen]` uses when none is given.
+    ///
+    /// `dir` is resolved like the [`write`](Self::write) path and must exist.
+    ///
+    /// # Panics
+    ///
+    /// - If the `OUT_DIR` environment variable is not set (when `dir` is relative)
+    /// - If a file cannot be written
+    pub fn write_per_group<P: AsRef<Path>>(&self, dir: P) -> Vec<PathBuf> {
+        let dir = resolve_out_path(dir.as_ref());
+        let mut by_group: BTreeMap<&str, Vec<(syn::Item, Option<SourceLocation>)>> =
+            BTreeMap::new();
+        for (item, location) in self.file.items.iter().zip(&self.locations) {
+            let group = location.as_ref().and_then(|l| l.group.as_deref());
+            by_group
+                .entry(group.unwrap_or(crate::DEFAULT_GROUP_NAME))
+                .or_default()
+                .push((item.clone(), location.clone()));
+        }
+        by_group
+            .into_iter()
+            .map(|(group, entries)| {
+                let mut destination = Destination {
+                    file: syn::File {
+                        items: vec![],
+                        ..self.file.clone()
+                    },
+                    prologue: self.prologue.clone(),
+                    source_comments: self.source_comments,
+                    unsafe_comments: self.unsafe_comments,
+                    allowed_lints: self.allowed_lints.clone(),
+                    banner: self.banner.clone(),
+                    ..Destination::empty()
+                };
+                for (item, location) in entries {
+                    destination.push(item, location);
+                }
+                destination.write(dir.join(format!("{group}.rs")))
+            })
+            .collect()
+    }
+}
+
+/// The unparsed `item` with a `// SAFETY:` line before each `unsafe`
+/// block. Each block is tagged with a marker statement before unparsing, so
+/// a note lands on the block it was written for; the marker line is then
+/// replaced by the note above the line opening the block.
+fn comment_unsafe_blocks(item: &syn::Item, location: Option<&SourceLocation>) -> String {
+    use syn::visit_mut::VisitMut;
+    #[derive(Default)]
+    struct UnsafeBlocks(Vec<String>);
+    impl VisitMut for UnsafeBlocks {
+        fn visit_expr_unsafe_mut(&mut self, block: &mut syn::ExprUnsafe) {
+            syn::visit_mut::visit_expr_unsafe_mut(self, block);
+            let mut transmutes = Transmutes::default();
+            transmutes.visit_expr_unsafe_mut(block);
+            let macro_name = syn::Ident::new(UNSAFE_MARKER, proc_macro2::Span::call_site());
+            let marker = proc_macro2::Literal::usize_unsuffixed(self.0.len());
+            self.0.push(match transmutes.0.first() {
+                Some((from, to)) => format!("reinterprets `{from}` as `{to}`"),
+                None => "no generated justification".to_string(),
+            });
+            block
+                .block
+                .stmts
+                .insert(0, syn::parse_quote!(#macro_name!(#marker);));
+        }
+    }
+    #[derive(Default)]
+    struct Transmutes(Vec<(String, String)>);
+    impl VisitMut for Transmutes {
+        fn visit_expr_path_mut(&mut self, path: &mut syn::ExprPath) {
+            let Some(last) = path.path.segments.last() else {
+                return;
+            };
+            if last.ident != "transmute" {
+                return;
+            }
+            let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+                return;
+            };
+            if let [syn::GenericArgument::Type(from), syn::GenericArgument::Type(to)] =
+                Vec::from_iter(&args.args)[..]
+            {
+                self.0.push((type_text(from), type_text(to)));
+            }
+        }
+    }
+
+    let mut item = item.clone();
+    let mut blocks = UnsafeBlocks::default();
+    blocks.visit_item_mut(&mut item);
+    let text = prettyplease::unparse(&syn::File {
+        items: vec![item],
+        ..empty_file()
+    });
+    if blocks.0.is_empty() {
+        return text;
+    }
+    let source = location.map(|l| format!(", for the item at {l}"));
+    let marker = format!("{UNSAFE_MARKER}!(");
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let Some(note) = line
+            .trim()
+            .strip_prefix(&marker)
+            .and_then(|rest| rest.strip_suffix(");"))
+            .and_then(|index| index.parse::<usize>().ok())
+            .and_then(|index| blocks.0.get(index))
+        else {
+            lines.push(line.to_string());
+            continue;
+        };
+        // The marker is the block's first statement: the line before it
+        // opens the block.
+        let at = lines.len().saturating_sub(1);
+        let opener = lines.get(at).map(String::as_str).unwrap_or("");
+        let indent = &opener[..opener.len() - opener.trim_start().len()];
+        let comment = format!(
+            "{indent}// SAFETY: {note}{}",
+            source.as_deref().unwrap_or("")
+        );
+        lines.insert(at, comment);
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// The macro name of the statements tagging `unsafe` blocks while they're
+/// unparsed.
+const UNSAFE_MARKER: &str = "__prebindgen_unsafe_block";
+
+/// `ty` as prettyplease prints it, on one line.
+pub(crate) fn type_text(ty: &syn::Type) -> String {
+    let text = prettyplease::unparse(&syn::File {
+        items: vec![syn::parse_quote!(type T = #ty;)],
+        ..empty_file()
+    });
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    text.strip_prefix("type T = ")
+        .and_then(|text| text.strip_suffix(';'))
+        .unwrap_or(&text)
+        .to_string()
+}
+
+fn empty_file() -> syn::File {
+    syn::File {
+        shebang: None,
+        attrs: vec![],
+        items: vec![],
+    }
+}
+
+/// The ABI-relevant parts of `item` for [`Destination::abi_digest`], with
+/// docs removed: the body-less signature of an `extern` fn, the signatures of
+/// an `extern` block's fns, or a `#[repr]` type definition.
+fn abi_items(item: &syn::Item) -> Vec<syn::Item> {
+    use syn::visit_mut::VisitMut;
+    struct StripDocs;
+    impl VisitMut for StripDocs {
+        fn visit_field_mut(&mut self, f: &mut syn::Field) {
+            f.attrs.retain(|a| !a.path().is_ident("doc"));
+        }
+        fn visit_variant_mut(&mut self, v: &mut syn::Variant) {
+            v.attrs.retain(|a| !a.path().is_ident("doc"));
+            syn::visit_mut::visit_variant_mut(self, v);
+        }
+    }
+    let has_repr = |attrs: &[syn::Attribute]| attrs.iter().any(|a| a.path().is_ident("repr"));
+    let mut items: Vec<syn::Item> = match item {
+        syn::Item::Fn(f) if f.sig.abi.is_some() => {
+            let mut f = f.clone();
+            f.block = Box::new(syn::parse_quote!({}));
+            vec![syn::Item::Fn(f)]
+        }
+        syn::Item::ForeignMod(m) => m
+            .items
+            .iter()
+            .filter_map(|i| match i {
+                syn::ForeignItem::Fn(f) => {
+                    let mut sig = f.sig.clone();
+                    sig.abi = Some(m.abi.clone());
+                    let attrs = &f.attrs;
+                    Some(syn::parse_quote!(#(#attrs)* #sig {}))
+                }
+                _ => None,
+            })
+            .collect(),
+        syn::Item::Struct(s) if has_repr(&s.attrs) => vec![item.clone()],
+        syn::Item::Enum(e) if has_repr(&e.attrs) => vec![item.clone()],
+        syn::Item::Union(u) if has_repr(&u.attrs) => vec![item.clone()],
+        _ => vec![],
+    };
+    for item in &mut items {
+        if let Some(attrs) = item_attrs_mut(item) {
+            attrs.retain(|a| !a.path().is_ident("doc"));
+        }
+        StripDocs.visit_item_mut(item);
+    }
+    items
+}
+
+/// 64-bit FNV-1a of `bytes`: a simple hash whose output, unlike `std`'s
+/// `DefaultHasher`, is fixed across Rust versions.
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// The outer attributes of `item`; `None` for verbatim tokens.
+pub(crate) fn item_attrs_mut(item: &mut syn::Item) -> Option<&mut Vec<syn::Attribute>> {
+    match item {
+        syn::Item::Const(i) => Some(&mut i.attrs),
+        syn::Item::Enum(i) => Some(&mut i.attrs),
+        syn::Item::ExternCrate(i) => Some(&mut i.attrs),
+        syn::Item::Fn(i) => Some(&mut i.attrs),
+        syn::Item::ForeignMod(i) => Some(&mut i.attrs),
+        syn::Item::Impl(i) => Some(&mut i.attrs),
+        syn::Item::Macro(i) => Some(&mut i.attrs),
+        syn::Item::Mod(i) => Some(&mut i.attrs),
+        syn::Item::Static(i) => Some(&mut i.attrs),
+        syn::Item::Struct(i) => Some(&mut i.attrs),
+        syn::Item::Trait(i) => Some(&mut i.attrs),
+        syn::Item::TraitAlias(i) => Some(&mut i.attrs),
+        syn::Item::Type(i) => Some(&mut i.attrs),
+        syn::Item::Union(i) => Some(&mut i.attrs),
+        syn::Item::Use(i) => Some(&mut i.attrs),
+        _ => None,
+    }
+}
+
+/// The `#[cfg]` attributes among `attrs`: put on everything generated from
+/// a cfg-gated source item (a variant, a field, a const) that names it, so
+/// the generated code compiles on the targets where the item is absent.
+pub(crate) fn cfg_attrs(attrs: &[syn::Attribute]) -> Vec<&syn::Attribute> {
+    attrs.iter().filter(|a| a.path().is_ident("cfg")).collect()
+}
+
+/// Format `source` with the `rustfmt` binary `program`. Returns `None` when
+/// it can't be run or fails; failures (but not absence) are reported as
+/// build warnings.
+fn run_rustfmt(program: &str, source: &str) -> Option<String> {
+    let mut command = Command::new(program);
+    command
+        .args([
+            "--emit",
+            "stdout",
+            "--edition",
+            RustEdition::detected().as_str(),
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Ok(dir) = env::var("CARGO_MANIFEST_DIR") {
+        command.current_dir(dir);
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            println!("cargo:warning=prebindgen: failed to run {program}: {e}");
+            return None;
+        }
+    };
+    // Feed stdin from a separate thread: a large file would otherwise fill
+    // the stdout pipe while we are still writing.
+    let mut stdin = child.stdin.take().expect("rustfmt stdin is piped");
+    let input = source.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output();
+    let written = writer.join().expect("rustfmt stdin writer panicked");
+    match (output, written) {
+        (Ok(output), Ok(())) if output.status.success() => String::from_utf8(output.stdout).ok(),
+        (Ok(output), _) => {
+            println!(
+                "cargo:warning=prebindgen: {program} failed ({}), writing unformatted output",
+                output.status
+            );
+            for line in String::from_utf8_lossy(&output.stderr).lines() {
+                println!("cargo:warning={line}");
+            }
+            None
+        }
+        (Err(e), _) => {
+            println!("cargo:warning=prebindgen: failed to run {program}: {e}");
+            None
+        }
+    }
+}
+
+/// Resolve a relative path against `OUT_DIR`; absolute paths pass through.
+fn resolve_out_path(path: &Path) -> PathBuf {
+    if path.is_relative() {
+        let out_dir = env::var("OUT_DIR").expect("OUT_DIR environment variable not set");
+        PathBuf::from(out_dir).join(path)
+    } else {
+        path.to_path_buf()
+    }
 }
 
 impl std::fmt::Display for Destination {
+    /// Renders the same source text [`Destination::write`] puts in the file.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", prettyplease::unparse(&self.file))
+        write!(f, "{}", self.render())
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,549 @@
+use super::*;
+use crate::api::test_util::unique_test_dir;
+
+fn in_group(item: syn::Item, group: &str) -> (syn::Item, SourceLocation) {
+    let location = SourceLocation {
+        group: Some(group.to_string()),
+        ..SourceLocation::default()
+    };
+    (item, location)
+}
+
+#[test]
+fn write_per_group_splits_items_by_group() {
+    let dir = unique_test_dir("destination_groups");
+    fs::create_dir_all(&dir).unwrap();
+
+    let destination: Destination = vec![
+        in_group(
+            syn::parse_quote! { pub struct Point { pub x: i32 } },
+            "structs",
+        ),
+        in_group(
+            syn::parse_quote! { pub fn origin() -> i32 { 0 } },
+            "functions",
+        ),
+        in_group(
+            syn::parse_quote! { pub struct Size { pub w: i32 } },
+            "structs",
+        ),
+    ]
+    .into_iter()
+    .collect();
+    let paths = destination.write_per_group(&dir);
+
+    assert_eq!(
+        paths,
+        vec![dir.join("functions.rs"), dir.join("structs.rs")]
+    );
+    let functions = fs::read_to_string(&paths[0]).unwrap();
+    let structs = fs::read_to_string(&paths[1]).unwrap();
+    assert!(functions.contains("fn origin"));
+    assert!(!functions.contains("struct"));
+    assert!(structs.contains("struct Point") && structs.contains("struct Size"));
+    assert!(!structs.contains("fn origin"));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn write_per_group_puts_ungrouped_items_in_default() {
+    let dir = unique_test_dir("destination_default_group");
+    fs::create_dir_all(&dir).unwrap();
+
+    let items: Vec<syn::Item> = vec![syn::parse_quote! { pub fn generated() {} }];
+    let destination: Destination = items.into_iter().collect();
+    let paths = destination.write_per_group(&dir);
+
+    assert_eq!(paths, vec![dir.join("default.rs")]);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn write_to_matches_written_file() {
+    let dir = unique_test_dir("destination_write_to");
+    fs::create_dir_all(&dir).unwrap();
+    let items = || -> Vec<syn::Item> {
+        vec![
+            syn::parse_quote! { pub struct Point { pub x: i32 } },
+            syn::parse_quote! { pub fn origin() -> i32 { 0 } },
+        ]
+    };
+
+    let mut buffer = Vec::new();
+    let destination: Destination = items().into_iter().collect();
+    destination.write_to(&mut buffer).unwrap();
+    let rendered = String::from_utf8(buffer).unwrap();
+    assert_eq!(rendered, destination.to_string());
+
+    let path = items()
+        .into_iter()
+        .collect::<Destination>()
+        .write(dir.join("bindings.rs"));
+    assert_eq!(fs::read_to_string(path).unwrap(), rendered);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn prologue_is_emitted_first() {
+    let items: Vec<syn::Item> = vec![
+        syn::parse_quote! { const _: () = assert!(::core::mem::size_of::<u32>() == 4); },
+        syn::parse_quote! { pub fn origin() -> i32 { 0 } },
+    ];
+    let destination = items
+        .into_iter()
+        .collect::<Destination>()
+        .with_prologue(quote::quote! {
+            #![allow(non_camel_case_types)]
+            use core::ffi::c_int;
+        });
+
+    let rendered = destination.to_string();
+    assert!(
+        rendered.starts_with("#![allow(non_camel_case_types)]\nuse core::ffi::c_int;\n"),
+        "{rendered}"
+    );
+    assert!(rendered.find("use core::ffi::c_int").unwrap() < rendered.find("const _").unwrap());
+}
+
+#[test]
+fn generated_banner_is_the_first_line() {
+    crate::Source::init_doctest_simulate();
+    let source = crate::Source::new("source_ffi");
+    let point: syn::Item = syn::parse_quote! { pub struct Point { pub x: i32 } };
+    let destination = std::iter::once(point)
+        .collect::<Destination>()
+        .with_prologue(quote::quote! { use core::ffi::c_int; })
+        .with_generated_banner(&source);
+    let out = destination.to_string();
+    assert_eq!(
+        out.lines().next(),
+        Some("// @generated by prebindgen from source_ffi 0.1.0")
+    );
+    assert!(
+        out.contains("use core::ffi::c_int;\npub struct Point"),
+        "{out}"
+    );
+
+    let module = destination
+        .with_banner("Generated file.\n\nDo not edit.")
+        .render_module(&syn::parse_quote!(ffi));
+    assert!(
+        module.starts_with("// Generated file.\n//\n// Do not edit.\npub mod ffi {"),
+        "{module}"
+    );
+}
+
+#[test]
+fn write_formatted_runs_rustfmt() {
+    let dir = unique_test_dir("destination_rustfmt");
+    fs::create_dir_all(&dir).unwrap();
+    let items: Vec<syn::Item> = vec![syn::parse_quote! { pub fn origin() -> i32 { 0 } }];
+    let destination: Destination = items.into_iter().collect();
+
+    let path = destination.write_formatted(dir.join("bindings.rs"));
+    let written = fs::read_to_string(path).unwrap();
+    // Whatever formatter ran, the output is the same item.
+    assert!(written.contains("pub fn origin() -> i32 {"), "{written}");
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn missing_rustfmt_falls_back() {
+    assert_eq!(run_rustfmt("prebindgen-no-such-rustfmt", "fn f() {}"), None);
+}
+
+#[test]
+fn failing_rustfmt_falls_back() {
+    // Not valid Rust: rustfmt reports an error instead of formatting.
+    assert_eq!(run_rustfmt("rustfmt", "fn {"), None);
+}
+
+#[test]
+fn source_comments_precede_located_items() {
+    let location = SourceLocation {
+        file: "src/lib.rs".to_string(),
+        line: 12,
+        column: 1,
+        ..SourceLocation::default()
+    };
+    let items = || {
+        vec![
+            (
+                syn::parse_quote! { pub struct Point { pub x: i32 } },
+                location.clone(),
+            ),
+            (
+                syn::parse_quote! { pub fn origin() -> i32 { 0 } },
+                SourceLocation {
+                    line: 20,
+                    ..location.clone()
+                },
+            ),
+            (
+                syn::parse_quote! { pub fn generated() {} },
+                SourceLocation::default(),
+            ),
+        ]
+    };
+
+    let plain: Destination = items().into_iter().collect();
+    assert!(!plain.to_string().contains("//"));
+
+    let commented = items()
+        .into_iter()
+        .collect::<Destination>()
+        .with_prologue(quote::quote! { use core::ffi::c_int; })
+        .with_source_comments(true)
+        .to_string();
+    let lines: Vec<&str> = commented.lines().collect();
+    assert_eq!(lines[0], "use core::ffi::c_int;");
+    assert_eq!(lines[1], "// src/lib.rs:12:1");
+    assert!(lines[2].starts_with("pub struct Point"));
+    let origin = lines.iter().position(|l| l.contains("fn origin")).unwrap();
+    assert_eq!(lines[origin - 1], "// src/lib.rs:20:1");
+    let generated = lines
+        .iter()
+        .position(|l| l.contains("fn generated"))
+        .unwrap();
+    assert!(!lines[generated - 1].starts_with("//"));
+    assert!(!commented.contains("// :0:0"));
+    assert!(syn::parse_file(&commented).is_ok());
+}
+
+#[test]
+fn unsafe_comments_precede_unsafe_blocks() {
+    let location = SourceLocation {
+        file: "src/lib.rs".to_string(),
+        line: 7,
+        column: 1,
+        ..SourceLocation::default()
+    };
+    let to_bits: syn::Item = syn::parse_quote! {
+        pub fn to_bits(v: f32) -> u32 {
+            unsafe { ::core::mem::transmute::<f32, u32>(v) }
+        }
+    };
+    let mut destination = std::iter::once((to_bits, location))
+        .collect::<Destination>()
+        .with_unsafe_comments(true);
+    // Generated code, collected without a location.
+    let read: syn::Item = syn::parse_quote! {
+        pub fn read(p: *const u8) -> u8 {
+            unsafe { *p }
+        }
+    };
+    destination.extend([read]);
+    let out = destination.to_string();
+    assert!(
+        out.contains(
+            "    // SAFETY: reinterprets `f32` as `u32`, for the item at src/lib.rs:7:1\n    \
+             unsafe {\n        ::core::mem::transmute::<f32, u32>(v)\n    }"
+        ),
+        "{out}"
+    );
+    assert!(
+        out.contains("    // SAFETY: no generated justification\n    unsafe {\n        *p\n    }"),
+        "{out}"
+    );
+}
+
+/// `unsafe {` text inside a macro invocation isn't a block: it gets no
+/// note and doesn't shift the notes of the real blocks.
+#[test]
+fn unsafe_comments_skip_macro_text() {
+    let item: syn::Item = syn::parse_quote! {
+        pub fn to_signed(b: u8) -> i8 {
+            let _ = stringify!(unsafe { b });
+            unsafe { ::core::mem::transmute::<u8, i8>(b) }
+        }
+    };
+    let out = std::iter::once(item)
+        .collect::<Destination>()
+        .with_unsafe_comments(true)
+        .to_string();
+    assert_eq!(out.matches("// SAFETY:").count(), 1, "{out}");
+    assert!(
+        out.contains("    // SAFETY: reinterprets `u8` as `i8`\n    unsafe {\n"),
+        "{out}"
+    );
+    assert!(syn::parse_file(&out).is_ok());
+}
+
+#[test]
+fn allowed_lints_are_added_to_every_item() {
+    let items: Vec<syn::Item> = vec![
+        syn::parse_quote! { pub struct point_t { pub x: i32 } },
+        syn::parse_quote! {
+            #[allow(dead_code)]
+            pub fn Origin() -> i32 { 0 }
+        },
+    ];
+    let rendered = items
+        .into_iter()
+        .collect::<Destination>()
+        .with_prologue(quote::quote! { use core::ffi::c_int; })
+        .with_allowed_lints(Destination::DEFAULT_ALLOWED_LINTS)
+        .with_allowed_lints(["clippy::missing_safety_doc", "non_snake_case"])
+        .to_string();
+    let file = syn::parse_file(&rendered).unwrap();
+    let allows: Vec<String> = file
+        .items
+        .iter()
+        .map(|item| match item {
+            syn::Item::Use(u) => &u.attrs,
+            syn::Item::Struct(s) => &s.attrs,
+            syn::Item::Fn(f) => &f.attrs,
+            other => panic!("unexpected item {}", quote::quote!(#other)),
+        })
+        .map(|attrs| {
+            attrs
+                .iter()
+                .map(|a| quote::quote!(#a).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    let expected =
+        "# [allow (non_camel_case_types , non_snake_case , clippy :: missing_safety_doc)]";
+    assert_eq!(allows[0], "", "prologue items are left alone");
+    assert_eq!(allows[1], expected);
+    assert_eq!(allows[2], format!("# [allow (dead_code)] {expected}"));
+}
+
+#[test]
+fn write_as_module_wraps_prologue_and_items() {
+    let dir = unique_test_dir("destination_module");
+    fs::create_dir_all(&dir).unwrap();
+
+    let items: Vec<syn::Item> = vec![
+        syn::parse_quote! { pub struct Point { pub x: i32 } },
+        syn::parse_quote! { #[no_mangle] pub extern "C" fn origin() -> i32 { 0 } },
+    ];
+    let destination: Destination =
+        items
+            .into_iter()
+            .collect::<Destination>()
+            .with_prologue(quote::quote! {
+                #![allow(non_camel_case_types)]
+                use core::ffi::c_int;
+                const _: () = assert!(::core::mem::size_of::<c_int>() == 4);
+            });
+    let path = destination.write_as_module("ffi", dir.join("ffi.rs"));
+
+    let file: syn::File = syn::parse_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(file.attrs.is_empty());
+    let [syn::Item::Mod(module)] = &file.items[..] else {
+        panic!("expected a single module item, got {:?}", file.items.len());
+    };
+    assert_eq!(module.ident, "ffi");
+    assert!(matches!(module.vis, syn::Visibility::Public(_)));
+    assert!(matches!(
+        module.attrs[..],
+        [syn::Attribute {
+            style: syn::AttrStyle::Inner(_),
+            ..
+        }]
+    ));
+    let children: Vec<String> = module
+        .content
+        .as_ref()
+        .unwrap()
+        .1
+        .iter()
+        .map(|item| match item {
+            syn::Item::Use(_) => "use".to_string(),
+            syn::Item::Const(c) => c.ident.to_string(),
+            syn::Item::Struct(s) => s.ident.to_string(),
+            syn::Item::Fn(f) => f.sig.ident.to_string(),
+            other => panic!("unexpected item {}", quote::quote!(#other)),
+        })
+        .collect();
+    assert_eq!(children, ["use", "_", "Point", "origin"]);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+#[should_panic(expected = "Destination::write_as_module: `not a name` is not a module name")]
+fn write_as_module_rejects_invalid_name() {
+    let destination: Destination = Vec::<syn::Item>::new().into_iter().collect();
+    destination.write_as_module("not a name", "unused.rs");
+}
+
+#[test]
+fn into_items_round_trips_through_a_visitor() {
+    let destination: Destination = vec![
+        in_group(
+            syn::parse_quote! { pub struct Point { pub x: i32 } },
+            "structs",
+        ),
+        in_group(
+            syn::parse_quote! { pub fn origin() -> i32 { 0 } },
+            "functions",
+        ),
+    ]
+    .into_iter()
+    .collect();
+    let groups: Vec<Option<&str>> = destination
+        .items()
+        .map(|(_, location)| location.and_then(|l| l.group.as_deref()))
+        .collect();
+    assert_eq!(groups, [Some("structs"), Some("functions")]);
+
+    let mut items = destination.into_items();
+    assert_eq!(items.len(), 2);
+    assert!(matches!(items[0].0, syn::Item::Struct(_)));
+    assert!(matches!(items[1].0, syn::Item::Fn(_)));
+    // Post-process, then collect again: the locations travel along.
+    if let syn::Item::Fn(f) = &mut items[1].0 {
+        f.sig.ident = syn::parse_quote!(renamed_origin);
+    }
+    let destination: Destination = items.into_iter().collect();
+    let out = destination.to_string();
+    assert!(out.contains("pub fn renamed_origin()"), "{out}");
+    assert_eq!(
+        destination
+            .items()
+            .filter_map(|(_, l)| l?.group.as_deref())
+            .collect::<Vec<_>>(),
+        ["structs", "functions"]
+    );
+}
+
+#[test]
+fn into_items_defaults_missing_locations() {
+    let item: syn::Item = syn::parse_quote! { pub const ANSWER: u32 = 42; };
+    let destination: Destination = vec![item].into_iter().collect();
+    assert!(destination.items().all(|(_, location)| location.is_none()));
+    let items = destination.into_items();
+    assert!(matches!(items[..], [(syn::Item::Const(_), ref l)] if *l == SourceLocation::default()));
+}
+
+#[test]
+fn abi_digest_tracks_exported_signatures_and_layouts() {
+    let digest = |items: Vec<syn::Item>| items.into_iter().collect::<Destination>().abi_digest();
+    let surface = |ret: syn::Type| -> Vec<syn::Item> {
+        vec![
+            syn::parse_quote! {
+                #[repr(C)]
+                pub struct point_t { pub x: i32, pub y: i32 }
+            },
+            syn::parse_quote! {
+                #[no_mangle]
+                pub extern "C" fn point_norm(p: point_t) -> #ret { 0 as #ret }
+            },
+            syn::parse_quote! {
+                pub(crate) fn helper() -> i32 { 1 }
+            },
+        ]
+    };
+    let base = digest(surface(syn::parse_quote!(i32)));
+    assert_eq!(base.len(), 16);
+    assert!(base.chars().all(|c| c.is_ascii_hexdigit()), "{base}");
+
+    // Identical runs, and changes outside the ABI (bodies, docs, item
+    // order, non-exported items), keep the digest.
+    assert_eq!(digest(surface(syn::parse_quote!(i32))), base);
+    let mut reordered = surface(syn::parse_quote!(i32));
+    reordered.reverse();
+    reordered[0] = syn::parse_quote! { pub(crate) fn helper() -> i32 { 2 } };
+    reordered[2] = syn::parse_quote! {
+        /// A point.
+        #[repr(C)]
+        pub struct point_t { pub x: i32, pub y: i32 }
+    };
+    assert_eq!(digest(reordered), base);
+
+    // A changed signature or layout changes it.
+    assert_ne!(digest(surface(syn::parse_quote!(i64))), base);
+    let mut relaid = surface(syn::parse_quote!(i32));
+    relaid[0] = syn::parse_quote! {
+        #[repr(C)]
+        pub struct point_t { pub y: i32, pub x: i32 }
+    };
+    assert_ne!(digest(relaid), base);
+}
+
+#[test]
+fn append_merges_items_and_deduplicates_assertions() {
+    let size_assert: syn::Item =
+        syn::parse_quote! { const _: () = assert!(::core::mem::size_of::<u32>() == 4); };
+    let mut structs: Destination = vec![
+        in_group(
+            syn::parse_quote! { pub struct Point { pub x: i32 } },
+            "structs",
+        ),
+        in_group(size_assert.clone(), "structs"),
+    ]
+    .into_iter()
+    .collect::<Destination>()
+    .with_prologue(quote::quote! { use core::ffi::c_int; });
+    let functions = vec![
+        in_group(size_assert.clone(), "functions"),
+        in_group(
+            syn::parse_quote! { pub fn origin() -> i32 { 0 } },
+            "functions",
+        ),
+    ]
+    .into_iter()
+    .collect::<Destination>()
+    .with_allowed_lints(["non_snake_case"]);
+
+    structs.append(functions);
+    structs.extend([syn::parse_quote! { pub fn extra() {} }] as [syn::Item; 1]);
+
+    let merged: Vec<(String, Option<&str>)> = structs
+        .items()
+        .map(|(item, location)| {
+            let name = match item {
+                syn::Item::Struct(s) => s.ident.to_string(),
+                syn::Item::Fn(f) => f.sig.ident.to_string(),
+                syn::Item::Const(c) => c.ident.to_string(),
+                _ => unreachable!(),
+            };
+            (name, location.and_then(|l| l.group.as_deref()))
+        })
+        .collect();
+    assert_eq!(
+        merged,
+        [
+            ("Point".to_string(), Some("structs")),
+            ("_".to_string(), Some("structs")),
+            ("origin".to_string(), Some("functions")),
+            ("extra".to_string(), None),
+        ]
+    );
+    let rendered = structs.to_string();
+    assert!(rendered.starts_with("use core::ffi::c_int;"), "{rendered}");
+    assert!(
+        rendered.contains("#[allow(non_snake_case)]\npub fn origin"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn collecting_deduplicates_assertions() {
+    let size_assert: syn::Item =
+        syn::parse_quote! { const _: () = assert!(::core::mem::size_of::<u32>() == 4); };
+    let align_assert: syn::Item =
+        syn::parse_quote! { const _: () = assert!(::core::mem::align_of::<u32>() == 4); };
+    let destination: Destination = [
+        size_assert.clone(),
+        align_assert.clone(),
+        size_assert.clone(),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(destination.items().count(), 2);
+
+    let destination: Destination = [
+        in_group(size_assert.clone(), "structs"),
+        in_group(size_assert, "functions"),
+    ]
+    .into_iter()
+    .collect();
+    let groups: Vec<Option<&str>> = destination
+        .items()
+        .map(|(_, location)| location.and_then(|l| l.group.as_deref()))
+        .collect();
+    assert_eq!(groups, [Some("structs")]);
+}
@@ -36,5 +36,8 @@ pub use self::{
     gravestone::{Gravestone, Transmute},
     niches::{NicheSlot, Niches},
     prebindgen::{const_path_alias, ConverterImpl, Prebindgen, Stage},
-    registry::{Direction, Generation, Registry, ScanError, TypeEntry, TypeKey, WriteRustError},
+    registry::{
+        Direction, Generation, GenerationSummary, Registry, ScanError, SkippedItem, TypeEntry,
+        TypeKey, WriteRustError,
+    },
 };
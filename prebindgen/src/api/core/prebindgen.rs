@@ -21,9 +21,12 @@ use std::collections::HashSet;
 
 use proc_macro2::TokenStream;
 
-use crate::api::core::{
-    niches::Niches,
-    registry::{Direction, Registry, TypeKey},
+use crate::api::{
+    core::{
+        niches::Niches,
+        registry::{Direction, Registry, TypeKey},
+    },
+    utils::edition::RustEdition,
 };
 
 /// A shared predicate over an item name, as used by
@@ -133,6 +136,40 @@ pub fn const_path_alias(c: &syn::ItemConst, source_module: &syn::Path) -> TokenS
     }
 }
 
+/// Re-emit a captured `#[prebindgen]` static as an **exported** static: same
+/// attributes (minus any symbol-naming ones), visibility, name and type, under
+/// `#[no_mangle]` — spelled `#[unsafe(no_mangle)]` when the consuming crate is on
+/// the 2024 edition (see [`RustEdition::from_cargo_env`]). With a
+/// source module the initializer is replaced by `<source_module>::<ident>` (a
+/// by-value read, so the static's type must be `Copy`), as
+/// [`const_path_alias`] does for consts; without one it is copied verbatim.
+/// Used by the default [`Prebindgen::on_static`].
+pub fn static_export(s: &syn::ItemStatic, source_module: Option<&syn::Path>) -> TokenStream {
+    let attrs = s.attrs.iter().filter(|a| {
+        let path = match &a.meta {
+            syn::Meta::List(list) if list.path.is_ident("unsafe") => list
+                .parse_args::<syn::Meta>()
+                .map(|m| m.path().clone())
+                .unwrap_or_else(|_| list.path.clone()),
+            meta => meta.path().clone(),
+        };
+        !(path.is_ident("no_mangle") || path.is_ident("export_name"))
+    });
+    let no_mangle = RustEdition::detected().no_mangle_attr();
+    let vis = &s.vis;
+    let ident = &s.ident;
+    let ty = &s.ty;
+    let init: TokenStream = match source_module {
+        Some(m) => quote::quote!(#m::#ident),
+        None => quote::ToTokens::to_token_stream(&s.expr),
+    };
+    quote::quote! {
+        #(#attrs)*
+        #no_mangle
+        #vis static #ident: #ty = #init;
+    }
+}
+
 /// The single extension point of the pipeline: implement this trait once per
 /// **destination language** (C/cbindgen, JNI/Kotlin, Swift, Python, …) to teach
 /// the language-agnostic [`Registry`] how that language represents Rust types
@@ -438,6 +475,18 @@ pub trait Prebindgen {
         Ok(())
     }
 
+    /// Drop a declared function whose signature reaches a type that cannot
+    /// be resolved, instead of failing the whole resolve. `Registry::resolve`
+    /// then reports each dropped function as a `cargo:warning` with its
+    /// source location and resolves the rest as if it had never been
+    /// captured — so emitters must look functions up in the registry rather
+    /// than trust [`Self::declared_functions`] alone.
+    ///
+    /// Default: `false` (an unresolved type fails the resolve).
+    fn skip_invalid_items(&self) -> bool {
+        false
+    }
+
     /// Post-**resolve** validation boundary — the counterpart of
     /// [`Self::validate`] that sees the fully resolved registry (converters,
     /// plans, metadata). Every artifact writer calls it before writing
@@ -490,6 +539,13 @@ pub trait Prebindgen {
         }
     }
 
+    /// Per-static emission. Default: export the static under its own symbol
+    /// (see [`static_export`]), aliasing the source item when
+    /// [`Self::source_module`] is available.
+    fn on_static(&self, s: &syn::ItemStatic, _registry: &Registry<Self::Metadata>) -> TokenStream {
+        static_export(s, self.source_module())
+    }
+
     // ── Structural type resolution (the converter-resolution surface) ──
 
     /// Resolve the **input** (wire → rust) converter for `ty`. The adapter
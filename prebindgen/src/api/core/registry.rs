@@ -1,10 +1,11 @@
 //! Single owner of everything parsed from the prebindgen source stream.
 //!
 //! [`Registry`] holds:
-//! * Item maps (`functions`, `structs`, `enums`, `consts`) indexed by ident.
+//! * Item maps (`functions`, `structs`, `enums`, `consts`, `statics`) indexed
+//!   by ident.
 //!   Duplicate names across kinds OR within a kind are an error — prebindgen
 //!   items live in one flat namespace.
-//! * `passthrough` — items that aren't function/struct/enum/const (use, mod,
+//! * `passthrough` — items that aren't function/struct/enum/const/static (use, mod,
 //!   type alias, macro_rules) emitted verbatim.
 //! * `input_types` / `output_types` — direction-specific type tables. Each
 //!   scanned type maps to either a resolved [`TypeEntry`] or an unresolved cell
@@ -13,19 +14,23 @@
 //!   plans before type resolution, then consumed at wrapper-emission sites.
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     fmt,
+    sync::OnceLock,
 };
 
 use quote::ToTokens;
 
 use crate::{
-    api::core::{
-        niches::Niches,
-        prebindgen::{Prebindgen, Stage},
-        types_util::bare_path_ident,
+    api::{
+        collect::destination::{cfg_attrs, item_attrs_mut},
+        core::{
+            niches::Niches,
+            prebindgen::{Prebindgen, Stage},
+            types_util::bare_path_ident,
+        },
     },
-    SourceLocation,
+    RecordKind, SourceLocation,
 };
 
 /// Canonical type-shape key: identity is the token string of the
@@ -233,10 +238,11 @@ pub struct Registry<M = ()> {
     pub structs: HashMap<syn::Ident, (syn::ItemStruct, SourceLocation)>,
     pub enums: HashMap<syn::Ident, (syn::ItemEnum, SourceLocation)>,
     pub consts: HashMap<syn::Ident, (syn::ItemConst, SourceLocation)>,
+    pub statics: HashMap<syn::Ident, (syn::ItemStatic, SourceLocation)>,
     /// Anything else (use, mod, type alias, macro_rules) — passed through.
     pub passthrough: Vec<(syn::Item, SourceLocation)>,
 
-    /// Origin crate name of each named item (fn/struct/enum/const),
+    /// Origin crate name of each named item (fn/struct/enum/const/static),
     /// recorded by [`Self::from_items`] from each item's
     /// [`SourceLocation::crate_name`] stamp (absent for hand-built,
     /// origin-less item streams). Adapters
@@ -246,6 +252,18 @@ pub struct Registry<M = ()> {
     /// crates on top of the flat crate.
     pub(crate) item_origins: HashMap<syn::Ident, String>,
 
+    /// Module path inside the origin crate of each named item whose
+    /// [`SourceLocation::module_path`] was kept (submodule items only),
+    /// appended by [`Self::origin_module`] so a type defined in `mod foo`
+    /// is referenced as `my_crate::foo::Type` without further configuration.
+    pub(crate) item_modules: HashMap<syn::Ident, String>,
+
+    /// Name in its origin crate of each named item the stream carries under
+    /// another name ([`SourceLocation::source_name`]), keyed by the stream
+    /// name; generated references are pointed back at it by
+    /// [`Self::restore_source_names`].
+    pub(crate) source_names: HashMap<syn::Ident, syn::Ident>,
+
     /// Module name of every ingested source, in first-seen stream order
     /// (crate names, dashes normalized to underscores). The FIRST entry
     /// doubles as the **default module** for references with no recorded
@@ -315,8 +333,11 @@ impl<M> Default for Registry<M> {
             structs: HashMap::new(),
             enums: HashMap::new(),
             consts: HashMap::new(),
+            statics: HashMap::new(),
             passthrough: Vec::new(),
             item_origins: HashMap::new(),
+            item_modules: HashMap::new(),
+            source_names: HashMap::new(),
             source_modules: Vec::new(),
             input_types: Default::default(),
             output_types: Default::default(),
@@ -332,6 +353,55 @@ impl<M> Default for Registry<M> {
     }
 }
 
+/// The items of a `#[prebindgen]` inline module, each located in the
+/// module (its `module_path` extended with the module name, so
+/// [`Registry::origin_module`] qualifies it as `my_crate::foo::Item`) and
+/// carrying the module's `#[cfg]`s; any other item is returned as is.
+/// Modules nest, so their items join the one flat namespace. An item that
+/// isn't `pub` can't be reached through the module path and is an error.
+fn expand_inline_module(
+    (item, loc): (syn::Item, SourceLocation),
+) -> Result<Vec<(syn::Item, SourceLocation)>, ScanError> {
+    let syn::Item::Mod(module) = item else {
+        return Ok(vec![(item, loc)]);
+    };
+    let Some((_, children)) = module.content else {
+        return Ok(Vec::new());
+    };
+    let cfgs: Vec<syn::Attribute> = cfg_attrs(&module.attrs).into_iter().cloned().collect();
+    let mut child_loc = loc;
+    child_loc.module_path = Some(match child_loc.module_path.take() {
+        Some(parent) => format!("{parent}::{}", module.ident),
+        None => module.ident.to_string(),
+    });
+    let mut expanded = Vec::new();
+    for mut child in children {
+        let (name, vis) = match &child {
+            syn::Item::Struct(i) => (&i.ident, &i.vis),
+            syn::Item::Enum(i) => (&i.ident, &i.vis),
+            syn::Item::Union(i) => (&i.ident, &i.vis),
+            syn::Item::Fn(i) => (&i.sig.ident, &i.vis),
+            syn::Item::Type(i) => (&i.ident, &i.vis),
+            syn::Item::Const(i) => (&i.ident, &i.vis),
+            syn::Item::Static(i) => (&i.ident, &i.vis),
+            syn::Item::Mod(i) => (&i.ident, &i.vis),
+            _ => continue,
+        };
+        if !matches!(vis, syn::Visibility::Public(_)) && name != "_" {
+            return Err(ScanError::PrivateModuleItem {
+                name: name.clone(),
+                module: child_loc.module_path.clone().unwrap_or_default(),
+                loc: Box::new(child_loc),
+            });
+        }
+        if let Some(attrs) = item_attrs_mut(&mut child) {
+            attrs.splice(0..0, cfgs.iter().cloned());
+        }
+        expanded.extend(expand_inline_module((child, child_loc.clone()))?);
+    }
+    Ok(expanded)
+}
+
 /// Payload of [`ScanError::DuplicateName`], boxed to keep the error enum
 /// small (`clippy::result_large_err`).
 #[derive(Debug)]
@@ -359,13 +429,36 @@ pub enum ScanError {
     },
     DisallowedImplTrait {
         ty: String,
-        loc: SourceLocation,
+        loc: Box<SourceLocation>,
     },
     UnsupportedReceiver {
-        loc: SourceLocation,
+        loc: Box<SourceLocation>,
     },
     UnsupportedParamPattern {
-        loc: SourceLocation,
+        loc: Box<SourceLocation>,
+    },
+    /// A declared function takes C variadics (`...`): its wrapper would have
+    /// to forward a `va_list`, which can't be expressed in stable Rust.
+    VariadicFunction {
+        name: syn::Ident,
+        loc: Box<SourceLocation>,
+    },
+    /// A declared function has type or const generic parameters, or `where`
+    /// bounds on them: it has no single signature to export. Lifetime
+    /// parameters are fine.
+    GenericFunction {
+        name: syn::Ident,
+        /// The offending parameters / predicates as written
+        generics: String,
+        loc: Box<SourceLocation>,
+    },
+    /// An item of a `#[prebindgen]` inline module isn't `pub`: the
+    /// generated code couldn't reach it through the module path.
+    PrivateModuleItem {
+        name: syn::Ident,
+        /// The module path of the item, without the crate name
+        module: String,
+        loc: Box<SourceLocation>,
     },
     /// An adapter-invariant check failed — see [`Prebindgen::validate`].
     /// The message is adapter-authored and printed verbatim.
@@ -433,6 +526,27 @@ impl fmt::Display for ScanError {
             ScanError::UnsupportedParamPattern { loc } => {
                 write!(f, "non-ident parameter pattern is not supported at {}", loc)
             }
+            ScanError::VariadicFunction { name, loc } => write!(
+                f,
+                "variadic functions cannot be proxied: `{}` at {}",
+                name, loc
+            ),
+            ScanError::GenericFunction {
+                name,
+                generics,
+                loc,
+            } => write!(
+                f,
+                "generic functions cannot be exported: `{}` has `{}` at {} — only lifetime \
+                 parameters are allowed; export a non-generic wrapper per instantiation instead",
+                name, generics, loc
+            ),
+            ScanError::PrivateModuleItem { name, module, loc } => write!(
+                f,
+                "item `{}` of #[prebindgen] module `{}` at {} is not `pub`; the generated \
+                 code can only reach public module items",
+                name, module, loc
+            ),
             ScanError::AdapterInvariant { message } => write!(f, "{}", message),
             ScanError::DeclaredNotFound { entries } => {
                 writeln!(
@@ -636,7 +750,11 @@ impl<M> Registry<M> {
         // Pass 1: collect and gather EVERY source module name first, so
         // cross-source type references (`source_a::TypeA` in a later-chained
         // source's signature) normalize order-independently in pass 2.
-        let items: Vec<(syn::Item, SourceLocation)> = items.into_iter().collect();
+        let mut expanded: Vec<(syn::Item, SourceLocation)> = Vec::new();
+        for item in items {
+            expanded.extend(expand_inline_module(item)?);
+        }
+        let items = expanded;
         for (_, loc) in &items {
             if let Some(crate_name) = &loc.crate_name {
                 let module = crate_name.replace('-', "_");
@@ -655,18 +773,30 @@ impl<M> Registry<M> {
         for (mut item, loc) in items {
             crate::api::core::types_util::normalize_item_types(&mut item, &modules);
             let crate_name = loc.crate_name.clone();
+            let module_path = loc.module_path.clone();
+            let source_name = loc
+                .source_name
+                .as_deref()
+                .and_then(|name| syn::parse_str::<syn::Ident>(name).ok());
             let named: Option<syn::Ident> = match &item {
                 syn::Item::Fn(f) => Some(f.sig.ident.clone()),
                 syn::Item::Struct(s) => Some(s.ident.clone()),
                 syn::Item::Enum(e) => Some(e.ident.clone()),
                 syn::Item::Const(c) if c.ident != "_" => Some(c.ident.clone()),
+                syn::Item::Static(s) => Some(s.ident.clone()),
                 _ => None,
             };
             match registry.index_item(item, loc) {
                 Ok(()) => {
                     // Only after successful indexing — a collision must keep
                     // the FIRST item's origin for the error below.
+                    if let (Some(ident), Some(source_name)) = (&named, source_name) {
+                        registry.source_names.insert(ident.clone(), source_name);
+                    }
                     if let (Some(ident), Some(crate_name)) = (named, crate_name) {
+                        if let Some(module_path) = module_path {
+                            registry.item_modules.insert(ident.clone(), module_path);
+                        }
                         registry.item_origins.insert(ident, crate_name);
                     }
                 }
@@ -684,13 +814,89 @@ impl<M> Registry<M> {
     /// The origin crate's **module path** for an item ingested via
     /// the item's [`SourceLocation`] stamp, or `None` when unknown —
     /// callers then fall
-    /// back to [`Self::default_module`].
+    /// back to [`Self::default_module`]. Items whose location carries a
+    /// [`SourceLocation::module_path`] (a `Source` built with
+    /// `qualify_by_module(true)`) get that module appended (`my_crate::foo`),
+    /// so the module must be reachable from the crate root.
     pub fn origin_module(&self, ident: &syn::Ident) -> Option<syn::Path> {
         let crate_name = self.item_origins.get(ident)?;
-        let module = crate_name.replace('-', "_");
+        let mut module = crate_name.replace('-', "_");
+        if let Some(module_path) = self.item_modules.get(ident) {
+            module = format!("{module}::{module_path}");
+        }
         syn::parse_str(&module).ok()
     }
 
+    /// Point the source-qualified paths of a generated `item` back at the
+    /// origin names of renamed items ([`SourceLocation::source_name`]):
+    /// `my_crate::MyFoo` becomes `my_crate::Foo` when the stream renamed
+    /// `Foo` to `MyFoo`, while bare generated names (mirrors, wire types)
+    /// keep the new one. A prefix counts as a source module if it is an
+    /// ingested crate, the renamed item's [`Self::origin_module`], or the
+    /// adapter's `source_module`. Macro arguments (layout assertions) are
+    /// rewritten too.
+    pub(crate) fn restore_source_names(
+        &self,
+        item: &mut syn::Item,
+        source_module: Option<&syn::Path>,
+    ) {
+        use syn::visit_mut::VisitMut;
+        if self.source_names.is_empty() {
+            return;
+        }
+        let path_text = |path: &syn::Path| {
+            path.segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::")
+        };
+        let modules: HashSet<String> = self
+            .source_modules
+            .iter()
+            .cloned()
+            .chain(source_module.map(path_text))
+            .chain(
+                self.source_names
+                    .keys()
+                    .filter_map(|ident| self.origin_module(ident))
+                    .map(|path| path_text(&path)),
+            )
+            .collect();
+        RestoreSourceNames {
+            names: &self.source_names,
+            modules: &modules,
+        }
+        .visit_item_mut(item);
+    }
+
+    /// The type a captured `type` alias finally stands for: `ty` must be a
+    /// bare alias name, and alias-of-alias chains (`type A = B; type B = i32;`)
+    /// are followed to the first non-alias target. The aliases are looked up
+    /// among all [`Self::passthrough`] items, so the chain resolves whatever
+    /// order it was captured in. `None` when `ty` is not a (non-generic)
+    /// captured alias, or the chain is cyclic.
+    pub fn alias_target(&self, ty: &syn::Type) -> Option<syn::Type> {
+        let alias = |ty: &syn::Type| -> Option<&syn::Type> {
+            let ident = bare_path_ident(ty)?;
+            self.passthrough.iter().find_map(|(item, _)| match item {
+                syn::Item::Type(t) if t.ident == ident && t.generics.params.is_empty() => {
+                    Some(&*t.ty)
+                }
+                _ => None,
+            })
+        };
+        let mut target = alias(ty)?;
+        let mut seen = HashSet::new();
+        while let Some(next) = alias(target) {
+            if !seen.insert(TypeKey::from_type(target)) {
+                return None;
+            }
+            target = next;
+        }
+        Some(target.clone())
+    }
+
     /// The default module for references with no recorded origin: the
     /// first-seen item origin. `None` for an origin-less item-level
     /// registry (adapters then fall back to `crate`). To change a module
@@ -804,7 +1010,9 @@ impl<M> Registry<M> {
         // Scan declared functions.
         for ident in &declared.functions {
             if let Some((item_fn, loc)) = self.functions.get(ident).cloned() {
-                self.scan_fn_signature(&item_fn, &loc)?;
+                if let Some(err) = self.scan_fn_signature(&item_fn, &loc).into_iter().next() {
+                    return Err(err);
+                }
             } else {
                 missing.push(("function", ident.to_string()));
             }
@@ -902,80 +1110,90 @@ impl<M> Registry<M> {
             }
         }
 
-        // Warn about indexed items that the adapter never claimed. An
-        // ignore *predicate* acknowledges every matching item in bulk —
-        // kind-agnostic, since prebindgen names live in one flat namespace;
-        // a predicate matching nothing is silent by design (it is a filter,
-        // not a claim — match counts vary across feature configurations).
+        // Warn about indexed items that the adapter never claimed.
+        for item in self.undeclared_items(declared) {
+            println!(
+                "cargo:warning=prebindgen: skipping undeclared #[prebindgen] {} `{}`",
+                item.kind, item.name
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Indexed items the adapter never claimed: undeclared fns, then
+    /// structs/enums, then consts, each sorted by name. An ignore
+    /// *predicate* acknowledges every matching item in bulk — kind-agnostic,
+    /// since prebindgen names live in one flat namespace; a predicate
+    /// matching nothing is silent by design (it is a filter, not a claim —
+    /// match counts vary across feature configurations).
+    fn undeclared_items(&self, declared: &DeclaredItems) -> Vec<SkippedItem> {
         let pred_ignored = |name: &str| {
             !declared.ignored_name_predicates.is_empty()
                 && declared.ignored_name_predicates.iter().any(|p| p(name))
         };
-        let mut skipped_fns: Vec<String> = self
+        let undeclared = |kind: RecordKind, ident: &syn::Ident, loc: &SourceLocation| SkippedItem {
+            kind,
+            name: ident.to_string(),
+            location: loc.clone(),
+            reason: "undeclared".to_string(),
+        };
+        let by_name = |items: &mut Vec<SkippedItem>| items.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut fns: Vec<SkippedItem> = self
             .functions
-            .keys()
-            .filter(|k| {
+            .iter()
+            .filter(|(k, _)| {
                 !declared.functions.contains(*k)
                     && !declared.ignored_functions.contains(*k)
                     && !declared.helper_functions.contains(*k)
                     && !pred_ignored(&k.to_string())
             })
-            .map(|k| k.to_string())
+            .map(|(k, (_, loc))| undeclared(RecordKind::Function, k, loc))
             .collect();
-        skipped_fns.sort();
-        for name in &skipped_fns {
-            println!(
-                "cargo:warning=prebindgen: skipping undeclared #[prebindgen] fn `{}`",
-                name
-            );
-        }
+        by_name(&mut fns);
 
-        let mut skipped_types: Vec<String> = Vec::new();
         let type_acknowledged = |key: &TypeKey| {
             declared.types.contains(key)
                 || declared.ignored_types.contains(key)
                 || declared.boundary_only_types.contains(key)
         };
-        for ident in self.structs.keys().chain(self.enums.keys()) {
-            let name = ident.to_string();
-            let key = TypeKey::from_ident(ident);
-            if !type_acknowledged(&key) && !pred_ignored(&name) {
-                skipped_types.push(name);
-            }
-        }
-        skipped_types.sort();
-        for name in &skipped_types {
-            println!(
-                "cargo:warning=prebindgen: skipping undeclared #[prebindgen] struct/enum `{}`",
-                name
-            );
-        }
+        let mut types: Vec<SkippedItem> = self
+            .structs
+            .iter()
+            .map(|(k, (_, loc))| (RecordKind::Struct, k, loc))
+            .chain(
+                self.enums
+                    .iter()
+                    .map(|(k, (_, loc))| (RecordKind::Enum, k, loc)),
+            )
+            .filter(|(_, k, _)| {
+                !type_acknowledged(&TypeKey::from_ident(k)) && !pred_ignored(&k.to_string())
+            })
+            .map(|(kind, k, loc)| undeclared(kind, k, loc))
+            .collect();
+        by_name(&mut types);
 
-        if let Some(decl_consts) = &declared.consts {
-            let mut skipped_consts: Vec<String> = self
+        let mut consts: Vec<SkippedItem> = match &declared.consts {
+            Some(decl_consts) => self
                 .consts
-                .keys()
+                .iter()
                 // Unnamed consts (`const _`, e.g. the injected feature
                 // guard) are infrastructure: not declarable, always emitted
                 // verbatim — never a skip.
-                .filter(|k| {
+                .filter(|(k, _)| {
                     *k != "_"
                         && !decl_consts.contains(*k)
                         && !declared.ignored_consts.contains(*k)
                         && !pred_ignored(&k.to_string())
                 })
-                .map(|k| k.to_string())
-                .collect();
-            skipped_consts.sort();
-            for name in &skipped_consts {
-                println!(
-                    "cargo:warning=prebindgen: skipping undeclared #[prebindgen] const `{}`",
-                    name
-                );
-            }
-        }
+                .map(|(k, (_, loc))| undeclared(RecordKind::Const, k, loc))
+                .collect(),
+            None => Vec::new(),
+        };
+        by_name(&mut consts);
 
-        Ok(())
+        fns.into_iter().chain(types).chain(consts).collect()
     }
 
     /// True iff the key was scanned as a top-level fn-signature input type.
@@ -1089,6 +1307,11 @@ impl<M> Registry<M> {
                 self.consts.insert(c.ident.clone(), (c, loc));
                 Ok(())
             }
+            syn::Item::Static(s) => {
+                self.check_no_duplicate(&s.ident, &loc)?;
+                self.statics.insert(s.ident.clone(), (s, loc));
+                Ok(())
+            }
             other => {
                 self.passthrough.push((other, loc));
                 Ok(())
@@ -1124,30 +1347,58 @@ impl<M> Registry<M> {
         if let Some((_, loc)) = self.consts.get(name) {
             return Some(loc.clone());
         }
+        if let Some((_, loc)) = self.statics.get(name) {
+            return Some(loc.clone());
+        }
         None
     }
 
-    fn scan_fn_signature(
+    /// Register the parameter and return types of `f`, returning every
+    /// problem of its signature in signature order (empty when it can be
+    /// exported). Also run over undeclared functions by
+    /// [`validate`](crate::validate).
+    pub(crate) fn scan_fn_signature(
         &mut self,
         f: &syn::ItemFn,
         loc: &SourceLocation,
-    ) -> Result<(), ScanError> {
+    ) -> Vec<ScanError> {
         // Mechanical: register every fn-signature type as the user wrote it.
         // No semantic transformations (no &T→T strip, no ZResult<T>→T strip,
         // no skip for () / ZResult<()>). The adapter handles structural
         // wrappers; propagation through `subs` then marks transitive deps
         // (e.g. &Foo's `&_` converter returns subs=[Foo], so Foo becomes
         // required).
+        let mut issues = Vec::new();
+        if f.sig.variadic.is_some() {
+            issues.push(ScanError::VariadicFunction {
+                name: f.sig.ident.clone(),
+                loc: Box::new(loc.clone()),
+            });
+        }
+        if let Some(generics) = non_lifetime_generics(&f.sig.generics) {
+            issues.push(ScanError::GenericFunction {
+                name: f.sig.ident.clone(),
+                generics,
+                loc: Box::new(loc.clone()),
+            });
+        }
         for input in &f.sig.inputs {
             match input {
                 syn::FnArg::Receiver(_) => {
-                    return Err(ScanError::UnsupportedReceiver { loc: loc.clone() });
+                    issues.push(ScanError::UnsupportedReceiver {
+                        loc: Box::new(loc.clone()),
+                    });
                 }
                 syn::FnArg::Typed(pt) => {
                     if !matches!(&*pt.pat, syn::Pat::Ident(_)) {
-                        return Err(ScanError::UnsupportedParamPattern { loc: loc.clone() });
+                        issues.push(ScanError::UnsupportedParamPattern {
+                            loc: Box::new(loc.clone()),
+                        });
                     }
-                    self.register_type_recursive(Direction::Input, &pt.ty, true, loc)?;
+                    issues.extend(
+                        self.register_type_recursive(Direction::Input, &pt.ty, true, loc)
+                            .err(),
+                    );
                 }
             }
         }
@@ -1155,8 +1406,11 @@ impl<M> Registry<M> {
             syn::ReturnType::Default => syn::parse_quote!(()),
             syn::ReturnType::Type(_, ty) => (**ty).clone(),
         };
-        self.register_type_recursive(Direction::Output, &ret_ty, true, loc)?;
-        Ok(())
+        issues.extend(
+            self.register_type_recursive(Direction::Output, &ret_ty, true, loc)
+                .err(),
+        );
+        issues
     }
 
     fn scan_struct(&mut self, s: &syn::ItemStruct, loc: &SourceLocation) -> Result<(), ScanError> {
@@ -1215,7 +1469,7 @@ impl<M> Registry<M> {
             if extract_fn_trait_args(ty).is_none() {
                 return Err(ScanError::DisallowedImplTrait {
                     ty: it.to_token_stream().to_string(),
-                    loc: loc.clone(),
+                    loc: Box::new(loc.clone()),
                 });
             }
         }
@@ -1309,6 +1563,150 @@ impl<M> Registry<M> {
     /// gen.write_kotlin(&kotlin_root)?;   // JNI adapter's second artifact
     /// ```
     pub fn resolve<E>(mut self, adapter: E) -> Result<Generation<E>, WriteRustError>
+    where
+        E: Prebindgen<Metadata = M>,
+        M: Clone + Default,
+    {
+        let dropped = if adapter.skip_invalid_items() {
+            self.drop_invalid_functions(&adapter)?
+        } else {
+            HashMap::new()
+        };
+        self.resolve_in_place(&adapter, &dropped)?;
+        let mut skipped = self.undeclared_items(&DeclaredItems::from_adapter(&adapter)?);
+        let mut dropped: Vec<SkippedItem> = dropped.into_values().collect();
+        dropped.sort_by(|a, b| a.name.cmp(&b.name));
+        skipped.extend(dropped);
+        Ok(Generation {
+            registry: self,
+            adapter,
+            skipped,
+            items: OnceLock::new(),
+        })
+    }
+
+    /// [`Prebindgen::skip_invalid_items`]: resolve trial copies of the index,
+    /// each time dropping the declared functions that reach an unresolved
+    /// type, until a trial succeeds. Returns the dropped functions; errors
+    /// that no dropped function explains are returned as-is.
+    fn drop_invalid_functions<E>(
+        &mut self,
+        adapter: &E,
+    ) -> Result<HashMap<syn::Ident, SkippedItem>, WriteRustError>
+    where
+        E: Prebindgen<Metadata = M>,
+        M: Clone + Default,
+    {
+        let declared = adapter.declared_functions();
+        let mut dropped = HashMap::new();
+        loop {
+            let mut trial = self.index_copy();
+            let err = match trial.resolve_in_place(adapter, &dropped) {
+                Ok(()) => return Ok(dropped),
+                Err(err) => err,
+            };
+            let WriteRustError::Resolve(crate::api::core::resolve::ResolveError::Unresolved {
+                entries,
+            }) = &err
+            else {
+                return Err(err);
+            };
+            let unresolved: HashSet<(Direction, TypeKey)> = entries
+                .iter()
+                .map(|e| (e.direction, e.key.clone()))
+                .collect();
+            let mut invalid: Vec<(syn::Ident, TypeKey)> = self
+                .functions
+                .keys()
+                .filter(|ident| declared.contains(*ident))
+                .filter_map(|ident| {
+                    let (f, _) = trial.functions.get(ident)?;
+                    let key = trial.unresolved_in_signature(&f.sig, &unresolved)?;
+                    Some((ident.clone(), key))
+                })
+                .collect();
+            if invalid.is_empty() {
+                return Err(err);
+            }
+            invalid.sort_by_key(|(ident, _)| ident.to_string());
+            for (ident, key) in invalid {
+                let (_, loc) = self.functions.remove(&ident).expect("indexed fn");
+                println!(
+                    "cargo:warning=prebindgen: {loc}: skipping fn `{ident}`: unresolved type `{key}`"
+                );
+                let skipped = SkippedItem {
+                    kind: RecordKind::Function,
+                    name: ident.to_string(),
+                    location: loc,
+                    reason: format!("unresolved type `{key}`"),
+                };
+                dropped.insert(ident, skipped);
+            }
+        }
+    }
+
+    /// First unresolved type reached from a signature, walking the type graph
+    /// from its parameter and return types and stopping at resolved entries
+    /// (their converters don't need what lies behind them).
+    fn unresolved_in_signature(
+        &self,
+        sig: &syn::Signature,
+        unresolved: &HashSet<(Direction, TypeKey)>,
+    ) -> Option<TypeKey> {
+        let mut queue: VecDeque<(Direction, syn::Type)> = sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::FnArg::Typed(pt) => Some((Direction::Input, (*pt.ty).clone())),
+                syn::FnArg::Receiver(_) => None,
+            })
+            .collect();
+        if let syn::ReturnType::Type(_, ty) = &sig.output {
+            queue.push_back((Direction::Output, (**ty).clone()));
+        }
+        let mut seen: HashSet<(Direction, TypeKey)> = HashSet::new();
+        while let Some((dir, ty)) = queue.pop_front() {
+            let node = (dir, TypeKey::from_type(&ty));
+            if !seen.insert(node.clone()) {
+                continue;
+            }
+            if unresolved.contains(&node) {
+                return Some(node.1);
+            }
+            if matches!(self.type_table(dir).get(&node.1), Some(Some(_))) {
+                continue;
+            }
+            queue.extend(self.immediate_edges(dir, &ty));
+        }
+        None
+    }
+
+    /// A fresh registry over the same indexed items (as returned by
+    /// [`Self::from_items`]), without any scan or resolution state.
+    fn index_copy(&self) -> Self {
+        Self {
+            functions: self.functions.clone(),
+            structs: self.structs.clone(),
+            enums: self.enums.clone(),
+            consts: self.consts.clone(),
+            statics: self.statics.clone(),
+            passthrough: self.passthrough.clone(),
+            item_origins: self.item_origins.clone(),
+            item_modules: self.item_modules.clone(),
+            source_names: self.source_names.clone(),
+            source_modules: self.source_modules.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// The body of [`Self::resolve`], leaving the result in `self`. `dropped`
+    /// are declared functions already removed by
+    /// [`Self::drop_invalid_functions`].
+    fn resolve_in_place<E>(
+        &mut self,
+        adapter: &E,
+        dropped: &HashMap<syn::Ident, SkippedItem>,
+    ) -> Result<(), WriteRustError>
     where
         E: Prebindgen<Metadata = M>,
         M: Clone + Default,
@@ -1333,13 +1731,16 @@ impl<M> Registry<M> {
                 .insert(ident.clone(), (item_fn, crate::SourceLocation::default()));
             self.item_origins.insert(ident, origin);
         }
-        let declared = DeclaredItems::from_adapter(&adapter)?;
+        let mut declared = DeclaredItems::from_adapter(adapter)?;
+        declared
+            .functions
+            .retain(|ident| !dropped.contains_key(ident));
         self.scan_declared_items(&declared)?;
         adapter
-            .validate(&self)
+            .validate(self)
             .map_err(|message| ScanError::AdapterInvariant { message })?;
-        self.apply_adapter_plans(&adapter, &declared)?;
-        crate::api::core::resolve::resolve(&mut self, &adapter)?;
+        self.apply_adapter_plans(adapter, &declared)?;
+        crate::api::core::resolve::resolve(self, adapter)?;
         // Post-resolve validation runs ONCE here, so a `Generation` is valid
         // by construction and the `write_*` emitters are genuinely pure
         // (previously each writer re-ran this, validating twice per build).
@@ -1347,12 +1748,9 @@ impl<M> Registry<M> {
         // channel. An invalid binding fails `resolve`; no `Generation` is
         // produced, so nothing can be written.
         adapter
-            .validate_resolved(&self)
+            .validate_resolved(self)
             .map_err(|message| ScanError::AdapterInvariant { message })?;
-        Ok(Generation {
-            registry: self,
-            adapter,
-        })
+        Ok(())
     }
 
     fn apply_adapter_plans<E>(
@@ -1425,6 +1823,34 @@ impl<M> Registry<M> {
 // Helpers
 // ──────────────────────────────────────────────────────────────────────
 
+/// The type / const generic parameters and type `where` predicates of a
+/// function, as written (`T: Copy, const N: usize where T: Default`), or None
+/// if it only has lifetimes.
+fn non_lifetime_generics(generics: &syn::Generics) -> Option<String> {
+    let params: Vec<String> = generics
+        .params
+        .iter()
+        .filter(|p| !matches!(p, syn::GenericParam::Lifetime(_)))
+        .map(|p| p.to_token_stream().to_string())
+        .collect();
+    let predicates: Vec<String> = generics
+        .where_clause
+        .iter()
+        .flat_map(|w| &w.predicates)
+        .filter(|p| matches!(p, syn::WherePredicate::Type(_)))
+        .map(|p| p.to_token_stream().to_string())
+        .collect();
+    let mut written = params.join(", ");
+    if !predicates.is_empty() {
+        if !written.is_empty() {
+            written.push(' ');
+        }
+        written.push_str("where ");
+        written.push_str(&predicates.join(", "));
+    }
+    (!written.is_empty()).then_some(written)
+}
+
 /// Immediate child type positions of `ty` (one level deep).
 pub fn immediate_subtype_positions(ty: &syn::Type) -> Vec<syn::Type> {
     match ty {
@@ -1507,6 +1933,64 @@ pub fn extract_fn_trait_args(ty: &syn::Type) -> Option<Vec<syn::Type>> {
 pub struct Generation<E: Prebindgen> {
     registry: Registry<E::Metadata>,
     adapter: E,
+    /// Indexed items left out of the bindings, for [`Self::summary`].
+    skipped: Vec<SkippedItem>,
+    /// The generated items, built on first use and shared by every writer
+    /// so the adapter runs — and prints its warnings — once.
+    items: OnceLock<Vec<syn::Item>>,
+}
+
+/// An indexed `#[prebindgen]` item left out of the generated bindings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkippedItem {
+    /// The item kind: a function, struct, enum or const.
+    pub kind: RecordKind,
+    /// The item's name as indexed.
+    pub name: String,
+    /// Where the item is defined.
+    pub location: SourceLocation,
+    /// Why it was left out: `"undeclared"`, or `"unresolved type `T`"` for a
+    /// function dropped by [`Prebindgen::skip_invalid_items`].
+    pub reason: String,
+}
+
+/// What a [`Generation`] emits, as counted by [`Generation::summary`]:
+/// a summary a build script can print before committing to the bindings.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GenerationSummary {
+    /// Declared functions the adapter wraps.
+    pub functions: usize,
+    /// Declared structs and enums the adapter emits.
+    pub types: usize,
+    /// Generated converter functions (including staged ones).
+    pub converters: usize,
+    /// Compile-time assertions: top-level `const _` items.
+    pub assertions: usize,
+    /// Indexed items left out: undeclared ones (each also a
+    /// `cargo:warning=`), then functions dropped as invalid.
+    pub skipped: Vec<SkippedItem>,
+}
+
+impl fmt::Display for GenerationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} functions, {} types, {} converters, {} assertions, {} skipped",
+            self.functions,
+            self.types,
+            self.converters,
+            self.assertions,
+            self.skipped.len()
+        )?;
+        for item in &self.skipped {
+            write!(
+                f,
+                "\n  {}: {} `{}`: {}",
+                item.location, item.kind, item.name, item.reason
+            )?;
+        }
+        Ok(())
+    }
 }
 
 // Opaque — exists so `Result<Generation, _>::expect_err` works in tests.
@@ -1525,11 +2009,74 @@ impl<E: Prebindgen> Generation<E> {
         &self,
         out_path: impl AsRef<std::path::Path>,
     ) -> Result<std::path::PathBuf, WriteRustError> {
-        Ok(crate::api::core::write::write_rust(
-            &self.registry,
-            &self.adapter,
-            out_path,
-        )?)
+        let destination: crate::api::collect::destination::Destination =
+            self.items()?.iter().cloned().collect();
+        Ok(destination.write(out_path))
+    }
+
+    /// The items [`Self::write_rust`] emits, collected into a
+    /// [`Destination`](crate::collect::Destination) to be written with
+    /// options `write_rust` leaves off (a banner, `unsafe` comments, a
+    /// module wrapper). A generated function named like a `#[prebindgen]`
+    /// function — its wrapper — carries that function's `SourceLocation`.
+    pub fn destination(
+        &self,
+    ) -> Result<crate::api::collect::destination::Destination, WriteRustError> {
+        let mut destination: crate::api::collect::destination::Destination =
+            std::iter::empty::<syn::Item>().collect();
+        for item in self.items()?.iter().cloned() {
+            let location = match &item {
+                syn::Item::Fn(f) => self.registry.functions.get(&f.sig.ident),
+                _ => None,
+            };
+            match location {
+                Some((_, location)) => {
+                    let location = location.clone();
+                    destination.extend([(item, location)])
+                }
+                None => destination.extend([item]),
+            }
+        }
+        Ok(destination)
+    }
+
+    /// Count what [`Self::write_rust`] would emit without writing anything:
+    /// see [`GenerationSummary`]. Adapters may add their own, richer
+    /// explain mode (e.g. the JNI adapter's `report`).
+    pub fn summary(&self) -> Result<GenerationSummary, WriteRustError> {
+        let items = self.items()?;
+        let declared_fns = self.adapter.declared_functions();
+        let declared_types = self.adapter.declared_types();
+        Ok(GenerationSummary {
+            functions: self
+                .registry
+                .functions
+                .keys()
+                .filter(|ident| declared_fns.contains(*ident))
+                .count(),
+            types: self
+                .registry
+                .structs
+                .keys()
+                .chain(self.registry.enums.keys())
+                .filter(|ident| declared_types.contains(&TypeKey::from_ident(ident)))
+                .count(),
+            converters: crate::api::core::write::collect_converter_items(&self.registry).len(),
+            assertions: items
+                .iter()
+                .filter(|item| matches!(item, syn::Item::Const(c) if c.ident == "_"))
+                .count(),
+            skipped: self.skipped.clone(),
+        })
+    }
+
+    /// The generated items, generating them on first use.
+    fn items(&self) -> Result<&[syn::Item], WriteRustError> {
+        if let Some(items) = self.items.get() {
+            return Ok(items);
+        }
+        let items = crate::api::core::write::generate_items(&self.registry, &self.adapter)?;
+        Ok(self.items.get_or_init(|| items))
     }
 
     /// The resolved registry (converter tables, plans, item maps).
@@ -1543,5 +2090,73 @@ impl<E: Prebindgen> Generation<E> {
     }
 }
 
+/// The visitor behind [`Registry::restore_source_names`].
+struct RestoreSourceNames<'a> {
+    names: &'a HashMap<syn::Ident, syn::Ident>,
+    modules: &'a HashSet<String>,
+}
+
+impl RestoreSourceNames<'_> {
+    /// `tokens` with each renamed ident that follows `<module>::` restored.
+    fn restore_tokens(&self, tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        use proc_macro2::{Group, TokenTree};
+        let mut out: Vec<TokenTree> = Vec::new();
+        for token in tokens {
+            let token = match token {
+                TokenTree::Group(g) => {
+                    let mut group = Group::new(g.delimiter(), self.restore_tokens(g.stream()));
+                    group.set_span(g.span());
+                    TokenTree::Group(group)
+                }
+                TokenTree::Ident(ident) => match self.names.get(&ident) {
+                    Some(source) if self.follows_module(&out) => TokenTree::Ident(source.clone()),
+                    _ => TokenTree::Ident(ident),
+                },
+                other => other,
+            };
+            out.push(token);
+        }
+        out.into_iter().collect()
+    }
+
+    /// Whether `prev` ends with `<module>::`, `<module>` a source module.
+    fn follows_module(&self, prev: &[proc_macro2::TokenTree]) -> bool {
+        use proc_macro2::TokenTree;
+        let mut segments = Vec::new();
+        let mut rest = prev;
+        while let [head @ .., TokenTree::Ident(segment), TokenTree::Punct(a), TokenTree::Punct(b)] =
+            rest
+        {
+            if a.as_char() != ':' || b.as_char() != ':' {
+                break;
+            }
+            segments.insert(0, segment.to_string());
+            rest = head;
+        }
+        !segments.is_empty() && self.modules.contains(&segments.join("::"))
+    }
+}
+
+impl syn::visit_mut::VisitMut for RestoreSourceNames<'_> {
+    fn visit_path_mut(&mut self, path: &mut syn::Path) {
+        let mut prefix = Vec::new();
+        for segment in path.segments.iter_mut() {
+            if !prefix.is_empty() && self.modules.contains(&prefix.join("::")) {
+                if let Some(source) = self.names.get(&segment.ident) {
+                    segment.ident = source.clone();
+                    break;
+                }
+            }
+            prefix.push(segment.ident.to_string());
+        }
+        syn::visit_mut::visit_path_mut(self, path);
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut syn::Macro) {
+        mac.tokens = self.restore_tokens(std::mem::take(&mut mac.tokens));
+        syn::visit_mut::visit_macro_mut(self, mac);
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -151,6 +151,55 @@ fn scan_declared_fails_disallowed_impl_trait_only_when_fn_declared() {
     }
 }
 
+#[test]
+fn scan_declared_rejects_variadic_function() {
+    let items = vec![fn_item(
+        "pub unsafe extern \"C\" fn log_fmt(fmt: *const u8, ...) -> i32 { 0 }",
+    )];
+    let mut reg: Registry<()> = Registry::from_items(items).unwrap();
+    let mut ext = StubExt::default();
+    ext.functions.insert(syn::parse_str("log_fmt").unwrap());
+    match reg.scan_declared(&ext) {
+        Err(e @ ScanError::VariadicFunction { .. }) => assert!(
+            e.to_string()
+                .starts_with("variadic functions cannot be proxied: `log_fmt` at "),
+            "{e}"
+        ),
+        other => panic!("expected VariadicFunction, got {:?}", other),
+    }
+}
+
+#[test]
+fn scan_declared_rejects_generic_function() {
+    let items = vec![
+        fn_item("pub fn f<T>(x: T) {}"),
+        fn_item("pub fn g(x: u8) where u8: Copy {}"),
+        fn_item("pub fn h<'a>(x: &'a u8) -> &'a u8 where 'a: 'a { x }"),
+    ];
+    let mut reg: Registry<()> = Registry::from_items(items).unwrap();
+    let scan = |reg: &mut Registry<()>, name: &str| {
+        let mut ext = StubExt::default();
+        ext.functions.insert(syn::parse_str(name).unwrap());
+        reg.scan_declared(&ext)
+    };
+    match scan(&mut reg, "f") {
+        Err(e @ ScanError::GenericFunction { .. }) => assert!(
+            e.to_string()
+                .starts_with("generic functions cannot be exported: `f` has `T` at "),
+            "{e}"
+        ),
+        other => panic!("expected GenericFunction, got {:?}", other),
+    }
+    match scan(&mut reg, "g") {
+        Err(ScanError::GenericFunction { generics, .. }) => {
+            assert_eq!(generics, "where u8 : Copy")
+        }
+        other => panic!("expected GenericFunction, got {:?}", other),
+    }
+    // Lifetimes, bounds between them included, are fine
+    assert!(scan(&mut reg, "h").is_ok());
+}
+
 #[test]
 fn scan_declared_rejects_function_declared_and_ignored_overlap() {
     let items = vec![fn_item("fn good(x: u64) -> u64 { x }")];
@@ -364,6 +413,9 @@ fn duplicate_name_across_sources_names_both_crates() {
                 line: 1,
                 column: 1,
                 crate_name: None,
+                group: None,
+                module_path: None,
+                source_name: None,
             },
             None,
         );
@@ -393,6 +445,9 @@ fn from_items_records_origins_from_location_stamps() {
         line: 1,
         column: 1,
         crate_name: Some(krate.to_string()),
+        group: None,
+        module_path: None,
+        source_name: None,
     };
     let f_a: syn::ItemFn = syn::parse_str("fn from_flat(x: u64) -> u64 { x }").unwrap();
     let f_b: syn::ItemFn = syn::parse_str("fn from_helper(x: u64) -> u64 { x }").unwrap();
@@ -425,6 +480,121 @@ fn from_items_records_origins_from_location_stamps() {
     );
 }
 
+/// Items captured in a submodule file carry its module path, which
+/// `origin_module` appends to the crate module: `foo::Foo` needs no manual
+/// qualification. Crate-root items stay at the crate module.
+#[test]
+fn origin_module_appends_captured_submodule_path() {
+    use crate::api::record::module_path_of_file;
+    assert_eq!(module_path_of_file("src/lib.rs"), None);
+    assert_eq!(module_path_of_file("example-ffi/src/main.rs"), None);
+    assert_eq!(
+        module_path_of_file("example-ffi/src/foo.rs").as_deref(),
+        Some("foo")
+    );
+    assert_eq!(
+        module_path_of_file("src/foo/mod.rs").as_deref(),
+        Some("foo")
+    );
+    assert_eq!(
+        module_path_of_file("src/foo/bar.rs").as_deref(),
+        Some("foo::bar")
+    );
+    assert_eq!(module_path_of_file("<unknown>"), None);
+
+    let loc = |file: &str| SourceLocation {
+        file: file.to_string(),
+        line: 1,
+        column: 1,
+        crate_name: Some("example-ffi".to_string()),
+        group: None,
+        module_path: module_path_of_file(file),
+        source_name: None,
+    };
+    let foo: syn::ItemStruct = syn::parse_str("pub struct Foo { pub x: u64 }").unwrap();
+    let root: syn::ItemFn = syn::parse_str("fn root_fn(x: u64) -> u64 { x }").unwrap();
+    let items = vec![
+        (syn::Item::Struct(foo), loc("example-ffi/src/foo/mod.rs")),
+        (syn::Item::Fn(root), loc("example-ffi/src/lib.rs")),
+    ];
+    let reg: Registry<()> = Registry::from_items(items).unwrap();
+
+    let path = |p: syn::Path| p.to_token_stream().to_string();
+    assert_eq!(
+        reg.origin_module(&syn::parse_str("Foo").unwrap()).map(path),
+        Some("example_ffi :: foo".to_string())
+    );
+    assert_eq!(
+        reg.origin_module(&syn::parse_str("root_fn").unwrap())
+            .map(path),
+        Some("example_ffi".to_string())
+    );
+}
+
+/// A recorded inline module round-trips into its items, located in the
+/// module (so `origin_module` qualifies them with it) and gated by the
+/// module's cfg.
+#[test]
+fn inline_module_record_indexes_its_items_in_the_module() {
+    use crate::api::record::{Record, RecordKind};
+    let record = Record::new(
+        RecordKind::Mod,
+        "foo".to_string(),
+        "pub mod foo { pub struct Point { pub x : i32 } pub fn origin () -> Point { } \
+         pub mod nested { pub const LIMIT : u32 = 8 ; } }"
+            .to_string(),
+        SourceLocation {
+            file: "example-ffi/src/lib.rs".to_string(),
+            crate_name: Some("example-ffi".to_string()),
+            ..SourceLocation::default()
+        },
+        Some("unix".to_string()),
+    );
+    let reg: Registry<()> = Registry::from_items([record.try_parse().unwrap()]).unwrap();
+
+    let path = |p: syn::Path| p.to_token_stream().to_string();
+    let ident = |name: &str| syn::parse_str::<syn::Ident>(name).unwrap();
+    assert_eq!(
+        reg.origin_module(&ident("Point")).map(path),
+        Some("example_ffi :: foo".to_string())
+    );
+    assert_eq!(
+        reg.origin_module(&ident("LIMIT")).map(path),
+        Some("example_ffi :: foo :: nested".to_string())
+    );
+    let (origin, _) = &reg.functions[&ident("origin")];
+    assert_eq!(
+        origin.attrs[0].to_token_stream().to_string(),
+        "# [cfg (unix)]"
+    );
+}
+
+/// An inline module item that isn't `pub` is rejected instead of being
+/// flattened into a path the generated code can't reach.
+#[test]
+fn inline_module_rejects_private_items() {
+    use crate::api::record::{Record, RecordKind};
+    let record = Record::new(
+        RecordKind::Mod,
+        "foo".to_string(),
+        "pub mod foo { pub mod nested { fn hidden () { } } }".to_string(),
+        SourceLocation {
+            file: "example-ffi/src/lib.rs".to_string(),
+            crate_name: Some("example-ffi".to_string()),
+            ..SourceLocation::default()
+        },
+        None,
+    );
+    let Err(err) = Registry::<()>::from_items([record.try_parse().unwrap()]) else {
+        panic!("private module item was accepted");
+    };
+    assert!(
+        matches!(&err, ScanError::PrivateModuleItem { name, module, .. }
+            if name == "hidden" && module == "foo::nested"),
+        "{err}"
+    );
+}
+
 /// N5: `Prebindgen::validate` runs during `resolve` after the scan; an
 /// adapter-invariant failure surfaces as `ScanError::AdapterInvariant`
 /// with the adapter's message verbatim.
@@ -611,3 +781,24 @@ fn foreign_qualified_declared_type_stays_supported() {
     assert!(reg.required_inputs_scan.contains(&foreign));
     assert!(reg.required_outputs_scan.contains(&foreign));
 }
+
+#[test]
+fn alias_target_follows_chains_in_any_order() {
+    let alias = |src: &str| -> (syn::Item, SourceLocation) {
+        (syn::parse_str(src).unwrap(), SourceLocation::default())
+    };
+    // Captured in reverse dependency order: `A` before the `B` it names.
+    let reg: Registry<()> = Registry::from_items(vec![
+        alias("pub type A = B;"),
+        alias("pub type B = C;"),
+        alias("pub type C = i8;"),
+        alias("pub type Loop1 = Loop2;"),
+        alias("pub type Loop2 = Loop1;"),
+    ])
+    .unwrap();
+    let ty = |s: &str| -> syn::Type { syn::parse_str(s).unwrap() };
+    assert_eq!(reg.alias_target(&ty("A")), Some(ty("i8")));
+    assert_eq!(reg.alias_target(&ty("C")), Some(ty("i8")));
+    assert_eq!(reg.alias_target(&ty("Loop1")), None);
+    assert_eq!(reg.alias_target(&ty("i8")), None);
+}
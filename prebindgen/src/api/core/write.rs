@@ -1,24 +1,18 @@
 //! Rust file emission for the resolved `Registry`.
 //!
-//! `write_rust` collects every resolved input/output converter (each entry
+//! `generate_items` collects every resolved input/output converter (each entry
 //! already carries its full `ItemFn`), every per-item `on_<kind>` output,
-//! and every passthrough item; concatenates them; and hands them to
-//! `Destination::write` (which does prettyplease formatting and
+//! and every passthrough item, and concatenates them; `Generation::write_rust`
+//! hands them to `Destination::write` (which does prettyplease formatting and
 //! resolves the path against `OUT_DIR`).
 
-use std::{
-    collections::{BTreeMap, HashMap},
-    path::{Path, PathBuf},
-};
+use std::collections::{BTreeMap, HashMap};
 
 use proc_macro2::TokenStream;
 
-use crate::api::{
-    collect::destination::Destination,
-    core::{
-        prebindgen::Prebindgen,
-        registry::{Registry, TypeEntry, TypeKey},
-    },
+use crate::api::core::{
+    prebindgen::Prebindgen,
+    registry::{Registry, TypeEntry, TypeKey},
 };
 
 /// Errors surfaced by the file-emission phase.
@@ -52,15 +46,12 @@ impl std::fmt::Display for WriteError {
 
 impl std::error::Error for WriteError {}
 
-/// Emit the resolved registry to a Rust file.
-///
-/// `out_path` may be relative (resolved against `OUT_DIR` by prebindgen) or
-/// absolute. Returns the path actually written.
-pub fn write_rust<P: AsRef<Path>, E: Prebindgen>(
+/// The items `Generation::write_rust` emits for the resolved registry, in
+/// file order.
+pub(crate) fn generate_items<E: Prebindgen>(
     registry: &Registry<E::Metadata>,
     ext: &E,
-    out_path: P,
-) -> Result<PathBuf, WriteError> {
+) -> Result<Vec<syn::Item>, WriteError> {
     // Validation already ran ONCE in `Registry::resolve` — a `Generation`
     // (the only source of a resolved registry) is valid by construction, so
     // this writer is a pure emission.
@@ -123,6 +114,14 @@ pub fn write_rust<P: AsRef<Path>, E: Prebindgen>(
             .map(|(_, (item, _))| ext.on_const(item, registry)),
     )?);
 
+    // Statics: every captured static is exported (no declaration gate).
+    items.extend(parse_items_from_tokens(
+        "on_static",
+        sorted_items_by_ident(&registry.statics)
+            .into_iter()
+            .map(|(_, (item, _))| ext.on_static(item, registry)),
+    )?);
+
     // 3. Passthrough items verbatim.
     for (item, _) in &registry.passthrough {
         items.push(item.clone());
@@ -130,12 +129,41 @@ pub fn write_rust<P: AsRef<Path>, E: Prebindgen>(
 
     // 4. Cross-cutting post-process pass. Adapters use this to qualify
     //    bare type references etc. — see Prebindgen::post_process_item.
+    //    Then source-qualified references to renamed items get their
+    //    origin names back.
     for item in &mut items {
         ext.post_process_item(item, registry);
+        registry.restore_source_names(item, ext.source_module());
     }
+    Ok(merge_extern_blocks(items))
+}
 
-    let dest: Destination = items.into_iter().collect();
-    Ok(dest.write(out_path))
+/// Fold every `extern` block into the first one with the same ABI, safety
+/// and attributes, so declarations emitted item by item (e.g. a bindings
+/// file in extern-declaration mode) read as a single block. Foreign items
+/// are order-independent, so moving them up is harmless.
+fn merge_extern_blocks(items: Vec<syn::Item>) -> Vec<syn::Item> {
+    let mut out: Vec<syn::Item> = Vec::with_capacity(items.len());
+    for item in items {
+        if let syn::Item::ForeignMod(block) = &item {
+            let first = out.iter_mut().find_map(|existing| match existing {
+                syn::Item::ForeignMod(first)
+                    if first.abi == block.abi
+                        && first.unsafety == block.unsafety
+                        && first.attrs == block.attrs =>
+                {
+                    Some(first)
+                }
+                _ => None,
+            });
+            if let Some(first) = first {
+                first.items.extend(block.items.iter().cloned());
+                continue;
+            }
+        }
+        out.push(item);
+    }
+    out
 }
 
 /// Walk both type tables, dedupe each entry's stored `function` AND each
@@ -1,5 +1,6 @@
 use std::{
     collections::HashSet,
+    path::{Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
 
@@ -7,8 +8,16 @@ use proc_macro2::TokenStream;
 use quote::ToTokens;
 
 use super::*;
+use crate::api::collect::destination::Destination;
+use crate::api::core::prebindgen::static_export;
 use crate::SourceLocation;
 
+/// Write the generated items for `reg` to `path`.
+fn write_rust(reg: &Registry<()>, ext: &IdentityExt, path: &Path) -> Result<PathBuf, WriteError> {
+    let dest: Destination = generate_items(reg, ext)?.into_iter().collect();
+    Ok(dest.write(path))
+}
+
 struct IdentityExt;
 
 impl Prebindgen for IdentityExt {
@@ -216,3 +225,45 @@ fn bad_generated_tokens_report_emission_phase() {
         err
     );
 }
+
+#[test]
+fn write_rust_exports_statics() {
+    let mut reg: Registry<()> = Registry::default();
+    reg.statics.insert(
+        syn::parse_quote!(SQUARES),
+        (
+            syn::parse_quote!(
+                /// Squares table.
+                #[no_mangle]
+                pub static SQUARES: [u8; 4] = [0, 1, 4, 9];
+            ),
+            SourceLocation::default(),
+        ),
+    );
+
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock drift")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("prebindgen-write-static-{unique}.rs"));
+    let written = write_rust(&reg, &IdentityExt, &path).expect("write_rust");
+    let content = std::fs::read_to_string(&written).expect("read generated file");
+    let _ = std::fs::remove_file(&written);
+
+    // No source module: the initializer is copied, the source's own
+    // `#[no_mangle]` replaced by exactly one edition-appropriate attribute.
+    let compact: String = content.split_whitespace().collect();
+    assert!(compact.contains("///Squarestable."), "{content}");
+    assert!(
+        compact.contains("pubstaticSQUARES:[u8;4]=[0,1,4,9];"),
+        "{content}"
+    );
+    assert_eq!(content.matches("no_mangle").count(), 1, "{content}");
+
+    let aliased = static_export(
+        &reg.statics[&syn::parse_quote!(SQUARES)].0,
+        Some(&syn::parse_quote!(my_flat)),
+    )
+    .to_string();
+    assert!(aliased.contains("= my_flat :: SQUARES"), "{aliased}");
+}
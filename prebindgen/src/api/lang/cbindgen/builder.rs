@@ -30,6 +30,22 @@ impl Cbindgen {
         self
     }
 
+    /// Return `String` values (also the elements of a returned `Vec<String>`)
+    /// as `*mut c_char` produced by `CString::into_raw` and export a single
+    /// `free_function(*mut c_char)` next to them (e.g. `"z_string_free"`).
+    /// Ownership of each returned string passes to the caller, who must
+    /// release it with `free_function` exactly once — never with C `free` or
+    /// the [`Self::free_memory_function`], since the memory belongs to Rust's
+    /// allocator. NULL is accepted and ignored. A string holding a NUL byte
+    /// is cut at the first one, which is all a C reader would see of it.
+    /// `String` fields of data structs are unaffected. Off by default.
+    /// Root-level modifier.
+    pub fn string_as_c_char(mut self, free_function: impl Into<String>) -> Self {
+        self.cstring_free_fn = Some(free_function.into());
+        self.clear_current();
+        self
+    }
+
     /// Set the **base** Rust-type mangler: maps a type's Rust short name (e.g.
     /// `ZKeyExpr`) to a canonical token (e.g. `keyexpr`). Its output feeds
     /// [`Self::mangle_type_name`], [`Self::mangle_destructor`] and
@@ -92,6 +108,478 @@ impl Cbindgen {
         self
     }
 
+    /// Prepend `prefix` to every exported function symbol (e.g. `zc_` to keep
+    /// the C API from clashing with another library linked into the same
+    /// process). Applied on top of [`Self::mangle_function`] to both the
+    /// wrapper's Rust name and its `#[no_mangle]` symbol; the wrapper still
+    /// calls the original `source_module::<name>`. Root-level modifier.
+    pub fn symbol_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.symbol_prefix = prefix.into();
+        self.clear_current();
+        self
+    }
+
+    /// Append `suffix` to every exported function symbol. The counterpart of
+    /// [`Self::symbol_prefix`]. Root-level modifier.
+    pub fn symbol_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.symbol_suffix = suffix.into();
+        self.clear_current();
+        self
+    }
+
+    /// Export the wrappers of the functions named by the keys of `names` under
+    /// the exact symbols they map to, via `#[export_name = "..."]` (spelled
+    /// `#[unsafe(export_name = "...")]` for edition 2024) instead of
+    /// `#[no_mangle]`: for a legacy C name or one that isn't a Rust
+    /// identifier. The mapped symbol is used verbatim — it overrides
+    /// [`Self::mangle_function`], `.base_name(...)` and the
+    /// [`Self::symbol_prefix`] / [`Self::symbol_suffix`] — while the wrapper
+    /// keeps its mangled Rust name. Calls accumulate. Root-level modifier.
+    ///
+    /// # Panics
+    ///
+    /// If a key is not a function name, or a symbol is empty.
+    pub fn export_name_map(mut self, names: HashMap<String, String>) -> Self {
+        for (function, symbol) in names {
+            let ident: syn::Ident = syn::parse_str(&function).unwrap_or_else(|_| {
+                panic!("Cbindgen::export_name_map: `{function}` is not a function name")
+            });
+            assert!(
+                !symbol.is_empty(),
+                "Cbindgen::export_name_map: empty symbol for `{function}`"
+            );
+            self.export_names.insert(ident, symbol);
+        }
+        self.clear_current();
+        self
+    }
+
+    /// Set the calling convention of the exported function wrappers (default
+    /// `"C"`), e.g. `"system"` for Windows APIs expecting `stdcall`. Must be
+    /// one of `C`, `system`, `stdcall`, `cdecl`; anything else is a build
+    /// error. Destructors and the memory freer stay `extern "C"`. Root-level
+    /// modifier.
+    pub fn abi(mut self, abi: impl Into<String>) -> Self {
+        let abi = abi.into();
+        assert!(
+            KNOWN_ABIS.contains(&abi.as_str()),
+            "Cbindgen::abi: unknown ABI `{}`; expected one of {}",
+            abi,
+            KNOWN_ABIS.join(", ")
+        );
+        self.abi = Some(abi);
+        self.clear_current();
+        self
+    }
+
+    /// Run every function wrapper's body under `std::panic::catch_unwind`, so a
+    /// panic in the Rust function (or in the wrapper's own `.panic()` paths)
+    /// never unwinds across the FFI boundary, which is undefined behavior. On a
+    /// caught panic the wrapper returns:
+    ///
+    /// * for a `Result<T, E>` function — its error indicator (`NULL` / `false`),
+    ///   with `*e` set to `E::from("<fn> panicked")`;
+    /// * otherwise — the [`Self::panic_return`] value if declared, else `()`,
+    ///   `NULL`, `false` or `0` for unit, pointer, `bool` and primitive
+    ///   returns, else `std::process::abort()`.
+    ///
+    /// See also [`Self::abort_on_panic`]. Root-level modifier.
+    pub fn catch_unwind(mut self, enable: bool) -> Self {
+        self.catch_unwind = enable;
+        self.clear_current();
+        self
+    }
+
+    /// With [`Self::catch_unwind`] enabled, call `std::process::abort()` on any
+    /// caught panic instead of returning a fallback value. Root-level modifier.
+    pub fn abort_on_panic(mut self, enable: bool) -> Self {
+        self.abort_on_panic = enable;
+        self.clear_current();
+        self
+    }
+
+    /// Set the value the most recently declared [`Self::function`] returns to C
+    /// when [`Self::catch_unwind`] catches a panic — needed for a return type
+    /// with no built-in fallback, which otherwise aborts. The expression must
+    /// have the wrapper's C return type. Ignored for `Result` functions, which
+    /// report the panic through their error out-param.
+    pub fn panic_return(mut self, expr: syn::Expr) -> Self {
+        match &self.current {
+            Some(CurrentDecl::Function(ident)) => {
+                let ident = ident.clone();
+                self.functions
+                    .get_mut(&ident)
+                    .expect("function entry vanished")
+                    .panic_return = Some(expr);
+            }
+            other => panic!(
+                "Cbindgen::panic_return must be chained after a `function(...)` call, \
+                 not after {}",
+                describe_current(other)
+            ),
+        }
+        self
+    }
+
+    /// Drop the source items' `///` docs from the generated layer. By default
+    /// the docs of declared functions, types, fields and enum variants are
+    /// carried onto their generated counterparts, so cbindgen turns them into
+    /// C header comments. Root-level modifier.
+    pub fn strip_docs(mut self, strip: bool) -> Self {
+        self.strip_docs = strip;
+        self.clear_current();
+        self
+    }
+
+    /// Append a `#[doc = " from <crate>:<file>:<line>"]` to each generated
+    /// function wrapper and type mirror, naming the `#[prebindgen]` item it was
+    /// generated from. Unlike [`Destination::with_source_comments`], these are
+    /// real doc attributes, so cbindgen carries them into the C header. The
+    /// line is added after the carried docs (and independently of
+    /// [`Self::strip_docs`]). Root-level modifier.
+    ///
+    /// [`Destination::with_source_comments`]: crate::collect::Destination::with_source_comments
+    pub fn annotate_provenance(mut self, annotate: bool) -> Self {
+        self.annotate_provenance = annotate;
+        self.clear_current();
+        self
+    }
+
+    /// Append a `# Safety` section to the docs of each function wrapper,
+    /// stating what every pointer parameter must point to and whether it may
+    /// be NULL (only where the wrapper decodes NULL), so the generated
+    /// `pub unsafe extern` functions pass `clippy::missing_safety_doc`
+    /// without an allow and the requirements reach the C header. Added after
+    /// the carried docs and independently of [`Self::strip_docs`]. Off by
+    /// default. Root-level modifier.
+    pub fn emit_safety_doc(mut self, emit: bool) -> Self {
+        self.emit_safety_doc = emit;
+        self.clear_current();
+        self
+    }
+
+    /// Check each pointer parameter lowered from a borrow (`&T` → `*const T`,
+    /// `&mut T` → `*mut T`, `&str` → `*const c_char`) for NULL at the top of
+    /// its function wrapper, before it is turned back into a reference, so
+    /// misuse from C fails loudly at the wrapper instead of surfacing as a
+    /// converter error or worse. The checks are `debug_assert!`s, free in
+    /// release builds, unless [`Self::release_null_checks`] is set. Off by
+    /// default. Root-level modifier.
+    pub fn emit_pointer_null_checks(mut self, emit: bool) -> Self {
+        self.pointer_null_checks = emit;
+        self.clear_current();
+        self
+    }
+
+    /// Emit the [`Self::emit_pointer_null_checks`] checks as `assert!`, so
+    /// they also abort release builds. Off by default. Root-level modifier.
+    pub fn release_null_checks(mut self, release: bool) -> Self {
+        self.release_null_checks = release;
+        self.clear_current();
+        self
+    }
+
+    /// Carry the source function's `#[name]` / `#[name(...)]` attributes onto
+    /// its exported wrapper. The docs (unless [`Self::strip_docs`]) and
+    /// `#[deprecated]` are always carried, so cbindgen can mark the C
+    /// declaration deprecated; use this for further attributes cbindgen or the
+    /// including crate understands (e.g. `"must_use"`). Repeatable.
+    /// Root-level modifier.
+    ///
+    /// # Panics
+    ///
+    /// For `"cfg"`: the wrapper is emitted only for functions the source crate
+    /// compiled, so a `#[cfg]` on it has no meaning.
+    pub fn keep_attribute(mut self, name: &str) -> Self {
+        assert!(
+            name != "cfg",
+            "Cbindgen::keep_attribute: `cfg` attributes are not carried onto wrappers"
+        );
+        if !self.kept_attributes.iter().any(|k| k == name) {
+            self.kept_attributes.push(name.to_string());
+        }
+        self.clear_current();
+        self
+    }
+
+    /// Require every [`Self::repr_c_struct`] source type to carry
+    /// `#[repr(C)]` or `#[repr(transparent)]`. The size/align assert against
+    /// the generated mirror can't catch a default-`repr(Rust)` struct whose
+    /// fields happen to fit but are reordered, so with this set such a struct
+    /// fails generation instead. Root-level modifier.
+    pub fn require_repr_c(mut self, require: bool) -> Self {
+        self.require_repr_c = require;
+        self.clear_current();
+        self
+    }
+
+    /// Lower every `Result<T, E>` return to an `i32` status code plus
+    /// out-parameters: `fn f(..) -> Result<T, E>` becomes
+    /// `int32_t f(<inputs>, T *out)`, returning `success` after writing `*out`
+    /// on `Ok`, and `err.into()` on `Err`. Replaces the default
+    /// `bool` / `E *e` lowering, so `E` needs no `.error()` declaration — it
+    /// must implement `Into<i32>` instead, plus `From<String>` when the
+    /// function has a fallible input or [`Self::catch_unwind`] is on. Error
+    /// codes should differ from `success`. Root-level modifier.
+    pub fn result_as_out_param(mut self, success: i32) -> Self {
+        self.result_as_out_param = Some(success);
+        self.clear_current();
+        self
+    }
+
+    /// Mark every exported function wrapper `#[inline]` (placed right after
+    /// `#[no_mangle]`), so that when the bindings crate and the source crate
+    /// are linked into one binary the optimizer can fold the thin wrapper
+    /// into its Rust callers. The C symbol is still exported. Off by default.
+    /// Root-level modifier.
+    pub fn inline_hint(mut self, enable: bool) -> Self {
+        self.inline_hint = enable;
+        self.clear_current();
+        self
+    }
+
+    /// Accept two declared functions exporting the same `#[no_mangle]`
+    /// symbol (after [`.base_name()`](Self::base_name), the function mangler
+    /// and the prefix / suffix). By default such a collision fails the build,
+    /// naming both source locations, unless the two functions' `#[cfg]`s are
+    /// provably exclusive (`target_os = "linux"` vs `target_os = "windows"`,
+    /// `X` vs `not(X)`). Set this when the variants are split by cfgs the
+    /// check can't see through. Root-level modifier.
+    pub fn allow_duplicate_symbols(mut self, allow: bool) -> Self {
+        self.allow_duplicate_symbols = allow;
+        self.clear_current();
+        self
+    }
+
+    /// Restrict the standard-library paths (`std::..`, `core::..`,
+    /// `alloc::..`) the declared functions' signatures may mention to those
+    /// under one of `prefixes` — whole path segments, so `"core::ffi"` allows
+    /// `core::ffi::c_char` but not `core::cell::Cell`. A signature reaching
+    /// any other one fails `resolve`, naming the function and the path.
+    /// Replaces the current list; unrestricted by default. Root-level
+    /// modifier.
+    pub fn allowed_prefixes(mut self, prefixes: &[&str]) -> Self {
+        self.allowed_prefixes = Some(prefixes.iter().map(|p| p.to_string()).collect());
+        self.clear_current();
+        self
+    }
+
+    /// Append `prefix` to the [`.allowed_prefixes()`](Self::allowed_prefixes)
+    /// list, which starts from `std`, `core` and `alloc` when unset.
+    /// Root-level modifier.
+    pub fn allowed_prefix(mut self, prefix: &str) -> Self {
+        self.allowed_prefixes
+            .get_or_insert_with(|| ["std", "core", "alloc"].map(String::from).to_vec())
+            .push(prefix.to_string());
+        self.clear_current();
+        self
+    }
+
+    /// Empty the [`.allowed_prefixes()`](Self::allowed_prefixes) list: any
+    /// `std::` / `core::` / `alloc::` path in a declared signature fails
+    /// `resolve`. Primitives and prelude names (`u8`, `Option`) stay allowed.
+    /// Root-level modifier.
+    pub fn clear_allowed_prefixes(self) -> Self {
+        self.allowed_prefixes(&[])
+    }
+
+    /// Allow only `core::` and `alloc::` paths (and primitives) in the
+    /// declared signatures, so an accidental `std::` type in a crate meant
+    /// for a `no_std` target is caught. Shorthand for
+    /// `.allowed_prefixes(&["core", "alloc"])`. Root-level modifier.
+    pub fn core_only(self) -> Self {
+        self.allowed_prefixes(&["core", "alloc"])
+    }
+
+    /// Cross `bool` parameters and returns (and `type` aliases of `bool`) as
+    /// `u8` in the exported signatures: inputs are decoded with `!= 0`,
+    /// outputs encoded with `as u8`, and the source function still sees a
+    /// `bool`. Sidesteps C ABIs that disagree on `bool`'s width. Data-struct
+    /// fields keep their `bool` layout. Root-level modifier.
+    pub fn bool_as_u8(mut self, enable: bool) -> Self {
+        self.bool_as_u8 = enable;
+        self.clear_current();
+        self
+    }
+
+    /// Cross `Option<&T>` parameters and returns of a scalar `T` (or a `type`
+    /// alias of one) as a plain `*const T`, NULL meaning `None`. The
+    /// converters rebuild the option with `if v.is_null() { None } else {
+    /// Some(&*v) }` and assert that `Option<&T>` has the size of
+    /// `*const T`, so the null niche the C side relies on is checked at
+    /// compile time. Off by default (such options don't resolve).
+    /// Root-level modifier.
+    pub fn option_ref_as_pointer(mut self, enable: bool) -> Self {
+        self.option_ref_as_pointer = enable;
+        self.clear_current();
+        self
+    }
+
+    /// Cross `Box<T>` parameters and returns as an owning `*mut` pointer to
+    /// `T`'s wire (the opaque handle struct, the inline-opaque counterpart,
+    /// or the scalar itself): returns are released with `Box::into_raw`,
+    /// parameters rebuilt with `Box::from_raw` after a null check. The C side
+    /// takes over ownership of a returned box and hands it back through a
+    /// `Box<T>` parameter. Off by default (such signatures don't resolve).
+    /// Root-level modifier.
+    pub fn box_as_raw_pointer(mut self, enable: bool) -> Self {
+        self.box_as_raw_pointer = enable;
+        self.clear_current();
+        self
+    }
+
+    /// Cross every `#[repr(transparent)]` struct with a single scalar field
+    /// (e.g. `pub struct Handle(pub u64)`) that no other declaration claims
+    /// as that scalar, as if it were [`Self::map_type`]d to it: the exported
+    /// signatures take and return the field's type (`u8` for `bool` under
+    /// [`Self::bool_as_u8`]), the converters `transmute`, and the same
+    /// size+align assert is emitted against the field's type. Off by default (such newtypes don't resolve unless
+    /// declared). Root-level modifier.
+    pub fn auto_transparent_newtypes(mut self, enable: bool) -> Self {
+        self.auto_transparent_newtypes = enable;
+        self.clear_current();
+        self
+    }
+
+    /// Fail the build on a [`Self::map_type`] that strips a generic wrapper
+    /// (`W<T>` mapped to `T`) unless `W` is a standard wrapper known to share
+    /// its field's ABI (`MaybeUninit`, `ManuallyDrop`, `Wrapping`, `Reverse`)
+    /// or is listed in `acknowledged`. The transmutes of any other wrapper are
+    /// only sound if it is `#[repr(transparent)]`, which can't be checked
+    /// from the binding crate. Off by default, where such a mapping is
+    /// reported as a `cargo:warning` instead. Root-level modifier.
+    pub fn strict_transparent<S: Into<String>>(
+        mut self,
+        strict: bool,
+        acknowledged: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.strict_transparent = strict;
+        self.acknowledged_wrappers
+            .extend(acknowledged.into_iter().map(Into::into));
+        self.clear_current();
+        self
+    }
+
+    /// Acknowledge `wrapper` as transparent over its generic type argument at
+    /// `index` (lifetimes and consts not counted) — e.g. `1` for a
+    /// `#[repr(transparent)] struct Tagged<Tag, T>` holding a `T` next to a
+    /// `PhantomData<Tag>`. A [`Self::map_type`] of `Tagged<Tag, T>` to `T` is
+    /// then accepted by [`Self::strict_transparent`], while stripping it to
+    /// any other argument is reported like an unknown wrapper. Without this,
+    /// wrappers are assumed transparent over their first argument.
+    /// Root-level modifier.
+    pub fn transparent_wrapper_arg(mut self, wrapper: impl Into<String>, index: usize) -> Self {
+        self.transparent_wrapper_args.insert(wrapper.into(), index);
+        self.clear_current();
+        self
+    }
+
+    /// Declare the exported symbols instead of defining them, for a binding
+    /// crate that links against a cdylib built separately: every
+    /// `#[no_mangle]` function wrapper (and the generated `_drop`, `_take`
+    /// and free functions, and exported statics) becomes a body-less
+    /// declaration in one `extern` block of the wrapper ABI. The C mirrors,
+    /// converters and layout assertions are emitted as usual. Off by default.
+    /// Root-level modifier.
+    pub fn emit_extern_block(mut self, enable: bool) -> Self {
+        self.extern_block = enable;
+        self.clear_current();
+        self
+    }
+
+    /// Carry `#[non_exhaustive]` from a source struct or enum onto its C
+    /// mirror. Off by default: the mirror is a distinct local type, so the
+    /// attribute is dropped from it with a `cargo:warning`. Either way, the
+    /// enum output converter gets a fallback arm (a variant added to the
+    /// source enum later has no C value, so the wrapper panics), and a
+    /// `#[non_exhaustive]` [`Self::data_struct`] can only be returned: the
+    /// binding crate can't construct it, so a function taking it is a build
+    /// error. Root-level modifier.
+    pub fn keep_non_exhaustive(mut self, keep: bool) -> Self {
+        self.keep_non_exhaustive = keep;
+        self.clear_current();
+        self
+    }
+
+    /// Emit a `const` assertion that `usize` is `bits` wide on the target the
+    /// generated file is compiled for. The C mirrors lay `usize` / `isize`
+    /// fields out at the target's width while the C header assumes the
+    /// width the binding was designed for, so a cross build for a target
+    /// with another pointer width must fail instead of corrupting layouts.
+    /// Set this when the bindings are generated for a fixed target (e.g. `32`
+    /// for a 32-bit embedded target). Root-level modifier.
+    ///
+    /// # Panics
+    ///
+    /// If `bits` is not `16`, `32` or `64`.
+    pub fn target_pointer_width(mut self, bits: u8) -> Self {
+        assert!(
+            matches!(bits, 16 | 32 | 64),
+            "Cbindgen::target_pointer_width: unsupported width {bits}; expected 16, 32 or 64"
+        );
+        self.target_pointer_width = Some(bits);
+        self.clear_current();
+        self
+    }
+
+    /// Emit, after each [`Self::enum_type`] mirror, a `const` assertion that
+    /// every variant's discriminant equals the source enum's, so a source
+    /// enum reordered or renumbered since capture fails the build instead of
+    /// silently shifting the C values. On by default. Root-level modifier.
+    pub fn assert_enum_discriminants(mut self, enable: bool) -> Self {
+        self.skip_enum_discriminant_asserts = !enable;
+        self.clear_current();
+        self
+    }
+
+    /// Emit, after each scalar const copied with its initializer, a `const`
+    /// assertion that it equals the source const, so an initializer whose
+    /// referenced consts drifted since capture fails the build instead of
+    /// exporting a stale value. On by default. Root-level modifier.
+    pub fn assert_const_values(mut self, enable: bool) -> Self {
+        self.skip_const_value_asserts = !enable;
+        self.clear_current();
+        self
+    }
+
+    /// Export each [`Self::enum_type`] with a `#[repr(<int>)]` (e.g.
+    /// `#[repr(i32)]`) as an integer type alias plus one `const` per variant
+    /// (`pub type inside_foo_t = i32;`,
+    /// `pub const INSIDE_FOO_DOUDDLE_DEE: inside_foo_t = 42;`) instead of a
+    /// `#[repr(C)]` Rust enum, for C toolchains that handle those poorly.
+    /// Values are converted by matching the constants, so an input that
+    /// matches none is a conversion error. Enums without an integer repr
+    /// keep their mirror. Off by default. Root-level modifier.
+    pub fn c_enum_as_integer(mut self, enable: bool) -> Self {
+        self.c_enum_as_integer = enable;
+        self.clear_current();
+        self
+    }
+
+    /// Skip a declared function whose signature uses a type no declaration
+    /// converts, instead of failing the build: it is reported as a
+    /// `cargo:warning` naming its source location and the offending type,
+    /// and the rest of the binding is generated without it. Handy while
+    /// porting an API incrementally. Off by default. Root-level modifier.
+    pub fn skip_invalid_items(mut self, skip: bool) -> Self {
+        self.skip_invalid_items = skip;
+        self.clear_current();
+        self
+    }
+
+    /// Add `derive` (e.g. `Debug`) to the `#[derive(...)]` list of every
+    /// generated `#[repr(C)]` data struct, `repr_c_struct` mirror and enum, for
+    /// binding generators or consumers that need it. A derive already present
+    /// is not repeated; opaque handles (incomplete C types) are unaffected.
+    /// Repeatable. Root-level modifier.
+    pub fn add_derive(mut self, derive: syn::Path) -> Self {
+        if !self.derives.contains(&derive) {
+            self.derives.push(derive);
+        }
+        self.clear_current();
+        self
+    }
+
     /// Declare a `#[prebindgen]` function to convert into the C layer.
     pub fn function(mut self, ident: syn::Ident) -> Self {
         assert!(
@@ -294,6 +782,31 @@ impl Cbindgen {
         self
     }
 
+    /// Cross `from` as the FFI type `to` by bitwise reinterpretation (e.g.
+    /// `chrono::DateTime<Utc>` → `i64`), wherever it appears — by value, behind
+    /// a reference or pointer, or as a generic argument of a supported wrapper.
+    /// Root-level modifier (resets the current declaration).
+    ///
+    /// The converters are a plain `transmute`, so **the caller is responsible
+    /// for the ABI compatibility of the pair**: only the size and alignment are
+    /// checked, by a fail-closed compile-time assert like
+    /// [`Self::opaque_data_struct`]'s.
+    pub fn map_type(mut self, from: syn::Type, to: syn::Type) -> Self {
+        let key = TypeKey::from_type(&from);
+        assert!(
+            self.type_cfg(&from).is_none() && !self.ignored_types.contains(&key),
+            "Cbindgen::map_type cannot map `{}` because it is already declared",
+            key
+        );
+        assert!(
+            self.mapped.insert(key.clone(), to).is_none(),
+            "Cbindgen::map_type: `{}` is already mapped",
+            key
+        );
+        self.clear_current();
+        self
+    }
+
     /// Mark a `#[prebindgen]` type as intentionally ignored by this adapter.
     /// Root-level modifier: suppresses the registry's "skipping undeclared"
     /// warning for that type without scanning or emitting it.
@@ -303,7 +816,8 @@ impl Cbindgen {
             !self.opaque.contains_key(&key)
                 && !self.data.contains_key(&key)
                 && !self.value_opaque.contains_key(&key)
-                && !self.enums.contains_key(&key),
+                && !self.enums.contains_key(&key)
+                && !self.mapped.contains_key(&key),
             "Cbindgen::ignore_type cannot ignore `{}` because it is already declared",
             key
         );
@@ -480,6 +994,205 @@ impl Cbindgen {
         ty.clone()
     }
 
+    /// `(source type, C wire type)` of a scalar `ty`: the scalar itself, or
+    /// for a source `type` alias chain ending in a scalar
+    /// ([`Registry::alias_target`]) the qualified alias and that scalar.
+    pub(super) fn scalar_wire(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<(syn::Type, syn::Type)> {
+        if is_scalar(ty) {
+            return Some((ty.clone(), ty.clone()));
+        }
+        let target = registry.alias_target(ty).filter(is_scalar)?;
+        Some((self.src_ty(ty), target))
+    }
+
+    /// If `ty` is `&T` with `T` a scalar (see [`Self::scalar_wire`]) and
+    /// [`Self::option_ref_as_pointer`] is on, `T`'s `(source, wire)` types.
+    pub(super) fn pointer_scalar_ref(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<(syn::Type, syn::Type)> {
+        if !self.option_ref_as_pointer {
+            return None;
+        }
+        let syn::Type::Reference(rf) = ty else {
+            return None;
+        };
+        if rf.mutability.is_some() {
+            return None;
+        }
+        self.scalar_wire(&rf.elem, registry)
+    }
+
+    /// The wire `ty` is reinterpreted as: its [`Self::map_type`] target, or
+    /// the field's scalar for a [`Self::auto_transparent_newtypes`] newtype.
+    pub(super) fn mapped_wire(&self, ty: &syn::Type, registry: &Registry<()>) -> Option<syn::Type> {
+        match self.mapped.get(&TypeKey::from_type(ty)) {
+            Some(wire) => Some(wire.clone()),
+            None => self.transparent_newtype_wire(ty, registry),
+        }
+    }
+
+    /// Under [`Self::auto_transparent_newtypes`], the scalar wire of `ty` if
+    /// it is an undeclared, non-generic `#[repr(transparent)]` struct whose
+    /// only field is a scalar (or a `type` alias of one).
+    pub(super) fn transparent_newtype_wire(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<syn::Type> {
+        if !self.auto_transparent_newtypes {
+            return None;
+        }
+        let key = TypeKey::from_type(ty);
+        if self.type_cfg(ty).is_some() || self.ignored_types.contains(&key) {
+            return None;
+        }
+        let ident = type_path_tail(ty)?;
+        let (item, _) = registry.structs.get(&ident)?;
+        if !item.generics.params.is_empty() || item.fields.len() != 1 {
+            return None;
+        }
+        let mut transparent = false;
+        for attr in item.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+            let _ = attr.parse_nested_meta(|meta| {
+                transparent |= meta.path.is_ident("transparent");
+                Ok(())
+            });
+        }
+        if !transparent {
+            return None;
+        }
+        let field = item.fields.iter().next()?;
+        let (_, wire) = self.scalar_wire(&field.ty, registry)?;
+        Some(wire)
+    }
+
+    /// If `ty` is `Box<T>` and [`Self::box_as_raw_pointer`] is on, `T` and
+    /// the pointee of its `*mut` wire: the handle struct of an opaque `T`,
+    /// the counterpart of an inline-opaque one, or a scalar `T` itself.
+    pub(super) fn box_pointee(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<(syn::Type, syn::Type)> {
+        if !self.box_as_raw_pointer {
+            return None;
+        }
+        let inner = box_inner(ty)?;
+        if self.opaque.contains_key(&TypeKey::from_type(&inner)) {
+            let c_struct = self.c_type_ident(&inner);
+            return Some((inner, syn::parse_quote!(#c_struct)));
+        }
+        if let Some(counterpart) = self.value_opaque_ty(&inner) {
+            let counterpart = counterpart.clone();
+            return Some((inner, counterpart));
+        }
+        let (_, wire) = self.scalar_wire(&inner, registry)?;
+        Some((inner, wire))
+    }
+
+    /// The C wire of a function pointer `ty` (`extern "C" fn(..) -> R`, or
+    /// an `Option` of one) and the data structs its signature points to
+    ///
+    /// The wire is the same signature with every reference, `Option` of a
+    /// reference or raw pointer to a scalar or data struct replaced by a raw
+    /// pointer to its C wire, at any depth of nested function pointers.
+    /// Scalars cross as themselves. `None` if `ty` is not such a function
+    /// pointer or another type appears in its signature.
+    pub(super) fn fn_pointer_wire(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<(syn::Type, Vec<syn::Type>)> {
+        let mut pointees = Vec::new();
+        let wire = self.fn_pointer_wire_into(ty, registry, &mut pointees)?;
+        Some((wire, pointees))
+    }
+
+    fn fn_pointer_wire_into(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+        pointees: &mut Vec<syn::Type>,
+    ) -> Option<syn::Type> {
+        if let Some(inner) = is_option(ty).then(|| first_type_arg(ty)).flatten() {
+            if !matches!(inner, syn::Type::BareFn(_)) {
+                return None;
+            }
+            let wire = self.fn_pointer_wire_into(&inner, registry, pointees)?;
+            return Some(syn::parse_quote!(::core::option::Option<#wire>));
+        }
+        let syn::Type::BareFn(f) = ty else {
+            return None;
+        };
+        if f.abi.is_none() || f.variadic.is_some() {
+            return None;
+        }
+        let mut wire = f.clone();
+        wire.lifetimes = None;
+        for input in &mut wire.inputs {
+            input.ty = self.fn_pointer_arg_wire(&input.ty, registry, pointees)?;
+        }
+        if let syn::ReturnType::Type(_, out) = &mut wire.output {
+            **out = self.fn_pointer_arg_wire(out, registry, pointees)?;
+        }
+        Some(syn::Type::BareFn(wire))
+    }
+
+    /// The wire of one parameter or the return of a function pointer; see
+    /// [`Self::fn_pointer_wire`].
+    fn fn_pointer_arg_wire(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+        pointees: &mut Vec<syn::Type>,
+    ) -> Option<syn::Type> {
+        if let Some(wire) = self.fn_pointer_wire_into(ty, registry, pointees) {
+            return Some(wire);
+        }
+        let ty = is_option(ty)
+            .then(|| first_type_arg(ty))
+            .flatten()
+            .filter(|inner| matches!(inner, syn::Type::Reference(_)))
+            .unwrap_or_else(|| ty.clone());
+        let (elem, mutable) = match &ty {
+            syn::Type::Reference(r) => (&*r.elem, r.mutability.is_some()),
+            syn::Type::Ptr(p) => (&*p.elem, p.mutability.is_some()),
+            _ => return self.scalar_wire(&ty, registry).map(|(_, wire)| wire),
+        };
+        let pointee: syn::Type = if type_path_tail(elem).is_some_and(|i| i == "c_void") {
+            syn::parse_quote!(::core::ffi::c_void)
+        } else if self.data.contains_key(&TypeKey::from_type(elem))
+            && !self.is_non_exhaustive(registry, elem)
+        {
+            pointees.push(elem.clone());
+            let c_struct = self.c_type_ident(elem);
+            syn::parse_quote!(#c_struct)
+        } else {
+            self.scalar_wire(elem, registry)?.1
+        };
+        Some(if mutable {
+            syn::parse_quote!(*mut #pointee)
+        } else {
+            syn::parse_quote!(*const #pointee)
+        })
+    }
+
+    /// The parameter / return wire of a scalar whose [`Self::scalar_wire`] is
+    /// `wire`: `u8` for `bool` under [`Self::bool_as_u8`], else `wire` itself.
+    pub(super) fn scalar_crossing(&self, wire: syn::Type) -> syn::Type {
+        if self.bool_as_u8 && type_path_tail(&wire).is_some_and(|i| i == "bool") {
+            syn::parse_quote!(u8)
+        } else {
+            wire
+        }
+    }
+
     /// Path to a source function (e.g. `zenoh_flat::z_keyexpr_try_from`).
     pub(super) fn src_fn(&self, ident: &syn::Ident) -> syn::Path {
         match &self.source_module {
@@ -557,6 +1270,57 @@ impl Cbindgen {
         }
     }
 
+    /// Like [`Self::src_ty`], but a generic type is qualified segment by
+    /// segment: every path naming a `#[prebindgen]` struct or enum, at any
+    /// depth of generic arguments, gets its origin module, and everything else
+    /// is kept as written. So `core::mem::MaybeUninit<Wrapper<Foo>>` becomes
+    /// `core::mem::MaybeUninit<myflat::Wrapper<myflat::Foo>>`, where `src_ty`
+    /// would leave it unchanged (and `Wrapper<Foo>` half-qualified).
+    pub(super) fn src_ty_exported(&self, ty: &syn::Type, registry: &Registry<()>) -> syn::Type {
+        use syn::visit_mut::VisitMut;
+        struct Qualify<'a> {
+            cbindgen: &'a Cbindgen,
+            registry: &'a Registry<()>,
+        }
+        impl VisitMut for Qualify<'_> {
+            fn visit_type_path_mut(&mut self, tp: &mut syn::TypePath) {
+                syn::visit_mut::visit_type_path_mut(self, tp);
+                if tp.qself.is_some() || tp.path.leading_colon.is_some() {
+                    return;
+                }
+                let [segment] = Vec::from_iter(tp.path.segments.iter())[..] else {
+                    return;
+                };
+                let ident = &segment.ident;
+                if !self.registry.structs.contains_key(ident)
+                    && !self.registry.enums.contains_key(ident)
+                {
+                    return;
+                }
+                let module = self
+                    .registry
+                    .origin_module(ident)
+                    .or_else(|| self.cbindgen.source_module.clone());
+                if let Some(mut path) = module {
+                    path.segments.push(segment.clone());
+                    tp.path = path;
+                }
+            }
+        }
+        let bare = matches!(ty, syn::Type::Path(tp)
+            if tp.path.segments.iter().all(|s| s.arguments.is_none()));
+        if bare {
+            return self.src_ty(ty);
+        }
+        let mut ty = ty.clone();
+        Qualify {
+            cbindgen: self,
+            registry,
+        }
+        .visit_type_mut(&mut ty);
+        ty
+    }
+
     pub(super) fn in_name(ty: &syn::Type) -> syn::Ident {
         format_ident!("__cbg_in_{}", sanitize(&TypeKey::from_type(ty)))
     }
@@ -582,6 +1346,31 @@ impl Cbindgen {
             .map(|c| &c.opaque)
     }
 
+    /// For a fixed-size array `[E; N]` (nested arrays allowed) whose leaf
+    /// element is a scalar or a declared inline-opaque by-value type: the
+    /// source array type, its C wire (the same shape over the leaf's wire,
+    /// e.g. `[foo_t; 3]` for `[Foo; 3]`) and the leaf element.
+    pub(super) fn array_wire(&self, ty: &syn::Type) -> Option<(syn::Type, syn::Type, syn::Type)> {
+        let syn::Type::Array(a) = ty else {
+            return None;
+        };
+        let len = &a.len;
+        let (src, wire, leaf) = match &*a.elem {
+            elem @ syn::Type::Array(_) => self.array_wire(elem)?,
+            elem if is_scalar(elem) => (elem.clone(), elem.clone(), elem.clone()),
+            elem => (
+                self.src_ty(elem),
+                self.value_opaque_ty(elem)?.clone(),
+                elem.clone(),
+            ),
+        };
+        Some((
+            syn::parse_quote!([#src; #len]),
+            syn::parse_quote!([#wire; #len]),
+            leaf,
+        ))
+    }
+
     /// Type keys used as a takeable callback parameter (any `.takeable_param(idx)`
     /// across all declared callbacks). These value_opaque types get a public
     /// `<base>_take(dst, src)` move function.
@@ -639,6 +1428,23 @@ impl Cbindgen {
         format_ident!("{}", self.c_type_name(ty))
     }
 
+    /// The integer a declared enum is exported as under
+    /// [`Self::c_enum_as_integer`]: its `#[repr(<int>)]`, if it has one.
+    pub(super) fn enum_as_integer(&self, e: &syn::ItemEnum) -> Option<syn::Ident> {
+        self.c_enum_as_integer.then(|| enum_int_repr(e)).flatten()
+    }
+
+    /// Name of the const exporting `variant` of an enum flattened by
+    /// [`Self::c_enum_as_integer`]: `<BASE>_<VARIANT>` in screaming snake case
+    /// (`INSIDE_FOO_DOUDDLE_DEE`).
+    pub(super) fn enum_variant_const(&self, ty: &syn::Type, variant: &syn::Ident) -> syn::Ident {
+        format_ident!(
+            "{}_{}",
+            self.rust_base(ty).to_uppercase(),
+            snake_case(&variant.to_string()).to_uppercase()
+        )
+    }
+
     /// Destructor symbol of an opaque handle: [`Self::mangle_destructor`] over the
     /// base, else `<base>_drop`.
     pub(super) fn destructor_symbol(&self, ty: &syn::Type) -> syn::Ident {
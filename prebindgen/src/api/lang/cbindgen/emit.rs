@@ -1,16 +1,18 @@
 use super::*;
+use crate::{
+    api::{collect::destination::type_text, core::const_path_alias, utils::edition::RustEdition},
+    SourceLocation,
+};
 
 impl Cbindgen {
     /// Whether the generated layer hands `char*` data memory to C — a `String`
     /// return value, or a declared data struct that is produced as output and has
     /// a `String` field. When true, a `free_memory_function` must be declared.
     pub(super) fn needs_free(&self, registry: &Registry<()>) -> bool {
-        let string_ty: syn::Type = syn::parse_quote!(String);
         // A `String` return hands out a `char*` — unless `String` is declared
-        // `opaque_ptr` (then it crosses as `string_t *`, freed by `string_drop`).
-        if registry.output_entry(&string_ty).is_some()
-            && !self.opaque.contains_key(&TypeKey::from_type(&string_ty))
-        {
+        // `opaque_ptr` (then it crosses as `string_t *`, freed by `string_drop`)
+        // or returned as a `CString` (freed by the `string_as_c_char` freer).
+        if self.returns_string(registry) && self.cstring_free_fn.is_none() {
             return true;
         }
         // Opaque error types are marshalled to a malloc'd `char*` message.
@@ -31,6 +33,14 @@ impl Cbindgen {
         })
     }
 
+    /// Whether a `String` is returned to C as a `char*`, rather than as a
+    /// `string_t *` handle of a `String` declared `opaque_ptr`.
+    pub(super) fn returns_string(&self, registry: &Registry<()>) -> bool {
+        let string_ty: syn::Type = syn::parse_quote!(String);
+        registry.output_entry(&string_ty).is_some()
+            && !self.opaque.contains_key(&TypeKey::from_type(&string_ty))
+    }
+
     /// Whether any declared function returns a `Vec<_>` (possibly nested under
     /// `Result`/`Option`), so the array builder/freer prelude must be emitted.
     pub(super) fn produces_array(&self, registry: &Registry<()>) -> bool {
@@ -47,8 +57,10 @@ impl Cbindgen {
     }
 
     /// Fields (`name`, `type`) of a declared data struct, looked up from the
-    /// registry's indexed structs. `None` if the type isn't an indexed named
-    /// struct.
+    /// registry's indexed structs. The fields of a tuple struct are named
+    /// `_0`, `_1`, … on the C side; [`source_member`] maps such a name back to
+    /// the source field. `None` if the type isn't an indexed struct with
+    /// fields.
     pub(super) fn struct_fields(
         &self,
         registry: &Registry<()>,
@@ -56,17 +68,293 @@ impl Cbindgen {
     ) -> Option<Vec<(syn::Ident, syn::Type)>> {
         let ident = type_path_tail(ty)?;
         let (item, _) = registry.structs.get(&ident)?;
-        if let syn::Fields::Named(named) = &item.fields {
-            Some(
+        match &item.fields {
+            syn::Fields::Named(named) => Some(
                 named
                     .named
                     .iter()
                     .map(|f| (f.ident.clone().unwrap(), f.ty.clone()))
                     .collect(),
-            )
+            ),
+            syn::Fields::Unnamed(unnamed) => Some(
+                unnamed
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| (format_ident!("_{}", i), f.ty.clone()))
+                    .collect(),
+            ),
+            syn::Fields::Unit => None,
+        }
+    }
+
+    /// The source type of a declared data struct with each of its lifetime
+    /// parameters set to `lifetime` — plain [`Self::src_ty`] for a struct
+    /// without any. The C mirror has no lifetimes (borrowed fields become
+    /// raw pointers), so the converters name them explicitly.
+    pub(super) fn src_ty_with_lifetimes(
+        &self,
+        registry: &Registry<()>,
+        ty: &syn::Type,
+        lifetime: &syn::Lifetime,
+    ) -> syn::Type {
+        let mut src = self.src_ty(ty);
+        let count = type_path_tail(ty)
+            .and_then(|ident| registry.structs.get(&ident))
+            .map_or(0, |(item, _)| item.generics.lifetimes().count());
+        if count > 0 {
+            if let syn::Type::Path(tp) = &mut src {
+                let lifetimes = std::iter::repeat_n(lifetime, count);
+                if let Some(last) = tp.path.segments.last_mut() {
+                    last.arguments =
+                        syn::PathArguments::AngleBracketed(syn::parse_quote!(<#(#lifetimes),*>));
+                }
+            }
+        }
+        src
+    }
+
+    /// Whether the source struct `ty` is `#[repr(C)]` or `#[repr(transparent)]`
+    /// (`None` if it isn't a captured struct).
+    pub(super) fn struct_has_c_layout(
+        &self,
+        registry: &Registry<()>,
+        ty: &syn::Type,
+    ) -> Option<bool> {
+        let ident = type_path_tail(ty)?;
+        let (item, _) = registry.structs.get(&ident)?;
+        let mut c_layout = false;
+        for attr in item.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+            // `repr(C, align(8))` etc.: only the presence of `C`/`transparent` matters.
+            let _ = attr.parse_nested_meta(|meta| {
+                c_layout |= meta.path.is_ident("C") || meta.path.is_ident("transparent");
+                if meta.input.peek(syn::token::Paren) {
+                    let _content;
+                    syn::parenthesized!(_content in meta.input);
+                }
+                Ok(())
+            });
+        }
+        Some(c_layout)
+    }
+
+    /// The `#[doc]` attributes among `attrs`, carried onto a generated item —
+    /// none when [`Self::strip_docs`] is set.
+    pub(super) fn docs(&self, attrs: &[syn::Attribute]) -> Vec<syn::Attribute> {
+        if self.strip_docs {
+            return Vec::new();
+        }
+        attrs
+            .iter()
+            .filter(|a| a.path().is_ident("doc"))
+            .cloned()
+            .collect()
+    }
+
+    /// The attributes of a source function carried onto its wrapper, in source
+    /// order: its [`Self::docs`], `#[deprecated]` and the
+    /// [`Self::keep_attribute`] ones.
+    fn kept_fn_attrs(&self, attrs: &[syn::Attribute]) -> Vec<syn::Attribute> {
+        attrs
+            .iter()
+            .filter(|a| {
+                let Some(name) = a.path().get_ident() else {
+                    return false;
+                };
+                (name == "doc" && !self.strip_docs)
+                    || name == "deprecated"
+                    || self.kept_attributes.iter().any(|k| name == k)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Docs of a declared type's indexed struct / enum item, followed by its
+    /// [`Self::provenance_doc`] (none when the type isn't a `#[prebindgen]`
+    /// item, e.g. a foreign opaque handle).
+    pub(super) fn type_docs(&self, registry: &Registry<()>, ty: &syn::Type) -> Vec<syn::Attribute> {
+        let Some(ident) = type_path_tail(ty) else {
+            return Vec::new();
+        };
+        let (mut docs, loc) = if let Some((s, loc)) = registry.structs.get(&ident) {
+            (self.docs(&s.attrs), loc)
+        } else if let Some((e, loc)) = registry.enums.get(&ident) {
+            (self.docs(&e.attrs), loc)
         } else {
-            None
+            return Vec::new();
+        };
+        docs.extend(self.provenance_doc(loc));
+        docs
+    }
+
+    /// The `#[doc = " from <crate>:<file>:<line>"]` naming where a generated
+    /// item's source was captured, when [`Self::annotate_provenance`] is on.
+    /// The crate is left out for items ingested without an origin stamp.
+    pub(super) fn provenance_doc(&self, loc: &SourceLocation) -> Option<syn::Attribute> {
+        if !self.annotate_provenance {
+            return None;
         }
+        let text = match &loc.crate_name {
+            Some(krate) => format!(" from {krate}:{}:{}", loc.file, loc.line),
+            None => format!(" from {}:{}", loc.file, loc.line),
+        };
+        Some(syn::parse_quote!(#[doc = #text]))
+    }
+
+    /// Whether the source struct or enum `ty` is `#[non_exhaustive]`.
+    pub(super) fn is_non_exhaustive(&self, registry: &Registry<()>, ty: &syn::Type) -> bool {
+        let Some(ident) = type_path_tail(ty) else {
+            return false;
+        };
+        let attrs = match (registry.structs.get(&ident), registry.enums.get(&ident)) {
+            (Some((s, _)), _) => &s.attrs,
+            (None, Some((e, _))) => &e.attrs,
+            (None, None) => return false,
+        };
+        attrs.iter().any(|a| a.path().is_ident("non_exhaustive"))
+    }
+
+    /// The first `#[non_exhaustive]` [`Cbindgen::data_struct`] mentioned
+    /// anywhere in `ty` (by value, behind a reference or as a generic
+    /// argument).
+    pub(super) fn non_exhaustive_data_in(
+        &self,
+        registry: &Registry<()>,
+        ty: &syn::Type,
+    ) -> Option<syn::Type> {
+        fn idents(tokens: TokenStream, out: &mut Vec<syn::Ident>) {
+            for tt in tokens {
+                match tt {
+                    proc_macro2::TokenTree::Ident(ident) => out.push(ident),
+                    proc_macro2::TokenTree::Group(group) => idents(group.stream(), out),
+                    _ => {}
+                }
+            }
+        }
+        let mut found = Vec::new();
+        idents(ty.to_token_stream(), &mut found);
+        // Keywords (`mut`, `dyn`, ...) don't parse as a type and are skipped.
+        found
+            .into_iter()
+            .filter_map(|ident| syn::parse2(quote!(#ident)).ok())
+            .find(|ty| {
+                self.data.contains_key(&TypeKey::from_type(ty))
+                    && self.is_non_exhaustive(registry, ty)
+            })
+    }
+
+    /// The first standard-library path (`std::..`, `core::..`, `alloc::..`)
+    /// mentioned anywhere in `ty` that no [`Cbindgen::allowed_prefixes`]
+    /// entry covers. Always `None` while the list is unset.
+    pub(super) fn disallowed_path_in(&self, ty: &syn::Type) -> Option<String> {
+        fn flatten(tokens: TokenStream, out: &mut Vec<proc_macro2::TokenTree>) {
+            for tt in tokens {
+                match tt {
+                    proc_macro2::TokenTree::Group(group) => flatten(group.stream(), out),
+                    tt => out.push(tt),
+                }
+            }
+        }
+        let allowed = self.allowed_prefixes.as_ref()?;
+        let is_colon = |tt: Option<&proc_macro2::TokenTree>| matches!(tt, Some(proc_macro2::TokenTree::Punct(p)) if p.as_char() == ':');
+        let mut tokens = Vec::new();
+        flatten(ty.to_token_stream(), &mut tokens);
+        for (i, tt) in tokens.iter().enumerate() {
+            let proc_macro2::TokenTree::Ident(head) = tt else {
+                continue;
+            };
+            if !matches!(head.to_string().as_str(), "std" | "core" | "alloc") {
+                continue;
+            }
+            // `a::std::..` is not a standard-library root.
+            if i >= 3
+                && is_colon(tokens.get(i - 1))
+                && matches!(tokens[i - 3], proc_macro2::TokenTree::Ident(_))
+            {
+                continue;
+            }
+            let mut segments = vec![head.to_string()];
+            let mut j = i + 1;
+            while is_colon(tokens.get(j)) && is_colon(tokens.get(j + 1)) {
+                let Some(proc_macro2::TokenTree::Ident(next)) = tokens.get(j + 2) else {
+                    break;
+                };
+                segments.push(next.to_string());
+                j += 3;
+            }
+            let covered = allowed.iter().any(|prefix| {
+                let prefix: Vec<&str> = prefix.split("::").map(str::trim).collect();
+                prefix.len() <= segments.len() && prefix.iter().zip(&segments).all(|(p, s)| p == s)
+            });
+            if !covered {
+                return Some(segments.join("::"));
+            }
+        }
+        None
+    }
+
+    /// `#[non_exhaustive]` for the C mirror of a `#[non_exhaustive]` source
+    /// type under [`Cbindgen::keep_non_exhaustive`]; otherwise the attribute
+    /// is dropped from the mirror with a warning.
+    pub(super) fn mirror_non_exhaustive(
+        &self,
+        registry: &Registry<()>,
+        ty: &syn::Type,
+    ) -> Option<syn::Attribute> {
+        if !self.is_non_exhaustive(registry, ty) {
+            return None;
+        }
+        if self.keep_non_exhaustive {
+            return Some(syn::parse_quote!(#[non_exhaustive]));
+        }
+        println!(
+            "cargo:warning=prebindgen: `#[non_exhaustive]` of `{}` is not carried onto its C \
+             mirror `{}`",
+            type_short(ty),
+            self.c_type_ident(ty)
+        );
+        None
+    }
+
+    /// Docs of field `fname` of a declared struct type.
+    pub(super) fn field_docs(
+        &self,
+        registry: &Registry<()>,
+        ty: &syn::Type,
+        fname: &syn::Ident,
+    ) -> Vec<syn::Attribute> {
+        source_field(registry, ty, fname)
+            .map(|f| self.docs(&f.attrs))
+            .unwrap_or_default()
+    }
+
+    /// `#[cfg]` attributes of field `fname` of a declared struct type. They go
+    /// on the mirror's field and on its initializers in the converters, so a
+    /// per-target field keeps the mirror's layout equal to the source's.
+    pub(super) fn field_cfgs(
+        &self,
+        registry: &Registry<()>,
+        ty: &syn::Type,
+        fname: &syn::Ident,
+    ) -> Vec<syn::Attribute> {
+        source_field(registry, ty, fname)
+            .map(|f| cfg_attrs(&f.attrs).into_iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// `#[derive(...)]` for a generated mirror type: its built-in `base` derives
+    /// followed by the [`Self::add_derive`] ones not already listed (compared
+    /// by last path segment, so `Debug` and `::core::fmt::Debug` are one).
+    /// `None` when the list is empty.
+    pub(super) fn derive_attr(&self, base: &[syn::Path]) -> Option<syn::Attribute> {
+        let name = |p: &syn::Path| p.segments.last().map(|s| s.ident.clone());
+        let mut derives: Vec<&syn::Path> = Vec::new();
+        for d in base.iter().chain(&self.derives) {
+            if !derives.iter().any(|have| name(have) == name(d)) {
+                derives.push(d);
+            }
+        }
+        (!derives.is_empty()).then(|| syn::parse_quote!(#[derive(#(#derives),*)]))
     }
 
     /// Wire type of a `repr_c_struct` field in the generated **visible** mirror: a
@@ -101,16 +389,27 @@ impl Cbindgen {
 
     /// Exported `#[no_mangle]` symbol for a declared function:
     /// [`Self::mangle_function`] over the base — a `.base_name(...)` override when
-    /// set, else the Rust fn ident — or that base verbatim when no mangler is set.
+    /// set, else the Rust fn ident — or that base verbatim when no mangler is set
+    /// — wrapped in the [`Self::symbol_prefix`] / [`Self::symbol_suffix`].
     pub(super) fn fn_symbol(&self, orig: &syn::Ident) -> syn::Ident {
         let base = self
             .functions
             .get(orig)
             .and_then(|c| c.base.clone())
             .unwrap_or_else(|| orig.to_string());
-        match &self.mangle_function {
-            Some(f) => format_ident!("{}", f(&base)),
-            None => format_ident!("{}", base),
+        let mangled = match &self.mangle_function {
+            Some(f) => f(&base),
+            None => base,
+        };
+        format_ident!("{}{}{}", self.symbol_prefix, mangled, self.symbol_suffix)
+    }
+
+    /// The symbol the wrapper of `orig` is exported under: its
+    /// [`Self::export_name_map`] entry, else [`Self::fn_symbol`].
+    pub(super) fn exported_symbol(&self, orig: &syn::Ident) -> String {
+        match self.export_names.get(orig) {
+            Some(symbol) => symbol.clone(),
+            None => self.fn_symbol(orig).to_string(),
         }
     }
 
@@ -145,31 +444,37 @@ impl Cbindgen {
             None => (return_ty.clone(), None),
         };
         let has_fallible_output = Self::output_is_fallible(&value_ty, registry);
+        // `result_as_out_param`: the `Result` becomes an `i32` status code, with
+        // the error type's own code for `Err` and no `E *e` out-param.
+        let success_code = err_ty.as_ref().and(self.result_as_out_param);
 
         // Error wiring: the error type must be declared via `.error()`.
-        let err_bits = err_ty.as_ref().map(|err_ty| {
-            assert!(
-                self.error.contains(&TypeKey::from_type(err_ty)),
-                "Cbindgen: function `{}` returns `Result<_, {}>` but `{}` is not a \
+        let err_bits = err_ty
+            .as_ref()
+            .filter(|_| success_code.is_none())
+            .map(|err_ty| {
+                assert!(
+                    self.error.contains(&TypeKey::from_type(err_ty)),
+                    "Cbindgen: function `{}` returns `Result<_, {}>` but `{}` is not a \
                  declared error type — add `.data_struct({}).error()`",
-                orig,
-                TypeKey::from_type(err_ty),
-                TypeKey::from_type(err_ty),
-                TypeKey::from_type(err_ty),
-            );
-            let entry = registry.output_entry(err_ty).unwrap_or_else(|| {
-                panic!(
-                    "Cbindgen::on_function: error type `{}` of `{}` has no output converter",
+                    orig,
                     TypeKey::from_type(err_ty),
-                    orig
+                    TypeKey::from_type(err_ty),
+                    TypeKey::from_type(err_ty),
+                );
+                let entry = registry.output_entry(err_ty).unwrap_or_else(|| {
+                    panic!(
+                        "Cbindgen::on_function: error type `{}` of `{}` has no output converter",
+                        TypeKey::from_type(err_ty),
+                        orig
+                    )
+                });
+                (
+                    entry.destination.clone(),
+                    entry.function.sig.ident.clone(),
+                    self.src_ty(err_ty),
                 )
             });
-            (
-                entry.destination.clone(),
-                entry.function.sig.ident.clone(),
-                self.src_ty(err_ty),
-            )
-        });
 
         // No `Result` channel ⇒ a fallible input must be declared `.panic()`.
         if err_ty.is_none() {
@@ -190,7 +495,7 @@ impl Cbindgen {
         //   * no Result                       → field 0 is the C return, rest out.
         let shape = self.lower_shape(&value_ty, registry);
         let result_slot = shape.niches.clone().carve().map(|(slot, _)| slot);
-        let result_in_band = err_ty.is_some() && result_slot.is_some();
+        let result_in_band = err_bits.is_some() && result_slot.is_some();
         let field0_is_return = result_in_band || err_ty.is_none();
 
         // Partition fields into the (optional) C return value + out-parameters,
@@ -230,9 +535,12 @@ impl Cbindgen {
             .collect();
 
         // C wrapper return type: the payload's field 0 (modes A/D), `bool` status
-        // (mode B), or `void` (a unit value with no `Result`).
+        // (mode B), `i32` status (`result_as_out_param`), or `void` (a unit value
+        // with no `Result`).
         let c_return: Option<syn::Type> = if field0_is_return {
             field0_wire.clone()
+        } else if success_code.is_some() {
+            Some(syn::parse_quote!(i32))
         } else {
             Some(syn::parse_quote!(bool))
         };
@@ -246,13 +554,16 @@ impl Cbindgen {
         } else {
             quote!(false)
         };
-        let input_route = match &err_bits {
-            Some((_, e_conv, e_ty_src)) => ErrRoute::Result {
+        let input_route = match (&err_bits, &err_ty) {
+            (Some((_, e_conv, e_ty_src)), _) => ErrRoute::Result {
                 e_conv,
                 e_ty_src: e_ty_src.clone(),
                 fail_return: fail_return.clone(),
             },
-            None => ErrRoute::Panic,
+            (None, Some(err_ty)) => ErrRoute::Code {
+                e_ty_src: self.src_ty(err_ty),
+            },
+            (None, None) => ErrRoute::Panic,
         };
         let (in_params, decodes, call_args) = self.emit_inputs(orig, f, registry, &input_route);
         let call = quote!(#call_path(#(#call_args),*));
@@ -262,8 +573,23 @@ impl Cbindgen {
             .map(|(err_wire, _, _)| quote!(e: *mut #err_wire));
         let ret_arrow = c_return.as_ref().map(|w| quote!(-> #w));
 
-        // Assemble the body per the three structural modes.
+        // Assemble the body per the three structural modes (plus the `i32`
+        // status variant of mode B).
         let body = match (&err_bits, field0_is_return) {
+            // `Result` under `result_as_out_param`: status code, value to out-params.
+            (None, _) if success_code.is_some() => {
+                let enc =
+                    self.encode_value(&value_ty, quote!(__v), &targets, registry, &input_route);
+                quote!(
+                    #(#decodes)*
+                    match #call {
+                        ::core::result::Result::Ok(__v) => { #enc #success_code }
+                        ::core::result::Result::Err(__err) => {
+                            ::core::convert::Into::<i32>::into(__err)
+                        }
+                    }
+                )
+            }
             // No `Result`: straight-line. `void` when there are no fields.
             (None, _) => {
                 if let Some(field0_wire) = field0_wire.as_ref() {
@@ -317,10 +643,47 @@ impl Cbindgen {
             }
         };
 
+        let body = self.catch_unwind_body(orig, body, c_return.as_ref(), &input_route);
+
+        let mut attrs = self.kept_fn_attrs(&f.attrs);
+        if let Some((_, loc)) = registry.functions.get(orig) {
+            attrs.extend(self.provenance_doc(loc));
+        }
+        if self.emit_safety_doc {
+            let params: syn::punctuated::Punctuated<syn::FnArg, syn::Token![,]> = syn::parse_quote!(
+                #(#in_params,)*
+                #(#out_param_decls,)*
+                #e_param
+            );
+            let after_docs = attrs.iter().any(|a| a.path().is_ident("doc"));
+            let mut nullable = self.nullable_inputs(f);
+            nullable.extend(e_param.is_some().then(|| "e".to_string()));
+            attrs.extend(safety_doc(after_docs, &params, &nullable));
+        }
+        // The wrapper calls the source function; a deprecated one would warn
+        // in the generated file.
+        let allow_deprecated = f
+            .attrs
+            .iter()
+            .any(|a| a.path().is_ident("deprecated"))
+            .then(|| quote!(#[allow(deprecated)]));
+        let abi = syn::LitStr::new(
+            self.abi.as_deref().unwrap_or("C"),
+            proc_macro2::Span::call_site(),
+        );
+        let inline = self.inline_hint.then(|| quote!(#[inline]));
+        let edition = RustEdition::detected();
+        let no_mangle = match self.export_names.get(orig) {
+            Some(symbol) => edition.export_name_attr(symbol),
+            None => edition.no_mangle_attr(),
+        };
         quote! {
-            #[no_mangle]
+            #(#attrs)*
+            #no_mangle
+            #inline
             #[allow(non_snake_case, unused_mut, unused_variables, unused_unsafe, dead_code)]
-            pub unsafe extern "C" fn #sym(
+            #allow_deprecated
+            pub unsafe extern #abi fn #sym(
                 #(#in_params,)*
                 #(#out_param_decls,)*
                 #e_param
@@ -330,6 +693,180 @@ impl Cbindgen {
         }
     }
 
+    /// Wrap a function wrapper's `body` in `catch_unwind` when
+    /// [`Self::catch_unwind`] is on, returning the fallback documented there on a
+    /// caught panic. `route` is the wrapper's `Result` wiring: a panic is
+    /// reported like an `Err` through `*e`, or as the error's status code.
+    fn catch_unwind_body(
+        &self,
+        orig: &syn::Ident,
+        body: TokenStream,
+        c_return: Option<&syn::Type>,
+        route: &ErrRoute,
+    ) -> TokenStream {
+        if !self.catch_unwind {
+            return body;
+        }
+        let abort = quote!(::std::process::abort());
+        let msg = format!("{orig} panicked");
+        let on_panic = if self.abort_on_panic {
+            abort
+        } else if let ErrRoute::Result {
+            e_conv,
+            e_ty_src,
+            fail_return,
+        } = route
+        {
+            quote!(
+                if !e.is_null() {
+                    *e = #e_conv(<#e_ty_src as ::core::convert::From<::std::string::String>>::from(
+                        ::std::string::String::from(#msg),
+                    ));
+                }
+                #fail_return
+            )
+        } else if let ErrRoute::Code { e_ty_src } = route {
+            error_code(e_ty_src, quote!(::std::string::String::from(#msg)))
+        } else if let Some(expr) = self
+            .functions
+            .get(orig)
+            .and_then(|c| c.panic_return.as_ref())
+        {
+            quote!(#expr)
+        } else {
+            match c_return {
+                None => quote!(()),
+                Some(ty @ syn::Type::Ptr(_)) => null_for(ty),
+                Some(ty) if is_scalar(ty) => quote!(::core::default::Default::default()),
+                Some(_) => abort,
+            }
+        };
+        quote!(
+            match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(move || unsafe { #body })) {
+                ::core::result::Result::Ok(__r) => __r,
+                ::core::result::Result::Err(_) => { #on_panic }
+            }
+        )
+    }
+
+    /// Re-emit a captured const. A scalar const whose initializer is portable
+    /// (see [`portable_const_expr`]) is copied with that initializer, so
+    /// cbindgen can evaluate it into a `#define`, followed — unless
+    /// [`Self::assert_const_values`] is off — by a `const _` assertion that
+    /// the copy equals the source const. A data struct const is copied onto
+    /// the C mirror alike (see [`Self::emit_struct_const`]). Any other const
+    /// path-aliases the source item, as the default [`Prebindgen::on_const`]
+    /// does, with a data struct type qualified against the source module.
+    pub(super) fn emit_const(&self, c: &syn::ItemConst, registry: &Registry<()>) -> TokenStream {
+        if let Some(tokens) = self.emit_struct_const(c, registry) {
+            return tokens;
+        }
+        let copied = (c.ident != "_" && is_scalar(&c.ty))
+            .then(|| portable_const_expr(&c.expr, registry))
+            .flatten();
+        let Some(expr) = copied else {
+            return match &self.source_module {
+                Some(m) if c.ident != "_" => {
+                    let mut c = c.clone();
+                    if self.data.contains_key(&TypeKey::from_type(&c.ty)) {
+                        *c.ty = self.src_ty(&c.ty);
+                    }
+                    const_path_alias(&c, m)
+                }
+                _ => c.to_token_stream(),
+            };
+        };
+        let attrs = &c.attrs;
+        let vis = &c.vis;
+        let ident = &c.ident;
+        let ty = &c.ty;
+        let assert = match &self.source_module {
+            Some(m) if !self.skip_const_value_asserts => {
+                let msg = format!("const `{ident}` differs from the source const");
+                let cfgs = cfg_attrs(attrs);
+                let same = scalar_eq(quote!(#ident), quote!(#m::#ident), ty);
+                quote!(
+                    #(#cfgs)*
+                    const _: () = assert!(#same, #msg);
+                )
+            }
+            _ => quote!(),
+        };
+        quote! {
+            #(#attrs)*
+            #vis const #ident: #ty = #expr;
+            #assert
+        }
+    }
+
+    /// Re-emit a const of a declared data struct whose mirror is emitted,
+    /// initialized with a struct expression setting every field, each a
+    /// scalar with a portable initializer: as the same const of the C mirror,
+    /// so cbindgen can write it into the header, followed — unless
+    /// [`Self::assert_const_values`] is off — by a `const _` assertion that
+    /// each field equals the source const's. `None` for any other const.
+    fn emit_struct_const(
+        &self,
+        c: &syn::ItemConst,
+        registry: &Registry<()>,
+    ) -> Option<TokenStream> {
+        let ty = &*c.ty;
+        if c.ident == "_"
+            || !self.data.contains_key(&TypeKey::from_type(ty))
+            || self.is_non_exhaustive(registry, ty)
+            || (registry.input_entry(ty).is_none() && registry.output_entry(ty).is_none())
+        {
+            return None;
+        }
+        let syn::Expr::Struct(init) = &*c.expr else {
+            return None;
+        };
+        if init.rest.is_some() || init.path.segments.last()?.ident != type_path_tail(ty)? {
+            return None;
+        }
+        let fields = self.struct_fields(registry, ty)?;
+        if fields.len() != init.fields.len() {
+            return None;
+        }
+        let mut inits: Vec<TokenStream> = Vec::new();
+        let mut names: Vec<(&syn::Ident, &syn::Type)> = Vec::new();
+        for (fname, fty) in &fields {
+            if !is_scalar(fty) || !self.field_cfgs(registry, ty, fname).is_empty() {
+                return None;
+            }
+            let value = init
+                .fields
+                .iter()
+                .find(|fv| matches!(&fv.member, syn::Member::Named(member) if member == fname))?;
+            let expr = portable_const_expr(&value.expr, registry)?;
+            inits.push(quote!(#fname: #expr));
+            names.push((fname, fty));
+        }
+        let attrs = &c.attrs;
+        let vis = &c.vis;
+        let ident = &c.ident;
+        let c_struct = self.c_type_ident(ty);
+        let assert = match &self.source_module {
+            Some(m) if !self.skip_const_value_asserts => {
+                let msg = format!("const `{ident}` differs from the source const");
+                let cfgs = cfg_attrs(attrs);
+                let same = names.iter().map(|(fname, fty)| {
+                    scalar_eq(quote!(#ident.#fname), quote!(#m::#ident.#fname), fty)
+                });
+                quote!(
+                    #(#cfgs)*
+                    const _: () = assert!(#(#same)&&*, #msg);
+                )
+            }
+            _ => quote!(),
+        };
+        Some(quote! {
+            #(#attrs)*
+            #vis const #ident: #c_struct = #c_struct { #(#inits),* };
+            #assert
+        })
+    }
+
     /// Lower how a *present / ok* value of `ty` is carried over the C ABI: an
     /// ordered list of wire components plus the representation niches still
     /// available for enclosing `Option`/`Result` layers. Mirrors the
@@ -584,6 +1121,10 @@ impl Cbindgen {
             let syn::Pat::Ident(pat_id) = &*pt.pat else {
                 continue;
             };
+            // Only the bare name is bound on the wrapper: a `mut x: T` (or
+            // `ref x`) binding is the source fn's own business, which still
+            // gets its by-value copy through the call `orig(x)`, so the
+            // wrapper parameter is plain `x`.
             let ident = &pat_id.ident;
             let arg_ty = &*pt.ty;
 
@@ -604,6 +1145,23 @@ impl Cbindgen {
                 continue;
             }
 
+            // `&mut [E]` slice (scalar `E`): two wire params (`*mut E`, `usize`),
+            // written through in place. NULL pointer ⇒ empty slice.
+            if let Some(elem) = scalar_slice_mut_elem(arg_ty) {
+                let len_id = format_ident!("{}_len", ident);
+                params.push(quote!(#ident: *mut #elem));
+                params.push(quote!(#len_id: usize));
+                decodes.push(quote!(
+                    let #ident: &mut [#elem] = if #ident.is_null() {
+                        &mut []
+                    } else {
+                        ::core::slice::from_raw_parts_mut(#ident, #len_id)
+                    };
+                ));
+                call_args.push(quote!(#ident));
+                continue;
+            }
+
             // `&[E]` slice (inline-opaque by-value `E`, e.g. a `repr_c_struct`):
             // two wire params (`*const E_counterpart`, `usize`), reinterpreted to
             // `&[E]` zero-copy. The counterpart is layout-identical to `E` (asserted
@@ -644,6 +1202,18 @@ impl Cbindgen {
 
             params.push(quote!(#ident: #wire));
 
+            if self.pointer_null_checks
+                && matches!(arg_ty, syn::Type::Reference(_))
+                && matches!(wire, syn::Type::Ptr(_))
+            {
+                let msg = format!("`{ident}` of `{orig}` must not be NULL");
+                decodes.push(if self.release_null_checks {
+                    quote!(assert!(!#ident.is_null(), #msg);)
+                } else {
+                    quote!(debug_assert!(!#ident.is_null(), #msg);)
+                });
+            }
+
             if returns_result(&entry.function.sig.output) {
                 let on_err = match route {
                     ErrRoute::Result {
@@ -657,6 +1227,10 @@ impl Cbindgen {
                         return #fail_return;
                     ),
                     ErrRoute::Panic => quote!(panic!("{}", __msg);),
+                    ErrRoute::Code { e_ty_src } => {
+                        let code = error_code(e_ty_src, quote!(__msg));
+                        quote!(return #code;)
+                    }
                 };
                 decodes.push(quote!(
                     let #ident = match #conv(#ident) {
@@ -676,4 +1250,206 @@ impl Cbindgen {
 
         (params, decodes, call_args)
     }
+
+    /// The wrapper parameters of `f` [`emit_inputs`](Self::emit_inputs)
+    /// decodes from a NULL pointer: an `Option` (NULL is `None`) and a slice
+    /// pointer (NULL is the empty slice).
+    fn nullable_inputs(&self, f: &syn::ItemFn) -> HashSet<String> {
+        f.sig
+            .inputs
+            .iter()
+            .filter_map(|input| {
+                let syn::FnArg::Typed(pt) = input else {
+                    return None;
+                };
+                let syn::Pat::Ident(pat_id) = &*pt.pat else {
+                    return None;
+                };
+                let ty = &*pt.ty;
+                (is_option(ty)
+                    || scalar_slice_elem(ty).is_some()
+                    || scalar_slice_mut_elem(ty).is_some()
+                    || self.value_opaque_slice_elem(ty).is_some())
+                .then(|| pat_id.ident.to_string())
+            })
+            .collect()
+    }
+}
+
+/// `lhs == rhs` for two values of the scalar type `ty`, usable in a const
+/// assertion: floats are compared by their bits, so a NaN const equals
+/// itself.
+fn scalar_eq(lhs: TokenStream, rhs: TokenStream, ty: &syn::Type) -> TokenStream {
+    match type_path_tail(ty) {
+        Some(tail) if tail == "f32" || tail == "f64" => {
+            quote!(#lhs.to_bits() == #rhs.to_bits())
+        }
+        _ => quote!(#lhs == #rhs),
+    }
+}
+
+/// Field `fname` (as named by [`Cbindgen::struct_fields`]) of the captured
+/// struct `ty`.
+fn source_field<'r>(
+    registry: &'r Registry<()>,
+    ty: &syn::Type,
+    fname: &syn::Ident,
+) -> Option<&'r syn::Field> {
+    let (s, _) = registry.structs.get(&type_path_tail(ty)?)?;
+    let index = tuple_field_index(fname);
+    s.fields
+        .iter()
+        .enumerate()
+        .find_map(|(i, f)| match &f.ident {
+            Some(ident) => (ident == fname).then_some(f),
+            None => (index == Some(i)).then_some(f),
+        })
+}
+
+/// How the binding crate reaches field `fname` (as named by
+/// [`Cbindgen::struct_fields`]) of a source struct `ty` value: `v.x` for a
+/// named field, `v.0` for the tuple-struct field mirrored as `_0`.
+pub(super) fn source_member(
+    registry: &Registry<()>,
+    ty: &syn::Type,
+    fname: &syn::Ident,
+) -> syn::Member {
+    match (source_field(registry, ty, fname), tuple_field_index(fname)) {
+        (Some(syn::Field { ident: None, .. }), Some(index)) => syn::Member::Unnamed(index.into()),
+        _ => syn::Member::Named(fname.clone()),
+    }
+}
+
+/// `i` for the mirror name `_i` of a tuple-struct field.
+fn tuple_field_index(fname: &syn::Ident) -> Option<usize> {
+    fname.to_string().strip_prefix('_')?.parse().ok()
+}
+
+/// `expr` with every reference to a captured const rewritten to its flat
+/// name (the one the generated file defines it under), if `expr` is built
+/// only from literals, unary / binary operators, parentheses, casts to
+/// scalars and such references; `None` otherwise.
+fn portable_const_expr(expr: &syn::Expr, registry: &Registry<()>) -> Option<syn::Expr> {
+    let mut expr = expr.clone();
+    flatten_const_refs(&mut expr, registry).then_some(expr)
+}
+
+fn flatten_const_refs(expr: &mut syn::Expr, registry: &Registry<()>) -> bool {
+    match expr {
+        syn::Expr::Lit(l) => matches!(
+            l.lit,
+            syn::Lit::Int(_)
+                | syn::Lit::Float(_)
+                | syn::Lit::Bool(_)
+                | syn::Lit::Byte(_)
+                | syn::Lit::Char(_)
+        ),
+        syn::Expr::Paren(p) => flatten_const_refs(&mut p.expr, registry),
+        syn::Expr::Group(g) => flatten_const_refs(&mut g.expr, registry),
+        syn::Expr::Unary(u) => {
+            matches!(u.op, syn::UnOp::Neg(_) | syn::UnOp::Not(_))
+                && flatten_const_refs(&mut u.expr, registry)
+        }
+        syn::Expr::Binary(b) => {
+            flatten_const_refs(&mut b.left, registry) && flatten_const_refs(&mut b.right, registry)
+        }
+        syn::Expr::Cast(c) => is_scalar(&c.ty) && flatten_const_refs(&mut c.expr, registry),
+        syn::Expr::Path(p) => match captured_const(p, registry) {
+            Some(ident) => {
+                *expr = syn::parse_quote!(#ident);
+                true
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// The captured const `p` names: a bare name, or one qualified by `crate`,
+/// `self`, `super` or a source module (never e.g. `u32::MAX`).
+fn captured_const(p: &syn::ExprPath, registry: &Registry<()>) -> Option<syn::Ident> {
+    let path = &p.path;
+    if p.qself.is_some()
+        || path
+            .segments
+            .iter()
+            .any(|s| !matches!(s.arguments, syn::PathArguments::None))
+    {
+        return None;
+    }
+    let head = path.segments.first()?.ident.to_string();
+    let flat = path.segments.len() == 1 && path.leading_colon.is_none()
+        || matches!(head.as_str(), "crate" | "self" | "super")
+        || registry.source_modules.contains(&head);
+    let ident = &path.segments.last()?.ident;
+    (flat && ident != "_" && registry.consts.contains_key(ident)).then(|| ident.clone())
+}
+
+/// The `# Safety` doc section of a function wrapper taking `params`, under
+/// [`Cbindgen::emit_safety_doc`]; led by an empty line when it follows other
+/// docs. The pointers named in `nullable` are documented as accepting NULL,
+/// every other one as required to be non-null.
+fn safety_doc(
+    after_docs: bool,
+    params: &syn::punctuated::Punctuated<syn::FnArg, syn::Token![,]>,
+    nullable: &HashSet<String>,
+) -> Vec<syn::Attribute> {
+    let mut lines: Vec<String> = Vec::new();
+    if after_docs {
+        lines.push(String::new());
+    }
+    lines.push(" # Safety".to_string());
+    lines.push(String::new());
+    let requirements: Vec<String> = params
+        .iter()
+        .filter_map(|arg| {
+            let syn::FnArg::Typed(pt) = arg else {
+                return None;
+            };
+            let name = match &*pt.pat {
+                syn::Pat::Ident(pat_id) => pat_id.ident.to_string(),
+                pat => pat.to_token_stream().to_string(),
+            };
+            let req = pointer_requirement(&pt.ty)?;
+            Some(if nullable.contains(&name) {
+                format!(" - `{name}` may be NULL; otherwise it must {req}")
+            } else {
+                format!(" - `{name}` must be non-null and {req}")
+            })
+        })
+        .collect();
+    if requirements.is_empty() {
+        lines.push(" Takes no pointers: any argument values are valid.".to_string());
+    } else {
+        lines.push(
+            " Each pointer must be properly aligned and valid for the whole call:".to_string(),
+        );
+        lines.extend(requirements);
+    }
+    lines
+        .into_iter()
+        .map(|line| syn::parse_quote!(#[doc = #line]))
+        .collect()
+}
+
+/// What a wrapper parameter of wire type `ty` must do when it isn't NULL
+/// (`point to ...`, `be ...`), if it is a pointer.
+fn pointer_requirement(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Ptr(p) => {
+            let pointee = type_text(&p.elem);
+            Some(if pointee.ends_with("c_char") && p.mutability.is_none() {
+                "point to a NUL-terminated string".to_string()
+            } else if p.mutability.is_some() {
+                format!("point to a `{pointee}` the callee may read and write")
+            } else {
+                format!("point to a `{pointee}` the callee may read")
+            })
+        }
+        syn::Type::BareFn(_) => Some("be a function of the declared signature".to_string()),
+        _ if is_option(ty) => first_type_arg(ty)
+            .filter(|inner| matches!(inner, syn::Type::BareFn(_)))
+            .map(|_| "be a function of the declared signature".to_string()),
+        _ => None,
+    }
 }
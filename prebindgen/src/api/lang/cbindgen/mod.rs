@@ -26,6 +26,11 @@
 //!   `#[repr(C)]` struct whose fields are mapped to C-ABI wire types
 //!   (`String` → `*mut c_char`). No per-struct destructor — each `char*` field
 //!   is released individually via the [`Cbindgen::free_memory_function`].
+//! * **Function pointer** (`extern "C" fn(..) -> R`, or an `Option` of one):
+//!   the same signature with each reference or raw pointer to a scalar or data
+//!   struct turned into a raw pointer to its C wire (`&Point` → `*const point`),
+//!   reinterpreted by `transmute` behind size and alignment asserts on the
+//!   pointer and on each pointed-to data struct.
 //! * **Direct `String` output**: a bare `char *` — a `malloc`'d, null-terminated
 //!   raw block (no wrapper struct), freed via the `free_memory_function`.
 //! * **[`Cbindgen::free_memory_function`]**: the single, type-agnostic raw memory
@@ -42,6 +47,10 @@
 //!   `e` may be `NULL`, in which case the error value is dropped. Infallible
 //!   producers return the value/pointer directly (no out-param).
 //!
+//!   With [`Cbindgen::result_as_out_param`] every `Result<T, E>` return instead
+//!   lowers to `int32_t f(<inputs>, T *out)`: a success code, or the `E`'s own
+//!   code (`E: Into<i32>`) with `*out` left untouched.
+//!
 //! ## Error handling (multiple error types)
 //!
 //! Any type used as the `E` of a `Result<T, E>` return **must be declared** as an
@@ -76,9 +85,10 @@ use quote::{format_ident, quote, ToTokens};
 // keep their call sites. `pub(crate) use` so the glob re-export reaches them.
 pub(crate) use crate::api::core::types_util::{
     first_type_arg, is_option_type as is_option, is_result_type as is_result, is_unit,
-    is_vec_type as is_vec, path_tail_ident as type_path_tail, result_parts,
+    is_vec_type as is_vec, path_tail_ident as type_path_tail, result_parts, type_from_ident,
 };
 use crate::api::{
+    collect::destination::cfg_attrs,
     core::{
         niches::{NicheSlot, Niches},
         prebindgen::{ConverterImpl, Prebindgen},
@@ -168,6 +178,9 @@ struct FnCfg {
     /// (set by [`Cbindgen::panic`]). Only meaningful for non-`Result` functions
     /// that have a fallible input.
     panic: bool,
+    /// Value returned by the wrapper when [`Cbindgen::catch_unwind`] catches a
+    /// panic (set by [`Cbindgen::panic_return`]). `None` ⇒ the built-in fallback.
+    panic_return: Option<syn::Expr>,
 }
 
 /// The declaration a chained modifier ([`Cbindgen::name`] / [`Cbindgen::error`]
@@ -197,6 +210,9 @@ enum ErrRoute<'a> {
     },
     /// Non-`Result` function declared `.panic()`: abort via `panic!`.
     Panic,
+    /// `Result<T, E>` function under [`Cbindgen::result_as_out_param`]: convert
+    /// the message to `E` and return its `i32` status code.
+    Code { e_ty_src: syn::Type },
 }
 
 /// C / cbindgen language adapter. Build it with [`Cbindgen::new`], declare the
@@ -224,6 +240,10 @@ pub struct Cbindgen {
     /// opaque `#[repr(C, align(_))]` counterpart of identical size+align (no
     /// `Box`). Keyed by the Rust type; the value carries the opaque counterpart.
     value_opaque: HashMap<TypeKey, ValueOpaqueCfg>,
+    /// Types crossed as a user-chosen FFI type of identical layout (e.g.
+    /// `chrono::DateTime<Utc>` → `i64`). Keyed by the Rust type; the value is
+    /// the wire type. Set by [`Cbindgen::map_type`].
+    mapped: HashMap<TypeKey, syn::Type>,
     /// Enum types.
     enums: HashMap<TypeKey, TypeCfg>,
     /// Declared callback signatures (`impl Fn(...) + Send + Sync + 'static`),
@@ -245,6 +265,10 @@ pub struct Cbindgen {
     /// generated code hands out. Set by [`Self::free_memory_function`]. Required
     /// (build error otherwise) whenever string memory is produced.
     free_fn: Option<String>,
+    /// Name of the generated freer of `String`s returned as
+    /// `CString::into_raw` pointers instead of `malloc`'d blocks released by
+    /// [`Self::free_memory_function`]. Set by [`Self::string_as_c_char`].
+    cstring_free_fn: Option<String>,
     /// The declaration that chained modifiers apply to. Set by declaration
     /// methods; reset to `None` by root-level modifiers.
     current: Option<CurrentDecl>,
@@ -265,8 +289,117 @@ pub struct Cbindgen {
     mangle_callback: Option<MangleN>,
     /// Rust function ident → exported `#[no_mangle]` symbol.
     mangle_function: Option<Mangle1>,
+    /// Prefix / suffix added around every mangled function symbol. Set by
+    /// [`Cbindgen::symbol_prefix`] / [`Cbindgen::symbol_suffix`].
+    symbol_prefix: String,
+    symbol_suffix: String,
+    /// Exact exported symbols of source functions, emitted as
+    /// `#[export_name]` in place of the mangled `#[no_mangle]` one. Set by
+    /// [`Cbindgen::export_name_map`].
+    export_names: HashMap<syn::Ident, String>,
+    /// Calling convention of the exported function wrappers. Set by
+    /// [`Cbindgen::abi`]; `None` ⇒ `"C"`.
+    abi: Option<String>,
+    /// Run each function wrapper's body under `catch_unwind` so a panic never
+    /// unwinds into C. Set by [`Cbindgen::catch_unwind`].
+    catch_unwind: bool,
+    /// On a caught panic, `abort()` instead of returning a fallback value. Set
+    /// by [`Cbindgen::abort_on_panic`].
+    abort_on_panic: bool,
+    /// Drop source `#[doc]` attributes instead of carrying them onto the
+    /// generated items. Set by [`Cbindgen::strip_docs`].
+    strip_docs: bool,
+    /// Append a `#[doc = " from <crate>:<file>:<line>"]` naming the source
+    /// item to every generated function and type mirror. Set by
+    /// [`Cbindgen::annotate_provenance`].
+    annotate_provenance: bool,
+    /// Append a `# Safety` doc section stating the pointer requirements to
+    /// every function wrapper. Set by [`Cbindgen::emit_safety_doc`].
+    emit_safety_doc: bool,
+    /// Check every borrowed pointer parameter for NULL before decoding it.
+    /// Set by [`Cbindgen::emit_pointer_null_checks`].
+    pointer_null_checks: bool,
+    /// Emit the NULL checks as `assert!` instead of `debug_assert!`. Set by
+    /// [`Cbindgen::release_null_checks`].
+    release_null_checks: bool,
+    /// Extra derives added to every generated data struct / enum mirror. Set
+    /// by [`Cbindgen::add_derive`].
+    derives: Vec<syn::Path>,
+    /// Reject a `repr_c_struct` source type without `#[repr(C)]` /
+    /// `#[repr(transparent)]`. Set by [`Cbindgen::require_repr_c`].
+    require_repr_c: bool,
+    /// Success status code of `Result` wrappers lowered to an `i32` status
+    /// plus out-parameters. Set by [`Cbindgen::result_as_out_param`].
+    result_as_out_param: Option<i32>,
+    /// Skip the exported-symbol collision check. Set by
+    /// [`Cbindgen::allow_duplicate_symbols`].
+    allow_duplicate_symbols: bool,
+    /// Standard-library path prefixes (`std`, `core::ffi`, ...) declared
+    /// function signatures may mention; `None` allows every path. Set by
+    /// [`Cbindgen::allowed_prefixes`] and its siblings.
+    allowed_prefixes: Option<Vec<String>>,
+    /// Mark the exported function wrappers `#[inline]`. Set by
+    /// [`Cbindgen::inline_hint`].
+    inline_hint: bool,
+    /// Names of the extra source attributes carried onto function wrappers,
+    /// next to the docs and `#[deprecated]`. Set by
+    /// [`Cbindgen::keep_attribute`].
+    kept_attributes: Vec<String>,
+    /// Drop declared functions whose types can't be converted, with a
+    /// warning, instead of failing the build. Set by
+    /// [`Cbindgen::skip_invalid_items`].
+    skip_invalid_items: bool,
+    /// Don't emit the per-variant discriminant assertions after each enum
+    /// mirror. Set by [`Cbindgen::assert_enum_discriminants`].
+    skip_enum_discriminant_asserts: bool,
+    /// Don't emit the value assertion after each copied const. Set by
+    /// [`Cbindgen::assert_const_values`].
+    skip_const_value_asserts: bool,
+    /// Export `#[repr(<int>)]` enums as an integer alias plus one const per
+    /// variant. Set by [`Cbindgen::c_enum_as_integer`].
+    c_enum_as_integer: bool,
+    /// Cross `bool` parameters and returns as `u8`. Set by
+    /// [`Cbindgen::bool_as_u8`].
+    bool_as_u8: bool,
+    /// Cross `Option<&T>` of a scalar `T` as a nullable `*const T`. Set by
+    /// [`Cbindgen::option_ref_as_pointer`].
+    option_ref_as_pointer: bool,
+    /// Cross `Box<T>` parameters and returns as an owning `*mut` pointer.
+    /// Set by [`Cbindgen::box_as_raw_pointer`].
+    box_as_raw_pointer: bool,
+    /// Cross undeclared `#[repr(transparent)]` newtypes of a scalar as the
+    /// scalar. Set by [`Cbindgen::auto_transparent_newtypes`].
+    auto_transparent_newtypes: bool,
+    /// Fail, instead of warning, on a [`Cbindgen::map_type`] that strips a
+    /// wrapper outside [`TRANSPARENT_WRAPPERS`] and `acknowledged_wrappers`.
+    /// Set by [`Cbindgen::strict_transparent`].
+    strict_transparent: bool,
+    /// Wrapper names accepted by [`Cbindgen::strict_transparent`] on top of
+    /// [`TRANSPARENT_WRAPPERS`].
+    acknowledged_wrappers: HashSet<String>,
+    /// Wrapper names known to be transparent over the generic type argument
+    /// at the given index rather than the first one. Set by
+    /// [`Cbindgen::transparent_wrapper_arg`].
+    transparent_wrapper_args: HashMap<String, usize>,
+    /// Declare the exported symbols in an `extern` block instead of defining
+    /// them. Set by [`Cbindgen::emit_extern_block`].
+    extern_block: bool,
+    /// Carry a source `#[non_exhaustive]` onto the C mirror instead of
+    /// dropping it. Set by [`Cbindgen::keep_non_exhaustive`].
+    keep_non_exhaustive: bool,
+    /// Pointer width, in bits, of the target the binding is built for; the
+    /// generated file asserts `usize` has it. Set by
+    /// [`Cbindgen::target_pointer_width`].
+    target_pointer_width: Option<u8>,
 }
 
+/// Calling conventions accepted by [`Cbindgen::abi`].
+const KNOWN_ABIS: &[&str] = &["C", "system", "stdcall", "cdecl"];
+
+/// Standard wrappers documented as having their single field's layout and
+/// ABI, so a [`Cbindgen::map_type`] stripping them is known sound.
+const TRANSPARENT_WRAPPERS: &[&str] = &["MaybeUninit", "ManuallyDrop", "Wrapping", "Reverse"];
+
 /// A mangler over a single name component (Rust short name, base, or fn ident).
 type Mangle1 = Box<dyn Fn(&str) -> String>;
 /// A mangler over a callback's argument bases.
@@ -310,6 +443,72 @@ fn enum_item<'r>(registry: &'r Registry<()>, ty: &syn::Type) -> Option<&'r syn::
     registry.enums.get(&ident).map(|(e, _)| e)
 }
 
+/// The integer of a `#[repr(<int>)]` enum (`i32` for `#[repr(i32)]`).
+fn enum_int_repr(e: &syn::ItemEnum) -> Option<syn::Ident> {
+    let mut int = None;
+    for attr in e.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                let name = ident.to_string();
+                if int.is_none()
+                    && matches!(
+                        name.as_str(),
+                        "i8" | "i16"
+                            | "i32"
+                            | "i64"
+                            | "isize"
+                            | "u8"
+                            | "u16"
+                            | "u32"
+                            | "u64"
+                            | "usize"
+                    )
+                {
+                    int = Some(ident.clone());
+                }
+            }
+            Ok(())
+        });
+    }
+    int
+}
+
+/// The discriminant of each variant of a fieldless enum as a literal value:
+/// an integer-literal discriminant, or one more than the previous variant's.
+/// `None` from the first variant whose discriminant isn't a literal on.
+fn enum_discriminants(e: &syn::ItemEnum) -> Vec<Option<i128>> {
+    let mut next = Some(0i128);
+    e.variants
+        .iter()
+        .map(|v| {
+            let value = match &v.discriminant {
+                Some((_, expr)) => int_literal(expr),
+                None => next,
+            };
+            next = value.and_then(|v| v.checked_add(1));
+            value
+        })
+        .collect()
+}
+
+/// The value of an integer literal, possibly negated.
+fn int_literal(expr: &syn::Expr) -> Option<i128> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(i),
+            ..
+        }) => i.base10_parse().ok(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => int_literal(expr)?.checked_neg(),
+        syn::Expr::Paren(p) => int_literal(&p.expr),
+        syn::Expr::Group(g) => int_literal(&g.expr),
+        _ => None,
+    }
+}
+
 /// Hard error on a non-C-like enum (only fieldless / unit variants supported).
 fn assert_unit_variants(e: &syn::ItemEnum) {
     for v in &e.variants {
@@ -350,6 +549,10 @@ fn is_str(ty: &syn::Type) -> bool {
     type_path_tail(ty).map(|i| i == "str").unwrap_or(false)
 }
 
+fn is_cstr(ty: &syn::Type) -> bool {
+    type_path_tail(ty).map(|i| i == "CStr").unwrap_or(false)
+}
+
 /// If `ty` is `Box<T>`, return `T` (used to peel an opaque-pointer struct field
 /// such as `Box<String>` / the inner of `Option<Box<String>>`).
 fn box_inner(ty: &syn::Type) -> Option<syn::Type> {
@@ -440,6 +643,20 @@ fn scalar_slice_elem(ty: &syn::Type) -> Option<syn::Type> {
     is_scalar(&elem).then_some(elem)
 }
 
+/// Element type of a `&mut [E]` input whose `E` is a C scalar (lowered to
+/// `*mut E` + `usize`, decoded in place).
+fn scalar_slice_mut_elem(ty: &syn::Type) -> Option<syn::Type> {
+    let syn::Type::Reference(r) = ty else {
+        return None;
+    };
+    r.mutability?;
+    let syn::Type::Slice(s) = &*r.elem else {
+        return None;
+    };
+    let elem = (*s.elem).clone();
+    is_scalar(&elem).then_some(elem)
+}
+
 /// C name for an out-parameter field. When the value's primary field (suffix
 /// `""`) is itself an out-param the whole group is `out`-prefixed (`out`,
 /// `out_len`, `out_present`); otherwise the accompanying fields use bare names
@@ -510,11 +727,32 @@ fn route_result(call: TokenStream, route: &ErrRoute<'_>) -> TokenStream {
                 ::core::result::Result::Err(message) => panic!("{}", message),
             }
         },
+        ErrRoute::Code { e_ty_src } => {
+            let code = error_code(e_ty_src, quote!(message));
+            quote! {
+                match #call {
+                    ::core::result::Result::Ok(value) => value,
+                    ::core::result::Result::Err(message) => return #code,
+                }
+            }
+        }
+    }
+}
+
+/// The `i32` status code of an internal error `message` for a function whose
+/// error type is `e_ty_src`: `E::from(message).into()`.
+fn error_code(e_ty_src: &syn::Type, message: TokenStream) -> TokenStream {
+    quote! {
+        ::core::convert::Into::<i32>::into(
+            <#e_ty_src as ::core::convert::From<::std::string::String>>::from(#message)
+        )
     }
 }
 
 /// C-ABI wire type for a struct field. `String` → `*mut c_char`; FFI-safe
-/// scalars pass through. `None` for anything else (unsupported this increment).
+/// scalars pass through; a borrowed scalar `&'a T` / `&'a mut T` becomes
+/// `*const T` / `*mut T`, dropping its lifetime. `None` for anything else
+/// (unsupported this increment).
 fn c_field_wire(ty: &syn::Type) -> Option<syn::Type> {
     if is_string(ty) {
         return Some(syn::parse_quote!(*mut ::core::ffi::c_char));
@@ -522,5 +760,14 @@ fn c_field_wire(ty: &syn::Type) -> Option<syn::Type> {
     if is_scalar(ty) {
         return Some(ty.clone());
     }
+    if let syn::Type::Reference(r) = ty {
+        if is_scalar(&r.elem) {
+            let elem = &r.elem;
+            return Some(match r.mutability {
+                Some(_) => syn::parse_quote!(*mut #elem),
+                None => syn::parse_quote!(*const #elem),
+            });
+        }
+    }
     None
 }
@@ -11,13 +11,15 @@ impl Cbindgen {
         registry: &Registry<()>,
     ) -> Option<ConverterImpl<()>> {
         self.in_custom(ty, registry)
+            .or_else(|| self.in_mapped(ty, registry))
             .or_else(|| self.in_opaque_handle(ty))
             .or_else(|| self.in_data_struct(ty, registry))
             .or_else(|| self.in_value_opaque(ty, registry))
             .or_else(|| self.in_enum(ty, registry))
             .or_else(|| self.in_string(ty))
             .or_else(|| self.in_str(ty))
-            .or_else(|| self.in_scalar(ty))
+            .or_else(|| self.in_scalar(ty, registry))
+            .or_else(|| self.in_fn_pointer(ty, registry))
             .or_else(|| self.in_wrappers(ty, registry))
     }
 
@@ -29,7 +31,9 @@ impl Cbindgen {
         registry: &Registry<()>,
     ) -> Option<ConverterImpl<()>> {
         self.out_custom(ty, registry)
+            .or_else(|| self.out_mapped(ty, registry))
             .or_else(|| self.out_terminal(ty, registry))
+            .or_else(|| self.out_fn_pointer(ty, registry))
             .or_else(|| self.out_wrappers(ty, registry))
     }
 }
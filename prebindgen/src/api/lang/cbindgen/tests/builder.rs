@@ -22,6 +22,177 @@ fn function_name_renames_symbol() {
     assert!(compact.contains("zenoh_flat::rust_init("), "{src}");
 }
 
+/// A source-side `#[prebindgen(rename = "...")]` names the function in the
+/// stream — declared and exported under it — while the wrapper still calls
+/// the Rust fn; a `.base_name()` in the binding crate still wins.
+#[test]
+fn source_rename_names_symbol() {
+    let record = crate::Record::new(
+        crate::RecordKind::Function,
+        "rust_open".to_string(),
+        "pub fn rust_open() {}".to_string(),
+        SourceLocation::default(),
+        None,
+    )
+    .with_rename(Some("z_open".to_string()));
+    let (item, loc) = record.try_parse().unwrap();
+    assert_eq!(loc.source_name.as_deref(), Some("rust_open"));
+    let registry =
+        || Registry::<()>::from_items([(item.clone(), loc.clone())]).expect("index items");
+    let cb = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .function(syn::parse_quote!(z_open))
+    };
+
+    let src = write(cb(), registry(), "srcrename");
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("extern\"C\"fnz_open("), "{src}");
+    assert!(compact.contains("zenoh_flat::rust_open("), "{src}");
+
+    let src = write(cb().base_name("z_open_v2"), registry(), "srcrename_base");
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("extern\"C\"fnz_open_v2("), "{src}");
+}
+
+/// `.symbol_prefix()` / `.symbol_suffix()` wrap the mangled symbol; the body
+/// still calls the original Rust fn.
+#[test]
+fn symbol_prefix_and_suffix_wrap_mangled_symbol() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn open() {
+            unimplemented!()
+        }
+    );
+    let reg =
+        Registry::<()>::from_items([(syn::Item::Fn(func), loc.clone())]).expect("index items");
+    let cb = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .mangle_function(|n| format!("z_{n}"))
+        .symbol_prefix("zc_")
+        .symbol_suffix("_v2")
+        .function(syn::parse_quote!(open));
+    let src = write(cb, reg, "fnprefix");
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("extern\"C\"fnzc_z_open_v2("), "{src}");
+    assert!(compact.contains("zenoh_flat::open("), "{src}");
+}
+
+/// `.inline_hint(true)` puts `#[inline]` right after `#[no_mangle]` on the
+/// function wrappers; it is off by default.
+#[test]
+fn inline_hint_follows_no_mangle() {
+    let registry = || {
+        let func: syn::ItemFn = syn::parse_quote!(
+            pub fn open() {
+                unimplemented!()
+            }
+        );
+        Registry::<()>::from_items([(syn::Item::Fn(func), SourceLocation::default())])
+            .expect("index items")
+    };
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .function(syn::parse_quote!(open))
+    };
+
+    let src = write(cbindgen(), registry(), "inline_off");
+    assert!(!src.contains("#[inline]"), "{src}");
+
+    let src = write(cbindgen().inline_hint(true), registry(), "inline_on");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("#[no_mangle]#[inline]#[allow(non_snake_case"),
+        "{src}"
+    );
+}
+
+/// `.emit_extern_block(true)` declares every exported symbol — wrappers and
+/// generated destructors — in one `extern` block without bodies, while the C
+/// mirrors and their converters are still emitted.
+#[test]
+fn emit_extern_block_declares_symbols_without_bodies() {
+    let loc = SourceLocation::default();
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "pub struct Point { pub x: f64 }",
+        "pub struct Session { inner: u32 }",
+        "pub fn point_make(x: f64) -> Point { unimplemented!() }",
+        "pub fn session_open() -> Session { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| (syn::parse_str(source).unwrap(), loc.clone()))
+    .collect();
+    let registry = Registry::<()>::from_items(items).expect("index items");
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .emit_extern_block(true)
+        .data_struct(syn::parse_quote!(Point))
+        .opaque_ptr(syn::parse_quote!(Session))
+        .function(syn::parse_quote!(point_make))
+        .function(syn::parse_quote!(session_open));
+
+    let src = write(cbindgen, registry, "extern_block");
+    let file: syn::File = syn::parse_str(&src).unwrap();
+    let blocks: Vec<&syn::ItemForeignMod> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::Item::ForeignMod(block) => Some(block),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(blocks.len(), 1, "{src}");
+    let declared: Vec<String> = blocks[0]
+        .items
+        .iter()
+        .map(|item| match item {
+            syn::ForeignItem::Fn(f) => f.sig.ident.to_string(),
+            other => panic!("unexpected foreign item {}", quote::quote!(#other)),
+        })
+        .collect();
+    for symbol in ["point_make", "session_open"] {
+        assert!(declared.iter().any(|d| d == symbol), "{src}");
+    }
+    assert!(
+        declared.len() > 2,
+        "the opaque destructor is declared: {src}"
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(!compact.contains("no_mangle"), "{src}");
+    assert!(!compact.contains("extern\"C\"fn"), "{src}");
+    assert!(compact.contains("#[repr(C)]"), "{src}");
+    assert!(compact.contains("fn__cbg_out_Point("), "{src}");
+}
+
+/// `.abi()` sets the calling convention of the function wrappers.
+#[test]
+fn abi_sets_wrapper_calling_convention() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn open() {
+            unimplemented!()
+        }
+    );
+    let reg =
+        Registry::<()>::from_items([(syn::Item::Fn(func), loc.clone())]).expect("index items");
+    let cb = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .abi("system")
+        .function(syn::parse_quote!(open));
+    let src = write(cb, reg, "fnabi");
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("unsafeextern\"system\"fnopen("), "{src}");
+}
+
+#[test]
+fn unknown_abi_panics() {
+    assert!(catch(|| {
+        let _ = Cbindgen::new().abi("fastcall");
+    }));
+}
+
 // ── Strict modifier rules (misapplied modifiers are build errors) ──────
 
 #[test]
@@ -223,3 +394,216 @@ fn qualified_signature_spelling_matches_bare_opaque_ptr() {
     assert!(compact.contains("extern\"C\"fnz_keyexpr_len("), "{src}");
     assert!(compact.contains("zenoh_flat::z_keyexpr_len("), "{src}");
 }
+
+/// Two declared functions renamed onto one exported symbol fail `resolve`
+/// with both locations, unless their `#[cfg]`s are exclusive or the check
+/// is turned off with `.allow_duplicate_symbols(true)`.
+#[test]
+fn duplicate_exported_symbols_are_rejected() {
+    let registry = |cfgs: [Option<syn::Attribute>; 2]| {
+        let [linux, windows] = cfgs;
+        let init_linux: syn::ItemFn = syn::parse_quote!(
+            #linux
+            pub fn init_linux() {}
+        );
+        let init_windows: syn::ItemFn = syn::parse_quote!(
+            #windows
+            pub fn init_windows() {}
+        );
+        let at = |line| SourceLocation {
+            file: "src/lib.rs".to_string(),
+            line,
+            column: 1,
+            ..SourceLocation::default()
+        };
+        Registry::<()>::from_items([
+            (syn::Item::Fn(init_linux), at(3)),
+            (syn::Item::Fn(init_windows), at(8)),
+        ])
+        .expect("index items")
+    };
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .function(syn::parse_quote!(init_linux))
+            .base_name("init")
+            .function(syn::parse_quote!(init_windows))
+            .base_name("init")
+    };
+
+    // Mutually exclusive cfgs: both variants may export `init`.
+    let exclusive = registry([
+        Some(syn::parse_quote!(#[cfg(target_os = "linux")])),
+        Some(syn::parse_quote!(#[cfg(not(target_os = "linux"))])),
+    ]);
+    let src = write(cbindgen(), exclusive, "dupsym_cfg");
+    let compact: String = src.split_whitespace().collect();
+    assert_eq!(compact.matches("extern\"C\"fninit(").count(), 2, "{src}");
+
+    // Unconditional: a link-time collision, reported up front.
+    let Err(err) = registry([None, None]).resolve(cbindgen()) else {
+        panic!("duplicate symbol must fail resolve");
+    };
+    let err = err.to_string();
+    assert!(err.contains("duplicate exported symbol `init`"), "{err}");
+    assert!(
+        err.contains("`init_linux` at src/lib.rs:3:1")
+            && err.contains("`init_windows` at src/lib.rs:8:1"),
+        "{err}"
+    );
+
+    // Opted out.
+    write(
+        cbindgen().allow_duplicate_symbols(true),
+        registry([None, None]),
+        "dupsym_allowed",
+    );
+}
+
+/// `.core_only()` rejects a declared function whose signature mentions a
+/// `std::` path; `.allowed_prefix("std::collections")` lets it through again.
+#[test]
+fn core_only_rejects_std_paths() {
+    let registry = || {
+        let func: syn::ItemFn = syn::parse_quote!(
+            pub fn table_len(table: &std::collections::HashMap<u32, u32>) -> core::ffi::c_int {
+                table.len() as core::ffi::c_int
+            }
+        );
+        let at = SourceLocation {
+            file: "src/lib.rs".to_string(),
+            line: 4,
+            column: 1,
+            ..SourceLocation::default()
+        };
+        Registry::<()>::from_items([(syn::Item::Fn(func), at)]).expect("index items")
+    };
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .function(syn::parse_quote!(table_len))
+    };
+
+    let Err(err) = registry().resolve(cbindgen().core_only()) else {
+        panic!("a `std::` parameter must fail resolve under core_only()");
+    };
+    let err = err.to_string();
+    assert!(
+        err.contains("`table_len` at src/lib.rs:4:1 uses `std::collections::HashMap`"),
+        "{err}"
+    );
+
+    // Not covered by a cleared list either, but fine with the prefix allowed
+    // — the remaining `core::ffi::c_int` is covered by `core`.
+    assert!(registry()
+        .resolve(cbindgen().clear_allowed_prefixes())
+        .is_err());
+    let disallowed = |cb: Cbindgen| match registry().resolve(cb) {
+        Err(err) => err.to_string().contains("no allowed path prefix"),
+        Ok(_) => false,
+    };
+    assert!(!disallowed(
+        cbindgen().core_only().allowed_prefix("std::collections")
+    ));
+    assert!(!disallowed(cbindgen()));
+}
+
+/// `.target_pointer_width(32)` emits a `const` assertion on the width of
+/// `usize`; nothing is emitted when it is unset.
+#[test]
+fn target_pointer_width_asserts_usize_size() {
+    let registry = || {
+        let func: syn::ItemFn = syn::parse_quote!(
+            pub fn buffer_len(len: usize) -> usize {
+                len
+            }
+        );
+        Registry::<()>::from_items([(syn::Item::Fn(func), SourceLocation::default())])
+            .expect("index items")
+    };
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .function(syn::parse_quote!(buffer_len))
+    };
+
+    let src = write(cbindgen().target_pointer_width(32), registry(), "ptr_width");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains(
+            "const_:()=assert!(::core::mem::size_of::<usize>()*8==32usize,\
+             \"target_pointer_width:thebindingsweregeneratedfora32-bittarget\");"
+        ),
+        "{src}"
+    );
+
+    let src = write(cbindgen(), registry(), "ptr_width_unset");
+    assert!(!src.contains("size_of::<usize>"), "{src}");
+}
+
+#[test]
+#[should_panic(expected = "Cbindgen::target_pointer_width: unsupported width 48")]
+fn target_pointer_width_rejects_unknown_width() {
+    let _ = Cbindgen::new().target_pointer_width(48);
+}
+
+/// `.export_name_map(...)` exports the mapped function under the exact symbol
+/// via `#[export_name]`, overriding the symbol prefix; the other functions
+/// keep their prefixed `#[no_mangle]` symbol. Under `emit_extern_block` the
+/// symbol is declared with `#[link_name]`.
+#[test]
+fn export_name_map_overrides_mangled_symbol() {
+    let registry = || {
+        let items: Vec<(syn::Item, SourceLocation)> = [
+            "pub fn session_open() -> i32 { unimplemented!() }",
+            "pub fn session_close() -> i32 { unimplemented!() }",
+        ]
+        .into_iter()
+        .map(|source| (syn::parse_str(source).unwrap(), SourceLocation::default()))
+        .collect();
+        Registry::<()>::from_items(items).expect("index items")
+    };
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .symbol_prefix("z_")
+            .export_name_map(HashMap::from([(
+                "session_open".to_string(),
+                "LegacyOpen@8".to_string(),
+            )]))
+            .function(syn::parse_quote!(session_open))
+            .function(syn::parse_quote!(session_close))
+    };
+
+    let src = write(cbindgen(), registry(), "export_name");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("#[export_name=\"LegacyOpen@8\"]#[allow("),
+        "{src}"
+    );
+    assert_eq!(compact.matches("#[no_mangle]").count(), 1, "{src}");
+    assert!(compact.contains("#[no_mangle]#[allow("), "{src}");
+    assert!(compact.contains("extern\"C\"fnz_session_close("), "{src}");
+
+    let src = write(
+        cbindgen().emit_extern_block(true),
+        registry(),
+        "export_name_extern",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("#[link_name=\"LegacyOpen@8\"]#[allow("),
+        "{src}"
+    );
+    assert!(compact.contains("pubfnz_session_open()->i32;"), "{src}");
+    assert!(!compact.contains("export_name"), "{src}");
+}
+
+#[test]
+#[should_panic(expected = "Cbindgen::export_name_map: `not a name` is not a function name")]
+fn export_name_map_rejects_invalid_function_name() {
+    let _ = Cbindgen::new().export_name_map(HashMap::from([(
+        "not a name".to_string(),
+        "sym".to_string(),
+    )]));
+}
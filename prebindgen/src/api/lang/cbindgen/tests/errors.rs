@@ -149,3 +149,372 @@ fn error_out_param_is_null_guarded() {
         search = &search[pos + 3..];
     }
 }
+
+/// `.catch_unwind(true)` runs the body under `catch_unwind`: a scalar return
+/// falls back to `Default`, a `Result` reports the panic through `*e`.
+#[test]
+fn catch_unwind_falls_back_per_return_kind() {
+    let loc = SourceLocation::default();
+    let count: syn::ItemFn = syn::parse_quote!(
+        pub fn z_count() -> u32 {
+            unimplemented!()
+        }
+    );
+    let open: syn::ItemFn = syn::parse_quote!(
+        pub fn z_open(s: String) -> Result<(), Error> {
+            unimplemented!()
+        }
+    );
+    let registry = Registry::<()>::from_items([
+        (syn::Item::Fn(count), loc.clone()),
+        (syn::Item::Fn(open), loc.clone()),
+        (syn::Item::Struct(error_struct()), loc.clone()),
+    ])
+    .expect("index items");
+
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .free_memory_function("z_free")
+        .catch_unwind(true)
+        .data_struct(syn::parse_quote!(Error))
+        .base_name("z_error")
+        .error()
+        .function(syn::parse_quote!(z_count))
+        .function(syn::parse_quote!(z_open));
+
+    let src = write(cbindgen, registry, "catch_unwind");
+    let compact: String = src.split_whitespace().collect();
+
+    assert!(
+        compact.contains("::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(move||unsafe{"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("Result::Err(_)=>::core::default::Default::default()"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::std::string::String::from(\"z_openpanicked\")"),
+        "{src}"
+    );
+    assert!(!compact.contains("::std::process::abort()"), "{src}");
+}
+
+/// `.abort_on_panic(true)` aborts on every caught panic; without it a return
+/// with no built-in fallback aborts unless `.panic_return(...)` supplies one.
+#[test]
+fn catch_unwind_abort_and_panic_return() {
+    let loc = SourceLocation::default();
+    let make = || {
+        let func: syn::ItemFn = syn::parse_quote!(
+            pub fn z_error_new() -> Error {
+                unimplemented!()
+            }
+        );
+        Registry::<()>::from_items([
+            (syn::Item::Fn(func), loc.clone()),
+            (syn::Item::Struct(error_struct()), loc.clone()),
+        ])
+        .expect("index items")
+    };
+    let adapter = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .free_memory_function("z_free")
+            .catch_unwind(true)
+            .data_struct(syn::parse_quote!(Error))
+            .base_name("z_error")
+    };
+
+    let src = write(
+        adapter().function(syn::parse_quote!(z_error_new)),
+        make(),
+        "catch_unwind_struct",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("Result::Err(_)=>::std::process::abort()"),
+        "{src}"
+    );
+
+    let src = write(
+        adapter()
+            .function(syn::parse_quote!(z_error_new))
+            .panic_return(syn::parse_quote!(z_error {
+                message: ::core::ptr::null_mut()
+            })),
+        make(),
+        "catch_unwind_panic_return",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("Result::Err(_)=>{z_error{message:::core::ptr::null_mut(),}}"),
+        "{src}"
+    );
+
+    let src = write(
+        adapter()
+            .abort_on_panic(true)
+            .function(syn::parse_quote!(z_error_new))
+            .panic_return(syn::parse_quote!(unreachable!())),
+        make(),
+        "catch_unwind_abort",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("Result::Err(_)=>::std::process::abort()"),
+        "{src}"
+    );
+}
+
+/// A panicking source function called through a `.catch_unwind(true)`
+/// wrapper returns the documented fallback instead of unwinding into C.
+#[test]
+fn catch_unwind_returns_the_fallback_when_called() {
+    let source = r#"
+        pub struct Error {
+            pub message: String,
+        }
+        impl From<String> for Error {
+            fn from(message: String) -> Self {
+                Error { message }
+            }
+        }
+        pub struct Session {
+            id: u64,
+        }
+        pub fn z_count() -> u32 {
+            panic!("boom")
+        }
+        pub fn z_level() -> i32 {
+            panic!("boom")
+        }
+        pub fn z_session_open() -> Session {
+            panic!("boom")
+        }
+        pub fn z_check() -> Result<(), Error> {
+            panic!("boom")
+        }
+    "#;
+    let test = r#"
+        #[test]
+        fn returns_the_fallback() {
+            assert_eq!(unsafe { z_count() }, 0);
+            assert_eq!(unsafe { z_level() }, -1);
+            assert!(unsafe { z_session_open() }.is_null());
+            let mut e = z_error { message: ::core::ptr::null_mut() };
+            assert!(!unsafe { z_check(&mut e) });
+            let message = unsafe { ::std::ffi::CStr::from_ptr(e.message) };
+            assert_eq!(message.to_str().unwrap(), "z_check panicked");
+        }
+    "#;
+    let cbindgen = Cbindgen::new()
+        .free_memory_function("z_free")
+        .catch_unwind(true)
+        .data_struct(syn::parse_quote!(Error))
+        .base_name("z_error")
+        .error()
+        .opaque_ptr(syn::parse_quote!(Session))
+        .function(syn::parse_quote!(z_count))
+        .function(syn::parse_quote!(z_level))
+        .panic_return(syn::parse_quote!(-1))
+        .function(syn::parse_quote!(z_session_open))
+        .function(syn::parse_quote!(z_check));
+    run_generated(cbindgen, source, test, "catch_unwind_run");
+
+    // Under `result_as_out_param` the panic becomes the function's error code.
+    let source = r#"
+        pub struct MathError(i32);
+        impl From<String> for MathError {
+            fn from(_: String) -> Self {
+                MathError(9)
+            }
+        }
+        impl From<MathError> for i32 {
+            fn from(e: MathError) -> i32 {
+                e.0
+            }
+        }
+        pub fn z_divide(a: f64, b: f64) -> Result<f64, MathError> {
+            panic!("boom")
+        }
+    "#;
+    let test = r#"
+        #[test]
+        fn returns_the_error_code() {
+            let mut out = 0.0;
+            assert_eq!(unsafe { z_divide(1.0, 0.0, &mut out) }, 9);
+        }
+    "#;
+    let cbindgen = Cbindgen::new()
+        .catch_unwind(true)
+        .result_as_out_param(0)
+        .function(syn::parse_quote!(z_divide));
+    run_generated(cbindgen, source, test, "catch_unwind_code_run");
+}
+
+/// With `skip_invalid_items`, a declared fn crossing a type nothing converts
+/// is dropped (with a warning) and the rest is still generated; by default
+/// the same binding fails to resolve.
+#[test]
+fn skip_invalid_items_drops_only_the_invalid_fn() {
+    let loc = SourceLocation::default();
+    let registry = || {
+        Registry::<()>::from_items([
+            (
+                syn::Item::Fn(syn::parse_quote!(
+                    pub fn z_valid(x: u32) -> u32 {
+                        x
+                    }
+                )),
+                loc.clone(),
+            ),
+            (
+                syn::Item::Fn(syn::parse_quote!(
+                    pub fn z_invalid(x: Unported) -> u32 {
+                        unimplemented!()
+                    }
+                )),
+                loc.clone(),
+            ),
+            (
+                syn::Item::Struct(syn::parse_quote!(
+                    pub struct Unported {
+                        pub v: u32,
+                    }
+                )),
+                loc.clone(),
+            ),
+        ])
+        .expect("index items")
+    };
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .function(syn::parse_quote!(z_valid))
+            .function(syn::parse_quote!(z_invalid))
+    };
+
+    assert!(registry().resolve(cbindgen()).is_err());
+
+    let src = write(
+        cbindgen().skip_invalid_items(true),
+        registry(),
+        "skip_invalid",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("extern\"C\"fnz_valid("), "{src}");
+    assert!(!compact.contains("z_invalid"), "{src}");
+}
+
+/// `Generation::summary` counts what `write_rust` emits — wrapped
+/// functions, emitted types, converters, `const _` assertions — and lists
+/// the left-out items: the undeclared ones, then those dropped by
+/// `skip_invalid_items`.
+#[test]
+fn summary_counts_emitted_and_skipped_items() {
+    let loc = |line| SourceLocation {
+        file: "src/lib.rs".to_string(),
+        line,
+        ..Default::default()
+    };
+    let registry = Registry::<()>::from_items([
+        (
+            syn::Item::Fn(syn::parse_quote!(
+                pub fn z_point_norm(p: Point) -> u32 {
+                    unimplemented!()
+                }
+            )),
+            loc(1),
+        ),
+        (
+            syn::Item::Fn(syn::parse_quote!(
+                pub fn z_invalid(x: Unported) -> u32 {
+                    unimplemented!()
+                }
+            )),
+            loc(2),
+        ),
+        (
+            syn::Item::Fn(syn::parse_quote!(
+                pub fn z_undeclared() {}
+            )),
+            loc(3),
+        ),
+        (
+            syn::Item::Struct(syn::parse_quote!(
+                #[repr(C)]
+                pub struct Point {
+                    pub x: u32,
+                    pub y: u32,
+                }
+            )),
+            loc(4),
+        ),
+        (
+            syn::Item::Struct(syn::parse_quote!(
+                pub struct Unported {
+                    pub v: u32,
+                }
+            )),
+            loc(5),
+        ),
+    ])
+    .expect("index items");
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .target_pointer_width(64)
+        .skip_invalid_items(true)
+        .repr_c_struct(syn::parse_quote!(Point))
+        .function(syn::parse_quote!(z_point_norm))
+        .panic()
+        .function(syn::parse_quote!(z_invalid));
+
+    let gen = registry.resolve(cbindgen).expect("resolve");
+    let summary = gen.summary().expect("summary");
+    // `__cbg_in_Point`, `__cbg_in_u32`, `__cbg_out_Point`, `__cbg_out_u32`;
+    // the pointer-width and the `Point` layout asserts.
+    assert_eq!(
+        (
+            summary.functions,
+            summary.types,
+            summary.converters,
+            summary.assertions
+        ),
+        (1, 1, 4, 2)
+    );
+    let skipped: Vec<(RecordKind, &str, usize, &str)> = summary
+        .skipped
+        .iter()
+        .map(|s| {
+            (
+                s.kind.clone(),
+                s.name.as_str(),
+                s.location.line,
+                s.reason.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        skipped,
+        [
+            (RecordKind::Function, "z_undeclared", 3, "undeclared"),
+            (RecordKind::Struct, "Unported", 5, "undeclared"),
+            (
+                RecordKind::Function,
+                "z_invalid",
+                2,
+                "unresolved type `Unported`"
+            ),
+        ]
+    );
+    let shown = summary.to_string();
+    assert!(
+        shown.starts_with("1 functions, 1 types, 4 converters, 2 assertions, 3 skipped"),
+        "{shown}"
+    );
+    assert!(
+        shown.contains("\n  src/lib.rs:2:0: function `z_invalid`: unresolved type `Unported`"),
+        "{shown}"
+    );
+}
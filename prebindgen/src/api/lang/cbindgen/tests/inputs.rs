@@ -34,6 +34,42 @@ fn slice_u8_input_two_params() {
     assert!(compact.contains("->*mutz_zbytes"), "{src}");
 }
 
+/// A mutable scalar slice `&mut [f32]` lowers to (`*mut f32`, `usize`) and is
+/// rebuilt with `from_raw_parts_mut`, alongside a const slice in the same call.
+#[test]
+fn slice_mut_input_two_params() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn z_scale(src: &[f32], dst: &mut [f32], by: f32) {
+            unimplemented!()
+        }
+    );
+    let registry =
+        Registry::<()>::from_items([(syn::Item::Fn(func), loc.clone())]).expect("index items");
+
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .function(syn::parse_quote!(z_scale));
+
+    let src = write(cbindgen, registry, "slice_mut");
+    let compact: String = src.split_whitespace().collect();
+
+    assert!(
+        compact
+            .contains("fnz_scale(src:*constf32,src_len:usize,dst:*mutf32,dst_len:usize,by:f32,)"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("letdst:&mut[f32]=ifdst.is_null(){&mut[]}else{::core::slice::from_raw_parts_mut(dst,dst_len)};"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::slice::from_raw_parts(src,src_len)"),
+        "{src}"
+    );
+    assert!(compact.contains("zenoh_flat::z_scale(src,dst,by)"), "{src}");
+}
+
 /// `Option<ZZBytes>` input (opaque, pointer-wire inner) reuses the handle
 /// wire `z_zbytes_t*`: NULL ⇒ `None`, non-NULL is consumed via the inner
 /// converter. The inner is fallible, so the decode routes through the
@@ -141,6 +177,47 @@ fn str_borrow_input_lowering() {
     assert!(compact.contains("panic!("), "{src}");
 }
 
+/// `&CStr` inputs borrow the `const char *` without a UTF-8 check; only a
+/// NULL pointer fails, routed through the `Result` error channel.
+#[test]
+fn cstr_borrow_input_lowering() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn z_set_name(name: &std::ffi::CStr) -> Result<(), Error> {
+            unimplemented!()
+        }
+    );
+    let registry = Registry::<()>::from_items([
+        (syn::Item::Fn(func), loc.clone()),
+        (syn::Item::Struct(error_struct()), loc.clone()),
+    ])
+    .expect("index items");
+
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .free_memory_function("z_free")
+        .data_struct(syn::parse_quote!(Error))
+        .base_name("z_error")
+        .error()
+        .function(syn::parse_quote!(z_set_name));
+
+    let src = write(cbindgen, registry, "cstr_borrow");
+    let compact: String = src.split_whitespace().collect();
+
+    assert!(
+        compact.contains("fnz_set_name(name:*const::core::ffi::c_char,e:*mutz_error"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::result::Result::Ok(::core::ffi::CStr::from_ptr(v))"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("if!e.is_null(){*e=__cbg_out_Error("),
+        "{src}"
+    );
+}
+
 /// `z_keyexpr_relation_to(a: &ZKeyExpr, b: &ZKeyExpr) -> SetIntersectionLevel`
 /// lowers to a borrow-input + enum-return wrapper; `.panic()` lets the
 /// fallible borrow decode abort.
@@ -245,3 +322,29 @@ fn mutable_opaque_borrow_input_lowering() {
         "{src}"
     );
 }
+
+/// A `mut` value binding stays with the source fn: the wrapper binds the
+/// bare name and passes the value through unchanged.
+#[test]
+fn mut_value_param_binds_bare_name() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn show_square(mut x: i32) -> i32 {
+            x *= x;
+            x
+        }
+    );
+    let registry =
+        Registry::<()>::from_items([(syn::Item::Fn(func), loc.clone())]).expect("index items");
+
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(source_crate))
+        .function(syn::parse_quote!(show_square));
+
+    let src = write(cbindgen, registry, "mut_value_param");
+    let compact: String = src.split_whitespace().collect();
+
+    assert!(compact.contains("fnshow_square(x:i32)->i32"), "{src}");
+    assert!(!compact.contains("mutx"), "{src}");
+    assert!(compact.contains("source_crate::show_square(x)"), "{src}");
+}
@@ -269,3 +269,1076 @@ fn opaque_error_lowering() {
         "{src}"
     );
 }
+
+/// Source `///` docs ride onto the generated function, data struct, field,
+/// enum and variant (for cbindgen's header comments); `.strip_docs(true)`
+/// drops them.
+#[test]
+fn docs_carried_to_generated_items() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        /// Sample a point.
+        pub fn z_sample(kind: Kind) -> Point {
+            unimplemented!()
+        }
+    );
+    let point: syn::ItemStruct = syn::parse_quote!(
+        /// A 2D point.
+        pub struct Point {
+            /// Horizontal coordinate.
+            pub x: f64,
+            pub y: f64,
+        }
+    );
+    let kind: syn::ItemEnum = syn::parse_quote!(
+        /// Sampling kind.
+        pub enum Kind {
+            /// Uniform sampling.
+            Uniform = 0,
+            Random = 1,
+        }
+    );
+    let registry = || {
+        Registry::<()>::from_items([
+            (syn::Item::Fn(func.clone()), loc.clone()),
+            (syn::Item::Struct(point.clone()), loc.clone()),
+            (syn::Item::Enum(kind.clone()), loc.clone()),
+        ])
+        .expect("index items")
+    };
+    let adapter = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .data_struct(syn::parse_quote!(Point))
+            .enum_type(syn::parse_quote!(Kind))
+            .function(syn::parse_quote!(z_sample))
+    };
+
+    let src = write(adapter(), registry(), "docs");
+    for doc in [
+        "/// Sample a point.\n#[no_mangle]",
+        "/// A 2D point.\n#[repr(C)]",
+        "/// Horizontal coordinate.\n    pub x: f64,",
+        "/// Sampling kind.\n#[repr(C)]",
+        "/// Uniform sampling.\n    Uniform = 0,",
+    ] {
+        assert!(src.contains(doc), "missing {doc:?}:\n{src}");
+    }
+
+    let src = write(adapter().strip_docs(true), registry(), "docs_stripped");
+    assert!(!src.contains("///"), "{src}");
+}
+
+/// `.annotate_provenance(true)` appends a `from <crate>:<file>:<line>` doc
+/// line after the carried docs of each function wrapper and type mirror.
+#[test]
+fn provenance_doc_follows_carried_docs() {
+    let at = |line| SourceLocation {
+        file: "src/lib.rs".to_string(),
+        line,
+        column: 1,
+        crate_name: Some("zenoh-flat".to_string()),
+        ..SourceLocation::default()
+    };
+    let func: syn::ItemFn = syn::parse_quote!(
+        /// Sample a point.
+        pub fn z_sample(kind: Kind) -> Point {
+            unimplemented!()
+        }
+    );
+    let point: syn::ItemStruct = syn::parse_quote!(
+        pub struct Point {
+            pub x: f64,
+        }
+    );
+    let kind: syn::ItemEnum = syn::parse_quote!(
+        /// Sampling kind.
+        pub enum Kind {
+            Uniform = 0,
+        }
+    );
+    let registry = || {
+        Registry::<()>::from_items([
+            (syn::Item::Fn(func.clone()), at(10)),
+            (syn::Item::Struct(point.clone()), at(20)),
+            (syn::Item::Enum(kind.clone()), at(30)),
+        ])
+        .expect("index items")
+    };
+    let adapter = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .data_struct(syn::parse_quote!(Point))
+            .enum_type(syn::parse_quote!(Kind))
+            .function(syn::parse_quote!(z_sample))
+    };
+
+    let src = write(
+        adapter().annotate_provenance(true),
+        registry(),
+        "provenance",
+    );
+    for doc in [
+        "/// Sample a point.\n/// from zenoh-flat:src/lib.rs:10\n#[no_mangle]",
+        "/// from zenoh-flat:src/lib.rs:20\n#[repr(C)]",
+        "/// Sampling kind.\n/// from zenoh-flat:src/lib.rs:30\n#[repr(C)]",
+    ] {
+        assert!(src.contains(doc), "missing {doc:?}:\n{src}");
+    }
+
+    let src = write(adapter(), registry(), "provenance_off");
+    assert!(!src.contains(" from zenoh-flat"), "{src}");
+}
+
+/// `.emit_safety_doc(true)` appends a `# Safety` section listing the pointer
+/// parameters to each function wrapper, without allowing the clippy lint; only
+/// a parameter decoded from NULL (here an `Option`) is said to accept it.
+#[test]
+fn safety_doc_lists_pointer_requirements() {
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        syn::parse_quote!(
+            /// Add to a counter.
+            pub fn z_counter_add(c: &mut Counter, mut label: &str, by: f64) -> f64 {
+                unimplemented!()
+            }
+        ),
+        syn::parse_quote!(
+            pub fn z_scale(by: f64) -> f64 {
+                by
+            }
+        ),
+        syn::parse_quote!(
+            pub fn z_counter_peek(c: Option<&Counter>) -> f64 {
+                unimplemented!()
+            }
+        ),
+    ]
+    .into_iter()
+    .map(|item| (item, SourceLocation::default()))
+    .collect();
+    let adapter = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .opaque_ptr(syn::parse_quote!(Counter))
+            .base_name("z_counter")
+            .function(syn::parse_quote!(z_counter_add))
+            .panic()
+            .function(syn::parse_quote!(z_scale))
+            .function(syn::parse_quote!(z_counter_peek))
+            .panic()
+    };
+    let registry = || Registry::<()>::from_items(items.clone()).unwrap();
+
+    let src = write(adapter().emit_safety_doc(true), registry(), "safety_doc");
+    for doc in [
+        "/// Add to a counter.\n///\n/// # Safety\n///\n/// Each pointer must be properly \
+         aligned and valid for the whole call:\n/// - `c` must be non-null and point to a \
+         `z_counter` the callee may read and write\n/// - `label` must be non-null and point \
+         to a NUL-terminated string\n#[no_mangle]",
+        "/// # Safety\n///\n/// Takes no pointers: any argument values are valid.\n#[no_mangle]",
+        "/// - `c` may be NULL; otherwise it must point to a `z_counter` the callee may read\n",
+    ] {
+        assert!(src.contains(doc), "missing {doc:?}:\n{src}");
+    }
+    assert!(!src.contains("missing_safety_doc"), "{src}");
+
+    let src = write(adapter(), registry(), "safety_doc_off");
+    assert!(!src.contains("# Safety"), "{src}");
+}
+
+/// `.emit_pointer_null_checks(true)` checks each borrowed pointer parameter
+/// for NULL before decoding it — a `debug_assert!` unless
+/// `.release_null_checks(true)` — and leaves value parameters alone.
+#[test]
+fn pointer_null_checks_guard_borrowed_params() {
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn z_counter_add(c: &mut Counter, label: &str, by: f64) -> f64 {
+            unimplemented!()
+        }
+    );
+    let registry = || {
+        Registry::<()>::from_items([(syn::Item::Fn(func.clone()), SourceLocation::default())])
+            .unwrap()
+    };
+    let adapter = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .opaque_ptr(syn::parse_quote!(Counter))
+            .base_name("z_counter")
+            .function(syn::parse_quote!(z_counter_add))
+            .panic()
+    };
+
+    let src = write(
+        adapter().emit_pointer_null_checks(true),
+        registry(),
+        "null_checks",
+    );
+    let compact: String = src.split_whitespace().collect();
+    for param in ["c", "label"] {
+        let check = format!(
+            "debug_assert!(!{param}.is_null(),\"`{param}`of`z_counter_add`mustnotbeNULL\");let{param}="
+        );
+        assert!(compact.contains(&check), "missing {check}:\n{src}");
+    }
+    assert!(!compact.contains("by.is_null()"), "{src}");
+
+    let src = write(
+        adapter()
+            .emit_pointer_null_checks(true)
+            .release_null_checks(true),
+        registry(),
+        "null_checks_release",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("assert!(!c.is_null()"), "{src}");
+    assert!(!compact.contains("debug_assert!"), "{src}");
+
+    let src = write(adapter(), registry(), "null_checks_off");
+    assert!(!src.contains("must not be NULL"), "{src}");
+}
+
+/// `#[deprecated]` is carried onto the function wrapper by default (with the
+/// call to the deprecated source fn allowed); other attributes only when
+/// named by `.keep_attribute()`.
+#[test]
+fn deprecated_and_kept_attributes_reach_wrapper() {
+    let registry = || {
+        let func: syn::ItemFn = syn::parse_quote!(
+            /// Old entry point.
+            #[deprecated(note = "use open2")]
+            #[must_use]
+            #[inline(never)]
+            pub fn open() -> i32 {
+                unimplemented!()
+            }
+        );
+        Registry::<()>::from_items([(syn::Item::Fn(func), SourceLocation::default())]).unwrap()
+    };
+    let adapter = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(myflat))
+            .function(syn::parse_quote!(open))
+    };
+
+    let src = write(adapter(), registry(), "deprecated_default");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("///Oldentrypoint.#[deprecated(note=\"useopen2\")]#[no_mangle]"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("#[allow(deprecated)]pubunsafeextern"),
+        "{src}"
+    );
+    assert!(
+        !src.contains("must_use") && !src.contains("inline(never)"),
+        "{src}"
+    );
+
+    let src = write(
+        adapter().keep_attribute("must_use").strip_docs(true),
+        registry(),
+        "deprecated_kept",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("#[deprecated(note=\"useopen2\")]#[must_use]#[no_mangle]"),
+        "{src}"
+    );
+    assert!(
+        !src.contains("///") && !src.contains("inline(never)"),
+        "{src}"
+    );
+}
+
+#[test]
+#[should_panic(expected = "Cbindgen::keep_attribute: `cfg` attributes are not carried")]
+fn keep_attribute_rejects_cfg() {
+    let _ = Cbindgen::new().keep_attribute("cfg");
+}
+
+/// `.map_type(from, to)` crosses `from` as `to` wherever it appears — by value
+/// and behind a reference — and guards the reinterpretation with a size/align
+/// assert.
+#[test]
+fn map_type_reinterprets_as_wire_type() {
+    let loc = SourceLocation::default();
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "pub fn stamp_shift(t: chrono::DateTime<chrono::Utc>, by: i64) -> chrono::DateTime<chrono::Utc> { unimplemented!() }",
+        "pub fn stamp_secs(t: &chrono::DateTime<chrono::Utc>) -> i64 { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| {
+        let function: syn::ItemFn = syn::parse_str(source).unwrap();
+        (syn::Item::Fn(function), loc.clone())
+    })
+    .collect();
+    let registry = Registry::<()>::from_items(items).unwrap();
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(myflat))
+        .map_type(
+            syn::parse_quote!(chrono::DateTime<chrono::Utc>),
+            syn::parse_quote!(i64),
+        )
+        .function(syn::parse_quote!(stamp_shift))
+        .function(syn::parse_quote!(stamp_secs))
+        .panic();
+
+    let src = write(cbindgen, registry, "map_type");
+    let compact: String = src.split_whitespace().collect();
+
+    assert!(
+        compact.contains("extern\"C\"fnstamp_shift(t:i64,by:i64)->i64"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::mem::transmute::<i64,chrono::DateTime<chrono::Utc>>(v)"),
+        "{src}"
+    );
+    assert!(
+        compact.contains(
+            "::core::mem::size_of::<chrono::DateTime<chrono::Utc>>()==::core::mem::size_of::<i64>()"
+        ),
+        "{src}"
+    );
+    assert!(compact.contains("fnstamp_secs(t:*consti64)"), "{src}");
+}
+
+/// `Generation::destination` collects what `write_rust` emits, so it can be
+/// written with `unsafe` blocks explained.
+#[test]
+fn destination_comments_unsafe_blocks() {
+    let items = vec![(
+        syn::parse_quote!(
+            pub fn stamp_secs(t: chrono::DateTime<chrono::Utc>) -> i64 {
+                unimplemented!()
+            }
+        ),
+        SourceLocation {
+            file: "src/lib.rs".to_string(),
+            line: 3,
+            column: 1,
+            ..SourceLocation::default()
+        },
+    )];
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(myflat))
+        .catch_unwind(true)
+        .map_type(
+            syn::parse_quote!(chrono::DateTime<chrono::Utc>),
+            syn::parse_quote!(i64),
+        )
+        .function(syn::parse_quote!(stamp_secs));
+    let generation = Registry::<()>::from_items(items)
+        .unwrap()
+        .resolve(cbindgen)
+        .expect("resolve");
+    let src = generation
+        .destination()
+        .expect("destination")
+        .with_unsafe_comments(true)
+        .to_string();
+    // A converter is generated code: its comment has no location.
+    assert!(
+        src.contains(
+            "    // SAFETY: reinterprets `i64` as `chrono::DateTime<chrono::Utc>`\n    unsafe {"
+        ),
+        "{src}"
+    );
+    // The wrapper carries the location of the function it wraps.
+    assert!(
+        src.contains(
+            "// SAFETY: no generated justification, for the item at src/lib.rs:3:1\n        \
+             ::std::panic::AssertUnwindSafe(move || unsafe {"
+        ),
+        "{src}"
+    );
+}
+
+/// `auto_transparent_newtypes` crosses an undeclared `#[repr(transparent)]`
+/// scalar newtype as its field's type, asserted like a `map_type`; a newtype
+/// that isn't `repr(transparent)` is left alone.
+#[test]
+fn auto_transparent_newtypes_cross_as_their_field() {
+    let loc = SourceLocation::default();
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "#[repr(transparent)] pub struct Handle(pub u64);",
+        "pub struct Plain(pub u64);",
+        "pub fn handle_next(h: Handle) -> Handle { unimplemented!() }",
+        "pub fn plain_get(p: Plain) -> u64 { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| (syn::parse_str::<syn::Item>(source).unwrap(), loc.clone()))
+    .collect();
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(myflat))
+            .auto_transparent_newtypes(true)
+            .function(syn::parse_quote!(handle_next))
+            .panic()
+    };
+
+    let src = write(
+        cbindgen(),
+        Registry::<()>::from_items(items.clone()).unwrap(),
+        "auto_transparent_newtypes",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("extern\"C\"fnhandle_next(h:u64)->u64"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::mem::transmute::<u64,myflat::Handle>(v)"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::mem::size_of::<myflat::Handle>()==::core::mem::size_of::<u64>()"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("auto_transparent_newtypes:`Handle`anditswiretypedifferinsize"),
+        "{src}"
+    );
+
+    let registry = Registry::<()>::from_items(items).unwrap();
+    assert!(registry
+        .resolve(cbindgen().function(syn::parse_quote!(plain_get)).panic())
+        .is_err());
+}
+
+/// Mapping an already-declared type is a builder error.
+#[test]
+#[should_panic(expected = "Cbindgen::map_type cannot map `Stamp`")]
+fn map_type_rejects_declared_type() {
+    let _ = Cbindgen::new()
+        .data_struct(syn::parse_quote!(Stamp))
+        .map_type(syn::parse_quote!(Stamp), syn::parse_quote!(i64));
+}
+
+/// Registry with one fn crossing `Guard<u32>`-style wrapper `wrapper`.
+fn wrapped_u32_registry(wrapper: &str) -> Registry<()> {
+    let function: syn::ItemFn = syn::parse_str(&format!(
+        "pub fn bump(v: {wrapper}<u32>) -> u32 {{ unimplemented!() }}"
+    ))
+    .unwrap();
+    Registry::<()>::from_items(vec![(syn::Item::Fn(function), SourceLocation::default())]).unwrap()
+}
+
+/// `strict_transparent` accepts stripping a standard transparent wrapper and
+/// a wrapper the caller acknowledges.
+#[test]
+fn strict_transparent_accepts_known_and_acknowledged_wrappers() {
+    for (wrapper, acknowledged) in [("core::num::Wrapping", vec![]), ("Guard", vec!["Guard"])] {
+        let cbindgen = Cbindgen::new()
+            .source_module(syn::parse_quote!(myflat))
+            .strict_transparent(true, acknowledged)
+            .map_type(
+                syn::parse_str(&format!("{wrapper}<u32>")).unwrap(),
+                syn::parse_quote!(u32),
+            )
+            .function(syn::parse_quote!(bump))
+            .panic();
+        let src = write(
+            cbindgen,
+            wrapped_u32_registry(wrapper),
+            "strict_transparent",
+        );
+        let compact: String = src.split_whitespace().collect();
+        assert!(compact.contains("extern\"C\"fnbump(v:u32)->u32"), "{src}");
+    }
+}
+
+/// Under `strict_transparent`, stripping a wrapper that is neither a known
+/// transparent one nor acknowledged fails the build.
+#[test]
+#[should_panic(
+    expected = "Cbindgen::strict_transparent: map_type of `Guard < u32 >` strips `Guard`"
+)]
+fn strict_transparent_rejects_unknown_wrapper() {
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(myflat))
+        .strict_transparent(true, ["Other"])
+        .map_type(syn::parse_quote!(Guard<u32>), syn::parse_quote!(u32))
+        .function(syn::parse_quote!(bump))
+        .panic();
+    let _ = write(
+        cbindgen,
+        wrapped_u32_registry("Guard"),
+        "strict_transparent",
+    );
+}
+
+/// `transparent_wrapper_arg` points the transparency check at a later type
+/// argument: `Tagged<Tag, u32>` may be stripped to `u32`, but not to `Tag`.
+#[test]
+fn transparent_wrapper_arg_selects_the_payload_argument() {
+    let registry = || {
+        let function: syn::ItemFn = syn::parse_quote!(
+            pub fn bump(v: Tagged<Meters, u32>) -> u32 {
+                unimplemented!()
+            }
+        );
+        Registry::<()>::from_items(vec![(syn::Item::Fn(function), SourceLocation::default())])
+            .unwrap()
+    };
+    let cbindgen = |to: syn::Type| {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(myflat))
+            .strict_transparent(true, Vec::<String>::new())
+            .transparent_wrapper_arg("Tagged", 1)
+            .map_type(syn::parse_quote!(Tagged<Meters, u32>), to)
+            .function(syn::parse_quote!(bump))
+            .panic()
+    };
+    let src = write(cbindgen(syn::parse_quote!(u32)), registry(), "wrapper_arg");
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("extern\"C\"fnbump(v:u32)->u32"), "{src}");
+
+    let Err(panic) = std::panic::catch_unwind(|| {
+        write(
+            cbindgen(syn::parse_quote!(Meters)),
+            registry(),
+            "wrapper_arg_tag",
+        )
+    }) else {
+        panic!("stripping `Tagged` to its tag must be rejected");
+    };
+    let msg = panic.downcast_ref::<String>().cloned().unwrap_or_default();
+    assert!(msg.contains("strips `Tagged`"), "{msg}");
+}
+
+/// A mapped generic type is qualified segment by segment: every source
+/// struct inside `MaybeUninit<Wrapper<Foo>>` gets the source module in the
+/// converters and in the size/align assert, the std path is left alone.
+#[test]
+fn map_type_qualifies_nested_source_types() {
+    let loc = SourceLocation::default();
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "pub struct Foo { pub x: u32 }",
+        "pub struct Wrapper<T> { pub inner: T }",
+        "pub fn slot_reset(v: core::mem::MaybeUninit<Wrapper<Foo>>) -> core::mem::MaybeUninit<Wrapper<Foo>> { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| (syn::parse_str(source).unwrap(), loc.clone()))
+    .collect();
+    let registry = Registry::<()>::from_items(items).unwrap();
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(myflat))
+        .map_type(
+            syn::parse_quote!(core::mem::MaybeUninit<Wrapper<Foo>>),
+            syn::parse_quote!(u32),
+        )
+        .function(syn::parse_quote!(slot_reset))
+        .panic();
+
+    let src = write(cbindgen, registry, "map_type_nested");
+    let compact: String = src.split_whitespace().collect();
+    let qualified = "core::mem::MaybeUninit<myflat::Wrapper<myflat::Foo>>";
+    assert!(
+        compact.contains(&format!(
+            "::core::mem::size_of::<{qualified}>()==::core::mem::size_of::<u32>()"
+        )),
+        "{src}"
+    );
+    assert!(
+        compact.contains(&format!("::core::mem::transmute::<u32,{qualified},>(v)")),
+        "{src}"
+    );
+    assert!(
+        compact.contains(&format!("::core::mem::transmute::<{qualified},u32,>(v)")),
+        "{src}"
+    );
+    assert_eq!(compact.matches("size_of::<core").count(), 1, "{src}");
+}
+
+/// A source `type` alias of a scalar crosses as the scalar, even when the
+/// alias chain was captured before the alias it names.
+#[test]
+fn scalar_alias_chain_crosses_as_scalar() {
+    let loc = SourceLocation::default();
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "pub type Status = RawStatus;",
+        "pub type RawStatus = i8;",
+        "pub fn status_flip(s: Status) -> RawStatus { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| (syn::parse_str(source).unwrap(), loc.clone()))
+    .collect();
+    let registry = Registry::<()>::from_items(items).unwrap();
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(myflat))
+        .function(syn::parse_quote!(status_flip));
+
+    let src = write(cbindgen, registry, "scalar_alias");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("extern\"C\"fnstatus_flip(s:i8)->i8"),
+        "{src}"
+    );
+    assert!(compact.contains("(v:i8)->myflat::Status{v}"), "{src}");
+    assert!(compact.contains("(v:myflat::RawStatus)->i8{v}"), "{src}");
+}
+
+#[test]
+fn bool_as_u8_rewrites_bool_params_and_returns() {
+    let registry = || {
+        let func: syn::ItemFn = syn::parse_quote!(
+            pub fn z_negate(flag: bool) -> bool {
+                unimplemented!()
+            }
+        );
+        Registry::<()>::from_items([(syn::Item::Fn(func), SourceLocation::default())]).unwrap()
+    };
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(myflat))
+            .function(syn::parse_quote!(z_negate))
+    };
+
+    let src = write(cbindgen().bool_as_u8(true), registry(), "bool_as_u8");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("extern\"C\"fnz_negate(flag:u8)->u8"),
+        "{src}"
+    );
+    assert!(compact.contains("(v:u8)->bool{v!=0}"), "{src}");
+    assert!(compact.contains("(v:bool)->u8{vasu8}"), "{src}");
+
+    let src = write(cbindgen(), registry(), "bool_native");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("extern\"C\"fnz_negate(flag:bool)->bool"),
+        "{src}"
+    );
+}
+
+#[test]
+fn option_ref_as_pointer_crosses_nullable_scalar_pointers() {
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn option_to_reference(input: Option<&u8>) -> Option<&u8> {
+            input
+        }
+    );
+    let registry =
+        Registry::<()>::from_items([(syn::Item::Fn(func), SourceLocation::default())]).unwrap();
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(myflat))
+        .option_ref_as_pointer(true)
+        .function(syn::parse_quote!(option_to_reference))
+        .panic();
+
+    let src = write(cbindgen, registry, "option_ref_as_pointer");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("extern\"C\"fnoption_to_reference(input:*constu8)->*constu8"),
+        "{src}"
+    );
+    // Parameter: NULL decodes to `None`.
+    assert!(
+        compact.contains(
+            "(v:*constu8,)->::core::option::Option<&'au8>{const_:()=assert!(\
+             ::core::mem::size_of::<::core::option::Option<&u8>>()==\
+             ::core::mem::size_of::<*constu8>(),"
+        ),
+        "{src}"
+    );
+    assert!(
+        compact.contains(
+            "ifv.is_null(){::core::option::Option::None}else{::core::option::Option::Some(&*v)}"
+        ),
+        "{src}"
+    );
+    // Return: `Some(&x)` is the pointer, `None` is NULL.
+    assert!(
+        compact.contains("(v:&u8)->*constu8{const_:()=assert!("),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::option::Option::None=>{__ret=::core::ptr::null();}"),
+        "{src}"
+    );
+}
+
+/// Under `box_as_raw_pointer`, a `Box<T>` return crosses as the owning
+/// `*mut` to `T`'s wire (released with `Box::into_raw`), and a `Box<T>`
+/// parameter is rebuilt from it with `Box::from_raw` after a null check.
+#[test]
+fn box_as_raw_pointer_crosses_owned_boxes() {
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        syn::parse_quote!(
+            pub fn z_counter_new() -> Box<Counter> {
+                unimplemented!()
+            }
+        ),
+        syn::parse_quote!(
+            pub fn z_counter_consume(c: Box<Counter>) -> u32 {
+                unimplemented!()
+            }
+        ),
+        syn::parse_quote!(
+            pub fn z_boxed_u64(v: Box<u64>) -> Box<u64> {
+                v
+            }
+        ),
+    ]
+    .into_iter()
+    .map(|item| (item, SourceLocation::default()))
+    .collect();
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .opaque_ptr(syn::parse_quote!(Counter))
+            .base_name("z_counter")
+            .function(syn::parse_quote!(z_counter_new))
+            .function(syn::parse_quote!(z_counter_consume))
+            .panic()
+            .function(syn::parse_quote!(z_boxed_u64))
+            .panic()
+    };
+
+    let src = write(
+        cbindgen().box_as_raw_pointer(true),
+        Registry::<()>::from_items(items.clone()).unwrap(),
+        "box_as_raw_pointer",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("extern\"C\"fnz_counter_new()->*mutz_counter"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("->*mutz_counter{::std::boxed::Box::into_raw(v)as*mutz_counter}"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("extern\"C\"fnz_counter_consume(c:*mutz_counter)->u32"),
+        "{src}"
+    );
+    assert!(
+        compact.contains(
+            "::core::result::Result::Ok(::std::boxed::Box::from_raw(vas*mutzenoh_flat::Counter),)"
+        ),
+        "{src}"
+    );
+    assert!(compact.contains("nullCounterboxpointer"), "{src}");
+    // A scalar pointee crosses as a pointer to itself.
+    assert!(
+        compact.contains("extern\"C\"fnz_boxed_u64(v:*mutu64)->*mutu64"),
+        "{src}"
+    );
+
+    // Off by default: such signatures don't resolve.
+    let off = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .function(syn::parse_quote!(z_boxed_u64))
+        .panic();
+    assert!(Registry::<()>::from_items(items)
+        .unwrap()
+        .resolve(off)
+        .is_err());
+}
+
+/// A scalar const whose initializer is built from literals, operators and
+/// other captured consts is copied with its references flattened, and
+/// asserted equal to the source const; any other const path-aliases the
+/// source item. `assert_const_values(false)` drops the assertions.
+#[test]
+fn const_expressions_are_copied_with_flattened_references() {
+    let registry = || {
+        let items: Vec<(syn::Item, SourceLocation)> = [
+            "pub const FLAG_BASE: u32 = 1 << 3;",
+            "pub const FLAG_ALL: u32 = (crate::flags::FLAG_BASE | 1) as u32;",
+            "pub const FLAG_MAX: u32 = u32::MAX;",
+            "pub const NAME: &str = \"flags\";",
+        ]
+        .into_iter()
+        .map(|source| (syn::parse_str(source).unwrap(), SourceLocation::default()))
+        .collect();
+        Registry::<()>::from_items(items).expect("index items")
+    };
+    let cbindgen = || Cbindgen::new().source_module(syn::parse_quote!(myflat));
+
+    let src = write(cbindgen(), registry(), "const_exprs");
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("pubconstFLAG_BASE:u32=1<<3;"), "{src}");
+    assert!(
+        compact.contains("pubconstFLAG_ALL:u32=(FLAG_BASE|1)asu32;"),
+        "{src}"
+    );
+    assert!(
+        compact.contains(
+            "const_:()=assert!(FLAG_ALL==myflat::FLAG_ALL,\"const`FLAG_ALL`differsfromthesourceconst\");"
+        ),
+        "{src}"
+    );
+    assert_eq!(compact.matches("const_:()=assert!(").count(), 2, "{src}");
+    // Not portable (`u32::MAX`) or not scalar: aliased.
+    assert!(
+        compact.contains("pubconstFLAG_MAX:u32=myflat::FLAG_MAX;"),
+        "{src}"
+    );
+    assert!(compact.contains("pubconstNAME:&str=myflat::NAME;"), "{src}");
+
+    let src = write(
+        cbindgen().assert_const_values(false),
+        registry(),
+        "const_exprs_unasserted",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("pubconstFLAG_ALL:u32=(FLAG_BASE|1)asu32;"),
+        "{src}"
+    );
+    assert!(!compact.contains("assert!"), "{src}");
+}
+
+/// A const of a data struct, set field by field from portable scalar
+/// initializers, is copied onto the C mirror and asserted equal to the source
+/// const field by field; one that can't be copied aliases the source const
+/// under its qualified type.
+#[test]
+fn data_struct_consts_are_copied_onto_the_mirror() {
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "pub struct Point { pub x: i32, pub y: i32 }",
+        "pub const ORIGIN_X: i32 = 0;",
+        "pub const ORIGIN: Point = Point { y: 0, x: ORIGIN_X };",
+        "pub const FAR: Point = Point { x: i32::MAX, y: 0 };",
+        "pub fn z_point_norm(p: Point) -> i32 { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| (syn::parse_str(source).unwrap(), SourceLocation::default()))
+    .collect();
+    let src = write(
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(myflat))
+            .data_struct(syn::parse_quote!(Point))
+            .base_name("z_point")
+            .function(syn::parse_quote!(z_point_norm)),
+        Registry::<()>::from_items(items).unwrap(),
+        "struct_consts",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("pubconstORIGIN:z_point=z_point{x:ORIGIN_X,y:0};"),
+        "{src}"
+    );
+    assert!(
+        compact.contains(
+            "const_:()=assert!(ORIGIN.x==myflat::ORIGIN.x&&ORIGIN.y==myflat::ORIGIN.y,\
+             \"const`ORIGIN`differsfromthesourceconst\");"
+        ),
+        "{src}"
+    );
+    // `i32::MAX` is not portable.
+    assert!(
+        compact.contains("pubconstFAR:myflat::Point=myflat::FAR;"),
+        "{src}"
+    );
+}
+
+/// Float consts and struct const fields are asserted equal to the source by
+/// their bits, so a copied NaN still compiles.
+#[test]
+fn nan_consts_are_asserted_by_bits() {
+    let source = r#"
+        pub struct Point { pub x: f64, pub y: f32 }
+        pub const Q: f64 = 0.0 / 0.0;
+        pub const HOLE: Point = Point { x: 0.0 / 0.0, y: 1.5 };
+        pub fn z_point_x(p: Point) -> f64 { p.x }
+    "#;
+    let test = r#"
+        #[test]
+        fn copies_nan() {
+            assert!(Q.is_nan());
+            assert!(HOLE.x.is_nan());
+            assert_eq!(HOLE.y, 1.5);
+        }
+    "#;
+    let cbindgen = Cbindgen::new()
+        .data_struct(syn::parse_quote!(Point))
+        .base_name("z_point")
+        .function(syn::parse_quote!(z_point_x));
+    run_generated(cbindgen, source, test, "nan_consts");
+}
+
+/// `&[E; N]` crosses as a pointer to an array of `E`'s wire — a scalar, a
+/// `repr_c_struct` mirror, or a nested array of them — reinterpreted in
+/// place after asserting the C and source arrays share their layout.
+#[test]
+fn array_borrows_cross_as_pointers_to_wire_arrays() {
+    let loc = SourceLocation::default();
+    let registry = Registry::<()>::from_items([
+        (
+            syn::Item::Fn(syn::parse_quote!(
+                pub fn array_of_arrays(input: &'static [[u8; 4]; 2]) -> u8 {
+                    input[0][0]
+                }
+            )),
+            loc.clone(),
+        ),
+        (
+            syn::Item::Fn(syn::parse_quote!(
+                pub fn foo_array(input: &[Foo; 3]) -> u32 {
+                    input[0].x
+                }
+            )),
+            loc.clone(),
+        ),
+        (
+            syn::Item::Fn(syn::parse_quote!(
+                pub fn foo_array_reset(input: &mut [Foo; 3]) {}
+            )),
+            loc.clone(),
+        ),
+        (
+            syn::Item::Struct(syn::parse_quote!(
+                #[repr(C)]
+                pub struct Foo {
+                    pub x: u32,
+                }
+            )),
+            loc,
+        ),
+    ])
+    .unwrap();
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(myflat))
+        .repr_c_struct(syn::parse_quote!(Foo))
+        .function(syn::parse_quote!(array_of_arrays))
+        .panic()
+        .function(syn::parse_quote!(foo_array))
+        .panic()
+        .function(syn::parse_quote!(foo_array_reset))
+        .panic();
+
+    let src = write(cbindgen, registry, "array_borrows");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("extern\"C\"fnfoo_array(input:*const[foo;3])->u32"),
+        "{src}"
+    );
+    assert!(
+        compact.contains(
+            "const_:()=assert!(::core::mem::size_of::<[foo;3]>()==::core::mem::size_of::<[myflat::Foo;3]>()\
+             &&::core::mem::align_of::<[foo;3]>()==::core::mem::align_of::<[myflat::Foo;3]>(),"
+        ),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::result::Result::Ok(&*(vas*const[myflat::Foo;3]))"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("extern\"C\"fnfoo_array_reset(input:*mut[foo;3])"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::result::Result::Ok(&mut*(vas*mut[myflat::Foo;3]))"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("extern\"C\"fnarray_of_arrays(input:*const[[u8;4];2])->u8"),
+        "{src}"
+    );
+}
+
+#[test]
+fn fn_pointer_crosses_with_exported_types_as_pointers() {
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        syn::parse_quote!(
+            #[repr(C)]
+            pub struct Point {
+                pub x: i32,
+            }
+        ),
+        syn::parse_quote!(
+            pub fn z_visit(f: Option<extern "C" fn(Option<&Point>) -> i32>) -> i32 {
+                unimplemented!()
+            }
+        ),
+        syn::parse_quote!(
+            pub fn z_counter_hook() -> extern "C" fn(&mut u32) {
+                unimplemented!()
+            }
+        ),
+    ]
+    .into_iter()
+    .map(|item| (item, SourceLocation::default()))
+    .collect();
+    let src = write(
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .data_struct(syn::parse_quote!(Point))
+            .base_name("z_point")
+            .function(syn::parse_quote!(z_visit))
+            .function(syn::parse_quote!(z_counter_hook)),
+        Registry::<()>::from_items(items).unwrap(),
+        "fn_pointer",
+    );
+    let compact: String = src.split_whitespace().collect();
+    // The nested `&Point` crosses as a pointer to its C mirror.
+    assert!(
+        compact.contains(
+            "extern\"C\"fnz_visit(f:::core::option::Option<extern\"C\"fn(*constz_point)->i32>,)->i32"
+        ),
+        "{src}"
+    );
+    assert!(compact.contains("pubstructz_point{pubx:i32,}"), "{src}");
+    assert!(
+        compact.contains("transmute::<::core::option::Option<extern\"C\"fn(*constz_point)->i32>,Option<extern\"C\"fn(Option<&zenoh_flat::Point>)->i32>,>(v)"),
+        "{src}"
+    );
+    // The whole pointer and the pointed-to struct are layout-checked.
+    assert!(compact.contains("doesnotmatchthelayoutofitsCwire"), "{src}");
+    assert!(
+        compact.contains(
+            "::core::mem::size_of::<zenoh_flat::Point>()==::core::mem::size_of::<z_point>()"
+        ),
+        "{src}"
+    );
+    // Returned function pointers cross the other way.
+    assert!(
+        compact.contains("extern\"C\"fnz_counter_hook()->extern\"C\"fn(*mutu32)"),
+        "{src}"
+    );
+}
+
+/// Under `bool_as_u8`, a `bool` newtype crosses as `u8` too: the input byte
+/// is checked to a valid `bool` before the reinterpretation.
+#[test]
+fn auto_transparent_bool_newtype_honors_bool_as_u8() {
+    let loc = SourceLocation::default();
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "#[repr(transparent)] pub struct Flag(pub bool);",
+        "pub fn flag_flip(f: Flag) -> Flag { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| (syn::parse_str::<syn::Item>(source).unwrap(), loc.clone()))
+    .collect();
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(myflat))
+        .auto_transparent_newtypes(true)
+        .bool_as_u8(true)
+        .function(syn::parse_quote!(flag_flip))
+        .panic();
+    let src = write(
+        cbindgen,
+        Registry::<()>::from_items(items).unwrap(),
+        "auto_transparent_bool",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("extern\"C\"fnflag_flip(f:u8)->u8"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::mem::transmute::<bool,myflat::Flag>(v!=0)"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::mem::transmute::<myflat::Flag,bool>(v)asu8"),
+        "{src}"
+    );
+}
@@ -1,5 +1,5 @@
 use super::*;
-use crate::{api::test_util::unique_test_dir, SourceLocation};
+use crate::{api::test_util::unique_test_dir, RecordKind, SourceLocation};
 
 mod builder;
 mod callbacks;
@@ -29,3 +29,59 @@ fn error_struct() -> syn::ItemStruct {
 fn catch<F: FnOnce()>(f: F) -> bool {
     std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).is_err()
 }
+
+/// The items of `source`, as captured from a source crate: impl blocks
+/// aren't recorded.
+fn registry_of(source: &str) -> Registry<()> {
+    let file = syn::parse_file(source).expect("valid source items");
+    let items = file
+        .items
+        .into_iter()
+        .filter(|item| !matches!(item, syn::Item::Impl(_)))
+        .map(|item| (item, SourceLocation::default()));
+    Registry::<()>::from_items(items).expect("index items")
+}
+
+/// Build the bindings of `source` (the source crate, mounted as
+/// `crate::source`) with `cbindgen`, then compile them with `rustc --test`
+/// together with `test`, a `#[test]` calling the generated wrappers, and run
+/// it: the generated code is checked to compile and behave.
+fn run_generated(cbindgen: Cbindgen, source: &str, test: &str, tag: &str) {
+    let generated = write(
+        cbindgen.source_module(syn::parse_quote!(crate::source)),
+        registry_of(source),
+        tag,
+    );
+    let dir = unique_test_dir(&format!("cbindgen_run_{tag}"));
+    std::fs::create_dir_all(&dir).unwrap();
+    let lib = dir.join("lib.rs");
+    std::fs::write(
+        &lib,
+        format!("{generated}\npub mod source {{\n{source}\n}}\n{test}\n"),
+    )
+    .unwrap();
+    let binary = dir.join("run");
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let compiled = std::process::Command::new(rustc)
+        .args(["--edition", "2021", "--test", "--crate-name", "generated"])
+        .arg(&lib)
+        .arg("-o")
+        .arg(&binary)
+        .output()
+        .expect("run rustc");
+    assert!(
+        compiled.status.success(),
+        "generated code doesn't compile:\n{}\n{generated}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    let ran = std::process::Command::new(&binary)
+        .output()
+        .expect("run the test binary");
+    assert!(
+        ran.status.success(),
+        "generated code misbehaves:\n{}{}",
+        String::from_utf8_lossy(&ran.stdout),
+        String::from_utf8_lossy(&ran.stderr)
+    );
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -509,3 +509,197 @@ fn borrowed_option_ref_output_nullable() {
     assert!(compact.contains("__cbg_out_ref_ZTimestamp"), "{src}");
     assert!(!compact.contains("out:*mut*constz_timestamp_t"), "{src}");
 }
+
+/// `result_as_out_param`: `Result<f64, E>` lowers to `i32 f(<inputs>, double *out)`.
+/// `Ok` writes `*out` and returns the success code; `Err` returns the error's own
+/// `i32` code. `E` is neither declared nor marshalled.
+#[test]
+fn result_as_out_param_status_codes() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn z_divide(a: f64, b: f64) -> Result<f64, MathError> {
+            unimplemented!()
+        }
+    );
+    let registry = Registry::<()>::from_items([(syn::Item::Fn(func), loc)]).expect("index items");
+
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .result_as_out_param(0)
+        .function(syn::parse_quote!(z_divide));
+
+    let src = write(cbindgen, registry, "result_code");
+    let compact: String = src.split_whitespace().collect();
+
+    assert!(
+        compact.contains("extern\"C\"fnz_divide(a:f64,b:f64,out:*mutf64)->i32"),
+        "{src}"
+    );
+    assert!(!compact.contains("e:*mut"), "{src}");
+    // Ok: value to `*out`, then the success code.
+    assert!(
+        compact.contains("Result::Ok(__v)=>{*out=__cbg_out_f64(__v);0i32}"),
+        "{src}"
+    );
+    // Err: the error's own code.
+    assert!(
+        compact.contains("Result::Err(__err)=>::core::convert::Into::<i32>::into(__err),"),
+        "{src}"
+    );
+    assert!(!compact.contains("__cbg_out_MathError"), "{src}");
+}
+
+/// `result_as_out_param` only needs `E: Into<i32>`: an error type without an
+/// `i32: From<E>` impl compiles, and its code is returned.
+#[test]
+fn result_as_out_param_accepts_into_i32_errors() {
+    let source = r#"
+        pub struct MathError(i32);
+        #[allow(clippy::from_over_into)]
+        impl Into<i32> for MathError {
+            fn into(self) -> i32 {
+                self.0
+            }
+        }
+        pub fn z_divide(a: f64, b: f64) -> Result<f64, MathError> {
+            if b == 0.0 {
+                Err(MathError(7))
+            } else {
+                Ok(a / b)
+            }
+        }
+    "#;
+    let test = r#"
+        #[test]
+        fn returns_the_error_code() {
+            let mut out = 0.0;
+            assert_eq!(unsafe { z_divide(1.0, 0.0, &mut out) }, 7);
+            assert_eq!(unsafe { z_divide(6.0, 3.0, &mut out) }, 0);
+            assert_eq!(out, 2.0);
+        }
+    "#;
+    let cbindgen = Cbindgen::new()
+        .result_as_out_param(0)
+        .function(syn::parse_quote!(z_divide));
+    run_generated(cbindgen, source, test, "result_code_into");
+}
+
+/// Under `result_as_out_param` a pointer payload also goes to `*out` (no in-band
+/// NULL), and a fallible input's message becomes the function's error code.
+#[test]
+fn result_as_out_param_pointer_and_input_error() {
+    let loc = SourceLocation::default();
+    let st: syn::ItemStruct = syn::parse_quote!(
+        pub struct Session {
+            inner: u64,
+        }
+    );
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn z_open(config: String) -> Result<Session, MathError> {
+            unimplemented!()
+        }
+    );
+    let registry = Registry::<()>::from_items([
+        (syn::Item::Struct(st), loc.clone()),
+        (syn::Item::Fn(func), loc),
+    ])
+    .expect("index items");
+
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .result_as_out_param(1)
+        .opaque_ptr(syn::parse_quote!(Session))
+        .function(syn::parse_quote!(z_open));
+
+    let src = write(cbindgen, registry, "result_code_ptr");
+    let compact: String = src.split_whitespace().collect();
+
+    assert!(
+        compact.contains("fnz_open(config:*const::core::ffi::c_char,out:*mut*mutsession,)->i32"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("{*out=__cbg_out_Session(__v);1i32}"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("Result::Err(__msg)=>{return::core::convert::Into::<i32,>::into("),
+        "{src}"
+    );
+    assert!(
+        compact.contains(
+            "<zenoh_flat::MathErroras::core::convert::From<::std::string::String,>>::from(__msg)"
+        ),
+        "{src}"
+    );
+}
+
+/// `.string_as_c_char(name)` returns `String`s as `CString::into_raw`
+/// pointers, cut at a NUL byte, and exports one freer of that name for all
+/// of them, without needing a `free_memory_function`.
+#[test]
+fn string_as_c_char_returns_cstring_with_one_free_helper() {
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        syn::parse_quote!(
+            pub fn z_greeting() -> String {
+                unimplemented!()
+            }
+        ),
+        syn::parse_quote!(
+            pub fn z_farewell(name: &str) -> String {
+                unimplemented!()
+            }
+        ),
+    ]
+    .into_iter()
+    .map(|item| (item, SourceLocation::default()))
+    .collect();
+    let registry = || Registry::<()>::from_items(items.clone()).expect("index items");
+    let adapter = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .function(syn::parse_quote!(z_greeting))
+            .function(syn::parse_quote!(z_farewell))
+            .panic()
+    };
+
+    let src = write(
+        adapter().string_as_c_char("z_string_free"),
+        registry(),
+        "string_as_c_char",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains(
+            "iflet::core::option::Option::Some(nul)=v.find('\\0'){v.truncate(nul);}\
+             ::std::ffi::CString::new(v).unwrap_or_default().into_raw()"
+        ),
+        "{src}"
+    );
+    assert!(
+        compact.contains("fnz_greeting()->*mut::core::ffi::c_char"),
+        "{src}"
+    );
+    assert_eq!(
+        compact
+            .matches("pubunsafeextern\"C\"fnz_string_free(p:*mut::core::ffi::c_char)")
+            .count(),
+        1,
+        "{src}"
+    );
+    assert!(
+        compact.contains("::core::mem::drop(::std::ffi::CString::from_raw(p));"),
+        "{src}"
+    );
+    assert!(!compact.contains("__cbg_alloc_cstr"), "{src}");
+    let freer = compact.find("fnz_string_free").expect("freer emitted");
+    assert!(
+        !compact[..freer].ends_with("#[allow(non_snake_case)]pubunsafeextern\"C\""),
+        "{src}"
+    );
+
+    // Off by default: the malloc'd block needs the universal freer.
+    assert!(catch(|| {
+        write(adapter(), registry(), "string_as_c_char_off");
+    }));
+}
@@ -485,3 +485,570 @@ fn repr_c_struct_mut_ref_and_maybe_uninit_out_param() {
         "{src}"
     );
 }
+
+/// `.add_derive(...)` extends the derive list of generated data structs and
+/// enums, without repeating a derive the mirror already has.
+#[test]
+fn add_derive_extends_mirror_derives() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn z_sample(kind: Kind) -> Point {
+            unimplemented!()
+        }
+    );
+    let point: syn::ItemStruct = syn::parse_quote!(
+        pub struct Point {
+            pub x: f64,
+        }
+    );
+    let kind: syn::ItemEnum = syn::parse_quote!(
+        pub enum Kind {
+            Uniform = 0,
+        }
+    );
+    let registry = Registry::<()>::from_items([
+        (syn::Item::Fn(func), loc.clone()),
+        (syn::Item::Struct(point), loc.clone()),
+        (syn::Item::Enum(kind), loc.clone()),
+    ])
+    .expect("index items");
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .add_derive(syn::parse_quote!(Debug))
+        .add_derive(syn::parse_quote!(Hash))
+        .add_derive(syn::parse_quote!(Debug))
+        .data_struct(syn::parse_quote!(Point))
+        .enum_type(syn::parse_quote!(Kind))
+        .function(syn::parse_quote!(z_sample));
+
+    let src = write(cbindgen, registry, "add_derive");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("#[derive(Debug,Hash)]#[allow(non_camel_case_types)]pubstruct"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("#[derive(Copy,Clone,Debug,Eq,PartialEq,Hash)]"),
+        "{src}"
+    );
+}
+
+/// Per-target fields of a data struct keep their `#[cfg]` on the mirror and
+/// on both converters' initializers, so each target gets the source layout.
+#[test]
+fn data_struct_keeps_field_cfgs() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn foo_echo(foo: Foo) -> Foo {
+            unimplemented!()
+        }
+    );
+    let foo: syn::ItemStruct = syn::parse_quote!(
+        pub struct Foo {
+            pub id: u64,
+            #[cfg(target_arch = "x86_64")]
+            pub x86_64_field: u64,
+            #[cfg(target_arch = "aarch64")]
+            pub aarch64_field: u64,
+        }
+    );
+    let registry = Registry::<()>::from_items([
+        (syn::Item::Fn(func), loc.clone()),
+        (syn::Item::Struct(foo), loc),
+    ])
+    .expect("index items");
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(example_flat))
+        .data_struct(syn::parse_quote!(Foo))
+        .function(syn::parse_quote!(foo_echo))
+        .panic();
+
+    let src = write(cbindgen, registry, "field_cfgs");
+    let compact: String = src.split_whitespace().collect();
+    for arch in ["x86_64", "aarch64"] {
+        let cfg = format!("#[cfg(target_arch=\"{arch}\")]");
+        assert!(
+            compact.contains(&format!("{cfg}pub{arch}_field:u64")),
+            "{src}"
+        );
+        assert_eq!(
+            compact
+                .matches(&format!("{cfg}{arch}_field:v.{arch}_field"))
+                .count(),
+            2,
+            "{src}"
+        );
+    }
+    assert!(compact.contains("pubid:u64,"), "{src}");
+}
+
+/// A tuple `data_struct` is mirrored with fields `_0`, `_1`, …; the converters
+/// reach the source fields by index, so both directions round-trip.
+#[test]
+fn tuple_data_struct_mirrors_fields_by_index() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn pair_echo(pair: Pair) -> Pair {
+            unimplemented!()
+        }
+    );
+    let pair: syn::ItemStruct = syn::parse_quote!(
+        pub struct Pair(
+            /// Identifier.
+            pub u64,
+            pub String,
+        );
+    );
+    let registry = Registry::<()>::from_items([
+        (syn::Item::Fn(func), loc.clone()),
+        (syn::Item::Struct(pair), loc),
+    ])
+    .expect("index items");
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(example_flat))
+        .free_memory_function("z_free")
+        .data_struct(syn::parse_quote!(Pair))
+        .function(syn::parse_quote!(pair_echo))
+        .panic();
+
+    let src = write(cbindgen, registry, "tuple_data_struct");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("///Identifier.pub_0:u64,pub_1:*mut::core::ffi::c_char,"),
+        "{src}"
+    );
+    // In: `Pair { 0: v._0, 1: .. v._1 .. }`; out: `pair { _0: v.0, _1: .. v.1 .. }`.
+    assert!(
+        compact.contains("example_flat::Pair{0:v._0,1:ifv._1.is_null()"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("pair{_0:v.0,_1:__cbg_alloc_cstr(v.1),}"),
+        "{src}"
+    );
+}
+
+/// A tuple `repr_c_struct` gets the same `_0`, `_1`, … mirror, still guarded
+/// by the whole-type size/align assertion.
+#[test]
+fn tuple_repr_c_struct_mirrors_fields_by_index() {
+    let registry = repr_registry(syn::parse_quote!(
+        #[repr(C)]
+        pub struct Pt(pub u64, pub f64);
+    ));
+    let src = write(repr_cbindgen(), registry, "tuple_repr_c");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("pubstructpt_t{pub_0:u64,pub_1:f64,}"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("size_of::<zenoh_flat::Pt>()==::core::mem::size_of::<pt_t>()"),
+        "{src}"
+    );
+}
+
+fn repr_registry(st: syn::ItemStruct) -> Registry<()> {
+    let loc = SourceLocation::default();
+    let take_fn: syn::ItemFn = syn::parse_quote!(
+        pub fn pt_sum(p: Pt) -> f64 {
+            unimplemented!()
+        }
+    );
+    Registry::<()>::from_items([
+        (syn::Item::Struct(st), loc.clone()),
+        (syn::Item::Fn(take_fn), loc),
+    ])
+    .expect("index items")
+}
+
+fn repr_cbindgen() -> Cbindgen {
+    Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .mangle_type_name(|base| format!("{base}_t"))
+        .mangle_destructor(|base| format!("{base}_drop"))
+        .mangle_function(|n| n.to_string())
+        .require_repr_c(true)
+        .repr_c_struct(syn::parse_quote!(Pt))
+        .function(syn::parse_quote!(pt_sum))
+        .panic()
+}
+
+/// `require_repr_c` rejects a `repr_c_struct` left at the default Rust layout,
+/// which the size/align assert alone would let through.
+#[test]
+#[should_panic(expected = "Cbindgen::repr_c_struct: `Pt` must be `#[repr(C)]`")]
+fn require_repr_c_rejects_struct_without_repr() {
+    let registry = repr_registry(syn::parse_quote!(
+        pub struct Pt {
+            pub x: u64,
+            pub y: f64,
+        }
+    ));
+    write(repr_cbindgen(), registry, "missing_repr");
+}
+
+/// `repr(C)` combined with other hints, and `repr(transparent)`, both pass; the
+/// whole-type size/align assert is still emitted.
+#[test]
+fn require_repr_c_accepts_c_and_transparent() {
+    let registry = repr_registry(syn::parse_quote!(
+        #[repr(align(16), C)]
+        pub struct Pt {
+            pub x: u64,
+            pub y: f64,
+        }
+    ));
+    let src = write(repr_cbindgen(), registry, "repr_c_align");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("size_of::<zenoh_flat::Pt>()==::core::mem::size_of::<pt_t>()"),
+        "{src}"
+    );
+
+    let registry = repr_registry(syn::parse_quote!(
+        #[repr(transparent)]
+        pub struct Pt {
+            pub x: u64,
+        }
+    ));
+    write(repr_cbindgen(), registry, "repr_transparent");
+}
+
+/// Types renamed by the `map::RenameType` stage reach the adapter under their
+/// new name: the mirror is `MyFoo` and the `&Foo` parameter crosses as
+/// `*const MyFoo`, while the generated code still names the source type
+/// `zenoh_flat::Foo`.
+#[test]
+fn renamed_type_is_referenced_by_new_name() {
+    let rename = crate::map::RenameType::builder()
+        .rename("Foo", "MyFoo")
+        .build();
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "#[repr(C)] pub struct Foo { pub a: u64 }",
+        "pub fn foo_a(p: &Foo) -> u64 { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| rename.call((syn::parse_str(source).unwrap(), SourceLocation::default())))
+    .collect();
+    let registry = Registry::<()>::from_items(items).unwrap();
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .mangle_rust_type(|n| n.to_string())
+        .mangle_type_name(|base| base.to_string())
+        .repr_c_struct(syn::parse_quote!(MyFoo))
+        .function(syn::parse_quote!(foo_a))
+        .panic();
+
+    let src = write(cbindgen, registry, "renamed_type");
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("pubstructMyFoo{"), "{src}");
+    assert!(compact.contains("fnfoo_a(p:*constMyFoo)"), "{src}");
+    assert!(compact.contains("zenoh_flat::Foo"), "{src}");
+    assert!(!compact.contains("zenoh_flat::MyFoo"), "{src}");
+}
+
+/// Each enum mirror is followed by one discriminant assertion per variant
+/// against the source enum, unless turned off.
+#[test]
+fn enum_discriminants_are_asserted_against_source() {
+    let registry = || {
+        let loc = SourceLocation::default();
+        Registry::<()>::from_items([
+            (
+                syn::Item::Fn(syn::parse_quote!(
+                    pub fn z_level(level: Level) {}
+                )),
+                loc.clone(),
+            ),
+            (
+                syn::Item::Enum(syn::parse_quote!(
+                    pub enum Level {
+                        High = 42,
+                        Low,
+                    }
+                )),
+                loc,
+            ),
+        ])
+        .expect("index items")
+    };
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(zenoh_flat))
+            .mangle_rust_type(|n| n.to_string())
+            .mangle_type_name(|base| base.to_string())
+            .enum_type(syn::parse_quote!(Level))
+            .function(syn::parse_quote!(z_level))
+    };
+
+    let src = write(cbindgen(), registry(), "enum_discriminants");
+    let compact: String = src.split_whitespace().collect();
+    for variant in ["High", "Low"] {
+        assert!(
+            compact.contains(&format!(
+                "assert!(Level::{variant}asi64==zenoh_flat::Level::{variant}asi64,"
+            )),
+            "{src}"
+        );
+    }
+
+    let src = write(
+        cbindgen().assert_enum_discriminants(false),
+        registry(),
+        "enum_discriminants_off",
+    );
+    assert!(!src.contains("discriminant differs"), "{src}");
+}
+
+/// `c_enum_as_integer(true)` exports a `#[repr(i32)]` enum as an `i32`
+/// alias plus one const per variant, converted by matching the consts; an
+/// enum without an integer repr keeps its `#[repr(C)]` mirror.
+#[test]
+fn c_enum_as_integer_flattens_int_repr_enums() {
+    let registry = || {
+        let items: Vec<(syn::Item, SourceLocation)> = [
+            "#[repr(i32)] pub enum InsideFoo { DouddleDee = 42, DouddleDum = 24, DouddleDo }",
+            "pub enum Level { High, Low }",
+            "pub fn inside_foo_value(x: InsideFoo) -> i32 { unimplemented!() }",
+            "pub fn inside_foo_default() -> InsideFoo { unimplemented!() }",
+            "pub fn z_level(level: Level) {}",
+        ]
+        .into_iter()
+        .map(|source| (syn::parse_str(source).unwrap(), SourceLocation::default()))
+        .collect();
+        Registry::<()>::from_items(items).expect("index items")
+    };
+    let cbindgen = || {
+        Cbindgen::new()
+            .source_module(syn::parse_quote!(example_flat))
+            .enum_type(syn::parse_quote!(InsideFoo))
+            .enum_type(syn::parse_quote!(Level))
+            .function(syn::parse_quote!(inside_foo_value))
+            .panic()
+            .function(syn::parse_quote!(inside_foo_default))
+            .function(syn::parse_quote!(z_level))
+    };
+
+    let src = write(
+        cbindgen().c_enum_as_integer(true),
+        registry(),
+        "c_enum_as_integer",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("pubtypeinside_foo=i32;"), "{src}");
+    assert!(!compact.contains("pubenuminside_foo"), "{src}");
+    for (name, value) in [
+        ("INSIDE_FOO_DOUDDLE_DEE", 42),
+        ("INSIDE_FOO_DOUDDLE_DUM", 24),
+        ("INSIDE_FOO_DOUDDLE_DO", 25),
+    ] {
+        assert!(
+            compact.contains(&format!("pubconst{name}:inside_foo={value};")),
+            "{src}"
+        );
+    }
+    assert!(
+        compact.contains(
+            "assert!(INSIDE_FOO_DOUDDLE_DEEasi64==example_flat::InsideFoo::DouddleDeeasi64,"
+        ),
+        "{src}"
+    );
+    // In: match the consts, an unknown value is an error.
+    assert!(
+        compact.contains(
+            "INSIDE_FOO_DOUDDLE_DEE=>{::core::result::Result::Ok(example_flat::InsideFoo::DouddleDee)}"
+        ),
+        "{src}"
+    );
+    assert!(
+        compact.contains(
+            "_=>::core::result::Result::Err(::std::format!(\"invalidInsideFoovalue{}\",v)),"
+        ),
+        "{src}"
+    );
+    // Out: the source variant maps to its const.
+    assert!(
+        compact.contains("example_flat::InsideFoo::DouddleDee=>INSIDE_FOO_DOUDDLE_DEE,"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("extern\"C\"fninside_foo_value(x:inside_foo)->i32"),
+        "{src}"
+    );
+    // No integer repr: the mirror stays.
+    assert!(compact.contains("pubenumlevel{High,Low,}"), "{src}");
+
+    let src = write(cbindgen(), registry(), "c_enum_as_integer_off");
+    let compact: String = src.split_whitespace().collect();
+    assert!(compact.contains("pubenuminside_foo{"), "{src}");
+    assert!(!compact.contains("INSIDE_FOO_DOUDDLE_DEE"), "{src}");
+}
+
+fn non_exhaustive_registry() -> Registry<()> {
+    let loc = SourceLocation::default();
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "#[non_exhaustive] #[repr(C)] pub struct Stats { pub sent: u64 }",
+        "#[non_exhaustive] pub enum Mode { Fast, Safe }",
+        "pub fn z_stats() -> Stats { unimplemented!() }",
+        "pub fn z_mode() -> Mode { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| (syn::parse_str(source).unwrap(), loc.clone()))
+    .collect();
+    Registry::<()>::from_items(items).expect("index items")
+}
+
+fn non_exhaustive_cbindgen() -> Cbindgen {
+    Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .mangle_type_name(|base| format!("{base}_t"))
+        .data_struct(syn::parse_quote!(Stats))
+        .enum_type(syn::parse_quote!(Mode))
+        .function(syn::parse_quote!(z_stats))
+        .function(syn::parse_quote!(z_mode))
+}
+
+/// `#[non_exhaustive]` on a source struct or enum is dropped from its C
+/// mirror unless `keep_non_exhaustive` is on; the enum output converter
+/// gets a fallback arm either way.
+#[test]
+fn non_exhaustive_is_dropped_from_mirrors() {
+    let src = write(
+        non_exhaustive_cbindgen(),
+        non_exhaustive_registry(),
+        "non_exhaustive",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(!compact.contains("#[non_exhaustive]#[allow"), "{src}");
+    assert!(compact.contains("pubstructstats_t{pubsent:u64,}"), "{src}");
+    assert!(
+        compact.contains(
+            "#[allow(unreachable_patterns)]_=>::core::panic!(\"`Mode`varianthasnoCcounterpart\"),"
+        ),
+        "{src}"
+    );
+
+    let src = write(
+        non_exhaustive_cbindgen().keep_non_exhaustive(true),
+        non_exhaustive_registry(),
+        "non_exhaustive_kept",
+    );
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("#[non_exhaustive]#[allow(non_camel_case_types)]pubstructstats_t"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("#[non_exhaustive]#[allow(non_camel_case_types)]pubenummode_t"),
+        "{src}"
+    );
+}
+
+/// A `#[non_exhaustive]` data struct can't be rebuilt from its mirror, so
+/// taking it by value is a build error.
+#[test]
+#[should_panic(expected = "Cbindgen::data_struct: `Stats` is `#[non_exhaustive]`")]
+fn non_exhaustive_data_struct_input_is_rejected() {
+    let loc = SourceLocation::default();
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "#[non_exhaustive] pub struct Stats { pub sent: u64 }",
+        "pub fn z_stats_log(stats: Stats) { unimplemented!() }",
+    ]
+    .into_iter()
+    .map(|source| (syn::parse_str(source).unwrap(), loc.clone()))
+    .collect();
+    let registry = Registry::<()>::from_items(items).expect("index items");
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(zenoh_flat))
+        .data_struct(syn::parse_quote!(Stats))
+        .function(syn::parse_quote!(z_stats_log));
+    write(cbindgen, registry, "non_exhaustive_input");
+}
+
+/// A `#[cfg]`-gated variant or const keeps its cfg on everything generated
+/// from it — the mirror variant, the converter arms and the assertions — so
+/// the bindings still compile on targets without it.
+#[test]
+fn cfg_gated_variants_and_consts_gate_their_assertions() {
+    let items: Vec<(syn::Item, SourceLocation)> = [
+        "pub enum Level { High, #[cfg(target_arch = \"x86_64\")] Wide, Low }",
+        "pub fn z_level(level: Level) -> Level { level }",
+        "#[cfg(target_arch = \"x86_64\")] pub const WIDE_BITS: u32 = 64;",
+    ]
+    .into_iter()
+    .map(|source| (syn::parse_str(source).unwrap(), SourceLocation::default()))
+    .collect();
+    let registry = Registry::<()>::from_items(items).expect("index items");
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(example_flat))
+        .enum_type(syn::parse_quote!(Level))
+        .function(syn::parse_quote!(z_level));
+
+    let src = write(cbindgen, registry, "cfg_gated_asserts");
+    let compact: String = src.split_whitespace().collect();
+    let cfg = "#[cfg(target_arch=\"x86_64\")]";
+    for gated in [
+        format!("{cfg}Wide,"),
+        format!("{cfg}level::Wide=>example_flat::Level::Wide,"),
+        format!("{cfg}example_flat::Level::Wide=>level::Wide,"),
+        format!("{cfg}assert!(level::Wideasi64==example_flat::Level::Wideasi64,"),
+        format!("{cfg}const_:()=assert!(WIDE_BITS==example_flat::WIDE_BITS,"),
+    ] {
+        assert!(compact.contains(&gated), "missing `{gated}` in {src}");
+    }
+    assert!(
+        compact.contains("assert!(level::Highasi64==example_flat::Level::Highasi64,"),
+        "{src}"
+    );
+    assert!(
+        !compact.contains(&format!("{cfg}assert!(level::High")),
+        "{src}"
+    );
+}
+
+/// A borrowed scalar field of a data struct becomes a raw pointer on the
+/// mirror, which then carries no lifetime parameter; the converters name the
+/// source struct's lifetime explicitly.
+#[test]
+fn data_struct_borrowed_field_drops_lifetime() {
+    let loc = SourceLocation::default();
+    let func: syn::ItemFn = syn::parse_quote!(
+        pub fn foo_echo(foo: Foo) -> Foo {
+            unimplemented!()
+        }
+    );
+    let foo: syn::ItemStruct = syn::parse_quote!(
+        pub struct Foo<'a> {
+            pub p: &'a u8,
+            pub q: &'a mut u32,
+        }
+    );
+    let registry = Registry::<()>::from_items([
+        (syn::Item::Fn(func), loc.clone()),
+        (syn::Item::Struct(foo), loc),
+    ])
+    .expect("index items");
+    let cbindgen = Cbindgen::new()
+        .source_module(syn::parse_quote!(example_flat))
+        .data_struct(syn::parse_quote!(Foo))
+        .function(syn::parse_quote!(foo_echo))
+        .panic();
+
+    let src = write(cbindgen, registry, "borrowed_field");
+    let compact: String = src.split_whitespace().collect();
+    assert!(
+        compact.contains("pubstructfoo{pubp:*constu8,pubq:*mutu32,}"),
+        "{src}"
+    );
+    assert!(
+        compact.contains("unsafefn__cbg_in_Foo<'__cbg>(v:foo)->example_flat::Foo<'__cbg>"),
+        "{src}"
+    );
+    assert!(compact.contains("p:&*v.p,q:&mut*v.q,"), "{src}");
+    assert!(
+        compact.contains("fn__cbg_out_Foo(v:example_flat::Foo<'_>)->foo"),
+        "{src}"
+    );
+    assert!(compact.contains("p:v.pas*constu8,q:v.qas*mutu32,"), "{src}");
+}
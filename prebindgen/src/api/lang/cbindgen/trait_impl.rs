@@ -1,4 +1,5 @@
-use super::{builder::callback_fn_type, *};
+use super::{builder::callback_fn_type, emit::source_member, *};
+use crate::{api::utils::edition::RustEdition, codegen::cfg_expr::cfgs_exclude, SourceLocation};
 
 /// Per-category **input** terminal converter builders. Each returns
 /// `Some(ConverterImpl)` only for the type category it claims (and `None`
@@ -54,23 +55,55 @@ impl Cbindgen {
         let fields = self.struct_fields(r, ty)?;
         let name = Self::in_name(ty);
         let c_struct = self.c_type_ident(ty);
-        let src = self.src_ty(ty);
+        // Borrowed fields are rebuilt from raw pointers, so the decoded
+        // struct's lifetimes are unbounded, chosen by the caller.
+        let lifetime: syn::Lifetime = syn::parse_quote!('__cbg);
+        let src = self.src_ty_with_lifetimes(r, ty, &lifetime);
+        let ctor = self.src_ty(ty);
+        let generics = (src != ctor).then(|| quote!(<#lifetime>));
+        // The binding crate can't construct a `#[non_exhaustive]` struct:
+        // the converter is a never-succeeding stub, and `validate` rejects
+        // every declared function that would call it.
+        if self.is_non_exhaustive(r, ty) {
+            let msg = non_exhaustive_input_msg(ty);
+            let function: syn::ItemFn = syn::parse_quote!(
+                #[allow(non_snake_case, unused_variables, dead_code)]
+                pub(crate) unsafe fn #name #generics(
+                    v: #c_struct,
+                ) -> ::core::result::Result<#src, ::std::string::String> {
+                    ::core::result::Result::Err(::std::string::String::from(#msg))
+                }
+            );
+            return Some(ConverterImpl {
+                subs: vec![],
+                destination: syn::parse_quote!(#c_struct),
+                function,
+                pre_stages: vec![],
+                niches: Niches::empty(),
+                metadata: (),
+            });
+        }
         let mut inits: Vec<TokenStream> = Vec::new();
         for (fname, fty) in &fields {
+            let cfgs = self.field_cfgs(r, ty, fname);
+            let member = source_member(r, ty, fname);
             if is_string(fty) {
-                inits.push(quote!(#fname: if v.#fname.is_null() {
+                inits.push(quote!(#(#cfgs)* #member: if v.#fname.is_null() {
                     ::std::string::String::new()
                 } else {
                     ::std::ffi::CStr::from_ptr(v.#fname).to_string_lossy().into_owned()
                 }));
+            } else if let syn::Type::Reference(rf) = fty {
+                let mutability = rf.mutability;
+                inits.push(quote!(#(#cfgs)* #member: &#mutability *v.#fname));
             } else {
-                inits.push(quote!(#fname: v.#fname));
+                inits.push(quote!(#(#cfgs)* #member: v.#fname));
             }
         }
         let function: syn::ItemFn = syn::parse_quote!(
             #[allow(non_snake_case, unused_variables, dead_code)]
-            pub(crate) unsafe fn #name(v: #c_struct) -> #src {
-                #src { #(#inits),* }
+            pub(crate) unsafe fn #name #generics(v: #c_struct) -> #src {
+                #ctor { #(#inits),* }
             }
         );
         Some(ConverterImpl {
@@ -232,9 +265,37 @@ impl Cbindgen {
         let name = Self::in_name(ty);
         let cname = self.c_type_ident(ty);
         let src = self.src_ty(ty);
+        if self.enum_as_integer(e).is_some() {
+            // An integer from C may match no constant: fallible.
+            let arms = e.variants.iter().map(|v| {
+                let id = &v.ident;
+                let cfgs = cfg_attrs(&v.attrs);
+                let value = self.enum_variant_const(ty, id);
+                quote!(#(#cfgs)* #value => ::core::result::Result::Ok(#src::#id),)
+            });
+            let msg = format!("invalid {} value {{}}", type_short(ty));
+            let function: syn::ItemFn = syn::parse_quote!(
+                #[allow(non_snake_case, unused_variables, dead_code)]
+                pub(crate) fn #name(v: #cname) -> ::core::result::Result<#src, ::std::string::String> {
+                    match v {
+                        #(#arms)*
+                        _ => ::core::result::Result::Err(::std::format!(#msg, v)),
+                    }
+                }
+            );
+            return Some(ConverterImpl {
+                subs: vec![],
+                destination: syn::parse_quote!(#cname),
+                function,
+                pre_stages: vec![],
+                niches: Niches::empty(),
+                metadata: (),
+            });
+        }
         let arms = e.variants.iter().map(|v| {
             let id = &v.ident;
-            quote!(#cname::#id => #src::#id,)
+            let cfgs = cfg_attrs(&v.attrs);
+            quote!(#(#cfgs)* #cname::#id => #src::#id,)
         });
         let function: syn::ItemFn = syn::parse_quote!(
             #[allow(non_snake_case, unused_variables, dead_code)]
@@ -311,21 +372,95 @@ impl Cbindgen {
         })
     }
 
-    /// FFI-safe scalar (`bool`, integers, floats): identity pass-through.
-    pub(crate) fn in_scalar(&self, ty: &syn::Type) -> Option<ConverterImpl<()>> {
-        if !is_scalar(ty) {
-            return None;
-        }
+    /// FFI-safe scalar (`bool`, integers, floats), or a source `type` alias
+    /// of one: identity pass-through.
+    pub(crate) fn in_scalar(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<ConverterImpl<()>> {
+        let (src, scalar) = self.scalar_wire(ty, registry)?;
+        let wire = self.scalar_crossing(scalar.clone());
         let name = Self::in_name(ty);
+        let decode = if wire == scalar {
+            quote!(v)
+        } else {
+            quote!(v != 0)
+        };
         let function: syn::ItemFn = syn::parse_quote!(
             #[allow(non_snake_case, unused_variables, dead_code)]
-            pub(crate) fn #name(v: #ty) -> #ty {
-                v
+            pub(crate) fn #name(v: #wire) -> #src {
+                #decode
             }
         );
         Some(ConverterImpl {
             subs: vec![],
-            destination: ty.clone(),
+            destination: wire,
+            function,
+            pre_stages: vec![],
+            niches: Niches::empty(),
+            metadata: (),
+        })
+    }
+
+    /// [`Cbindgen::map_type`]-mapped type: reinterpreted as its wire type.
+    /// A `bool` wire crosses as `u8` under [`Cbindgen::bool_as_u8`], checked
+    /// to a valid `bool` before the reinterpretation.
+    pub(crate) fn in_mapped(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<ConverterImpl<()>> {
+        let mapped = self.mapped_wire(ty, registry)?;
+        let wire = self.scalar_crossing(mapped.clone());
+        let name = Self::in_name(ty);
+        let src = self.src_ty_exported(ty, registry);
+        let decode = if wire == mapped {
+            quote!(v)
+        } else {
+            quote!(v != 0)
+        };
+        let function: syn::ItemFn = syn::parse_quote!(
+            #[allow(non_snake_case, unused_variables, dead_code)]
+            pub(crate) fn #name(v: #wire) -> #src {
+                unsafe { ::core::mem::transmute::<#mapped, #src>(#decode) }
+            }
+        );
+        Some(ConverterImpl {
+            subs: vec![],
+            destination: wire.clone(),
+            function,
+            pre_stages: vec![],
+            niches: Niches::empty(),
+            metadata: (),
+        })
+    }
+
+    /// [`Cbindgen::map_type`]-mapped type: reinterpreted from its wire type,
+    /// then widened to `u8` for a `bool` wire under [`Cbindgen::bool_as_u8`].
+    pub(crate) fn out_mapped(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<ConverterImpl<()>> {
+        let mapped = self.mapped_wire(ty, registry)?;
+        let wire = self.scalar_crossing(mapped.clone());
+        let name = Self::out_name(ty);
+        let src = self.src_ty_exported(ty, registry);
+        let encode = if wire == mapped {
+            quote!(::core::mem::transmute::<#src, #mapped>(v))
+        } else {
+            quote!(::core::mem::transmute::<#src, #mapped>(v) as u8)
+        };
+        let function: syn::ItemFn = syn::parse_quote!(
+            #[allow(non_snake_case, unused_variables, dead_code)]
+            pub(crate) fn #name(v: #src) -> #wire {
+                unsafe { #encode }
+            }
+        );
+        Some(ConverterImpl {
+            subs: vec![],
+            destination: wire.clone(),
             function,
             pre_stages: vec![],
             niches: Niches::empty(),
@@ -334,15 +469,129 @@ impl Cbindgen {
     }
 }
 
+/// Function pointers: reinterpreted as their wire signature.
+impl Cbindgen {
+    /// Function pointer (see [`Cbindgen::fn_pointer_wire`]): reinterpreted
+    /// from its wire signature.
+    pub(crate) fn in_fn_pointer(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<ConverterImpl<()>> {
+        let (wire, pointees) = self.fn_pointer_wire(ty, registry)?;
+        let name = Self::in_name(ty);
+        let src = self.src_ty_exported(ty, registry);
+        let asserts = self.fn_pointer_asserts(ty, &src, &wire, &pointees);
+        let function: syn::ItemFn = syn::parse_quote!(
+            #[allow(non_snake_case, unused_variables, dead_code)]
+            pub(crate) fn #name(v: #wire) -> #src {
+                #(#asserts)*
+                unsafe { ::core::mem::transmute::<#wire, #src>(v) }
+            }
+        );
+        Some(ConverterImpl {
+            subs: pointees,
+            destination: wire,
+            function,
+            pre_stages: vec![],
+            niches: Niches::empty(),
+            metadata: (),
+        })
+    }
+
+    /// Function pointer (see [`Cbindgen::fn_pointer_wire`]): reinterpreted
+    /// as its wire signature.
+    pub(crate) fn out_fn_pointer(
+        &self,
+        ty: &syn::Type,
+        registry: &Registry<()>,
+    ) -> Option<ConverterImpl<()>> {
+        let (wire, pointees) = self.fn_pointer_wire(ty, registry)?;
+        let name = Self::out_name(ty);
+        let src = self.src_ty_exported(ty, registry);
+        let asserts = self.fn_pointer_asserts(ty, &src, &wire, &pointees);
+        let function: syn::ItemFn = syn::parse_quote!(
+            #[allow(non_snake_case, unused_variables, dead_code)]
+            pub(crate) fn #name(v: #src) -> #wire {
+                #(#asserts)*
+                unsafe { ::core::mem::transmute::<#src, #wire>(v) }
+            }
+        );
+        Some(ConverterImpl {
+            subs: pointees,
+            destination: wire,
+            function,
+            pre_stages: vec![],
+            niches: Niches::empty(),
+            metadata: (),
+        })
+    }
+
+    /// The asserts making a function pointer transmute sound as far as
+    /// layout goes: the whole pointer type has its wire's size and
+    /// alignment, and so does every data struct its signature points to,
+    /// compared to its C mirror.
+    fn fn_pointer_asserts(
+        &self,
+        ty: &syn::Type,
+        src: &syn::Type,
+        wire: &syn::Type,
+        pointees: &[syn::Type],
+    ) -> Vec<syn::Stmt> {
+        let msg = format!(
+            "function pointer `{}` does not match the layout of its C wire",
+            ty.to_token_stream()
+        );
+        let mut asserts: Vec<syn::Stmt> = vec![syn::parse_quote!(
+            const _: () = assert!(
+                ::core::mem::size_of::<#src>() == ::core::mem::size_of::<#wire>()
+                    && ::core::mem::align_of::<#src>() == ::core::mem::align_of::<#wire>(),
+                #msg
+            );
+        )];
+        for pointee in pointees {
+            let src = self.src_ty(pointee);
+            let c_struct = self.c_type_ident(pointee);
+            let msg = format!(
+                "data struct `{}` pointed to by function pointer `{}` does not match \
+                 the layout of `{c_struct}`",
+                type_short(pointee),
+                ty.to_token_stream()
+            );
+            asserts.push(syn::parse_quote!(
+                const _: () = assert!(
+                    ::core::mem::size_of::<#src>() == ::core::mem::size_of::<#c_struct>()
+                        && ::core::mem::align_of::<#src>()
+                            == ::core::mem::align_of::<#c_struct>(),
+                    #msg
+                );
+            ));
+        }
+        asserts
+    }
+}
+
 /// Per-section [`Cbindgen::prerequisites`] emitters. Each returns the runtime-
 /// support items for one concern; the trait method concatenates them in order,
 /// so the emitted preamble is identical to the former single function.
 impl Cbindgen {
+    /// The [`Self::target_pointer_width`] assertion, first in the file so a
+    /// cross-build mismatch is the error reported.
+    fn prereq_pointer_width(&self) -> Option<syn::Item> {
+        let bits = usize::from(self.target_pointer_width?);
+        let msg =
+            format!("target_pointer_width: the bindings were generated for a {bits}-bit target");
+        Some(syn::parse_quote!(
+            const _: () = assert!(::core::mem::size_of::<usize>() * 8 == #bits, #msg);
+        ))
+    }
+
     /// C allocator extern + raw C-string allocator + the universal memory freer.
     /// Emitted when the layer hands `char*`/array memory to C. Panics if such
     /// memory is produced but no `.free_memory_function` is declared.
     fn prereq_alloc_free(&self, registry: &Registry<()>, produces_array: bool) -> Vec<syn::Item> {
         let mut items: Vec<syn::Item> = Vec::new();
+        let no_mangle = RustEdition::detected().no_mangle_attr();
         if !(self.needs_free(registry) || produces_array) {
             return items;
         }
@@ -382,7 +631,7 @@ impl Cbindgen {
         // Universal raw memory freer: type-agnostic C `free`, no length, no
         // destructor (NULL-safe via C `free`).
         items.push(syn::parse_quote!(
-            #[no_mangle]
+            #no_mangle
             #[allow(non_snake_case, unused_variables)]
             pub unsafe extern "C" fn #free_ident(p: *mut ::core::ffi::c_void) {
                 free(p);
@@ -391,6 +640,30 @@ impl Cbindgen {
         items
     }
 
+    /// The string freer of [`Cbindgen::string_as_c_char`]: releases a
+    /// `String` returned as a `CString::into_raw` pointer. Emitted once, when
+    /// any `String` is returned.
+    fn prereq_cstring_free(&self, registry: &Registry<()>) -> Vec<syn::Item> {
+        let Some(name) = &self.cstring_free_fn else {
+            return Vec::new();
+        };
+        if !self.returns_string(registry) {
+            return Vec::new();
+        }
+        let free_ident = format_ident!("{}", name);
+        let no_mangle = RustEdition::detected().no_mangle_attr();
+        vec![syn::parse_quote!(
+            /// Release a string returned by this library. `p` must come from
+            /// one of its functions and not be used afterwards; NULL is ignored.
+            #no_mangle
+            pub unsafe extern "C" fn #free_ident(p: *mut ::core::ffi::c_char) {
+                if !p.is_null() {
+                    ::core::mem::drop(::std::ffi::CString::from_raw(p));
+                }
+            }
+        )]
+    }
+
     /// Array builder: copy a `Vec<W>` into a C-`malloc`'d block of `W` and
     /// return `(ptr, len)` (empty ⇒ `(NULL, 0)`). The block is freed C-side
     /// via the `z_free_array` macro (per-element drop + the universal freer).
@@ -423,15 +696,18 @@ impl Cbindgen {
     /// `_drop`. The C type is an opaque/incomplete struct.
     fn prereq_opaque_handles(&self, registry: &Registry<()>) -> Vec<syn::Item> {
         let mut items: Vec<syn::Item> = Vec::new();
+        let no_mangle = RustEdition::detected().no_mangle_attr();
         for (key, _cfg) in sorted_by_key(&self.opaque) {
             let ty = key.to_type();
             if registry.input_entry(&ty).is_none() && registry.output_entry(&ty).is_none() {
                 continue;
             }
             let c_struct = self.c_type_ident(&ty);
+            let docs = self.type_docs(registry, &ty);
             // Opaque/incomplete C type: the handle is `#c_struct *`, which IS the
             // `Box::into_raw` pointer to the source value.
             items.push(syn::parse_quote!(
+                #(#docs)*
                 #[repr(C)]
                 #[allow(non_camel_case_types)]
                 pub struct #c_struct {
@@ -441,7 +717,7 @@ impl Cbindgen {
             let src = self.src_ty(&ty);
             let drop_ident = self.destructor_symbol(&ty);
             items.push(syn::parse_quote!(
-                #[no_mangle]
+                #no_mangle
                 #[allow(non_snake_case, unused_variables)]
                 pub unsafe extern "C" fn #drop_ident(this_: *mut #c_struct) {
                     if !this_.is_null() {
@@ -477,10 +753,18 @@ impl Cbindgen {
                         fty.to_token_stream()
                     )
                 });
-                field_defs.push(quote!(pub #fname: #wire));
+                let field_docs = self.field_docs(registry, &ty, fname);
+                let cfgs = self.field_cfgs(registry, &ty, fname);
+                field_defs.push(quote!(#(#field_docs)* #(#cfgs)* pub #fname: #wire));
             }
+            let docs = self.type_docs(registry, &ty);
+            let non_exhaustive = self.mirror_non_exhaustive(registry, &ty);
+            let derive = self.derive_attr(&[]);
             items.push(syn::parse_quote!(
+                #(#docs)*
                 #[repr(C)]
+                #derive
+                #non_exhaustive
                 #[allow(non_camel_case_types)]
                 pub struct #c_struct {
                     #(#field_defs,)*
@@ -490,6 +774,83 @@ impl Cbindgen {
         items
     }
 
+    /// Mapped types: the fail-closed size+align equality asserts proving each
+    /// [`Cbindgen::map_type`] transmute sound (ABI beyond layout is the user's),
+    /// and each [`Cbindgen::auto_transparent_newtypes`] one.
+    fn prereq_mapped_types(&self, registry: &Registry<()>) -> Vec<syn::Item> {
+        let newtypes = registry.structs.keys().filter_map(|ident| {
+            let ty = type_from_ident(ident);
+            let wire = self.transparent_newtype_wire(&ty, registry)?;
+            Some((TypeKey::from_type(&ty), wire, "auto_transparent_newtypes"))
+        });
+        let mut mapped: Vec<(TypeKey, syn::Type, &str)> = self
+            .mapped
+            .iter()
+            .map(|(key, wire)| (key.clone(), wire.clone(), "map_type"))
+            .chain(newtypes)
+            .collect();
+        mapped.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        mapped
+            .into_iter()
+            .filter(|(key, _, _)| {
+                let ty = key.to_type();
+                registry.input_entry(&ty).is_some() || registry.output_entry(&ty).is_some()
+            })
+            .map(|(key, wire, origin)| {
+                self.check_transparent_wrapper(&key, &wire);
+                let src = self.src_ty_exported(&key.to_type(), registry);
+                let size_msg = format!("{origin}: `{key}` and its wire type differ in size");
+                let align_msg = format!("{origin}: `{key}` and its wire type differ in alignment");
+                syn::parse_quote!(
+                    const _: () = {
+                        assert!(
+                            ::core::mem::size_of::<#src>() == ::core::mem::size_of::<#wire>(),
+                            #size_msg
+                        );
+                        assert!(
+                            ::core::mem::align_of::<#src>() == ::core::mem::align_of::<#wire>(),
+                            #align_msg
+                        );
+                    };
+                )
+            })
+            .collect()
+    }
+
+    /// Reject (under [`Cbindgen::strict_transparent`]) or warn about a
+    /// [`Cbindgen::map_type`] of `W<.., T, ..>` to `T` whose wrapper `W` isn't
+    /// known to be transparent over that argument — the first one, unless
+    /// [`Cbindgen::transparent_wrapper_arg`] says otherwise.
+    fn check_transparent_wrapper(&self, key: &TypeKey, wire: &syn::Type) {
+        let Some((wrapper, index)) = stripped_wrapper(&key.to_type(), wire) else {
+            return;
+        };
+        let payload = self
+            .transparent_wrapper_args
+            .get(&wrapper)
+            .copied()
+            .unwrap_or(0);
+        if index == payload
+            && (TRANSPARENT_WRAPPERS.contains(&wrapper.as_str())
+                || self.acknowledged_wrappers.contains(&wrapper)
+                || self.transparent_wrapper_args.contains_key(&wrapper))
+        {
+            return;
+        }
+        if self.strict_transparent {
+            panic!(
+                "Cbindgen::strict_transparent: map_type of `{key}` strips `{wrapper}`, \
+                 which is not a known transparent wrapper; acknowledge it in \
+                 `strict_transparent` if it is `#[repr(transparent)]`"
+            );
+        }
+        println!(
+            "cargo:warning=prebindgen: map_type of `{key}` strips `{wrapper}`, which is not a \
+             known transparent wrapper; the transmute is only sound if it is \
+             `#[repr(transparent)]`"
+        );
+    }
+
     /// Value-opaque types: the opaque `#[repr(C, align(_))]` counterpart is
     /// defined elsewhere (e.g. a size/align probe generator). Here we emit only
     /// the fail-closed size+align equality asserts and the typed `_drop` (drops
@@ -497,6 +858,7 @@ impl Cbindgen {
     /// for types delivered as takeable callback params.
     fn prereq_value_opaque(&self, registry: &Registry<()>) -> Vec<syn::Item> {
         let mut items: Vec<syn::Item> = Vec::new();
+        let no_mangle = RustEdition::detected().no_mangle_attr();
         let takeable_keys = self.takeable_type_keys();
         let mut vo: Vec<(&TypeKey, &ValueOpaqueCfg)> = self.value_opaque.iter().collect();
         vo.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
@@ -505,7 +867,7 @@ impl Cbindgen {
             if registry.input_entry(&ty).is_none() && registry.output_entry(&ty).is_none() {
                 continue;
             }
-            let src = self.src_ty(&ty);
+            let src = self.src_ty_exported(&ty, registry);
             let opaque = &cfg.opaque;
             // `repr_c_struct`: the opaque counterpart is an auto-generated
             // **visible-field** `#[repr(C)]` mirror (so C reads the fields directly),
@@ -513,10 +875,18 @@ impl Cbindgen {
             // `mirror_field_wire` (scalar / enum / opaque pointer). The size/align
             // assert below then proves the whole-struct reinterpret sound.
             if cfg.generate_mirror {
+                if self.require_repr_c {
+                    assert!(
+                        self.struct_has_c_layout(registry, &ty) != Some(false),
+                        "Cbindgen::repr_c_struct: `{}` must be `#[repr(C)]` or \
+                         `#[repr(transparent)]` (required by `require_repr_c`)",
+                        type_short(&ty)
+                    );
+                }
                 let mirror_ident = self.c_type_ident(&ty);
                 let fields = self.struct_fields(registry, &ty).unwrap_or_else(|| {
                     panic!(
-                        "Cbindgen::repr_c_struct: `{}` is not a named struct",
+                        "Cbindgen::repr_c_struct: `{}` is not a struct with fields",
                         type_short(&ty)
                     )
                 });
@@ -533,11 +903,19 @@ impl Cbindgen {
                                 fty.to_token_stream()
                             )
                         });
-                        quote!(pub #fname: #wire)
+                        let field_docs = self.field_docs(registry, &ty, fname);
+                        let cfgs = self.field_cfgs(registry, &ty, fname);
+                        quote!(#(#field_docs)* #(#cfgs)* pub #fname: #wire)
                     })
                     .collect();
+                let docs = self.type_docs(registry, &ty);
+                let non_exhaustive = self.mirror_non_exhaustive(registry, &ty);
+                let derive = self.derive_attr(&[]);
                 items.push(syn::parse_quote!(
+                    #(#docs)*
                     #[repr(C)]
+                    #derive
+                    #non_exhaustive
                     #[allow(non_camel_case_types)]
                     pub struct #mirror_ident {
                         #(#field_defs,)*
@@ -606,7 +984,7 @@ impl Cbindgen {
             // gravestone (a valid, safely-droppable empty value), so dropping
             // it is a harmless no-op; a live slot drops normally.
             items.push(syn::parse_quote!(
-                #[no_mangle]
+                #no_mangle
                 #[allow(non_snake_case, unused_variables)]
                 pub unsafe extern "C" fn #drop_ident(this_: *mut #opaque) {
                     if !this_.is_null() {
@@ -628,7 +1006,7 @@ impl Cbindgen {
                 // mirror, `gravestone()` for a bare-`Box` mirror / non-mirror owned).
                 let writeback = self.value_opaque_writeback(registry, &ty, &format_ident!("src"));
                 items.push(syn::parse_quote!(
-                    #[no_mangle]
+                    #no_mangle
                     #[allow(non_snake_case, unused_variables)]
                     pub unsafe extern "C" fn #take_ident(
                         dst: *mut #opaque,
@@ -658,22 +1036,111 @@ impl Cbindgen {
                 continue;
             };
             assert_unit_variants(e);
+            if let Some(int) = self.enum_as_integer(e) {
+                items.extend(self.prereq_enum_as_integer(registry, &ty, e, &int));
+                continue;
+            }
             let cname = self.c_type_ident(&ty);
             let variants = e.variants.iter().map(|v| {
                 let id = &v.ident;
+                let docs = self.docs(&v.attrs);
+                let cfgs = cfg_attrs(&v.attrs);
                 match &v.discriminant {
-                    Some((_, expr)) => quote!(#id = #expr),
-                    None => quote!(#id),
+                    Some((_, expr)) => quote!(#(#docs)* #(#cfgs)* #id = #expr),
+                    None => quote!(#(#docs)* #(#cfgs)* #id),
                 }
             });
+            let docs = self.type_docs(registry, &ty);
+            let non_exhaustive = self.mirror_non_exhaustive(registry, &ty);
+            let derive = self.derive_attr(&[
+                syn::parse_quote!(Copy),
+                syn::parse_quote!(Clone),
+                syn::parse_quote!(Debug),
+                syn::parse_quote!(Eq),
+                syn::parse_quote!(PartialEq),
+            ]);
             items.push(syn::parse_quote!(
+                #(#docs)*
                 #[repr(C)]
-                #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+                #derive
+                #non_exhaustive
                 #[allow(non_camel_case_types)]
                 pub enum #cname {
                     #(#variants),*
                 }
             ));
+            if !self.skip_enum_discriminant_asserts {
+                let src = self.src_ty(&ty);
+                let asserts = e.variants.iter().map(|v| {
+                    let id = &v.ident;
+                    let cfgs = cfg_attrs(&v.attrs);
+                    let msg = format!(
+                        "enum_type: `{key}::{id}` discriminant differs from the source enum"
+                    );
+                    quote!(#(#cfgs)* assert!(#cname::#id as i64 == #src::#id as i64, #msg);)
+                });
+                items.push(syn::parse_quote!(
+                    const _: () = {
+                        #(#asserts)*
+                    };
+                ));
+            }
+        }
+        items
+    }
+
+    /// Under [`Cbindgen::c_enum_as_integer`], the export of enum `e` with
+    /// `#[repr(#int)]`: the C name as an alias of `int`, one const per
+    /// variant (its literal discriminant when known, so cbindgen can emit a
+    /// `#define`), and the discriminant assertions.
+    fn prereq_enum_as_integer(
+        &self,
+        registry: &Registry<()>,
+        ty: &syn::Type,
+        e: &syn::ItemEnum,
+        int: &syn::Ident,
+    ) -> Vec<syn::Item> {
+        let cname = self.c_type_ident(ty);
+        let src = self.src_ty(ty);
+        let docs = self.type_docs(registry, ty);
+        let mut items: Vec<syn::Item> = vec![syn::parse_quote!(
+            #(#docs)*
+            #[allow(non_camel_case_types)]
+            pub type #cname = #int;
+        )];
+        for (v, value) in e.variants.iter().zip(enum_discriminants(e)) {
+            let id = &v.ident;
+            let docs = self.docs(&v.attrs);
+            let cfgs = cfg_attrs(&v.attrs);
+            let name = self.enum_variant_const(ty, id);
+            let value = match value {
+                Some(value) => {
+                    let lit = proc_macro2::Literal::i128_unsuffixed(value);
+                    quote!(#lit)
+                }
+                None => quote!(#src::#id as #int),
+            };
+            items.push(syn::parse_quote!(
+                #(#docs)*
+                #(#cfgs)*
+                pub const #name: #cname = #value;
+            ));
+        }
+        if !self.skip_enum_discriminant_asserts {
+            let key = TypeKey::from_type(ty);
+            let asserts = e.variants.iter().map(|v| {
+                let id = &v.ident;
+                let cfgs = cfg_attrs(&v.attrs);
+                let name = self.enum_variant_const(ty, id);
+                let msg =
+                    format!("enum_type: `{key}::{id}` discriminant differs from the source enum");
+                quote!(#(#cfgs)* assert!(#name as i64 == #src::#id as i64, #msg);)
+            });
+            items.push(syn::parse_quote!(
+                const _: () = {
+                    #(#asserts)*
+                };
+            ));
         }
         items
     }
@@ -748,11 +1215,12 @@ impl Prebindgen for Cbindgen {
     type Metadata = ();
 
     // Consts have no declaration mechanism here (`declared_consts` stays
-    // `None`), so every indexed const re-emits through the default
-    // `on_const` — a path-alias against this source module, keeping consts
-    // with non-portable initializers valid in the generated file. (cbindgen
-    // cannot evaluate a path initializer, so aliased consts don't surface
-    // as `#define`s in the C header.)
+    // `None`), so every indexed const re-emits through `on_const`: copied
+    // when scalar with a portable initializer, else a path-alias against
+    // this source module, keeping consts with non-portable initializers
+    // valid in the generated file. (cbindgen cannot evaluate a path
+    // initializer, so aliased consts don't surface as `#define`s in the C
+    // header.)
     fn source_module(&self) -> Option<&syn::Path> {
         self.source_module.as_ref()
     }
@@ -813,6 +1281,86 @@ impl Prebindgen for Cbindgen {
         result
     }
 
+    /// Two declared functions exporting one symbol would only fail at link
+    /// time; report them here with both source locations instead. A
+    /// `#[non_exhaustive]` data struct taken as a parameter can't be built
+    /// by the wrapper, so it is reported too.
+    fn validate(&self, registry: &Registry<()>) -> Result<(), String> {
+        let mut idents: Vec<&syn::Ident> = self.functions.keys().collect();
+        idents.sort();
+        let mut errors = Vec::new();
+        for ident in &idents {
+            let Some((f, loc)) = registry.functions.get(*ident) else {
+                continue;
+            };
+            for input in &f.sig.inputs {
+                let syn::FnArg::Typed(pt) = input else {
+                    continue;
+                };
+                if let Some(ty) = self.non_exhaustive_data_in(registry, &pt.ty) {
+                    errors.push(format!(
+                        "{} (`{}` at {loc})",
+                        non_exhaustive_input_msg(&ty),
+                        f.sig.ident
+                    ));
+                }
+            }
+            let output = match &f.sig.output {
+                syn::ReturnType::Type(_, ty) => Some(&**ty),
+                syn::ReturnType::Default => None,
+            };
+            let typed = f.sig.inputs.iter().filter_map(|input| match input {
+                syn::FnArg::Typed(pt) => Some(&*pt.ty),
+                syn::FnArg::Receiver(_) => None,
+            });
+            if let Some(path) = typed
+                .chain(output)
+                .find_map(|ty| self.disallowed_path_in(ty))
+            {
+                errors.push(format!(
+                    "`{}` at {loc} uses `{path}`, which no allowed path prefix covers — \
+                     extend the list with `.allowed_prefix(...)`",
+                    f.sig.ident
+                ));
+            }
+        }
+        if self.allow_duplicate_symbols {
+            return if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(errors.join("\n"))
+            };
+        }
+        let mut by_symbol: HashMap<String, Vec<(&syn::ItemFn, &SourceLocation)>> = HashMap::new();
+        for ident in idents {
+            let Some((f, loc)) = registry.functions.get(ident) else {
+                continue;
+            };
+            let symbol = self.exported_symbol(ident);
+            let seen = by_symbol.entry(symbol.clone()).or_default();
+            for (prev, prev_loc) in seen.iter() {
+                if !cfgs_exclude(&prev.attrs, &f.attrs) {
+                    errors.push(format!(
+                        "duplicate exported symbol `{symbol}`: produced by both `{}` at {prev_loc} \
+                         and `{}` at {loc} — rename one via `.base_name(...)`, guard them with \
+                         exclusive `#[cfg]`s, or set `allow_duplicate_symbols(true)`",
+                        prev.sig.ident, f.sig.ident,
+                    ));
+                }
+            }
+            seen.push((f, loc));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    fn skip_invalid_items(&self) -> bool {
+        self.skip_invalid_items
+    }
+
     fn declared_types(&self) -> HashSet<TypeKey> {
         self.opaque
             .keys()
@@ -836,11 +1384,14 @@ impl Prebindgen for Cbindgen {
         // lives in the `impl Cbindgen` block above; order is significant.
         let produces_array = self.produces_array(registry);
         let mut items: Vec<syn::Item> = Vec::new();
+        items.extend(self.prereq_pointer_width());
         items.extend(self.prereq_alloc_free(registry, produces_array));
+        items.extend(self.prereq_cstring_free(registry));
         items.extend(self.prereq_array_builder(produces_array));
         items.extend(self.prereq_opaque_handles(registry));
         items.extend(self.prereq_data_structs(registry));
         items.extend(self.prereq_value_opaque(registry));
+        items.extend(self.prereq_mapped_types(registry));
         items.extend(self.prereq_enums(registry));
         items.extend(self.prereq_callback_structs(registry));
         items.extend(self.prereq_domain_constants(registry));
@@ -853,6 +1404,18 @@ impl Prebindgen for Cbindgen {
         self.emit_function_wrapper(f, registry)
     }
 
+    fn on_const(&self, c: &syn::ItemConst, registry: &Registry<()>) -> TokenStream {
+        self.emit_const(c, registry)
+    }
+
+    fn post_process_item(&self, item: &mut syn::Item, _registry: &Registry<()>) {
+        if self.extern_block {
+            if let Some(declaration) = extern_declaration(item) {
+                *item = declaration;
+            }
+        }
+    }
+
     fn on_struct(&self, _s: &syn::ItemStruct, _registry: &Registry<()>) -> TokenStream {
         // The `#[repr(C)]` mirror + converters come from prerequisites /
         // on_output_type; the original (non-FFI-safe) struct is dropped.
@@ -990,7 +1553,7 @@ impl Cbindgen {
     pub(crate) fn out_terminal(
         &self,
         ty: &syn::Type,
-        _r: &Registry<()>,
+        r: &Registry<()>,
     ) -> Option<ConverterImpl<()>> {
         // Unit return: trivial converter so `()` (and `Result<(), _>`) resolves.
         // Never actually called — void-returning wrappers ignore it, and
@@ -1015,12 +1578,26 @@ impl Cbindgen {
         // `free_memory_function`. A `String` explicitly declared `opaque_ptr`
         // (held by C as `string_t *`) opts out — the opaque-handle branch below
         // owns it then (mirroring the input side, where `in_opaque_handle` wins).
+        // With `string_as_c_char` the block is Rust's own `CString`, released
+        // by the generated string freer instead; a NUL byte ends the string
+        // there, as C would read it.
         if is_string(ty) && !self.opaque.contains_key(&TypeKey::from_type(ty)) {
             let name = Self::out_name(ty);
+            let encode = if self.cstring_free_fn.is_some() {
+                quote!({
+                    let mut v = v;
+                    if let ::core::option::Option::Some(nul) = v.find('\0') {
+                        v.truncate(nul);
+                    }
+                    ::std::ffi::CString::new(v).unwrap_or_default().into_raw()
+                })
+            } else {
+                quote!(__cbg_alloc_cstr(v))
+            };
             let function: syn::ItemFn = syn::parse_quote!(
                 #[allow(non_snake_case, unused_variables, dead_code)]
                 pub(crate) fn #name(v: ::std::string::String) -> *mut ::core::ffi::c_char {
-                    __cbg_alloc_cstr(v)
+                    #encode
                 }
             );
             return Some(ConverterImpl {
@@ -1033,18 +1610,25 @@ impl Cbindgen {
             });
         }
 
-        // FFI-safe scalar (`bool`, integers, floats): identity pass-through.
-        if is_scalar(ty) {
+        // FFI-safe scalar (`bool`, integers, floats), or a source `type`
+        // alias of one: identity pass-through.
+        if let Some((src, scalar)) = self.scalar_wire(ty, r) {
+            let wire = self.scalar_crossing(scalar.clone());
             let name = Self::out_name(ty);
+            let encode = if wire == scalar {
+                quote!(v)
+            } else {
+                quote!(v as u8)
+            };
             let function: syn::ItemFn = syn::parse_quote!(
                 #[allow(non_snake_case, unused_variables, dead_code)]
-                pub(crate) fn #name(v: #ty) -> #ty {
-                    v
+                pub(crate) fn #name(v: #src) -> #wire {
+                    #encode
                 }
             );
             return Some(ConverterImpl {
                 subs: vec![],
-                destination: ty.clone(),
+                destination: wire,
                 function,
                 pre_stages: vec![],
                 niches: Niches::empty(),
@@ -1102,16 +1686,20 @@ impl Cbindgen {
         // Data struct output: encode each field into its C wire (`String` →
         // malloc'd `char*` raw block, freed by the `free_memory_function`).
         if self.data.contains_key(&key) {
-            let fields = self.struct_fields(_r, ty)?;
+            let fields = self.struct_fields(r, ty)?;
             let name = Self::out_name(ty);
             let c_struct = self.c_type_ident(ty);
-            let src = self.src_ty(ty);
+            let src = self.src_ty_with_lifetimes(r, ty, &syn::parse_quote!('_));
             let mut inits: Vec<TokenStream> = Vec::new();
             for (fname, fty) in &fields {
+                let cfgs = self.field_cfgs(r, ty, fname);
+                let member = source_member(r, ty, fname);
                 if is_string(fty) {
-                    inits.push(quote!(#fname: __cbg_alloc_cstr(v.#fname)));
+                    inits.push(quote!(#(#cfgs)* #fname: __cbg_alloc_cstr(v.#member)));
+                } else if let (syn::Type::Reference(_), Some(wire)) = (fty, c_field_wire(fty)) {
+                    inits.push(quote!(#(#cfgs)* #fname: v.#member as #wire));
                 } else {
-                    inits.push(quote!(#fname: v.#fname));
+                    inits.push(quote!(#(#cfgs)* #fname: v.#member));
                 }
             }
             let function: syn::ItemFn = syn::parse_quote!(
@@ -1155,19 +1743,33 @@ impl Cbindgen {
 
         // Enum output: `match` the source enum to the C enum.
         if self.enums.contains_key(&key) {
-            let e = enum_item(_r, ty)?;
+            let e = enum_item(r, ty)?;
             assert_unit_variants(e);
             let name = Self::out_name(ty);
             let cname = self.c_type_ident(ty);
             let src = self.src_ty(ty);
+            let flattened = self.enum_as_integer(e).is_some();
             let arms = e.variants.iter().map(|v| {
                 let id = &v.ident;
-                quote!(#src::#id => #cname::#id,)
+                let cfgs = cfg_attrs(&v.attrs);
+                if flattened {
+                    let value = self.enum_variant_const(ty, id);
+                    quote!(#(#cfgs)* #src::#id => #value,)
+                } else {
+                    quote!(#(#cfgs)* #src::#id => #cname::#id,)
+                }
+            });
+            // A `#[non_exhaustive]` source enum can't be matched exhaustively
+            // from the binding crate; a variant added after generation has
+            // no C value.
+            let fallback = self.is_non_exhaustive(r, ty).then(|| {
+                let msg = format!("`{}` variant has no C counterpart", type_short(ty));
+                quote!(#[allow(unreachable_patterns)] _ => ::core::panic!(#msg),)
             });
             let function: syn::ItemFn = syn::parse_quote!(
                 #[allow(non_snake_case, unused_variables, dead_code)]
                 pub(crate) fn #name(v: #src) -> #cname {
-                    match v { #(#arms)* }
+                    match v { #(#arms)* #fallback }
                 }
             );
             return Some(ConverterImpl {
@@ -1194,12 +1796,72 @@ impl Cbindgen {
         ty: &syn::Type,
         r: &Registry<()>,
     ) -> Option<ConverterImpl<()>> {
+        // `Box<T>` under `box_as_raw_pointer`: the owning pointer a
+        // `Box<T>` return handed out, taken back with `Box::from_raw`.
+        if let Some((inner, pointee)) = self.box_pointee(ty, r) {
+            let name = Self::in_name(ty);
+            let src = self.src_ty(&inner);
+            let null_msg = format!("null {} box pointer", type_short(&inner));
+            let function: syn::ItemFn = syn::parse_quote!(
+                #[allow(non_snake_case, unused_variables, dead_code)]
+                pub(crate) unsafe fn #name(
+                    v: *mut #pointee,
+                ) -> ::core::result::Result<::std::boxed::Box<#src>, ::std::string::String> {
+                    if v.is_null() {
+                        return ::core::result::Result::Err(
+                            ::std::string::String::from(#null_msg),
+                        );
+                    }
+                    ::core::result::Result::Ok(::std::boxed::Box::from_raw(v as *mut #src))
+                }
+            );
+            return Some(ConverterImpl {
+                subs: vec![inner],
+                destination: syn::parse_quote!(*mut #pointee),
+                function,
+                pre_stages: vec![],
+                niches: Niches::empty(),
+                metadata: (),
+            });
+        }
         // `Option<X>` input: a single nullable C param, NULL = `None`. The inner
         // `X` is reused wholesale (its own converter — e.g. an `&T` borrow — does
         // the non-null decode), so `Option<&ZConfig>` binds the *reference*
         // converter, never the owned one.
         if is_option(ty) {
             let inner = first_type_arg(ty)?;
+            // `Option<&T>` of a scalar under `option_ref_as_pointer`: a nullable
+            // `*const T`, read in place.
+            if let Some((src, wire)) = self.pointer_scalar_ref(&inner, r) {
+                let name = Self::in_name(ty);
+                let msg = option_ref_size_msg(&src);
+                let function: syn::ItemFn = syn::parse_quote!(
+                    #[allow(non_snake_case, unused_variables, dead_code)]
+                    pub(crate) unsafe fn #name<'a>(v: *const #wire) -> ::core::option::Option<&'a #src> {
+                        const _: () = assert!(
+                            ::core::mem::size_of::<::core::option::Option<&#src>>()
+                                == ::core::mem::size_of::<*const #wire>(),
+                            #msg
+                        );
+                        if v.is_null() {
+                            ::core::option::Option::None
+                        } else {
+                            ::core::option::Option::Some(&*v)
+                        }
+                    }
+                );
+                let syn::Type::Reference(rf) = &inner else {
+                    unreachable!("pointer_scalar_ref matches references only")
+                };
+                return Some(ConverterImpl {
+                    subs: vec![(*rf.elem).clone()],
+                    destination: syn::parse_quote!(*const #wire),
+                    function,
+                    pre_stages: vec![],
+                    niches: Niches::empty(),
+                    metadata: (),
+                });
+            }
             let entry = r.input_entry(&inner)?;
             let inner_wire = entry.destination.clone();
             let inner_conv = entry.function.sig.ident.clone();
@@ -1312,6 +1974,45 @@ impl Cbindgen {
             return None;
         };
         let elem = (*rf.elem).clone();
+        // `&[E; N]` / `&mut [E; N]` fixed-size array borrow: the C pointer to an
+        // array of the element's wire (`*const [foo_t; 3]` for `&[Foo; 3]`),
+        // reinterpreted in place once the two array layouts are asserted equal.
+        if let Some((src, wire, leaf)) = self.array_wire(&elem) {
+            let name = Self::in_name(ty);
+            let null_ptr_msg = format!("null {} array pointer", type_short(&leaf));
+            let layout_msg = format!(
+                "array of {}: C and Rust arrays differ in size or alignment",
+                type_short(&leaf)
+            );
+            let (ptr, mutability) = match rf.mutability {
+                Some(_) => (quote!(*mut), quote!(mut)),
+                None => (quote!(*const), quote!()),
+            };
+            let function: syn::ItemFn = syn::parse_quote!(
+                #[allow(non_snake_case, unused_variables, dead_code)]
+                pub(crate) unsafe fn #name<'a>(
+                    v: #ptr #wire,
+                ) -> ::core::result::Result<&'a #mutability #src, ::std::string::String> {
+                    const _: () = assert!(
+                        ::core::mem::size_of::<#wire>() == ::core::mem::size_of::<#src>()
+                            && ::core::mem::align_of::<#wire>() == ::core::mem::align_of::<#src>(),
+                        #layout_msg
+                    );
+                    if v.is_null() {
+                        return ::core::result::Result::Err(::std::string::String::from(#null_ptr_msg));
+                    }
+                    ::core::result::Result::Ok(&#mutability *(v as #ptr #src))
+                }
+            );
+            return Some(ConverterImpl {
+                subs: vec![leaf],
+                destination: syn::parse_quote!(#ptr #wire),
+                function,
+                pre_stages: vec![],
+                niches: Niches::empty(),
+                metadata: (),
+            });
+        }
         // `&[E]` slice: marker only — the two-param (`*const E_wire`, `usize`)
         // lowering is done structurally in `emit_inputs`. A scalar `E` crosses as
         // itself (`*const E`); a declared inline-opaque by-value `E` (e.g. a
@@ -1356,6 +2057,25 @@ impl Cbindgen {
                 }
             }
         }
+        // `&mut [E]` scalar slice: marker only, like `&[E]` above (`*mut E`).
+        if let Some(e) = scalar_slice_mut_elem(ty) {
+            let name = format_ident!(
+                "__cbg_inmark_slice_mut_{}",
+                sanitize(&TypeKey::from_type(&e))
+            );
+            let function: syn::ItemFn = syn::parse_quote!(
+                #[allow(non_snake_case, dead_code, unused)]
+                pub(crate) fn #name() {}
+            );
+            return Some(ConverterImpl {
+                subs: vec![e.clone()],
+                destination: syn::parse_quote!(*mut #e),
+                function,
+                pre_stages: vec![],
+                niches: Niches::empty(),
+                metadata: (),
+            });
+        }
         // `&str`: borrow a UTF-8 C string directly from the caller.
         if rf.mutability.is_none() && is_str(&elem) {
             let name = Self::in_name(ty);
@@ -1386,6 +2106,31 @@ impl Cbindgen {
                 metadata: (),
             });
         }
+        // `&CStr`: borrow the caller's C string as-is (no UTF-8 check).
+        if rf.mutability.is_none() && is_cstr(&elem) {
+            let name = Self::in_name(ty);
+            let function: syn::ItemFn = syn::parse_quote!(
+                #[allow(non_snake_case, unused_variables, dead_code)]
+                pub(crate) unsafe fn #name<'a>(
+                    v: *const ::core::ffi::c_char,
+                ) -> ::core::result::Result<&'a ::core::ffi::CStr, ::std::string::String> {
+                    if v.is_null() {
+                        return ::core::result::Result::Err(
+                            ::std::string::String::from("null pointer passed for CStr argument"),
+                        );
+                    }
+                    ::core::result::Result::Ok(::core::ffi::CStr::from_ptr(v))
+                }
+            );
+            return Some(ConverterImpl {
+                subs: vec![],
+                destination: syn::parse_quote!(*const ::core::ffi::c_char),
+                function,
+                pre_stages: vec![],
+                niches: Niches::empty(),
+                metadata: (),
+            });
+        }
         // `&mut T` (mutable borrow). Three sub-cases, all wiring to a `*mut` of the
         // wire (the C memory IS the Rust value for a value-opaque mirror — asserted
         // layout-identical — so the cast is sound; `&mut` is a borrow, no gravestone).
@@ -1455,11 +2200,13 @@ impl Cbindgen {
                 metadata: (),
             });
         }
-        // `&T` (shared borrow) of an opaque handle or value-opaque type.
+        // `&T` (shared borrow) of an opaque handle, value-opaque or mapped type.
         let key1 = TypeKey::from_type(&elem);
         let wire_ty: syn::Type = if self.opaque.contains_key(&key1) {
             let c_struct = self.c_type_ident(&elem);
             syn::parse_quote!(#c_struct)
+        } else if let Some(wire) = self.mapped.get(&key1) {
+            wire.clone()
         } else {
             self.value_opaque_ty(&elem)?.clone()
         };
@@ -1569,6 +2316,54 @@ impl Cbindgen {
                 metadata: (),
             });
         }
+        // `Box<T>` under `box_as_raw_pointer` → the owning `*mut`, released
+        // with `Box::into_raw`.
+        if let Some((inner, pointee)) = self.box_pointee(ty, r) {
+            let name = Self::out_name(ty);
+            let src = self.src_ty(&inner);
+            let function: syn::ItemFn = syn::parse_quote!(
+                #[allow(non_snake_case, dead_code, unused)]
+                pub(crate) fn #name(v: ::std::boxed::Box<#src>) -> *mut #pointee {
+                    ::std::boxed::Box::into_raw(v) as *mut #pointee
+                }
+            );
+            return Some(ConverterImpl {
+                subs: vec![inner],
+                destination: syn::parse_quote!(*mut #pointee),
+                function,
+                pre_stages: vec![],
+                niches: Niches::empty(),
+                metadata: (),
+            });
+        }
+        // `&T` of a scalar under `option_ref_as_pointer` → non-owning `*const T`,
+        // whose NULL niche carries an enclosing `Option`'s `None`.
+        if let Some((src, wire)) = self.pointer_scalar_ref(ty, r) {
+            let name = Self::out_name(ty);
+            let msg = option_ref_size_msg(&src);
+            let function: syn::ItemFn = syn::parse_quote!(
+                #[allow(non_snake_case, dead_code, unused)]
+                pub(crate) fn #name(v: &#src) -> *const #wire {
+                    const _: () = assert!(
+                        ::core::mem::size_of::<::core::option::Option<&#src>>()
+                            == ::core::mem::size_of::<*const #wire>(),
+                        #msg
+                    );
+                    v
+                }
+            );
+            let syn::Type::Reference(rf) = ty else {
+                unreachable!("pointer_scalar_ref matches references only")
+            };
+            return Some(ConverterImpl {
+                subs: vec![(*rf.elem).clone()],
+                destination: syn::parse_quote!(*const #wire),
+                function,
+                pre_stages: vec![],
+                niches: Niches::empty(),
+                metadata: (),
+            });
+        }
         // `&T` shared borrow of an opaque/value-opaque type → non-owning `*const`.
         if let syn::Type::Reference(rf) = ty {
             if rf.mutability.is_none() {
@@ -1600,7 +2395,8 @@ impl Cbindgen {
             return None;
         }
         // `Result<T, E>` marker — real lowering (bool + out-param + error-param)
-        // is in `on_function`.
+        // is in `on_function`. Under `result_as_out_param` `E` becomes a status
+        // code and never crosses the ABI, so it needs no converter.
         if is_result(ty) {
             let (ok, err) = result_parts(ty)?;
             let name = format_ident!("__cbg_result_{}", sanitize(&TypeKey::from_type(ty)));
@@ -1608,8 +2404,13 @@ impl Cbindgen {
                 #[allow(non_snake_case, dead_code, unused)]
                 pub(crate) fn #name() {}
             );
+            let subs = if self.result_as_out_param.is_some() {
+                vec![ok]
+            } else {
+                vec![ok, err]
+            };
             return Some(ConverterImpl {
-                subs: vec![ok, err],
+                subs,
                 destination: syn::parse_quote!(()),
                 function,
                 pre_stages: vec![],
@@ -1620,3 +2421,126 @@ impl Cbindgen {
         None
     }
 }
+
+/// Why a `#[non_exhaustive]` [`Cbindgen::data_struct`] can't be a parameter.
+fn non_exhaustive_input_msg(ty: &syn::Type) -> String {
+    format!(
+        "Cbindgen::data_struct: `{}` is `#[non_exhaustive]`, so the binding crate can't \
+         construct it from its C mirror; it can only be returned",
+        type_short(ty)
+    )
+}
+
+/// Under [`Cbindgen::emit_extern_block`], the declaration of an exported
+/// (`#[no_mangle]` or `#[export_name]`) fn or static `item`: a one-item
+/// `extern` block (of the fn's ABI, `"C"` for a static) that `write_rust`
+/// merges with the others. `None` for an item that isn't exported.
+fn extern_declaration(item: &syn::Item) -> Option<syn::Item> {
+    // An `#[export_name]` symbol is declared by `#[link_name]`.
+    let keep = |attrs: &[syn::Attribute]| -> Vec<syn::Attribute> {
+        attrs
+            .iter()
+            .filter(|a| !is_no_mangle(a) && !a.path().is_ident("inline"))
+            .map(|a| match export_name(a) {
+                Some(symbol) => syn::parse_quote!(#[link_name = #symbol]),
+                None => a.clone(),
+            })
+            .collect()
+    };
+    let is_exported = |attrs: &[syn::Attribute]| {
+        attrs
+            .iter()
+            .any(|a| is_no_mangle(a) || export_name(a).is_some())
+    };
+    let unsafety =
+        (RustEdition::detected() == RustEdition::Edition2024).then(<syn::Token![unsafe]>::default);
+    match item {
+        syn::Item::Fn(f) if is_exported(&f.attrs) => {
+            let attrs = keep(&f.attrs);
+            let vis = &f.vis;
+            let abi = f.sig.abi.clone()?;
+            let mut sig = f.sig.clone();
+            sig.abi = None;
+            sig.unsafety = None;
+            Some(syn::parse_quote!(
+                #unsafety #abi {
+                    #(#attrs)*
+                    #vis #sig;
+                }
+            ))
+        }
+        syn::Item::Static(s) if is_exported(&s.attrs) => {
+            let attrs = keep(&s.attrs);
+            let (vis, mutability, ident, ty) = (&s.vis, &s.mutability, &s.ident, &s.ty);
+            Some(syn::parse_quote!(
+                #unsafety extern "C" {
+                    #(#attrs)*
+                    #vis static #mutability #ident: #ty;
+                }
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// `#[no_mangle]`, or its 2024 spelling `#[unsafe(no_mangle)]`.
+fn is_no_mangle(attr: &syn::Attribute) -> bool {
+    match &attr.meta {
+        syn::Meta::List(list) if list.path.is_ident("unsafe") => list
+            .parse_args::<syn::Path>()
+            .is_ok_and(|p| p.is_ident("no_mangle")),
+        meta => meta.path().is_ident("no_mangle"),
+    }
+}
+
+/// The symbol of `#[export_name = "..."]`, or of its 2024 spelling
+/// `#[unsafe(export_name = "...")]`.
+fn export_name(attr: &syn::Attribute) -> Option<syn::LitStr> {
+    let meta = match &attr.meta {
+        syn::Meta::List(list) if list.path.is_ident("unsafe") => list.parse_args().ok()?,
+        meta => meta.clone(),
+    };
+    match meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(symbol),
+                    ..
+                }),
+            ..
+        }) if path.is_ident("export_name") => Some(symbol),
+        _ => None,
+    }
+}
+
+/// Name of the wrapper `W` when `from` is `W<.., to, ..>` (a path whose last
+/// segment has `to` among its generic type arguments), i.e. a mapping that
+/// strips it, with the index of `to` among those arguments.
+fn stripped_wrapper(from: &syn::Type, to: &syn::Type) -> Option<(String, usize)> {
+    let syn::Type::Path(tp) = from else {
+        return None;
+    };
+    let last = tp.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(ab) = &last.arguments else {
+        return None;
+    };
+    let to = TypeKey::from_type(to);
+    ab.args
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .position(|inner| TypeKey::from_type(inner) == to)
+        .map(|index| (last.ident.to_string(), index))
+}
+
+/// Message of the `Option<&T>`-is-pointer-sized assertion emitted under
+/// [`Cbindgen::option_ref_as_pointer`].
+fn option_ref_size_msg(src: &syn::Type) -> String {
+    format!(
+        "option_ref_as_pointer: `Option<&{}>` is not pointer-sized",
+        src.to_token_stream()
+    )
+}
@@ -2,6 +2,7 @@
 //! (`New`/`Push`/`Free` helper trio).
 
 use super::*;
+use crate::api::utils::edition::RustEdition;
 
 /// Classify a slice/`Vec` **input** param for the "build the Rust-side Vec
 /// incrementally" path: an immutable slice `&[T]` (`by_ref = true`, the target
@@ -175,10 +176,11 @@ pub(crate) fn build_vec_build_helper_items(
         let push_id = syn::Ident::new(&push_sym, Span::call_site());
         let free_id = syn::Ident::new(&free_sym, Span::call_site());
 
+        let no_mangle = RustEdition::detected().no_mangle_attr();
         named.push((
             new_sym.clone(),
             syn::parse_quote!(
-                #[no_mangle]
+                #no_mangle
                 #[allow(non_snake_case, unused_variables)]
                 pub(crate) unsafe extern "C" fn #new_id(
                     _env: jni::JNIEnv,
@@ -222,10 +224,11 @@ pub(crate) fn build_vec_build_helper_items(
         }
         let module = &h.plan.root.struct_module;
         let sid = &h.plan.root.struct_ident;
+        let no_mangle = RustEdition::detected().no_mangle_attr();
         named.push((
             push_sym.clone(),
             syn::parse_quote!(
-                #[no_mangle]
+                #no_mangle
                 #[allow(non_snake_case, unused_mut, unused_variables, dead_code)]
                 pub(crate) unsafe extern "C" fn #push_id<'a>(
                     mut env: jni::JNIEnv<'a>,
@@ -244,10 +247,11 @@ pub(crate) fn build_vec_build_helper_items(
             ),
         ));
 
+        let no_mangle = RustEdition::detected().no_mangle_attr();
         named.push((
             free_sym.clone(),
             syn::parse_quote!(
-                #[no_mangle]
+                #no_mangle
                 #[allow(non_snake_case, unused_variables)]
                 pub(crate) unsafe extern "C" fn #free_id(
                     _env: jni::JNIEnv,
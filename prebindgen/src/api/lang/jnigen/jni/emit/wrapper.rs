@@ -3,6 +3,7 @@
 
 use super::*;
 use crate::api::core::types_util::result_ok_type;
+use crate::api::utils::edition::RustEdition;
 
 pub(crate) fn emit_jni_function_wrapper(
     ext: &JniGen,
@@ -376,8 +377,9 @@ pub(crate) fn emit_jni_function_wrapper_with_callee(
     // `__domain_sink` (typed domain error) for a fallible fn — a capture is
     // passed for each. Declared after the wire params + builder so the order
     // matches the Kotlin `external fun`.
+    let no_mangle = RustEdition::detected().no_mangle_attr();
     quote! {
-        #[no_mangle]
+        #no_mangle
         #[allow(non_snake_case, unused_mut, unused_variables, dead_code)]
         pub unsafe extern "C" fn #wrapper_ident<'a>(
             mut env: jni::JNIEnv<'a>,
@@ -247,3 +247,32 @@ fn method_and_factory_same_name_do_not_collide() {
     // Instance method `of()` and companion factory `of()` are distinct scopes.
     resolve_result("jni_ov_scopes", registry, jni).expect("method vs factory don't collide");
 }
+
+/// A source-side `#[prebindgen(rename = "...")]` names the Kotlin function
+/// and its native symbol, while the wrapper still calls the Rust fn.
+#[test]
+fn source_rename_names_function() {
+    let record = crate::Record::new(
+        crate::RecordKind::Function,
+        "rust_open".to_string(),
+        "pub fn rust_open() -> i64 { 0 }".to_string(),
+        myflat_loc(),
+        None,
+    )
+    .with_rename(Some("z_open".to_string()));
+    let registry =
+        Registry::<KotlinMeta>::from_items([record.try_parse().unwrap()]).expect("index items");
+    let jni = JniGen::new()
+        .set_package_prefix("io.test.jni")
+        .package(crate::package!("session").fun(crate::fun!(z_open)));
+    let dir = unique_test_dir("jni_sym_source_rename");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let gen = registry.resolve(jni).expect("resolve");
+    let rust_path = gen.write_rust(dir.join("gen.rs")).expect("write_rust");
+    let rust = std::fs::read_to_string(&rust_path).unwrap();
+    let compact: String = rust.split_whitespace().collect();
+    assert!(compact.contains("myflat::rust_open("), "{rust}");
+    assert!(!compact.contains("myflat::z_open"), "{rust}");
+    assert!(rust.contains("zOpen"), "{rust}");
+}
@@ -5,6 +5,7 @@
 //! namespace via `use super::*`.
 
 use super::*;
+use crate::api::utils::edition::RustEdition;
 
 /// The `#[allow(...)]` carried by every generated converter `fn`.
 ///
@@ -567,8 +568,9 @@ pub(crate) fn build_handle_destructor_items(
             };
         );
         named.push((format!("{symbol}__align_assert"), item));
+        let no_mangle = RustEdition::detected().no_mangle_attr();
         let item: syn::Item = syn::parse_quote!(
-            #[no_mangle]
+            #no_mangle
             #[allow(non_snake_case, unused_variables)]
             pub(crate) unsafe extern "C" fn #ident(
                 _env: jni::JNIEnv,
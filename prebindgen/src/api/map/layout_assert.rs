@@ -0,0 +1,141 @@
+use roxygen::roxygen;
+
+use crate::api::{collect::destination::cfg_attrs, record::SourceLocation};
+
+/// Builder for configuring LayoutAsserts instances
+pub struct Builder {
+    source_crate: String,
+    max_alignment: Option<usize>,
+}
+
+impl Builder {
+    /// Create a new Builder for configuring LayoutAsserts
+    #[roxygen]
+    pub fn new<S: Into<String>>(
+        /// The path the copied items are compared against (e.g., the source
+        /// crate name returned by [`Source::crate_name`](crate::Source::crate_name))
+        source_crate: S,
+    ) -> Self {
+        Self {
+            source_crate: source_crate.into(),
+            max_alignment: None,
+        }
+    }
+
+    /// Also assert that no copied struct, enum or union is aligned to more
+    /// than `align` bytes
+    ///
+    /// Some C compilers can't pass over-aligned types (e.g. ones holding a
+    /// SIMD field) by value; the cap catches them when the generated file is
+    /// compiled. Unlike the layout check, it applies to items without a
+    /// `#[repr]` as well.
+    #[roxygen]
+    pub fn max_alignment(
+        mut self,
+        /// Largest alignment allowed, in bytes
+        align: usize,
+    ) -> Self {
+        self.max_alignment = Some(align);
+        self
+    }
+
+    /// Build the LayoutAsserts instance with the configured options
+    ///
+    /// # Panics
+    ///
+    /// If the source crate is not a valid path.
+    pub fn build(self) -> LayoutAsserts {
+        let source_crate = syn::parse_str::<syn::Path>(&self.source_crate).unwrap_or_else(|e| {
+            panic!(
+                "LayoutAsserts: `{}` is not a valid source crate path: {e}",
+                self.source_crate
+            )
+        });
+        LayoutAsserts {
+            source_crate,
+            max_alignment: self.max_alignment,
+        }
+    }
+}
+
+/// Follows every copied `#[repr(...)]` struct, enum and union with a
+/// compile-time check that its size and alignment equal the source crate's
+/// original
+///
+/// The copies are laid out by the same rules only as long as the two
+/// definitions stay in sync, and unions in particular are read through any
+/// field, so a drifted copy is caught when the generated file is compiled.
+/// The check uses nothing but `size_of` / `align_of`, so it needs no derives
+/// on the type. Generic items and items without a `#[repr]` pass through
+/// alone, save for the [`Builder::max_alignment`] cap. Run it before
+/// [`RenameType`](crate::map::RenameType): the assert names the source type
+/// by the copy's name.
+///
+/// # Example
+///
+/// ```
+/// # prebindgen::Source::init_doctest_simulate();
+/// let source = prebindgen::Source::new("source_ffi");
+/// let asserts = prebindgen::map::LayoutAsserts::builder(source.crate_name()).build();
+/// let items = source
+///     .items_all()
+///     .flat_map(|item| asserts.call(item))
+///     .collect::<Vec<_>>();
+/// ```
+pub struct LayoutAsserts {
+    source_crate: syn::Path,
+    max_alignment: Option<usize>,
+}
+
+impl LayoutAsserts {
+    /// Create a builder comparing the copies against items of `source_crate`
+    pub fn builder<S: Into<String>>(source_crate: S) -> Builder {
+        Builder::new(source_crate)
+    }
+
+    /// Process a single item, returning it followed by its layout assertion
+    /// and alignment cap (if any)
+    pub fn call(
+        &self,
+        (item, location): (syn::Item, SourceLocation),
+    ) -> Vec<(syn::Item, SourceLocation)> {
+        let (ident, attrs, generics) = match &item {
+            syn::Item::Struct(s) => (&s.ident, &s.attrs, &s.generics),
+            syn::Item::Enum(e) => (&e.ident, &e.attrs, &e.generics),
+            syn::Item::Union(u) => (&u.ident, &u.attrs, &u.generics),
+            _ => return vec![(item, location)],
+        };
+        if !generics.params.is_empty() {
+            return vec![(item, location)];
+        }
+        let source_crate = &self.source_crate;
+        let cfgs = cfg_attrs(attrs);
+        let mut asserts: Vec<syn::Item> = Vec::new();
+        if attrs.iter().any(|a| a.path().is_ident("repr")) {
+            let msg = format!("`{ident}` differs in size or alignment from the source type");
+            asserts.push(syn::parse_quote!(
+                #(#cfgs)*
+                const _: () = assert!(
+                    ::core::mem::size_of::<#ident>() == ::core::mem::size_of::<#source_crate::#ident>()
+                        && ::core::mem::align_of::<#ident>()
+                            == ::core::mem::align_of::<#source_crate::#ident>(),
+                    #msg
+                );
+            ));
+        }
+        if let Some(max) = self.max_alignment {
+            let msg = format!("`{ident}` is aligned to more than {max} bytes");
+            let max = proc_macro2::Literal::usize_unsuffixed(max);
+            asserts.push(syn::parse_quote!(
+                #(#cfgs)*
+                const _: () = assert!(::core::mem::align_of::<#ident>() <= #max, #msg);
+            ));
+        }
+        let mut items = vec![(item, location.clone())];
+        items.extend(asserts.into_iter().map(|assert| (assert, location.clone())));
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests;
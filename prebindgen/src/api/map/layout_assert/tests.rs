@@ -0,0 +1,92 @@
+use quote::ToTokens;
+
+use super::*;
+
+fn asserts(item: syn::Item) -> Vec<String> {
+    LayoutAsserts::builder("source_ffi")
+        .build()
+        .call((item, SourceLocation::default()))
+        .into_iter()
+        .map(|(item, _)| item.to_token_stream().to_string())
+        .collect()
+}
+
+#[test]
+fn asserts_repr_union_layout() {
+    let items = asserts(syn::parse_quote! {
+        #[repr(C)]
+        #[cfg(feature = "raw")]
+        pub union Value { pub id: Handle, pub bits: u64 }
+    });
+    assert_eq!(items.len(), 2, "{items:?}");
+    let compact: String = items[1].split_whitespace().collect();
+    assert!(
+        compact.starts_with("#[cfg(feature=\"raw\")]const_:()=assert!"),
+        "{compact}"
+    );
+    assert!(
+        compact.contains(
+            "::core::mem::size_of::<Value>()==::core::mem::size_of::<source_ffi::Value>()"
+        ),
+        "{compact}"
+    );
+    assert!(
+        compact.contains(
+            "::core::mem::align_of::<Value>()==::core::mem::align_of::<source_ffi::Value>()"
+        ),
+        "{compact}"
+    );
+}
+
+#[test]
+fn skips_items_without_repr_or_with_generics() {
+    for item in [
+        syn::parse_quote! { pub struct Plain { pub x: i32 } },
+        syn::parse_quote! { #[repr(C)] pub struct Wrap<T> { pub x: T } },
+        syn::parse_quote! { pub fn origin() -> i32 { 0 } },
+    ] {
+        assert_eq!(asserts(item).len(), 1);
+    }
+}
+
+#[test]
+fn max_alignment_caps_every_copied_type() {
+    let capped = |item: syn::Item| -> Vec<String> {
+        LayoutAsserts::builder("source_ffi")
+            .max_alignment(16)
+            .build()
+            .call((item, SourceLocation::default()))
+            .into_iter()
+            .map(|(item, _)| {
+                item.to_token_stream()
+                    .to_string()
+                    .split_whitespace()
+                    .collect()
+            })
+            .collect()
+    };
+    let items = capped(syn::parse_quote! {
+        #[repr(C, align(32))]
+        pub struct Lanes { pub v: [f32; 8] }
+    });
+    assert_eq!(items.len(), 3, "{items:?}");
+    assert!(
+        items[1].contains("::core::mem::size_of::<Lanes>()=="),
+        "{items:?}"
+    );
+    assert_eq!(
+        items[2],
+        "const_:()=assert!(::core::mem::align_of::<Lanes>()<=16,\
+         \"`Lanes`isalignedtomorethan16bytes\");"
+    );
+    // No `#[repr]`: only the cap
+    let items = capped(syn::parse_quote! {
+        #[cfg(unix)]
+        pub enum Mode { On, Off }
+    });
+    assert_eq!(items.len(), 2, "{items:?}");
+    assert!(
+        items[1].starts_with("#[cfg(unix)]const_:()=assert!(::core::mem::align_of::<Mode>()<="),
+        "{items:?}"
+    );
+}
@@ -0,0 +1,5 @@
+pub(crate) mod layout_assert;
+pub(crate) mod rename_type;
+pub(crate) mod strip_derive;
+pub(crate) mod strip_macro;
+pub(crate) mod struct_align;
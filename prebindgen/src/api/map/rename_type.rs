@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use roxygen::roxygen;
+use syn::visit_mut::VisitMut;
+
+use crate::api::record::SourceLocation;
+
+/// Builder for configuring RenameType instances
+pub struct Builder {
+    renames: HashMap<String, String>,
+}
+
+impl Builder {
+    /// Create a new Builder for configuring RenameType
+    pub fn new() -> Self {
+        Self {
+            renames: HashMap::new(),
+        }
+    }
+
+    /// Rename the type `from` to `to`
+    ///
+    /// Can be called several times; a later rename of the same type wins.
+    #[roxygen]
+    pub fn rename<S: Into<String>, T: Into<String>>(
+        mut self,
+        /// The current type name (e.g., "Foo")
+        from: S,
+        /// The new type name (e.g., "MyFoo")
+        to: T,
+    ) -> Self {
+        self.renames.insert(from.into(), to.into());
+        self
+    }
+
+    /// Add every `(from, to)` pair of `renames`
+    ///
+    /// Accepts a `HashMap<String, String>` or any other iterator of pairs.
+    #[roxygen]
+    pub fn renames<I, S, T>(
+        mut self,
+        /// The `(from, to)` type name pairs
+        renames: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = (S, T)>,
+        S: Into<String>,
+        T: Into<String>,
+    {
+        self.renames
+            .extend(renames.into_iter().map(|(s, t)| (s.into(), t.into())));
+        self
+    }
+
+    /// Build the RenameType instance with the configured options
+    ///
+    /// # Panics
+    ///
+    /// If a new name is not a valid identifier.
+    pub fn build(self) -> RenameType {
+        let renames = self
+            .renames
+            .into_iter()
+            .map(|(from, to)| {
+                let ident = syn::parse_str::<syn::Ident>(&to).unwrap_or_else(|e| {
+                    panic!(
+                        "RenameType: `{to}` (the new name of `{from}`) is not an identifier: {e}"
+                    )
+                });
+                (from, ident)
+            })
+            .collect();
+        RenameType { renames }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Renames structs, enums, unions and type aliases, and every reference to
+/// them
+///
+/// The rename is applied to the definition and to any path segment naming a
+/// renamed type in every other item (function signatures, fields, aliases), so
+/// the whole stream must go through the same instance. Run it before the items
+/// reach [`Registry::from_items`](crate::core::Registry::from_items) so every
+/// later stage sees only the new names.
+///
+/// Only the generated side is renamed: the definition's
+/// [`SourceLocation::source_name`] keeps the original name, and the generated
+/// code still references the source type by it (`source::Foo`), while the
+/// mirrors and wire types the adapter derives from the type are named after
+/// `MyFoo`.
+///
+/// # Example
+///
+/// ```
+/// # prebindgen::Source::init_doctest_simulate();
+/// let source = prebindgen::Source::new("source_ffi");
+/// let rename = prebindgen::map::RenameType::builder()
+///     .rename("TestStruct", "MyTestStruct")
+///     .build();
+/// let items = source
+///     .items_all()
+///     .map(|item| rename.call(item))
+///     .collect::<Vec<_>>();
+/// ```
+pub struct RenameType {
+    renames: HashMap<String, syn::Ident>,
+}
+
+impl RenameType {
+    /// Create a builder for configuring a rename-type instance
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
+    /// Process a single item, renaming the configured types
+    pub fn call(
+        &self,
+        (mut item, mut location): (syn::Item, SourceLocation),
+    ) -> (syn::Item, SourceLocation) {
+        let ident = match &mut item {
+            syn::Item::Struct(s) => Some(&mut s.ident),
+            syn::Item::Enum(e) => Some(&mut e.ident),
+            syn::Item::Union(u) => Some(&mut u.ident),
+            syn::Item::Type(t) => Some(&mut t.ident),
+            _ => None,
+        };
+        if let Some(ident) = ident {
+            let original = ident.to_string();
+            if self.rename(ident) {
+                // A type renamed twice is still found under its first name
+                location.source_name.get_or_insert(original);
+            }
+        }
+        Renamer(self).visit_item_mut(&mut item);
+        (item, location)
+    }
+
+    /// Rename `ident` if it names a renamed type; whether it did.
+    fn rename(&self, ident: &mut syn::Ident) -> bool {
+        let Some(to) = self.renames.get(&ident.to_string()) else {
+            return false;
+        };
+        *ident = syn::Ident::new(&to.to_string(), ident.span());
+        true
+    }
+}
+
+/// Renames every path segment naming a renamed type.
+struct Renamer<'a>(&'a RenameType);
+
+impl VisitMut for Renamer<'_> {
+    fn visit_path_segment_mut(&mut self, segment: &mut syn::PathSegment) {
+        self.0.rename(&mut segment.ident);
+        syn::visit_mut::visit_path_segment_mut(self, segment);
+    }
+}
+
+#[cfg(test)]
+mod tests;
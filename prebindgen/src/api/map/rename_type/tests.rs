@@ -0,0 +1,78 @@
+use quote::ToTokens;
+
+use super::*;
+
+fn rename(filter: &RenameType, item: syn::Item) -> String {
+    let (item, _) = filter.call((item, SourceLocation::default()));
+    item.to_token_stream().to_string()
+}
+
+#[test]
+fn renames_definition_and_references() {
+    let filter = RenameType::builder()
+        .renames(HashMap::from([("Foo".to_string(), "MyFoo".to_string())]))
+        .build();
+    assert_eq!(
+        rename(&filter, syn::parse_quote! { pub struct Foo { pub x: i32 } }),
+        "pub struct MyFoo { pub x : i32 }"
+    );
+    assert_eq!(
+        rename(
+            &filter,
+            syn::parse_quote! { pub fn foo_len(foo: &Foo, all: Vec<crate::Foo>) -> Option<Foo> {} }
+        ),
+        "pub fn foo_len (foo : & MyFoo , all : Vec < crate :: MyFoo >) -> Option < MyFoo > { }"
+    );
+    assert_eq!(
+        rename(
+            &filter,
+            syn::parse_quote! { pub struct Bar { pub foo: Foo, pub food: u8 } }
+        ),
+        "pub struct Bar { pub foo : MyFoo , pub food : u8 }"
+    );
+}
+
+#[test]
+fn renames_enums_unions_and_aliases() {
+    let filter = RenameType::builder()
+        .rename("mode", "Mode")
+        .rename("raw", "Raw")
+        .rename("handle", "Handle")
+        .build();
+    assert_eq!(
+        rename(&filter, syn::parse_quote! { pub enum mode { A, B } }),
+        "pub enum Mode { A , B }"
+    );
+    assert_eq!(
+        rename(
+            &filter,
+            syn::parse_quote! { pub union raw { pub a: u32, pub m: mode } }
+        ),
+        "pub union Raw { pub a : u32 , pub m : Mode }"
+    );
+    assert_eq!(
+        rename(&filter, syn::parse_quote! { pub type handle = *mut raw; }),
+        "pub type Handle = * mut Raw ;"
+    );
+}
+
+#[test]
+fn definition_keeps_its_source_name() {
+    let filter = RenameType::builder().rename("Foo", "MyFoo").build();
+    let (_, location) = filter.call((
+        syn::parse_quote! { pub struct Foo; },
+        SourceLocation::default(),
+    ));
+    assert_eq!(location.source_name.as_deref(), Some("Foo"));
+    let (_, location) = filter.call((
+        syn::parse_quote! { pub fn take(foo: Foo) {} },
+        SourceLocation::default(),
+    ));
+    assert_eq!(location.source_name, None);
+}
+
+#[test]
+#[should_panic(expected = "RenameType: `My Foo` (the new name of `Foo`) is not an identifier")]
+fn rejects_invalid_new_name() {
+    let _ = RenameType::builder().rename("Foo", "My Foo").build();
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+use roxygen::roxygen;
+
+use crate::api::record::SourceLocation;
+
+/// Builder for configuring StripDerives instances
+///
+/// Derives are matched by the last segment of their path, so `"Serialize"`
+/// also matches `#[derive(serde::Serialize)]`.
+pub struct Builder {
+    strip: HashSet<String>,
+    keep_only: Option<HashSet<String>>,
+}
+
+impl Builder {
+    /// Create a new Builder for configuring StripDerives
+    pub fn new() -> Self {
+        Self {
+            strip: HashSet::new(),
+            keep_only: None,
+        }
+    }
+
+    /// Remove the named derive
+    ///
+    /// Can be called several times; combines with [`keep_only`](Self::keep_only).
+    #[roxygen]
+    pub fn strip_derive<S: Into<String>>(
+        mut self,
+        /// The derive name to remove (e.g., "Serialize")
+        derive: S,
+    ) -> Self {
+        self.strip.insert(derive.into());
+        self
+    }
+
+    /// Remove every derive not in `derives`
+    ///
+    /// Calling this again extends the keep-list. A derive named in both lists
+    /// is removed, so a strip-list can narrow down a broad keep-list.
+    #[roxygen]
+    pub fn keep_only(
+        mut self,
+        /// The derive names to keep (e.g., `&["Copy", "Clone"]`)
+        derives: &[&str],
+    ) -> Self {
+        self.keep_only
+            .get_or_insert_with(HashSet::new)
+            .extend(derives.iter().map(|d| d.to_string()));
+        self
+    }
+
+    /// Build the StripDerives instance with the configured options
+    pub fn build(self) -> StripDerives {
+        StripDerives { builder: self }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Removes derives from structs, enums and unions
+///
+/// When no derive is left the whole `#[derive]` attribute is deleted. Other
+/// items pass through unchanged.
+///
+/// # Example
+///
+/// ```
+/// # prebindgen::Source::init_doctest_simulate();
+/// let source = prebindgen::Source::new("source_ffi");
+/// let strip = prebindgen::map::StripDerives::builder()
+///     .keep_only(&["Copy", "Clone"])
+///     .build();
+/// let items = source
+///     .items_all()
+///     .map(|item| strip.call(item))
+///     .collect::<Vec<_>>();
+/// ```
+pub struct StripDerives {
+    builder: Builder,
+}
+
+impl StripDerives {
+    /// Create a builder for configuring a strip-derives instance
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
+    /// Process a single item, removing the configured derives
+    pub fn call(
+        &self,
+        (mut item, location): (syn::Item, SourceLocation),
+    ) -> (syn::Item, SourceLocation) {
+        let attrs = match &mut item {
+            syn::Item::Struct(s) => &mut s.attrs,
+            syn::Item::Enum(e) => &mut e.attrs,
+            syn::Item::Union(u) => &mut u.attrs,
+            _ => return (item, location),
+        };
+        attrs.retain_mut(|attr| self.strip_attr(attr));
+        (item, location)
+    }
+
+    /// Drop the stripped derives from `attr`; false if it should be removed.
+    fn strip_attr(&self, attr: &mut syn::Attribute) -> bool {
+        if !attr.path().is_ident("derive") {
+            return true;
+        }
+        let Ok(derives) = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+        ) else {
+            return true;
+        };
+        let kept: Vec<&syn::Path> = derives.iter().filter(|path| self.keeps(path)).collect();
+        if kept.is_empty() {
+            return false;
+        }
+        if kept.len() < derives.len() {
+            *attr = syn::parse_quote!(#[derive(#(#kept),*)]);
+        }
+        true
+    }
+
+    fn keeps(&self, path: &syn::Path) -> bool {
+        let Some(name) = path.segments.last().map(|s| s.ident.to_string()) else {
+            return true;
+        };
+        !self.builder.strip.contains(&name)
+            && self
+                .builder
+                .keep_only
+                .as_ref()
+                .is_none_or(|keep| keep.contains(&name))
+    }
+}
+
+#[cfg(test)]
+mod tests;
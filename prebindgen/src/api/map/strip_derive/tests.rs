@@ -0,0 +1,70 @@
+use quote::ToTokens;
+
+use super::*;
+
+fn strip(filter: &StripDerives, item: syn::Item) -> String {
+    let (item, _) = filter.call((item, SourceLocation::default()));
+    item.to_token_stream().to_string()
+}
+
+#[test]
+fn keep_only_reduces_derives() {
+    let filter = StripDerives::builder()
+        .keep_only(&["Copy", "Clone"])
+        .build();
+    let out = strip(
+        &filter,
+        syn::parse_quote! {
+            #[derive(Debug, Clone, Copy, serde::Serialize)]
+            pub struct Point { pub x: i32 }
+        },
+    );
+    assert_eq!(
+        out,
+        "# [derive (Clone , Copy)] pub struct Point { pub x : i32 }"
+    );
+}
+
+#[test]
+fn empty_derive_attribute_is_removed() {
+    let filter = StripDerives::builder().keep_only(&["Copy"]).build();
+    let out = strip(
+        &filter,
+        syn::parse_quote! {
+            #[derive(Debug)]
+            #[repr(C)]
+            pub enum Mode { A, B }
+        },
+    );
+    assert_eq!(out, "# [repr (C)] pub enum Mode { A , B }");
+}
+
+#[test]
+fn strip_list_composes_with_keep_list() {
+    let filter = StripDerives::builder()
+        .keep_only(&["Copy", "Clone", "Debug"])
+        .strip_derive("Debug")
+        .build();
+    let out = strip(
+        &filter,
+        syn::parse_quote! {
+            #[derive(Debug, Clone, Copy, PartialEq)]
+            pub struct Point { pub x: i32 }
+        },
+    );
+    assert_eq!(
+        out,
+        "# [derive (Clone , Copy)] pub struct Point { pub x : i32 }"
+    );
+
+    // A strip-list alone leaves the other derives
+    let filter = StripDerives::builder().strip_derive("Serialize").build();
+    let out = strip(
+        &filter,
+        syn::parse_quote! {
+            #[derive(Debug, serde::Serialize)]
+            pub struct Point { pub x: i32 }
+        },
+    );
+    assert_eq!(out, "# [derive (Debug)] pub struct Point { pub x : i32 }");
+}
@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use roxygen::roxygen;
+
+use crate::api::record::SourceLocation;
+
+/// Built-in attributes, which are never treated as attribute macros: a
+/// keep-list leaves them in place (they can still be named in a strip-list).
+const BUILTIN_ATTRIBUTES: &[&str] = &[
+    "allow",
+    "cfg",
+    "cfg_attr",
+    "cold",
+    "default",
+    "deny",
+    "deprecated",
+    "derive",
+    "doc",
+    "expect",
+    "export_name",
+    "forbid",
+    "inline",
+    "link_section",
+    "must_use",
+    "no_mangle",
+    "non_exhaustive",
+    "repr",
+    "track_caller",
+    "unsafe",
+    "warn",
+];
+
+/// Builder for configuring StripMacros instances
+///
+/// Attributes are matched by the last segment of their path, whatever their
+/// form: `"serde"` matches `#[serde]`, `#[serde(default)]` and
+/// `#[serde = "..."]`, and `"instrument"` matches `#[tracing::instrument]`.
+pub struct Builder {
+    strip: HashSet<String>,
+    keep: Option<HashSet<String>>,
+}
+
+impl Builder {
+    /// Create a new Builder for configuring StripMacros
+    pub fn new() -> Self {
+        Self {
+            strip: HashSet::new(),
+            keep: None,
+        }
+    }
+
+    /// Remove the named attribute macros
+    ///
+    /// Can be called several times; combines with [`keep`](Self::keep).
+    #[roxygen]
+    pub fn strip(
+        mut self,
+        /// The attribute names to remove (e.g., `&["my_internal_macro"]`)
+        names: &[&str],
+    ) -> Self {
+        self.strip.extend(names.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Remove every attribute macro not in `names`
+    ///
+    /// Built-in attributes (`doc`, `repr`, `derive`, `cfg`, ...) are not
+    /// attribute macros and stay. Calling this again extends the keep-list. An
+    /// attribute named in both lists is removed.
+    #[roxygen]
+    pub fn keep(
+        mut self,
+        /// The attribute names to keep (e.g., `&["serde"]`)
+        names: &[&str],
+    ) -> Self {
+        self.keep
+            .get_or_insert_with(HashSet::new)
+            .extend(names.iter().map(|n| n.to_string()));
+        self
+    }
+
+    /// Build the StripMacros instance with the configured options
+    pub fn build(self) -> StripMacros {
+        StripMacros { builder: self }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Removes attribute macros from items and from their fields and variants
+///
+/// Use it to drop macros a binding generator can't expand (or the binding
+/// crate doesn't depend on) from copied items, while keeping the ones it
+/// needs.
+///
+/// # Example
+///
+/// ```
+/// # prebindgen::Source::init_doctest_simulate();
+/// let source = prebindgen::Source::new("source_ffi");
+/// let strip = prebindgen::map::StripMacros::builder()
+///     .keep(&["serde"])
+///     .build();
+/// let items = source
+///     .items_all()
+///     .map(|item| strip.call(item))
+///     .collect::<Vec<_>>();
+/// ```
+pub struct StripMacros {
+    builder: Builder,
+}
+
+impl StripMacros {
+    /// Create a builder for configuring a strip-macros instance
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
+    /// Process a single item, removing the configured attribute macros
+    pub fn call(
+        &self,
+        (mut item, location): (syn::Item, SourceLocation),
+    ) -> (syn::Item, SourceLocation) {
+        match &mut item {
+            syn::Item::Struct(s) => {
+                self.strip_attrs(&mut s.attrs);
+                self.strip_fields(&mut s.fields);
+            }
+            syn::Item::Enum(e) => {
+                self.strip_attrs(&mut e.attrs);
+                for variant in &mut e.variants {
+                    self.strip_attrs(&mut variant.attrs);
+                    self.strip_fields(&mut variant.fields);
+                }
+            }
+            syn::Item::Union(u) => {
+                self.strip_attrs(&mut u.attrs);
+                for field in &mut u.fields.named {
+                    self.strip_attrs(&mut field.attrs);
+                }
+            }
+            syn::Item::Fn(f) => self.strip_attrs(&mut f.attrs),
+            syn::Item::Const(c) => self.strip_attrs(&mut c.attrs),
+            syn::Item::Static(s) => self.strip_attrs(&mut s.attrs),
+            syn::Item::Type(t) => self.strip_attrs(&mut t.attrs),
+            _ => {}
+        }
+        (item, location)
+    }
+
+    fn strip_fields(&self, fields: &mut syn::Fields) {
+        for field in fields.iter_mut() {
+            self.strip_attrs(&mut field.attrs);
+        }
+    }
+
+    fn strip_attrs(&self, attrs: &mut Vec<syn::Attribute>) {
+        attrs.retain(|attr| self.keeps(attr.path()));
+    }
+
+    fn keeps(&self, path: &syn::Path) -> bool {
+        let Some(name) = path.segments.last().map(|s| s.ident.to_string()) else {
+            return true;
+        };
+        if self.builder.strip.contains(&name) {
+            return false;
+        }
+        let builtin = path.segments.len() == 1 && BUILTIN_ATTRIBUTES.contains(&name.as_str());
+        builtin
+            || self
+                .builder
+                .keep
+                .as_ref()
+                .is_none_or(|keep| keep.contains(&name))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,103 @@
+use quote::ToTokens;
+
+use super::*;
+
+fn strip(filter: &StripMacros, item: syn::Item) -> String {
+    let (item, _) = filter.call((item, SourceLocation::default()));
+    item.to_token_stream().to_string()
+}
+
+#[test]
+fn strip_list_removes_one_macro_and_keeps_another() {
+    let filter = StripMacros::builder().strip(&["my_internal_macro"]).build();
+    let out = strip(
+        &filter,
+        syn::parse_quote! {
+            #[my_internal_macro]
+            #[serde(rename_all = "camelCase")]
+            #[repr(C)]
+            pub struct Point {
+                #[my_internal_macro(skip)]
+                #[serde(default)]
+                pub x: i32,
+            }
+        },
+    );
+    assert_eq!(
+        out,
+        "# [serde (rename_all = \"camelCase\")] # [repr (C)] \
+         pub struct Point { # [serde (default)] pub x : i32 , }"
+    );
+}
+
+#[test]
+fn keep_list_removes_other_macros_but_not_builtins() {
+    let filter = StripMacros::builder().keep(&["serde"]).build();
+    let out = strip(
+        &filter,
+        syn::parse_quote! {
+            #[doc = " A mode."]
+            #[derive(Clone, serde::Serialize)]
+            #[serde(tag = "kind")]
+            #[internal::tracked = "yes"]
+            #[repr(u8)]
+            pub enum Mode {
+                #[internal::tracked]
+                #[serde(rename = "a")]
+                A,
+            }
+        },
+    );
+    assert_eq!(
+        out,
+        "# [doc = \" A mode.\"] # [derive (Clone , serde :: Serialize)] \
+         # [serde (tag = \"kind\")] # [repr (u8)] \
+         pub enum Mode { # [serde (rename = \"a\")] A , }"
+    );
+}
+
+#[test]
+fn strip_list_wins_over_keep_list_on_functions() {
+    let filter = StripMacros::builder()
+        .keep(&["tracing", "instrument"])
+        .strip(&["instrument"])
+        .build();
+    let out = strip(
+        &filter,
+        syn::parse_quote! {
+            #[tracing::instrument(skip(x))]
+            #[inline]
+            pub fn f(x: i32) -> i32 { x }
+        },
+    );
+    assert_eq!(out, "# [inline] pub fn f (x : i32) -> i32 { x }");
+}
+
+#[test]
+fn keep_list_keeps_default_variant_and_function_builtins() {
+    let filter = StripMacros::builder().keep(&[]).build();
+    let out = strip(
+        &filter,
+        syn::parse_quote! {
+            #[derive(Default)]
+            pub enum Mode {
+                #[default]
+                A,
+                B,
+            }
+        },
+    );
+    assert_eq!(
+        out,
+        "# [derive (Default)] pub enum Mode { # [default] A , B , }"
+    );
+    let out = strip(
+        &filter,
+        syn::parse_quote! {
+            #[cold]
+            #[track_caller]
+            pub fn f() {}
+        },
+    );
+    assert_eq!(out, "# [cold] # [track_caller] pub fn f () { }");
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use roxygen::roxygen;
+
+use crate::api::record::SourceLocation;
+
+/// Builder for configuring StructAlign instances
+///
+/// Structs are matched by name, as they appear in the item stream.
+pub struct Builder {
+    forced: HashMap<String, usize>,
+}
+
+impl Builder {
+    /// Create a new Builder for configuring StructAlign
+    pub fn new() -> Self {
+        Self {
+            forced: HashMap::new(),
+        }
+    }
+
+    /// Give the named struct an alignment of `align` bytes
+    ///
+    /// Can be called several times; a struct named again takes the latest
+    /// alignment.
+    ///
+    /// # Panics
+    ///
+    /// If `align` is not a power of two.
+    #[roxygen]
+    pub fn force_align<S: Into<String>>(
+        mut self,
+        /// The struct name (e.g., "Header")
+        type_name: S,
+        /// The alignment in bytes, a power of two
+        align: usize,
+    ) -> Self {
+        let type_name = type_name.into();
+        assert!(
+            align.is_power_of_two(),
+            "StructAlign: alignment {align} for `{type_name}` is not a power of two"
+        );
+        self.forced.insert(type_name, align);
+        self
+    }
+
+    /// Build the StructAlign instance with the configured options
+    pub fn build(self) -> StructAlign {
+        StructAlign { builder: self }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forces the alignment of chosen structs
+///
+/// Matches a C struct declared with an explicit alignment: each configured
+/// struct gets `align(N)` in its `#[repr]`, replacing an `align` already
+/// there and joining the other hints, so `#[repr(C)]` becomes
+/// `#[repr(C, align(16))]`. A struct without a `#[repr]` gets
+/// `#[repr(align(N))]`. Other items pass through unchanged.
+///
+/// # Example
+///
+/// ```
+/// # prebindgen::Source::init_doctest_simulate();
+/// let source = prebindgen::Source::new("source_ffi");
+/// let align = prebindgen::map::StructAlign::builder()
+///     .force_align("TestStruct", 16)
+///     .build();
+/// let items = source
+///     .items_all()
+///     .map(|item| align.call(item))
+///     .collect::<Vec<_>>();
+/// ```
+pub struct StructAlign {
+    builder: Builder,
+}
+
+impl StructAlign {
+    /// Create a builder for configuring a struct-align instance
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
+    /// Process a single item, forcing the configured alignment
+    pub fn call(
+        &self,
+        (mut item, location): (syn::Item, SourceLocation),
+    ) -> (syn::Item, SourceLocation) {
+        let syn::Item::Struct(s) = &mut item else {
+            return (item, location);
+        };
+        let Some(&align) = self.builder.forced.get(&s.ident.to_string()) else {
+            return (item, location);
+        };
+        let align = proc_macro2::Literal::usize_unsuffixed(align);
+        let align: syn::Meta = syn::parse_quote!(align(#align));
+        // Merge the `#[repr]`s into one, in place of the first, dropping
+        // every existing `align(..)`
+        let at = s
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("repr"))
+            .unwrap_or(s.attrs.len());
+        let mut hints: Vec<syn::Meta> = Vec::new();
+        s.attrs.retain(|attr| {
+            if !attr.path().is_ident("repr") {
+                return true;
+            }
+            let Ok(metas) = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+            ) else {
+                return true;
+            };
+            hints.extend(metas.into_iter().filter(|m| !m.path().is_ident("align")));
+            false
+        });
+        hints.push(align);
+        let at = at.min(s.attrs.len());
+        s.attrs.insert(at, syn::parse_quote!(#[repr(#(#hints),*)]));
+        (item, location)
+    }
+}
+
+#[cfg(test)]
+mod tests;
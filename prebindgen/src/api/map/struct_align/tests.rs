@@ -0,0 +1,52 @@
+use quote::ToTokens;
+
+use super::*;
+
+fn align(filter: &StructAlign, item: syn::Item) -> String {
+    let (item, _) = filter.call((item, SourceLocation::default()));
+    item.to_token_stream().to_string()
+}
+
+#[test]
+fn force_align_joins_existing_repr() {
+    let filter = StructAlign::builder().force_align("Header", 16).build();
+    let out = align(
+        &filter,
+        syn::parse_quote! {
+            #[derive(Clone, Copy)]
+            #[repr(C, align(4))]
+            #[doc = "header"]
+            pub struct Header { pub len: u32 }
+        },
+    );
+    assert_eq!(
+        out,
+        "# [derive (Clone , Copy)] # [repr (C , align (16))] # [doc = \"header\"] \
+         pub struct Header { pub len : u32 }"
+    );
+}
+
+#[test]
+fn force_align_adds_repr_to_named_struct_only() {
+    let filter = StructAlign::builder().force_align("Header", 16).build();
+    assert_eq!(
+        align(
+            &filter,
+            syn::parse_quote! { pub struct Header { pub len: u32 } }
+        ),
+        "# [repr (align (16))] pub struct Header { pub len : u32 }"
+    );
+    assert_eq!(
+        align(
+            &filter,
+            syn::parse_quote! { #[repr(C)] pub struct Body { pub len: u32 } }
+        ),
+        "# [repr (C)] pub struct Body { pub len : u32 }"
+    );
+}
+
+#[test]
+#[should_panic(expected = "alignment 12 for `Header` is not a power of two")]
+fn force_align_rejects_non_power_of_two() {
+    let _ = StructAlign::builder().force_align("Header", 12);
+}
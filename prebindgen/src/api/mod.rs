@@ -4,8 +4,10 @@ pub(crate) mod collect;
 pub(crate) mod core;
 pub(crate) mod gen;
 pub(crate) mod lang;
+pub(crate) mod map;
 pub(crate) mod record;
 pub(crate) mod source;
 #[cfg(test)]
 pub(crate) mod test_util;
 pub(crate) mod utils;
+pub(crate) mod validate;
@@ -1,13 +1,20 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
-/// Represents a record of a struct, enum, union, or function definition.
+/// A raw `#[prebindgen]` record: one captured item as stored in the JSONL
+/// data files, before it is parsed into a `syn::Item`.
 ///
-/// **Internal API**: This type is public only for interaction with the proc-macro crate.
-/// It should not be used directly by end users.
-#[doc(hidden)]
+/// Most build scripts consume parsed items through
+/// [`Source::items_all`](crate::Source::items_all); the raw records returned
+/// by [`Source::raw_records`](crate::Source::raw_records) (on a source built
+/// with `keep_raw_records(true)`) serve tooling that
+/// needs the capture metadata itself (kind, group, cfg, location) without
+/// re-reading the JSONL files. The fields below are part of the stable API.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct Record {
-    /// The kind of definition (struct, enum, union, or function)
+    /// The kind of item captured: see [`RecordKind`]
     pub kind: RecordKind,
     /// The name of the type or function
     pub name: String,
@@ -18,10 +25,15 @@ pub struct Record {
     /// Optional cfg attribute value to be applied to the generated code
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cfg: Option<String>,
+    /// Optional exported name of a function from
+    /// `#[prebindgen(rename = "...")]`. The parsed item carries this name,
+    /// with the Rust name kept as its
+    /// [`SourceLocation::source_name`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rename: Option<String>,
 }
 
 /// Source location information for tracking where code originated
-#[doc(hidden)]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct SourceLocation {
     /// The source file path
@@ -38,6 +50,26 @@ pub struct SourceLocation {
     /// `Registry::from_items` call without losing per-item origins.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub crate_name: Option<String>,
+    /// Group the item was captured into (one of the `#[prebindgen]` string
+    /// arguments). Like `crate_name`, it is stamped by `Source` from the
+    /// data file name rather than captured, and lets the final
+    /// `Destination` split its output per group.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Module path of the item inside its crate (`foo::bar`, without the
+    /// crate name), or `None` for the crate root. Captured by the proc-macro
+    /// from the source file path (`src/foo/bar.rs`, `src/foo/bar/mod.rs`);
+    /// items inside inline `mod foo { .. }` blocks report the module of the
+    /// enclosing file. [`Source`](crate::Source) drops it unless built with
+    /// `qualify_by_module(true)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    /// Name of the item in its crate when the stream carries it under
+    /// another one (renamed by [`map::RenameType`](crate::map::RenameType) or
+    /// `#[prebindgen(rename = "...")]`), or `None` when the names agree. Adapters keep referencing the source
+    /// item by this name while everything they generate uses the new one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_name: Option<String>,
 }
 
 impl std::fmt::Display for SourceLocation {
@@ -47,16 +79,24 @@ impl std::fmt::Display for SourceLocation {
 }
 
 impl SourceLocation {
+    /// Location of `span` in the crate being compiled.
+    ///
+    /// **Internal API**: This method is public only for interaction with the proc-macro crate.
+    #[doc(hidden)]
     pub fn from_span(span: &proc_macro2::Span) -> Self {
         if_rust_version::if_rust_version! { >= 1.88 {
             // Convert proc_macro2::Span to proc_macro::Span to access file() method
             #[allow(clippy::incompatible_msrv)]
             {
+                let file = span.unwrap().file();
                 Self {
-                    file: span.unwrap().file(),
+                    module_path: module_path_of_file(&file),
+                    file,
                     line: span.unwrap().line(),
                     column: span.unwrap().column(),
                     crate_name: None,
+                    group: None,
+                    source_name: None,
                 }
             }
         } else {
@@ -67,18 +107,37 @@ impl SourceLocation {
                 line: 0,
                 column: 0,
                 crate_name: None,
+                group: None,
+                module_path: None,
+                source_name: None,
             }
         }}
     }
 }
 
-/// The kind of record (struct, enum, union, or function).
-///
-/// **Internal API**: This type is public only for interaction with the proc-macro crate.
-/// It should not be used directly by end users.
-#[doc(hidden)]
+/// Module path of a source file relative to its crate's `src/` directory:
+/// `src/foo/bar.rs` and `src/foo/bar/mod.rs` give `foo::bar`, the crate
+/// roots `src/lib.rs` and `src/main.rs` give `None`. Paths outside a `src/`
+/// directory (build-script output, `<unknown>`) give `None` as well.
+pub(crate) fn module_path_of_file(file: &str) -> Option<String> {
+    let components: Vec<&str> = file.split(['/', '\\']).collect();
+    let src = components.iter().rposition(|c| *c == "src")?;
+    let mut segments: Vec<&str> = components[src + 1..].to_vec();
+    let last = segments.pop()?.strip_suffix(".rs")?;
+    if !(segments.is_empty() && (last == "lib" || last == "main")) && last != "mod" {
+        segments.push(last);
+    }
+    if segments.is_empty() {
+        None
+    } else {
+        Some(segments.join("::"))
+    }
+}
+
+/// The kind of item a [`Record`] holds.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum RecordKind {
     /// A struct definition with named or unnamed fields
     Struct,
@@ -92,12 +151,16 @@ pub enum RecordKind {
     TypeAlias,
     /// A constant definition
     Const,
+    /// A static item definition
+    Static,
+    /// An inline module whose items are recorded with it
+    Mod,
 }
 impl RecordKind {
     /// Returns true if this record kind represents a type definition.
     ///
     /// Type definitions include structs, enums, unions, and type aliases.
-    /// Functions, constants, and unknown types are not considered type definitions.
+    /// Functions, constants, statics, and unknown types are not considered type definitions.
     pub fn is_type(&self) -> bool {
         matches!(
             self,
@@ -106,17 +169,28 @@ impl RecordKind {
     }
 }
 
-impl From<&(syn::Item, SourceLocation)> for RecordKind {
-    fn from((item, source_location): &(syn::Item, SourceLocation)) -> Self {
-        match item {
+impl RecordKind {
+    /// The kind of `item`, or `None` for items `#[prebindgen]` doesn't record
+    fn of_item(item: &syn::Item) -> Option<Self> {
+        Some(match item {
             syn::Item::Struct(_) => RecordKind::Struct,
             syn::Item::Enum(_) => RecordKind::Enum,
             syn::Item::Union(_) => RecordKind::Union,
             syn::Item::Fn(_) => RecordKind::Function,
             syn::Item::Type(_) => RecordKind::TypeAlias,
             syn::Item::Const(_) => RecordKind::Const,
-            _ => panic!("Unknown syn::Item variant for RecordKind at {source_location}"),
-        }
+            syn::Item::Static(_) => RecordKind::Static,
+            syn::Item::Mod(_) => RecordKind::Mod,
+            _ => return None,
+        })
+    }
+}
+
+impl From<&(syn::Item, SourceLocation)> for RecordKind {
+    fn from((item, source_location): &(syn::Item, SourceLocation)) -> Self {
+        RecordKind::of_item(item).unwrap_or_else(|| {
+            panic!("Unknown syn::Item variant for RecordKind at {source_location}")
+        })
     }
 }
 
@@ -129,6 +203,8 @@ impl std::fmt::Display for RecordKind {
             RecordKind::Function => write!(f, "function"),
             RecordKind::TypeAlias => write!(f, "type"),
             RecordKind::Const => write!(f, "const"),
+            RecordKind::Static => write!(f, "static"),
+            RecordKind::Mod => write!(f, "mod"),
         }
     }
 }
@@ -151,9 +227,19 @@ impl Record {
             content,
             source_location,
             cfg,
+            rename: None,
         }
     }
 
+    /// Set the exported name recorded by `#[prebindgen(rename = "...")]`.
+    ///
+    /// **Internal API**: This method is public only for interaction with the proc-macro crate.
+    #[doc(hidden)]
+    pub fn with_rename(mut self, rename: Option<String>) -> Self {
+        self.rename = rename;
+        self
+    }
+
     /// Serialize this record to a JSON-lines compatible string.
     ///
     /// **Internal API**: This method is public only for interaction with the proc-macro crate.
@@ -162,31 +248,29 @@ impl Record {
         serde_json::to_string(self)
     }
 
-    pub(crate) fn parse(self: &Record) -> (syn::Item, SourceLocation) {
+    /// Parse the record into its item, reporting unparseable content (or
+    /// content not matching the recorded kind) as a [`ParseError`]
+    pub(crate) fn try_parse(&self) -> Result<(syn::Item, SourceLocation), ParseError> {
+        let error = |message: String| ParseError {
+            name: self.name.clone(),
+            content: self.content.clone(),
+            location: Box::new(self.source_location.clone()),
+            data_file: None,
+            message,
+        };
+
         // Parse the raw content into a syntax tree
-        let parsed = syn::parse_file(&self.content)
-            .map_err(|e| {
-                panic!(
-                    "Failed to parse record content at {}: {}",
-                    self.source_location, e
-                )
-            })
-            .unwrap();
+        let parsed = syn::parse_file(&self.content).map_err(|e| error(e.to_string()))?;
 
         // Check that we have exactly one item
         let mut items = parsed.items.into_iter();
-        let mut item = items.next().unwrap_or_else(|| {
-            panic!(
-                "Expected exactly one item in record, found 0 at {}",
-                self.source_location
-            )
-        });
-
+        let mut item = items
+            .next()
+            .ok_or_else(|| error("expected exactly one item, found 0".to_string()))?;
         if items.next().is_some() {
-            panic!(
-                "Expected exactly one item in record, found more than 1 at {}",
-                self.source_location
-            );
+            return Err(error(
+                "expected exactly one item, found more than 1".to_string(),
+            ));
         }
 
         // Add cfg attribute if specified
@@ -194,7 +278,7 @@ impl Record {
             // Parse the cfg condition as tokens
             let cfg_tokens: proc_macro2::TokenStream = cfg_value
                 .parse()
-                .unwrap_or_else(|_| panic!("Invalid cfg condition: {}", cfg_value));
+                .map_err(|_| error(format!("invalid cfg condition: {cfg_value}")))?;
             let cfg_attr: syn::Attribute = syn::parse_quote! { #[cfg(#cfg_tokens)] };
             match &mut item {
                 syn::Item::Struct(s) => s.attrs.insert(0, cfg_attr),
@@ -203,21 +287,87 @@ impl Record {
                 syn::Item::Fn(f) => f.attrs.insert(0, cfg_attr),
                 syn::Item::Type(t) => t.attrs.insert(0, cfg_attr),
                 syn::Item::Const(c) => c.attrs.insert(0, cfg_attr),
+                syn::Item::Static(s) => s.attrs.insert(0, cfg_attr),
+                syn::Item::Mod(m) => m.attrs.insert(0, cfg_attr),
                 _ => {}
             }
         }
 
-        // Create RecordSyn first
-        let record_syn = (item, self.source_location.clone());
-
         // Check that the item type matches the record kind
-        let actual_kind: RecordKind = (&record_syn).into();
+        let actual_kind =
+            RecordKind::of_item(&item).ok_or_else(|| error("unsupported item kind".to_string()))?;
         if actual_kind != self.kind {
-            panic!(
-                "Record kind mismatch at {}: expected {}, found {}",
-                self.source_location, self.kind, actual_kind
-            );
+            return Err(error(format!(
+                "record kind mismatch: expected {}, found {}",
+                self.kind, actual_kind
+            )));
         }
-        record_syn
+
+        // Name a renamed function after its exported name, remembering the
+        // Rust one so generated calls still reach it
+        let mut source_location = self.source_location.clone();
+        if let Some(rename) = &self.rename {
+            let syn::Item::Fn(f) = &mut item else {
+                return Err(error(format!(
+                    "'rename' is only supported on functions, found {}",
+                    self.kind
+                )));
+            };
+            let exported =
+                syn::parse_str::<syn::Ident>(rename).map_err(|e| error(e.to_string()))?;
+            let source_name = std::mem::replace(&mut f.sig.ident, exported);
+            source_location.source_name = Some(source_name.to_string());
+        }
+        Ok((item, source_location))
     }
 }
+
+/// A [`Record`] whose stored content could not be turned into its item, or
+/// a data file line that isn't a record at all
+///
+/// Reported by [`Source::try_items_all`](crate::Source::try_items_all) for
+/// data files that are corrupted or were written by an incompatible
+/// `#[prebindgen]` macro.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Name of the recorded item; empty for a line that isn't a record
+    pub name: String,
+    /// The raw content that failed to parse: the record's item, or the
+    /// whole line that isn't a record
+    pub content: String,
+    /// Where the item was recorded from, with the origin crate and group;
+    /// only the crate and group are known for a line that isn't a record
+    pub location: Box<SourceLocation>,
+    /// The JSONL data file the record was read from, if it came from one
+    pub data_file: Option<PathBuf>,
+    /// What went wrong
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.data_file, self.name.is_empty()) {
+            (Some(data_file), true) => write!(
+                f,
+                "Failed to read a record from {}: {}",
+                data_file.display(),
+                self.message
+            ),
+            (Some(data_file), false) => write!(
+                f,
+                "Failed to parse record `{}` at {} (read from {}): {}",
+                self.name,
+                self.location,
+                data_file.display(),
+                self.message
+            ),
+            (None, _) => write!(
+                f,
+                "Failed to parse record `{}` at {}: {}",
+                self.name, self.location, self.message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -1,6 +1,6 @@
 use std::{
     cell::RefCell,
-    collections::{HashMap, HashSet},
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
     fs,
     path::{Path, PathBuf},
 };
@@ -9,12 +9,22 @@ use itertools::Itertools;
 use roxygen::roxygen;
 
 use crate::{
-    api::{batching::cfg_filter, record::Record, utils::jsonl::read_jsonl_file},
-    SourceLocation, TargetTriple, CRATE_NAME_FILE, FEATURES_FILE,
+    api::{
+        batching::cfg_filter,
+        collect::destination::item_attrs_mut,
+        record::{ParseError, Record, RecordKind},
+        utils::jsonl::{read_jsonl_lines, JsonlLine},
+    },
+    codegen::cfg_expr::cfgs_exclude,
+    SourceLocation, TargetTriple, CRATE_NAME_FILE, CRATE_VERSION_FILE, FEATURES_FILE,
 };
 
 /// File extension for data files
 const JSONL_EXTENSION: &str = ".jsonl";
+
+/// The records of a group, each with the data file it was read from, and the
+/// data file lines that aren't records
+type GroupRecords = (Vec<(Record, PathBuf)>, Vec<ParseError>);
 thread_local! {
     static DOCTEST_SOURCE: RefCell<Option<Source>> = const { RefCell::new(None) };
 }
@@ -36,6 +46,9 @@ thread_local! {
 /// Items are organized into groups based on the first parameter of the `#[prebindgen]`
 /// macro. For example, `#[prebindgen("structs")]` creates items in the "structs" group.
 /// Items without an explicit group are placed in the "default" group.
+/// An item may be recorded into several groups (`#[prebindgen("structs", "all")]`);
+/// it is then yielded by each of them, but only once by an iterator spanning
+/// several groups, stamped with the first of them in group-name order.
 ///
 /// # Example
 ///
@@ -67,15 +80,39 @@ thread_local! {
 #[derive(Clone)]
 pub struct Source {
     crate_name: String,
-    items: HashMap<String, Vec<(syn::Item, SourceLocation)>>,
+    crate_version: Option<String>,
+    // Keyed by group name; each group is sorted by source location so the
+    // item order (and thus the generated file) is stable across builds
+    items: BTreeMap<String, Vec<(syn::Item, SourceLocation)>>,
+    // The records the items were parsed from, keyed and ordered the same
+    // way; only kept when asked for with `Builder::keep_raw_records`
+    records: Option<BTreeMap<String, Vec<Record>>>,
+    // Records whose content failed to parse, left out of `items`, keyed by
+    // group; each comes with the number of the group's items before it
+    parse_errors: BTreeMap<String, Vec<(usize, ParseError)>>,
     // Configuration needed to build a CfgFilter at iteration time
     features_constant: Option<String>,
     target_triple: Option<String>,
     features_list: Vec<String>, // normalized list from features.txt
+    keep_enabled_cfg: bool,
+    // Resolve features.txt without the features constant assertion
+    features_from_source: bool,
+    // Source feature aliases renamed to one binding crate feature each
+    feature_mappings: Vec<(Vec<String>, String)>,
+    // Bare cfg predicates treated as always false
+    forbidden_cfgs: Vec<String>,
+    // Data and sidecar files read from the input directory
+    consumed_files: Vec<PathBuf>,
 }
 
 impl Source {
     /// Create a new `Source` instance from specified directory
+    ///
+    /// # Panics
+    ///
+    /// Panics with a description of the misconfigured directory if it can't be
+    /// read or holds no records; see [`Source::try_new`] for the
+    /// non-panicking variant.
     #[roxygen]
     pub fn new<P: AsRef<Path>>(
         /// Path to the directory containing prebindgen data files
@@ -85,6 +122,28 @@ impl Source {
         Self::builder(input_dir).build()
     }
 
+    /// Create a new `Source` instance from specified directory, reporting a
+    /// missing, uninitialized or empty directory as a [`SourceError`] instead
+    /// of panicking
+    ///
+    /// This lets a build script emit a `cargo::warning` and fall back
+    /// gracefully when a dependency crate forgot to call
+    /// [`init_prebindgen_out_dir`](crate::init_prebindgen_out_dir).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let err = prebindgen::Source::try_new("/nonexistent/prebindgen").err().unwrap();
+    /// assert!(matches!(err, prebindgen::SourceError::DirNotFound(_)));
+    /// ```
+    #[roxygen]
+    pub fn try_new<P: AsRef<Path>>(
+        /// Path to the directory containing prebindgen data files
+        input_dir: P,
+    ) -> Result<Self, SourceError> {
+        Self::builder(input_dir).try_build()
+    }
+
     /// Create a builder to configure how `Source` is constructed
     pub fn builder<P: AsRef<Path>>(input_dir: P) -> Builder {
         Builder::new(input_dir)
@@ -92,105 +151,198 @@ impl Source {
 }
 
 impl Source {
-    fn build_internal(
-        input_dir: &Path,
-        features_constant: Option<String>,
-        target_triple: Option<String>,
-        crate_name_override: Option<String>,
-    ) -> Self {
+    fn build_internal(builder: Builder) -> Result<Self, SourceError> {
+        let Builder {
+            input_dir,
+            features_constant,
+            target_triple,
+            crate_name: crate_name_override,
+            keep_enabled_cfg,
+            features_from_source,
+            feature_mappings,
+            forbidden_cfgs,
+            parallel,
+            rerun_if_changed: _,
+            group_prefix,
+            max_items,
+            qualify_by_module,
+            keep_raw_records,
+        } = builder;
+        let input_dir = input_dir.as_path();
         if let Some(source) = DOCTEST_SOURCE.with(|source| (*source.borrow()).clone()) {
-            return source;
+            return Ok(source);
         }
         if !input_dir.is_dir() {
-            panic!(
-                "Input directory {} does not exist or is not a directory",
-                input_dir.display()
-            );
+            return Err(SourceError::DirNotFound(input_dir.to_path_buf()));
         }
         // The stored name (CARGO_PKG_NAME at capture time) doubles as the
         // "directory was initialized" check, so it is read even when
         // overridden. The override wins: it is the name THIS crate
         // references the source crate by (a Cargo.toml dependency rename).
-        let stored_crate_name = read_stored_crate_name(input_dir).unwrap_or_else(|| {
-            panic!(
-                "The directory {} was not initialized with init_prebindgen_out_dir(). \
-                Please ensure that init_prebindgen_out_dir() is called in the build.rs of the source crate.",
-                input_dir.display()
-            )
-        });
+        let stored_crate_name = read_stored_crate_name(input_dir)
+            .ok_or_else(|| SourceError::CrateNameMissing(input_dir.to_path_buf()))?;
         let crate_name = crate_name_override.unwrap_or(stored_crate_name);
 
         let groups = Self::discover_groups(input_dir);
-        let mut items = HashMap::new();
-        for group in groups {
-            let records = Self::read_group(input_dir, &group);
-            let group_items = records
-                .iter()
-                .map(|r| {
-                    // Stamp the origin crate into every item's location: the
-                    // captured JSONL doesn't carry it (the proc-macro runs
-                    // inside the crate), but from here on the item streams
-                    // are self-describing — streams from several sources can
-                    // be chained into one `Registry::from_items` call
-                    // without losing per-item origins.
-                    let (item, mut loc) = r.parse();
-                    loc.crate_name = Some(crate_name.clone());
-                    (item, loc)
-                })
-                .collect::<Vec<_>>();
+        let mut consumed_files: Vec<PathBuf> = [CRATE_NAME_FILE, CRATE_VERSION_FILE, FEATURES_FILE]
+            .iter()
+            .map(|name| input_dir.join(name))
+            .filter(|path| path.is_file())
+            .collect();
+        let mut items = BTreeMap::new();
+        let mut group_records = BTreeMap::new();
+        let mut parse_errors = BTreeMap::new();
+        let mut count = 0;
+        for raw_group in groups {
+            let paths = Self::group_files(input_dir, &raw_group);
+            let (mut records, mut line_errors) = Self::read_group(&paths, parallel)?;
+            consumed_files.extend(paths);
+            // Checked before parsing: a misdirected source can hold far more
+            // records than are worth turning into `syn` items
+            count += records.len();
+            if let Some(limit) = max_items.filter(|&limit| count > limit) {
+                return Err(SourceError::TooManyItems {
+                    dir: input_dir.to_path_buf(),
+                    count,
+                    limit,
+                });
+            }
+            let group = match &group_prefix {
+                Some(prefix) => format!("{prefix}_{raw_group}"),
+                None => raw_group,
+            };
+            // Stamp the origin crate into every record's location: the
+            // captured JSONL doesn't carry it (the proc-macro runs inside the
+            // crate), but from here on the record and item streams are
+            // self-describing — streams from several sources can be chained
+            // into one `Registry::from_items` call without losing per-item
+            // origins.
+            // The module path is guessed from the file path, so it is only
+            // kept when asked for.
+            for (record, _) in &mut records {
+                record.source_location.crate_name = Some(crate_name.clone());
+                record.source_location.group = Some(group.clone());
+                if !qualify_by_module {
+                    record.source_location.module_path = None;
+                }
+            }
+            for error in &mut line_errors {
+                error.location.crate_name = Some(crate_name.clone());
+                error.location.group = Some(group.clone());
+            }
+            // Lines that aren't records have no location to order them by:
+            // they lead their group
+            let mut group_errors: Vec<_> = line_errors.into_iter().map(|e| (0, e)).collect();
+            let mut group_items = Vec::with_capacity(records.len());
+            for (record, data_file) in &records {
+                match record.try_parse() {
+                    Ok(entry) => group_items.push(entry),
+                    Err(mut error) => {
+                        error.data_file = Some(data_file.clone());
+                        group_errors.push((group_items.len(), error));
+                    }
+                }
+            }
+            if !group_errors.is_empty() {
+                parse_errors.insert(group.clone(), group_errors);
+            }
+            if keep_raw_records {
+                let records = records.into_iter().map(|(record, _)| record).collect();
+                group_records.insert(group.clone(), records);
+            }
             items.insert(group, group_items);
         }
 
+        check_conflicting_definitions(&items)?;
+
         // Read features list once and store normalized list
         let features_list = read_features_from_out_dir(input_dir);
 
-        Self {
+        Ok(Self {
             crate_name,
+            crate_version: read_stored_crate_version(input_dir),
             items,
+            records: keep_raw_records.then_some(group_records),
+            parse_errors,
             features_constant,
             features_list,
             target_triple,
-        }
+            keep_enabled_cfg,
+            features_from_source,
+            feature_mappings,
+            forbidden_cfgs,
+            consumed_files,
+        })
     }
 
     #[doc(hidden)]
     /// Initialize the Source for doctests by creating a dummy Source with a few test items.
     pub fn init_doctest_simulate() {
+        let items: BTreeMap<String, Vec<(syn::Item, SourceLocation)>> = BTreeMap::from([
+            (
+                "structs".to_string(),
+                vec![(
+                    syn::parse_quote! {
+                        #[prebindgen("structs")]
+                        pub struct TestStruct {
+                            pub field: i32,
+                        }
+                    },
+                    SourceLocation {
+                        crate_name: Some("source_ffi".to_string()),
+                        group: Some("structs".to_string()),
+                        ..SourceLocation::default()
+                    },
+                )],
+            ),
+            (
+                "functions".to_string(),
+                vec![(
+                    syn::parse_quote! {
+                        #[prebindgen("functions")]
+                        pub fn test_function() -> i32 { 42 }
+                    },
+                    SourceLocation {
+                        crate_name: Some("source_ffi".to_string()),
+                        group: Some("functions".to_string()),
+                        ..SourceLocation::default()
+                    },
+                )],
+            ),
+        ]);
+        let records = items
+            .iter()
+            .map(|(group, entries)| {
+                let records = entries
+                    .iter()
+                    .map(|entry| {
+                        let (item, location) = entry;
+                        Record::new(
+                            RecordKind::from(entry),
+                            item_name(item),
+                            quote::quote!(#item).to_string(),
+                            location.clone(),
+                            None,
+                        )
+                    })
+                    .collect();
+                (group.clone(), records)
+            })
+            .collect();
         let source = Self {
             crate_name: "source_ffi".to_string(),
-            items: HashMap::from([
-                (
-                    "structs".to_string(),
-                    vec![(
-                        syn::parse_quote! {
-                            #[prebindgen("structs")]
-                            pub struct TestStruct {
-                                pub field: i32,
-                            }
-                        },
-                        SourceLocation {
-                            crate_name: Some("source_ffi".to_string()),
-                            ..SourceLocation::default()
-                        },
-                    )],
-                ),
-                (
-                    "functions".to_string(),
-                    vec![(
-                        syn::parse_quote! {
-                            #[prebindgen("functions")]
-                            pub fn test_function() -> i32 { 42 }
-                        },
-                        SourceLocation {
-                            crate_name: Some("source_ffi".to_string()),
-                            ..SourceLocation::default()
-                        },
-                    )],
-                ),
-            ]),
+            crate_version: Some("0.1.0".to_string()),
+            items,
+            records: Some(records),
+            parse_errors: BTreeMap::new(),
             features_constant: None,
             target_triple: None,
             features_list: Vec::new(),
+            keep_enabled_cfg: false,
+            features_from_source: false,
+            feature_mappings: Vec::new(),
+            forbidden_cfgs: Vec::new(),
+            consumed_files: Vec::new(),
         };
         DOCTEST_SOURCE.with(|cell| {
             *cell.borrow_mut() = Some(source);
@@ -210,6 +362,82 @@ impl Source {
         &self.crate_name
     }
 
+    /// Returns the version of the source crate that generated the prebindgen data
+    ///
+    /// `None` when the data was produced by an older prebindgen that did not
+    /// record it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # prebindgen::Source::init_doctest_simulate();
+    /// let source = prebindgen::Source::new("source_ffi");
+    /// let header_comment = format!(
+    ///     "/* {} {} */",
+    ///     source.crate_name(),
+    ///     source.crate_version().unwrap_or_default()
+    /// );
+    /// ```
+    pub fn crate_version(&self) -> Option<String> {
+        self.crate_version.clone()
+    }
+
+    /// Returns the files read from the prebindgen output directory
+    ///
+    /// The JSONL data files of every group, plus the crate name, crate
+    /// version and features files written by
+    /// [`init_prebindgen_out_dir`](crate::init_prebindgen_out_dir), all of
+    /// them inside the directory [`Builder::rerun_if_changed`] reports to
+    /// Cargo.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # prebindgen::Source::init_doctest_simulate();
+    /// let source = prebindgen::Source::new("source_ffi");
+    /// for path in source.consumed_files() {
+    ///     println!("reading {}", path.display());
+    /// }
+    /// ```
+    pub fn consumed_files(&self) -> &[PathBuf] {
+        &self.consumed_files
+    }
+
+    /// Returns the names of all groups found in the prebindgen output directory
+    ///
+    /// Group names are taken from the `{group}_{pid}_{tid}.jsonl` data files;
+    /// the returned list is deduplicated and sorted. Useful to generate one
+    /// bindings file per group without hardcoding the group names.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # prebindgen::Source::init_doctest_simulate();
+    /// let source = prebindgen::Source::new("source_ffi");
+    /// assert_eq!(source.groups(), vec!["functions", "structs"]);
+    /// ```
+    pub fn groups(&self) -> Vec<String> {
+        self.items.keys().cloned().collect()
+    }
+
+    /// Returns the features enabled on the source crate when it was built
+    ///
+    /// Read from the `features.txt` file written by `init_prebindgen_out_dir`;
+    /// sorted and deduplicated, the same list the `features!()` macro reports
+    /// on the source side. Empty when the file is absent (data produced by an
+    /// older prebindgen).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # prebindgen::Source::init_doctest_simulate();
+    /// let source = prebindgen::Source::new("source_ffi");
+    /// assert!(source.features().is_empty());
+    /// ```
+    pub fn features(&self) -> Vec<String> {
+        self.features_list.clone()
+    }
+
     /// Returns an iterator over items from specific groups
     ///
     /// # Parameters
@@ -229,12 +457,14 @@ impl Source {
         &'a self,
         groups: &'a [&'a str],
     ) -> impl Iterator<Item = (syn::Item, SourceLocation)> + 'a {
+        self.expect_parsed(|group| groups.contains(&group));
         // Build a cfg filter and apply it lazily with itertools::batching
         let mut filter = self.build_cfg_filter();
         groups
             .iter()
             .filter_map(|group| self.items.get(*group))
             .flat_map(|records| records.iter().cloned())
+            .unique_by(cross_group_key)
             .batching(move |iter| filter.call(iter))
     }
 
@@ -256,18 +486,22 @@ impl Source {
         &'a self,
         groups: &'a [&'a str],
     ) -> impl Iterator<Item = (syn::Item, SourceLocation)> + 'a {
+        self.expect_parsed(|group| !groups.contains(&group));
         // Build a cfg filter and apply it lazily with itertools::batching
         let mut filter = self.build_cfg_filter();
         self.items
             .iter()
             .filter(|(group, _)| !groups.contains(&group.as_str()))
             .flat_map(|(_, records)| records.iter().cloned())
+            .unique_by(cross_group_key)
             .batching(move |iter| filter.call(iter))
     }
 
     /// Returns an iterator over all items from all groups
     ///
     /// This is the most commonly used method for processing all prebindgen items.
+    /// Items are yielded group by group in group-name order, and within a group
+    /// in source location order, so the output is identical between builds.
     ///
     /// # Example
     ///
@@ -277,17 +511,195 @@ impl Source {
     /// let items: Vec<_> = source.items_all().collect();
     /// assert_eq!(items.len(), 2); // should contain TestStruct and test_function
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a record failed to parse; see [`Source::try_items_all`] for
+    /// the non-panicking variant.
     pub fn items_all(&self) -> impl Iterator<Item = (syn::Item, SourceLocation)> + '_ {
+        self.expect_parsed(|_| true);
+        self.parsed_items_all()
+    }
+
+    /// Returns an iterator over all items from all groups, reporting each
+    /// record that failed to parse as a [`ParseError`]
+    ///
+    /// Same as [`items_all`](Self::items_all), with an `Err` in place of
+    /// every record whose stored content isn't a valid item and every data
+    /// file line that isn't a record (a corrupted data file, or one written
+    /// by an incompatible `#[prebindgen]` macro), instead of panicking on the
+    /// first one. Errors keep the record order; lines that aren't records
+    /// come first in their group.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # prebindgen::Source::init_doctest_simulate();
+    /// let source = prebindgen::Source::new("source_ffi");
+    /// for entry in source.try_items_all() {
+    ///     match entry {
+    ///         Ok((item, location)) => { /* process the item */ }
+    ///         Err(e) => println!("cargo::warning=skipping {}: {}", e.name, e.message),
+    ///     }
+    /// }
+    /// ```
+    pub fn try_items_all(
+        &self,
+    ) -> impl Iterator<Item = Result<(syn::Item, SourceLocation), ParseError>> + '_ {
+        // The cfg filter is fed one item at a time so the errors can be
+        // placed between them; its prelude is taken up front
+        let mut filter = self.build_cfg_filter();
+        let prelude = filter.call(&mut std::iter::empty());
+        let mut seen = HashSet::new();
+        let entries = self.items.iter().flat_map(move |(group, items)| {
+            let mut errors = self
+                .parse_errors
+                .get(group)
+                .into_iter()
+                .flatten()
+                .peekable();
+            let mut entries = Vec::with_capacity(items.len());
+            for (index, entry) in items.iter().enumerate() {
+                while let Some((_, error)) = errors.next_if(|(at, _)| *at <= index) {
+                    entries.push(Err(error.clone()));
+                }
+                if seen.insert(cross_group_key(entry)) {
+                    let mut item = std::iter::once(entry.clone());
+                    while let Some(kept) = filter.call(&mut item) {
+                        entries.push(Ok(kept));
+                    }
+                }
+            }
+            entries.extend(errors.map(|(_, error)| Err(error.clone())));
+            entries
+        });
+        prelude.map(Ok).into_iter().chain(entries)
+    }
+
+    /// Internal: the items of all groups, leaving out unparseable records
+    fn parsed_items_all(&self) -> impl Iterator<Item = (syn::Item, SourceLocation)> + '_ {
+        // Build a cfg filter and apply it lazily with itertools::batching
+        let mut filter = self.build_cfg_filter();
+        self.items
+            .values()
+            .flat_map(|records| records.iter().cloned())
+            .unique_by(cross_group_key)
+            .batching(move |iter| filter.call(iter))
+    }
+
+    /// Internal: panic with the first parse failure among the records of the
+    /// groups selected by `in_group`
+    fn expect_parsed(&self, in_group: impl Fn(&str) -> bool) {
+        let failed = self
+            .parse_errors
+            .iter()
+            .filter(|(group, _)| in_group(group))
+            .find_map(|(_, errors)| errors.first().map(|(_, e)| e));
+        if let Some(e) = failed {
+            panic!("{e}");
+        }
+    }
+
+    /// Returns an iterator over the items of all groups whose name and kind
+    /// satisfy `pred`
+    ///
+    /// Same as [`items_all`](Self::items_all), minus the items `pred` rejects;
+    /// the features assertion the cfg filter prepends is kept regardless.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # prebindgen::Source::init_doctest_simulate();
+    /// use prebindgen::RecordKind;
+    /// let source = prebindgen::Source::new("source_ffi");
+    /// let items: Vec<_> = source
+    ///     .items_matching(|name, kind| kind == RecordKind::Function && name.starts_with("test_"))
+    ///     .collect();
+    /// assert_eq!(items.len(), 1); // only test_function
+    /// ```
+    pub fn items_matching<'a, F>(
+        &'a self,
+        pred: F,
+    ) -> impl Iterator<Item = (syn::Item, SourceLocation)> + 'a
+    where
+        F: Fn(&str, RecordKind) -> bool + 'a,
+    {
+        self.expect_parsed(|_| true);
         // Build a cfg filter and apply it lazily with itertools::batching
         let mut filter = self.build_cfg_filter();
         self.items
             .values()
             .flat_map(|records| records.iter().cloned())
+            .unique_by(cross_group_key)
+            .filter(move |entry| pred(&item_name(&entry.0), RecordKind::from(entry)))
             .batching(move |iter| filter.call(iter))
     }
 
+    /// Returns the features that the cfgs of the items mention but that are
+    /// neither enabled nor mapped, each with the location of the code it guards
+    ///
+    /// Feature filtering treats such features as disabled and silently drops
+    /// the code they guard, which hides a misspelled feature name or a
+    /// feature missing from the source crate's list. A build script can check
+    /// this list and fail or warn. Empty when feature filtering is off.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # prebindgen::Source::init_doctest_simulate();
+    /// let source = prebindgen::Source::new("source_ffi");
+    /// assert!(source.unmapped_features().is_empty());
+    /// ```
+    pub fn unmapped_features(&self) -> Vec<(String, SourceLocation)> {
+        let mut filter = self.cfg_filter_builder().report_unknown_features().build();
+        let mut items = self
+            .items
+            .values()
+            .flat_map(|records| records.iter().cloned())
+            .unique_by(cross_group_key);
+        while filter.next_kept(&mut items, false).is_some() {}
+        filter.unmapped().to_vec()
+    }
+
+    /// Returns an iterator over the raw records of all groups, or `None`
+    /// unless the source was built with [`Builder::keep_raw_records`]
+    ///
+    /// Each [`Record`] is yielded as read from the data files, with the
+    /// origin crate and group stamped into its
+    /// [`source_location`](Record::source_location). Records come group by
+    /// group in group-name order, and within a group in source location
+    /// order. Unlike [`items_all`](Self::items_all), no cfg filtering is
+    /// applied and an item recorded into several groups is yielded once per
+    /// group, so custom tooling sees exactly what was captured.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # prebindgen::Source::init_doctest_simulate();
+    /// use prebindgen::RecordKind;
+    /// let source = prebindgen::Source::builder("source_ffi")
+    ///     .keep_raw_records(true)
+    ///     .build();
+    /// let functions: Vec<_> = source
+    ///     .raw_records()
+    ///     .expect("kept with keep_raw_records")
+    ///     .filter(|record| record.kind == RecordKind::Function)
+    ///     .map(|record| record.name)
+    ///     .collect();
+    /// assert_eq!(functions, vec!["test_function"]);
+    /// ```
+    pub fn raw_records(&self) -> Option<impl Iterator<Item = Record> + '_> {
+        let records = self.records.as_ref()?;
+        Some(records.values().flat_map(|records| records.iter().cloned()))
+    }
+
     /// Internal: construct a CfgFilter from the stored configuration and features file
     fn build_cfg_filter(&self) -> cfg_filter::CfgFilter {
+        self.cfg_filter_builder().build()
+    }
+
+    /// Internal: the builder behind [`build_cfg_filter`](Self::build_cfg_filter)
+    fn cfg_filter_builder(&self) -> cfg_filter::Builder {
         let mut builder = cfg_filter::CfgFilter::builder();
         if let Some(const_name) = &self.features_constant {
             // If the provided constant isn't fully qualified, qualify it with the crate name
@@ -302,7 +714,22 @@ impl Source {
                 .iter()
                 .map(|f| format!("{}/{}", self.crate_name, f))
                 .join(" ");
-            builder = builder.predefined_features(qualified_const, features_list);
+            builder = builder
+                .predefined_features(qualified_const, features_list)
+                .keep_enabled_cfg(self.keep_enabled_cfg);
+        } else if self.features_from_source {
+            builder = builder
+                .enable_from_source(self)
+                .keep_enabled_cfg(self.keep_enabled_cfg);
+        } else {
+            // Feature filtering is off: feature cfgs are the destination's to evaluate
+            builder = builder.error_on_unmapped(false);
+        }
+        for (from, to) in &self.feature_mappings {
+            builder = builder.map_many(from, to);
+        }
+        for name in &self.forbidden_cfgs {
+            builder = builder.forbid_cfg(name);
         }
         if let Some(target) = &self.target_triple {
             let target_triple = TargetTriple::parse(target)
@@ -319,48 +746,124 @@ impl Source {
             if let Some(env) = target_triple.env() {
                 builder = builder.enable_target_env(env);
             }
+            if let Some(width) = target_triple.pointer_width() {
+                builder = builder.enable_target_pointer_width(width);
+            }
+            for family in target_triple.families() {
+                builder = builder.enable_target_family(family);
+            }
         }
-        builder.build()
+        builder
     }
 
-    /// Internal method to read all exported files matching the group name pattern `<group>_*`
-    fn read_group<P: AsRef<Path>>(input_dir: P, group: &str) -> Vec<Record> {
+    /// Internal method to list the exported files matching the group name
+    /// pattern `<group>_*`, sorted by file name
+    fn group_files<P: AsRef<Path>>(input_dir: P, group: &str) -> Vec<PathBuf> {
         let pattern = format!("{group}_");
-        let mut record_map = HashMap::new();
-
-        // Read the directory and find all matching files
+        let mut paths = Vec::new();
         if let Ok(entries) = fs::read_dir(&input_dir) {
             for entry in entries.flatten() {
                 let path = entry.path();
                 if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
                     if file_name.starts_with(&pattern) && file_name.ends_with(JSONL_EXTENSION) {
-                        #[cfg(feature = "debug")]
-                        println!("Reading exported file: {}", path.display());
-                        let path_clone = path.clone();
-
-                        match read_jsonl_file(&path) {
-                            Ok(records) => {
-                                for record in records {
-                                    // Use a HashMap to deduplicate records by name and cfg
-                                    let key = if let Some(cfg) = &record.cfg {
-                                        format!("{}#{}", record.name, cfg)
-                                    } else {
-                                        record.name.clone()
-                                    };
-                                    record_map.insert(key, record);
-                                }
-                            }
-                            Err(e) => {
-                                panic!("Failed to read {}: {}", path_clone.display(), e);
-                            }
-                        }
+                        paths.push(path);
+                    }
+                }
+            }
+        }
+        paths.sort();
+        paths
+    }
+
+    /// Internal method to read the exported files of one group
+    ///
+    /// With `parallel`, each file is read on its own scoped thread; the files
+    /// are merged in file-name order either way, so both modes return the
+    /// same records
+    ///
+    /// Each record comes with the data file it was read from; the lines that
+    /// aren't records are returned as [`ParseError`]s.
+    fn read_group(paths: &[PathBuf], parallel: bool) -> Result<GroupRecords, SourceError> {
+        let read_file = |path: &PathBuf| {
+            #[cfg(feature = "debug")]
+            println!("Reading exported file: {}", path.display());
+            read_jsonl_lines(path)
+                .map(|lines| (path.clone(), lines))
+                .map_err(|e| SourceError::DataFileUnreadable {
+                    file: path.clone(),
+                    message: e.to_string(),
+                })
+        };
+        let files: Vec<_> = if parallel {
+            std::thread::scope(|scope| {
+                let workers = paths
+                    .iter()
+                    .map(|path| scope.spawn(move || read_file(path)))
+                    .collect::<Vec<_>>();
+                workers
+                    .into_iter()
+                    .map(|worker| {
+                        worker
+                            .join()
+                            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                    })
+                    .collect::<Result<_, _>>()
+            })?
+        } else {
+            paths.iter().map(read_file).collect::<Result<_, _>>()?
+        };
+
+        // The same item may be recorded by several proc-macro invocations
+        // (codegen units, re-expansion); collapse records that only differ
+        // in formatting, keeping the first by source location. Records with
+        // the same name but another content or cfg are kept apart
+        let mut record_map: HashMap<_, (Record, PathBuf)> = HashMap::new();
+        let mut line_errors = Vec::new();
+        let lines = files
+            .into_iter()
+            .flat_map(|(path, lines): (PathBuf, Vec<JsonlLine>)| {
+                lines.into_iter().map(move |line| (path.clone(), line))
+            });
+        for (path, line) in lines {
+            let record = match line.record {
+                Ok(record) => record,
+                Err(e) => {
+                    line_errors.push(ParseError {
+                        name: String::new(),
+                        content: line.text,
+                        location: Box::default(),
+                        data_file: Some(path),
+                        message: format!("line {}: {e}", line.number),
+                    });
+                    continue;
+                }
+            };
+            let key = (
+                record.kind.to_string(),
+                record.name.clone(),
+                normalized_content(&record.content),
+                record.cfg.clone(),
+            );
+            match record_map.entry(key) {
+                Entry::Occupied(mut kept) => {
+                    if record_order_key(&record) < record_order_key(&kept.get().0) {
+                        kept.insert((record, path));
                     }
                 }
+                Entry::Vacant(slot) => {
+                    slot.insert((record, path));
+                }
             }
         }
 
-        // Return deduplicated records for this group
-        record_map.into_values().collect::<Vec<_>>()
+        // Return deduplicated records for this group, ordered by source location
+        // (name and cfg break ties) rather than by the order the per-thread files
+        // were listed, so the output doesn't change between builds
+        let records = record_map
+            .into_values()
+            .sorted_by(|(a, _), (b, _)| record_order_key(a).cmp(&record_order_key(b)))
+            .collect::<Vec<_>>();
+        Ok((records, line_errors))
     }
 
     /// Internal method to discover all available groups from the directory
@@ -387,6 +890,186 @@ impl Source {
     }
 }
 
+/// Errors reported by [`Source::try_new`] and [`Builder::try_build`]
+///
+/// Each variant carries the offending directory, so a build script can tell
+/// which dependency is misconfigured.
+#[derive(Debug)]
+pub enum SourceError {
+    /// The directory does not exist or is not a directory
+    DirNotFound(PathBuf),
+    /// The directory was not initialized with
+    /// [`init_prebindgen_out_dir`](crate::init_prebindgen_out_dir): the crate
+    /// name file is missing
+    CrateNameMissing(PathBuf),
+    /// The directory contains no data files written by `#[prebindgen]`
+    NoRecords(PathBuf),
+    /// Two groups define the same item differently under cfgs that can hold
+    /// together, e.g. a struct changed between the builds that recorded them
+    ConflictingDefinitions {
+        /// Name of the conflicting item
+        name: String,
+        /// Location of the definition met first
+        first: Box<SourceLocation>,
+        /// Location of the definition that differs from it
+        second: Box<SourceLocation>,
+    },
+    /// A data file in the directory could not be read
+    DataFileUnreadable {
+        /// The data file
+        file: PathBuf,
+        /// Why reading it failed
+        message: String,
+    },
+    /// The directory holds more records than allowed by
+    /// [`Builder::max_items`]
+    TooManyItems {
+        /// The directory read
+        dir: PathBuf,
+        /// Records read before giving up; at least `limit + 1`
+        count: usize,
+        /// The configured limit
+        limit: usize,
+    },
+}
+
+impl std::fmt::Display for SourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceError::DirNotFound(dir) => write!(
+                f,
+                "Input directory {} does not exist or is not a directory",
+                dir.display()
+            ),
+            SourceError::CrateNameMissing(dir) => write!(
+                f,
+                "The directory {} was not initialized with init_prebindgen_out_dir(). \
+                Please ensure that init_prebindgen_out_dir() is called in the build.rs of the source crate.",
+                dir.display()
+            ),
+            SourceError::NoRecords(dir) => write!(
+                f,
+                "The directory {} contains no #[prebindgen] records. \
+                Please ensure that the source crate marks its FFI items with #[prebindgen].",
+                dir.display()
+            ),
+            SourceError::ConflictingDefinitions {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "`{name}` is defined differently at {first} and at {second}, and their cfgs \
+                don't exclude each other. The prebindgen data is likely stale: rebuild the \
+                source crate so every group records the same definition.",
+            ),
+            SourceError::DataFileUnreadable { file, message } => {
+                write!(f, "Failed to read {}: {message}", file.display())
+            }
+            SourceError::TooManyItems { dir, count, limit } => write!(
+                f,
+                "The directory {} holds at least {count} #[prebindgen] records, more than the \
+                limit of {limit}. Please check that the source path points at the prebindgen \
+                output of the intended crate, or raise the limit with max_items().",
+                dir.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+/// Sort key giving records a stable order: source location, then name and cfg
+/// for records sharing a location
+fn record_order_key(record: &Record) -> (&str, usize, usize, &str, Option<&str>) {
+    let loc = &record.source_location;
+    (
+        &loc.file,
+        loc.line,
+        loc.column,
+        &record.name,
+        record.cfg.as_deref(),
+    )
+}
+
+/// Record content with its formatting normalized away, so copies of one
+/// item captured by different proc-macro invocations compare equal
+fn normalized_content(content: &str) -> String {
+    content
+        .parse::<proc_macro2::TokenStream>()
+        .map(|tokens| tokens.to_string())
+        .unwrap_or_else(|_| content.split_whitespace().join(" "))
+}
+
+/// Identity of an item across groups: the same capture recorded into several
+/// groups differs only in the group stamp of its location
+fn cross_group_key((item, loc): &(syn::Item, SourceLocation)) -> (String, usize, usize, String) {
+    (
+        loc.file.clone(),
+        loc.line,
+        loc.column,
+        quote::ToTokens::to_token_stream(item).to_string(),
+    )
+}
+
+/// Reject two items of one kind, name and module, found in any groups, whose
+/// definitions differ apart from their cfgs while the cfgs can hold
+/// together. Identical definitions are left to the cross-group dedup
+fn check_conflicting_definitions(
+    items: &BTreeMap<String, Vec<(syn::Item, SourceLocation)>>,
+) -> Result<(), SourceError> {
+    type Definition<'a> = (String, Vec<syn::Attribute>, &'a SourceLocation);
+    let mut seen: HashMap<_, Vec<Definition>> = HashMap::new();
+    for (item, loc) in items.values().flatten() {
+        let name = item_name(item);
+        if name.is_empty() || name == "_" {
+            continue;
+        }
+        let mut body = item.clone();
+        let mut cfgs = Vec::new();
+        if let Some(attrs) = item_attrs_mut(&mut body) {
+            (cfgs, *attrs) = std::mem::take(attrs)
+                .into_iter()
+                .partition(|a| a.path().is_ident("cfg"));
+        }
+        let body = quote::ToTokens::to_token_stream(&body).to_string();
+        let key = (
+            std::mem::discriminant(item),
+            name.clone(),
+            loc.module_path.clone(),
+        );
+        let definitions = seen.entry(key).or_default();
+        if let Some((_, _, first)) = definitions
+            .iter()
+            .find(|(other, other_cfgs, _)| *other != body && !cfgs_exclude(other_cfgs, &cfgs))
+        {
+            return Err(SourceError::ConflictingDefinitions {
+                name,
+                first: Box::new((*first).clone()),
+                second: Box::new(loc.clone()),
+            });
+        }
+        definitions.push((body, cfgs, loc));
+    }
+    Ok(())
+}
+
+/// Name of a captured item: the ident of its struct, enum, fn, alias, const,
+/// static or module
+fn item_name(item: &syn::Item) -> String {
+    match item {
+        syn::Item::Struct(s) => s.ident.to_string(),
+        syn::Item::Enum(e) => e.ident.to_string(),
+        syn::Item::Union(u) => u.ident.to_string(),
+        syn::Item::Fn(f) => f.sig.ident.to_string(),
+        syn::Item::Type(t) => t.ident.to_string(),
+        syn::Item::Const(c) => c.ident.to_string(),
+        syn::Item::Static(s) => s.ident.to_string(),
+        syn::Item::Mod(m) => m.ident.to_string(),
+        _ => String::new(),
+    }
+}
+
 /// Read the crate name from the stored file
 fn read_stored_crate_name(input_dir: &Path) -> Option<String> {
     let crate_name_path = input_dir.join(CRATE_NAME_FILE);
@@ -395,6 +1078,13 @@ fn read_stored_crate_name(input_dir: &Path) -> Option<String> {
         .map(|s| s.trim().to_string())
 }
 
+/// Read the crate version from the stored file
+fn read_stored_crate_version(input_dir: &Path) -> Option<String> {
+    fs::read_to_string(input_dir.join(CRATE_VERSION_FILE))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
 /// Read enabled features list from FEATURES_FILE and normalize into a sorted, deduplicated Vec<String>
 fn read_features_from_out_dir(input_dir: &Path) -> Vec<String> {
     let features_path = input_dir.join(FEATURES_FILE);
@@ -418,6 +1108,16 @@ pub struct Builder {
     features_constant: Option<String>,
     target_triple: Option<String>,
     crate_name: Option<String>,
+    keep_enabled_cfg: bool,
+    features_from_source: bool,
+    feature_mappings: Vec<(Vec<String>, String)>,
+    forbidden_cfgs: Vec<String>,
+    parallel: bool,
+    rerun_if_changed: bool,
+    group_prefix: Option<String>,
+    max_items: Option<usize>,
+    qualify_by_module: bool,
+    keep_raw_records: bool,
 }
 
 impl Builder {
@@ -436,6 +1136,16 @@ impl Builder {
             features_constant: Some("FEATURES".to_string()),
             target_triple,
             crate_name: None,
+            keep_enabled_cfg: false,
+            features_from_source: false,
+            feature_mappings: Vec::new(),
+            forbidden_cfgs: Vec::new(),
+            parallel: false,
+            rerun_if_changed: false,
+            group_prefix: None,
+            max_items: None,
+            qualify_by_module: false,
+            keep_raw_records: false,
         }
     }
 
@@ -459,6 +1169,30 @@ impl Builder {
         self
     }
 
+    /// Keep the module path captured with each item (default `false`), so
+    /// generated code references an item defined in `src/foo.rs` as
+    /// `my_crate::foo::Item` instead of `my_crate::Item`.
+    ///
+    /// The module path is derived from the source file path, which is only
+    /// right when the module is public and declared at its default location:
+    /// enable it for crates that expose their submodules (`pub mod foo;`),
+    /// and keep it off for crates that re-export items at the root
+    /// (`mod foo; pub use foo::*;`) or use `#[path]`.
+    pub fn qualify_by_module(mut self, enable: bool) -> Self {
+        self.qualify_by_module = enable;
+        self
+    }
+
+    /// Keep the raw records the items are parsed from (default `false`), for
+    /// [`Source::raw_records`].
+    ///
+    /// Off by default so a source holding thousands of records doesn't keep
+    /// a second copy of each for build scripts that only read the items.
+    pub fn keep_raw_records(mut self, enable: bool) -> Self {
+        self.keep_raw_records = enable;
+        self
+    }
+
     /// Enables or disables filtering by features when extracting collected data.
     /// Accepts name of the constant with the list of features in the source crate.
     ///
@@ -542,13 +1276,180 @@ impl Builder {
         self
     }
 
+    /// Keep `#[cfg(feature = "...")]` attributes on features the source crate
+    /// was built with, instead of removing them, so the generated file still
+    /// tracks them when it is `include!`d under several configurations.
+    /// Code guarded by disabled features is dropped either way.
+    ///
+    /// Only applies when feature filtering is enabled; off by default.
+    #[roxygen]
+    pub fn keep_enabled_cfg(
+        mut self,
+        /// Whether cfgs on enabled features are kept
+        keep: bool,
+    ) -> Self {
+        self.keep_enabled_cfg = keep;
+        self
+    }
+
+    /// Resolve feature cfgs against the features the source crate was built
+    /// with even when [`enable_feature_filtering`](Self::enable_feature_filtering)
+    /// is disabled
+    ///
+    /// The features listed in `features.txt` are enabled and their cfgs
+    /// removed, and code guarded by any other feature is dropped, as with
+    /// feature filtering — only the assert on the features constant is left
+    /// out, for source crates that don't define one. Has no effect while
+    /// feature filtering is enabled; off by default.
+    #[roxygen]
+    pub fn features_from_source(
+        mut self,
+        /// Whether the recorded features are resolved without the assert
+        enable: bool,
+    ) -> Self {
+        self.features_from_source = enable;
+        self
+    }
+
+    /// Rename several source crate features to one feature of this crate
+    ///
+    /// Every `#[cfg(feature = "<from>")]` predicate on the yielded items
+    /// becomes `#[cfg(feature = "<to>")]` — e.g. the `tokio`, `async-std`
+    /// and `smol` features of the source crate all gated by this crate's
+    /// `async`. Calls accumulate. With feature filtering enabled, a feature
+    /// the source crate was built with is still resolved as enabled, so
+    /// only the features it was built without are renamed.
+    #[roxygen]
+    pub fn map_many<S: AsRef<str>>(
+        mut self,
+        /// Source crate feature names
+        from: &[S],
+        /// Feature of this crate they are renamed to
+        to: &str,
+    ) -> Self {
+        self.feature_mappings.push((
+            from.iter().map(|f| f.as_ref().to_string()).collect(),
+            to.to_string(),
+        ));
+        self
+    }
+
+    /// Drop the items guarded by the bare cfg predicate `name`
+    ///
+    /// `#[cfg(test)]` and `#[cfg(debug_assertions)]` code is never meant for
+    /// the generated bindings, yet an item guarded by them is copied like any
+    /// other. With `forbid_cfg("test")` the predicate is treated as false:
+    /// `#[cfg(test)]` items are dropped and `not(test)` cfgs removed. Calls
+    /// accumulate.
+    #[roxygen]
+    pub fn forbid_cfg(
+        mut self,
+        /// The cfg predicate name (e.g., "test")
+        name: &str,
+    ) -> Self {
+        self.forbidden_cfgs.push(name.to_string());
+        self
+    }
+
+    /// Read the data files on worker threads, one per file. Worth it for
+    /// source crates whose thousands of records are spread over dozens of
+    /// per-thread files. The items, their order and the iterators over them
+    /// are the same as with the sequential reader.
+    ///
+    /// Only reading and decoding the records is parallel: the parsed
+    /// `syn` items are not `Send`, so they are still built on the calling
+    /// thread. Off by default.
+    #[roxygen]
+    pub fn parallel(
+        mut self,
+        /// Whether data files are read in parallel
+        parallel: bool,
+    ) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Namespace the groups of this source as `{prefix}_{group}`
+    ///
+    /// Source crates aggregated into one binding crate often use the same
+    /// group names (`functions` in each). With a distinct prefix per
+    /// `Source`, [`Source::groups`], [`Source::items_in_groups`] and the
+    /// group stamped into every item's location use the prefixed names, so
+    /// the chained streams stay apart — e.g. in
+    /// [`Destination::write_per_group`](crate::collect::Destination::write_per_group).
+    /// The separator is unambiguous: `#[prebindgen]` rejects group names
+    /// containing `_`.
+    #[roxygen]
+    pub fn group_prefix(
+        mut self,
+        /// Prefix of every group name (e.g., "cryptolib")
+        prefix: impl Into<String>,
+    ) -> Self {
+        self.group_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Fail with [`SourceError::TooManyItems`] if the source holds more than
+    /// `limit` records
+    ///
+    /// A safety valve against misconfiguration: a `Source` pointed at the
+    /// wrong directory, or at one shared with an unrelated crate, yields
+    /// thousands of bogus items instead of failing. Unlimited by default.
+    #[roxygen]
+    pub fn max_items(
+        mut self,
+        /// The largest number of records accepted
+        limit: usize,
+    ) -> Self {
+        self.max_items = Some(limit);
+        self
+    }
+
+    /// Print `cargo:rerun-if-changed=<dir>` for the prebindgen output
+    /// directory the `Source` reads, so Cargo reruns the build script when
+    /// the source crate's FFI surface changes — including when a data file
+    /// is added or removed — instead of keeping stale bindings.
+    ///
+    /// Note that once a build script prints any `rerun-if-changed` line,
+    /// Cargo no longer reruns it on every change in the package, only on
+    /// changes of the listed paths (and of the build script itself). Off by
+    /// default.
+    #[roxygen]
+    pub fn rerun_if_changed(
+        mut self,
+        /// Whether rerun lines are printed
+        enable: bool,
+    ) -> Self {
+        self.rerun_if_changed = enable;
+        self
+    }
+
     /// Build the `Source` instance
+    ///
+    /// # Panics
+    ///
+    /// Panics with the [`SourceError`] description if the directory is
+    /// missing, uninitialized, or holds no records; see [`Builder::try_build`]
+    /// for the non-panicking variant.
     pub fn build(self) -> Source {
-        Source::build_internal(
-            &self.input_dir,
-            self.features_constant,
-            self.target_triple,
-            self.crate_name,
-        )
+        self.try_build().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Build the `Source` instance, returning a [`SourceError`] if the
+    /// directory is missing, uninitialized, or holds no records
+    pub fn try_build(self) -> Result<Source, SourceError> {
+        let input_dir = self.input_dir.clone();
+        let rerun_dir = self.rerun_if_changed.then(|| self.input_dir.clone());
+        let source = Source::build_internal(self)?;
+        if source.groups().is_empty() {
+            return Err(SourceError::NoRecords(input_dir));
+        }
+        if let Some(dir) = rerun_dir {
+            println!("cargo:rerun-if-changed={}", dir.display());
+        }
+        Ok(source)
     }
 }
+
+#[cfg(test)]
+mod tests;
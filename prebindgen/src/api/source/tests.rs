@@ -0,0 +1,841 @@
+use std::{fs, path::Path};
+
+use super::*;
+use crate::api::{
+    record::RecordKind, test_util::unique_test_dir, utils::jsonl::write_to_jsonl_file,
+};
+
+/// Lay out a prebindgen output directory the way `init_prebindgen_out_dir`
+/// and the proc-macro would: the crate name / features sidecars plus one
+/// JSONL data file per `(file_name, records)` entry.
+fn write_out_dir(dir: &Path, files: &[(&str, Vec<Record>)]) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join(CRATE_NAME_FILE), "source_ffi").unwrap();
+    fs::write(dir.join(FEATURES_FILE), "").unwrap();
+    for (file_name, records) in files {
+        write_to_jsonl_file(dir.join(file_name), records).unwrap();
+    }
+}
+
+fn fn_record(name: &str) -> Record {
+    Record::new(
+        RecordKind::Function,
+        name.to_string(),
+        format!("pub fn {name}() {{}}"),
+        SourceLocation::default(),
+        None,
+    )
+}
+
+fn located_fn_record(name: &str, file: &str, line: usize) -> Record {
+    Record::new(
+        RecordKind::Function,
+        name.to_string(),
+        format!("pub fn {name}() {{}}"),
+        SourceLocation {
+            file: file.to_string(),
+            line,
+            column: 1,
+            crate_name: None,
+            group: None,
+            module_path: None,
+            source_name: None,
+        },
+        None,
+    )
+}
+
+fn item_names(source: &Source) -> Vec<String> {
+    source
+        .items_all()
+        .map(|(item, _)| match item {
+            syn::Item::Fn(f) => f.sig.ident.to_string(),
+            other => panic!("unexpected item {}", quote::quote!(#other)),
+        })
+        .collect()
+}
+
+fn unfiltered(dir: &Path) -> Source {
+    Source::builder(dir)
+        .enable_feature_filtering(None::<&str>)
+        .enable_target_filtering(None::<&str>)
+        .build()
+}
+
+#[test]
+fn groups_are_sorted_and_deduplicated() {
+    let dir = unique_test_dir("source_groups");
+    write_out_dir(
+        &dir,
+        &[
+            ("structs_1_2.jsonl", vec![fn_record("a")]),
+            ("functions_1_2.jsonl", vec![fn_record("b")]),
+            ("structs_3_4.jsonl", vec![fn_record("c")]),
+        ],
+    );
+    // Stray files are not groups.
+    fs::write(dir.join("notes.txt"), "not a record file").unwrap();
+    fs::write(dir.join("README"), "").unwrap();
+
+    let source = unfiltered(&dir);
+    assert_eq!(source.groups(), vec!["functions", "structs"]);
+    // Every item carries the group it was read from.
+    let groups: Vec<_> = source
+        .items_all()
+        .map(|(_, loc)| loc.group.unwrap())
+        .collect();
+    assert_eq!(groups, vec!["functions", "structs", "structs"]);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn try_new_reports_missing_dir() {
+    let dir = unique_test_dir("source_missing");
+    match Source::try_new(&dir) {
+        Err(SourceError::DirNotFound(path)) => assert_eq!(path, dir),
+        other => panic!("expected DirNotFound, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn try_new_reports_uninitialized_dir() {
+    let dir = unique_test_dir("source_uninit");
+    fs::create_dir_all(&dir).unwrap();
+    write_to_jsonl_file(dir.join("default_1_2.jsonl"), &[fn_record("a")]).unwrap();
+
+    match Source::try_new(&dir) {
+        Err(e @ SourceError::CrateNameMissing(_)) => {
+            assert!(e.to_string().contains(&dir.display().to_string()))
+        }
+        other => panic!("expected CrateNameMissing, got {:?}", other.err()),
+    }
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn build_rejects_dir_without_data_files() {
+    let dir = unique_test_dir("source_groups_empty");
+    write_out_dir(&dir, &[]);
+    let result = std::panic::catch_unwind(|| unfiltered(&dir));
+    let message = *result.err().unwrap().downcast::<String>().unwrap();
+    assert!(
+        message.contains("contains no #[prebindgen] records"),
+        "{message}"
+    );
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn try_new_reports_no_records() {
+    let dir = unique_test_dir("source_no_records");
+    write_out_dir(&dir, &[]);
+    fs::write(dir.join("notes.txt"), "").unwrap();
+
+    match Source::try_new(&dir) {
+        Err(SourceError::NoRecords(path)) => assert_eq!(path, dir),
+        other => panic!("expected NoRecords, got {:?}", other.err()),
+    }
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn max_items_rejects_sources_over_the_limit() {
+    let dir = unique_test_dir("source_max_items");
+    write_out_dir(
+        &dir,
+        &[
+            ("functions_1_2.jsonl", vec![fn_record("a"), fn_record("b")]),
+            ("structs_1_2.jsonl", vec![fn_record("c")]),
+        ],
+    );
+    let builder = || {
+        Source::builder(&dir)
+            .enable_feature_filtering(None::<&str>)
+            .enable_target_filtering(None::<&str>)
+    };
+
+    let source = builder().max_items(3).try_build().unwrap();
+    assert_eq!(item_names(&source), vec!["a", "b", "c"]);
+    match builder().max_items(2).try_build() {
+        Err(
+            e @ SourceError::TooManyItems {
+                count: 3, limit: 2, ..
+            },
+        ) => {
+            let message = e.to_string();
+            assert!(message.contains("at least 3"), "{message}");
+            assert!(message.contains("check that the source path"), "{message}");
+        }
+        other => panic!("expected TooManyItems, got {:?}", other.err()),
+    }
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn items_all_order_is_stable_across_thread_files() {
+    // Two proc-macro threads each wrote part of both groups; which file the
+    // directory listing yields first must not affect the item order.
+    let thread_a = vec![
+        located_fn_record("b_second", "src/b.rs", 20),
+        located_fn_record("a_first", "src/a.rs", 10),
+    ];
+    let thread_b = vec![
+        located_fn_record("b_first", "src/b.rs", 5),
+        located_fn_record("a_second", "src/a.rs", 30),
+    ];
+    let dir_ab = unique_test_dir("source_order_ab");
+    write_out_dir(
+        &dir_ab,
+        &[
+            ("default_1_1.jsonl", thread_a.clone()),
+            ("default_1_2.jsonl", thread_b.clone()),
+            (
+                "extra_1_1.jsonl",
+                vec![located_fn_record("z_extra", "src/a.rs", 1)],
+            ),
+        ],
+    );
+    let dir_ba = unique_test_dir("source_order_ba");
+    write_out_dir(
+        &dir_ba,
+        &[
+            (
+                "extra_2_9.jsonl",
+                vec![located_fn_record("z_extra", "src/a.rs", 1)],
+            ),
+            ("default_2_9.jsonl", thread_b),
+            ("default_2_1.jsonl", thread_a),
+        ],
+    );
+
+    let expected = ["a_first", "a_second", "b_first", "b_second", "z_extra"];
+    for _ in 0..3 {
+        assert_eq!(item_names(&unfiltered(&dir_ab)), expected);
+        assert_eq!(item_names(&unfiltered(&dir_ba)), expected);
+    }
+    fs::remove_dir_all(&dir_ab).ok();
+    fs::remove_dir_all(&dir_ba).ok();
+}
+
+#[test]
+fn features_are_read_from_features_file() {
+    let dir = unique_test_dir("source_features");
+    write_out_dir(&dir, &[("default_1_1.jsonl", vec![fn_record("a")])]);
+    fs::write(dir.join(FEATURES_FILE), "std\nalloc\n\nstd\n").unwrap();
+    assert_eq!(unfiltered(&dir).features(), vec!["alloc", "std"]);
+
+    // Data written before features.txt existed
+    fs::remove_file(dir.join(FEATURES_FILE)).unwrap();
+    assert!(unfiltered(&dir).features().is_empty());
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn crate_version_is_read_when_recorded() {
+    let dir = unique_test_dir("source_crate_version");
+    write_out_dir(&dir, &[("default_1_1.jsonl", vec![fn_record("a")])]);
+    // Data written before crate_version.txt existed
+    assert_eq!(unfiltered(&dir).crate_version(), None);
+
+    fs::write(dir.join(CRATE_VERSION_FILE), "1.2.3\n").unwrap();
+    assert_eq!(unfiltered(&dir).crate_version().as_deref(), Some("1.2.3"));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn consumed_files_cover_data_and_sidecar_files() {
+    let dir = unique_test_dir("source_consumed_files");
+    write_out_dir(
+        &dir,
+        &[
+            ("structs_1_2.jsonl", vec![fn_record("a")]),
+            ("functions_1_2.jsonl", vec![fn_record("b")]),
+            ("functions_3_4.jsonl", vec![fn_record("c")]),
+        ],
+    );
+    fs::write(dir.join(CRATE_VERSION_FILE), "1.2.3\n").unwrap();
+    fs::write(dir.join("notes.txt"), "not a record file").unwrap();
+
+    let mut consumed = unfiltered(&dir).consumed_files().to_vec();
+    consumed.sort();
+    let mut expected: Vec<PathBuf> = [
+        CRATE_NAME_FILE,
+        CRATE_VERSION_FILE,
+        FEATURES_FILE,
+        "functions_1_2.jsonl",
+        "functions_3_4.jsonl",
+        "structs_1_2.jsonl",
+    ]
+    .iter()
+    .map(|name| dir.join(name))
+    .collect();
+    expected.sort();
+    assert_eq!(consumed, expected);
+
+    // A sidecar that was never written is not reported
+    fs::remove_file(dir.join(CRATE_VERSION_FILE)).unwrap();
+    assert!(!unfiltered(&dir)
+        .consumed_files()
+        .contains(&dir.join(CRATE_VERSION_FILE)));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn item_in_several_groups_is_yielded_once_by_items_all() {
+    let dir = unique_test_dir("source_multi_group");
+    let size = located_fn_record("size", "src/lib.rs", 3);
+    write_out_dir(
+        &dir,
+        &[
+            ("structs_1_1.jsonl", vec![size.clone()]),
+            ("all_1_1.jsonl", vec![size, fn_record("other")]),
+        ],
+    );
+    let source = unfiltered(&dir);
+    assert_eq!(source.items_in_groups(&["structs"]).count(), 1);
+    assert_eq!(source.items_in_groups(&["all"]).count(), 2);
+    assert_eq!(source.items_in_groups(&["structs", "all"]).count(), 2);
+    assert_eq!(source.items_except_groups(&["default"]).count(), 2);
+    assert_eq!(item_names(&source), ["other", "size"]);
+    let (_, loc) = source.items_all().find(|(_, loc)| loc.line == 3).unwrap();
+    assert_eq!(loc.group.as_deref(), Some("all"));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn parallel_read_matches_sequential_read() {
+    let dir = unique_test_dir("source_parallel");
+    let files: Vec<(String, Vec<Record>)> = ["alpha", "beta", "gamma"]
+        .iter()
+        .flat_map(|group| {
+            (0..4).map(move |thread| {
+                let records = (0..25)
+                    .map(|i| {
+                        located_fn_record(
+                            &format!("{group}_{thread}_{i}"),
+                            &format!("src/{group}.rs"),
+                            // Interleave the threads' lines within each file
+                            i * 4 + (3 - thread),
+                        )
+                    })
+                    .collect();
+                (format!("{group}_1_{thread}.jsonl"), records)
+            })
+        })
+        .collect();
+    let files: Vec<(&str, Vec<Record>)> = files
+        .iter()
+        .map(|(name, records)| (name.as_str(), records.clone()))
+        .collect();
+    write_out_dir(&dir, &files);
+
+    let items = |parallel: bool| {
+        Source::builder(&dir)
+            .enable_feature_filtering(None::<&str>)
+            .enable_target_filtering(None::<&str>)
+            .parallel(parallel)
+            .build()
+            .items_all()
+            .map(|(item, loc)| (quote::quote!(#item).to_string(), loc))
+            .collect::<Vec<_>>()
+    };
+    let sequential = items(false);
+    assert_eq!(sequential.len(), 300);
+    assert_eq!(items(true), sequential);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn identical_records_collapse_and_cfg_variants_are_kept() {
+    let dir = unique_test_dir("source_dedup");
+    let record = located_fn_record("dup", "src/lib.rs", 7);
+    let mut reformatted = record.clone();
+    reformatted.content = "pub fn dup ( ) { }".to_string();
+    let variant = |cfg: &str| {
+        let mut r = located_fn_record("variant", "src/lib.rs", 9);
+        r.cfg = Some(cfg.to_string());
+        r
+    };
+    write_out_dir(
+        &dir,
+        &[
+            (
+                "default_1_1.jsonl",
+                vec![record.clone(), record, variant("unix")],
+            ),
+            ("default_1_2.jsonl", vec![reformatted, variant("windows")]),
+        ],
+    );
+    let items: Vec<_> = unfiltered(&dir).items_all().collect();
+    assert_eq!(items.len(), 3);
+    let names = item_names(&unfiltered(&dir));
+    assert_eq!(names.iter().filter(|n| *n == "dup").count(), 1);
+    assert_eq!(names.iter().filter(|n| *n == "variant").count(), 2);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn map_many_renames_feature_aliases() {
+    let dir = unique_test_dir("source_map_many");
+    let records = ["tokio", "async-std", "smol"]
+        .into_iter()
+        .enumerate()
+        .map(|(line, feature)| {
+            let mut record = located_fn_record(&format!("spawn_{line}"), "src/lib.rs", line);
+            record.content = format!("#[cfg(feature = \"{feature}\")] {}", record.content);
+            record
+        })
+        .collect();
+    write_out_dir(&dir, &[("default_1_1.jsonl", records)]);
+    let source = Source::builder(&dir)
+        .enable_target_filtering(None::<&str>)
+        .map_many(&["tokio", "async-std", "smol"], "async")
+        .build();
+    let cfgs: Vec<String> = source
+        .items_all()
+        // The features assert prelude is yielded too; only the fns matter
+        .filter_map(|(item, _)| match item {
+            syn::Item::Fn(f) => {
+                let attrs = &f.attrs;
+                Some(quote::quote!(#(#attrs)*).to_string())
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(cfgs, vec!["# [cfg (feature = \"async\")]"; 3]);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn features_from_source_resolves_without_the_assert() {
+    let dir = unique_test_dir("source_features_from_source");
+    let records = ["std", "unstable"]
+        .into_iter()
+        .enumerate()
+        .map(|(line, feature)| {
+            let mut record = located_fn_record(feature, "src/lib.rs", line);
+            record.content = format!("#[cfg(feature = \"{feature}\")] {}", record.content);
+            record
+        })
+        .collect();
+    write_out_dir(&dir, &[("default_1_1.jsonl", records)]);
+    fs::write(dir.join(FEATURES_FILE), "std\n").unwrap();
+    let items: Vec<String> = Source::builder(&dir)
+        .enable_feature_filtering(None::<&str>)
+        .enable_target_filtering(None::<&str>)
+        .features_from_source(true)
+        .build()
+        .items_all()
+        .map(|(item, _)| quote::quote!(#item).to_string())
+        .collect();
+    // No features assert prelude; `std` is resolved, `unstable` dropped
+    assert_eq!(items, vec!["pub fn std () { }"]);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn unmapped_features_lists_silently_disabled_features() {
+    let dir = unique_test_dir("source_unmapped_features");
+    let records = ["std", "sdt"]
+        .into_iter()
+        .enumerate()
+        .map(|(line, feature)| {
+            let mut record = located_fn_record(feature, "src/lib.rs", line);
+            record.content = format!("#[cfg(feature = \"{feature}\")] {}", record.content);
+            record
+        })
+        .collect();
+    write_out_dir(&dir, &[("default_1_1.jsonl", records)]);
+    fs::write(dir.join(FEATURES_FILE), "std\n").unwrap();
+    let source = Source::builder(&dir)
+        .enable_feature_filtering(None::<&str>)
+        .enable_target_filtering(None::<&str>)
+        .features_from_source(true)
+        .build();
+    let unmapped: Vec<_> = source
+        .unmapped_features()
+        .into_iter()
+        .map(|(name, location)| (name, location.line))
+        .collect();
+    assert_eq!(unmapped, vec![("sdt".to_string(), 1)]);
+    // Without feature filtering, feature cfgs aren't resolved at all
+    assert!(unfiltered(&dir).unmapped_features().is_empty());
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn module_path_is_kept_only_when_qualifying_by_module() {
+    let dir = unique_test_dir("source_qualify_by_module");
+    let mut record = located_fn_record("open", "src/ext.rs", 1);
+    record.source_location.module_path = Some("ext".to_string());
+    write_out_dir(&dir, &[("default_1_1.jsonl", vec![record])]);
+    let module_paths = |source: Source| -> Vec<Option<String>> {
+        source
+            .items_all()
+            .map(|(_, location)| location.module_path)
+            .collect()
+    };
+    let builder = || Source::builder(&dir).enable_feature_filtering(None::<&str>);
+    assert_eq!(module_paths(builder().build()), vec![None]);
+    assert_eq!(
+        module_paths(builder().qualify_by_module(true).build()),
+        vec![Some("ext".to_string())]
+    );
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn forbid_cfg_drops_test_only_items() {
+    let dir = unique_test_dir("source_forbid_cfg");
+    let mut test_only = located_fn_record("test_helper", "src/lib.rs", 1);
+    test_only.content = format!("#[cfg(test)] {}", test_only.content);
+    let records = vec![test_only, located_fn_record("open", "src/lib.rs", 2)];
+    write_out_dir(&dir, &[("default_1_1.jsonl", records)]);
+    let fn_names = |source: Source| -> Vec<String> {
+        source
+            .items_all()
+            .filter_map(|(item, _)| match item {
+                syn::Item::Fn(f) => Some(f.sig.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let source = Source::builder(&dir)
+        .enable_target_filtering(None::<&str>)
+        .build();
+    assert_eq!(fn_names(source), ["test_helper", "open"]);
+    let source = Source::builder(&dir)
+        .enable_target_filtering(None::<&str>)
+        .forbid_cfg("test")
+        .build();
+    assert_eq!(fn_names(source), ["open"]);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn items_matching_filters_by_name_and_kind() {
+    let dir = unique_test_dir("source_items_matching");
+    let public_struct = Record::new(
+        RecordKind::Struct,
+        "public_config".to_string(),
+        "pub struct public_config { pub x: u32 }".to_string(),
+        SourceLocation {
+            line: 1,
+            ..SourceLocation::default()
+        },
+        None,
+    );
+    write_out_dir(
+        &dir,
+        &[(
+            "default_1_1.jsonl",
+            vec![
+                public_struct,
+                located_fn_record("public_open", "src/lib.rs", 2),
+                located_fn_record("internal_helper", "src/lib.rs", 3),
+                located_fn_record("public_close", "src/lib.rs", 4),
+            ],
+        )],
+    );
+    let source = unfiltered(&dir);
+    let matched: Vec<(String, usize)> = source
+        .items_matching(|name, kind| kind == RecordKind::Function && name.starts_with("public_"))
+        .map(|(item, loc)| match item {
+            syn::Item::Fn(f) => (f.sig.ident.to_string(), loc.line),
+            other => panic!("unexpected item {}", quote::quote!(#other)),
+        })
+        .collect();
+    assert_eq!(
+        matched,
+        [
+            ("public_open".to_string(), 2),
+            ("public_close".to_string(), 4)
+        ]
+    );
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn group_prefix_keeps_chained_sources_apart() {
+    let crypto = unique_test_dir("source_prefix_crypto");
+    let codec = unique_test_dir("source_prefix_codec");
+    write_out_dir(
+        &crypto,
+        &[("functions_1_2.jsonl", vec![fn_record("encrypt")])],
+    );
+    write_out_dir(
+        &codec,
+        &[("functions_1_2.jsonl", vec![fn_record("encode")])],
+    );
+    let prefixed = |dir: &Path, prefix: &str| {
+        Source::builder(dir)
+            .enable_feature_filtering(None::<&str>)
+            .enable_target_filtering(None::<&str>)
+            .group_prefix(prefix)
+            .build()
+    };
+    let crypto_source = prefixed(&crypto, "cryptolib");
+    let codec_source = prefixed(&codec, "codeclib");
+    assert_eq!(crypto_source.groups(), vec!["cryptolib_functions"]);
+    assert_eq!(
+        crypto_source
+            .items_in_groups(&["cryptolib_functions"])
+            .count(),
+        1
+    );
+    assert_eq!(crypto_source.items_in_groups(&["functions"]).count(), 0);
+
+    let groups: Vec<(String, Option<String>)> = crypto_source
+        .items_all()
+        .chain(codec_source.items_all())
+        .map(|(item, loc)| {
+            let syn::Item::Fn(f) = item else {
+                panic!("unexpected item");
+            };
+            (f.sig.ident.to_string(), loc.group)
+        })
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            (
+                "encrypt".to_string(),
+                Some("cryptolib_functions".to_string())
+            ),
+            ("encode".to_string(), Some("codeclib_functions".to_string())),
+        ]
+    );
+    fs::remove_dir_all(&crypto).ok();
+    fs::remove_dir_all(&codec).ok();
+}
+
+#[test]
+fn conflicting_definitions_across_groups_are_rejected() {
+    let dir = unique_test_dir("source_conflicting_definitions");
+    let foo = |field: &str, line: usize, cfg: Option<&str>| {
+        Record::new(
+            RecordKind::Struct,
+            "Foo".to_string(),
+            format!("pub struct Foo {{ pub a: {field} }}"),
+            SourceLocation {
+                file: "src/lib.rs".to_string(),
+                line,
+                ..SourceLocation::default()
+            },
+            cfg.map(str::to_string),
+        )
+    };
+
+    // The same definition recorded into two groups is yielded once
+    write_out_dir(
+        &dir,
+        &[
+            ("structs_1_1.jsonl", vec![foo("u32", 1, None)]),
+            ("all_1_1.jsonl", vec![foo("u32", 1, None)]),
+        ],
+    );
+    assert_eq!(unfiltered(&dir).items_all().count(), 1);
+    fs::remove_dir_all(&dir).ok();
+
+    // Definitions under mutually exclusive cfgs may differ
+    write_out_dir(
+        &dir,
+        &[
+            ("structs_1_1.jsonl", vec![foo("u32", 1, Some("unix"))]),
+            ("all_1_1.jsonl", vec![foo("u64", 3, Some("not(unix)"))]),
+        ],
+    );
+    assert_eq!(unfiltered(&dir).items_all().count(), 2);
+    fs::remove_dir_all(&dir).ok();
+
+    write_out_dir(
+        &dir,
+        &[
+            ("structs_1_1.jsonl", vec![foo("u32", 1, None)]),
+            ("all_1_1.jsonl", vec![foo("u64", 3, None)]),
+        ],
+    );
+    let err = Source::builder(&dir)
+        .enable_feature_filtering(None::<&str>)
+        .enable_target_filtering(None::<&str>)
+        .try_build()
+        .err()
+        .unwrap();
+    assert!(
+        matches!(
+            &err,
+            SourceError::ConflictingDefinitions { name, first, second }
+                if name == "Foo" && first.line == 3 && second.line == 1
+        ),
+        "{err}"
+    );
+    assert!(
+        err.to_string()
+            .starts_with("`Foo` is defined differently at src/lib.rs:3:0 and at src/lib.rs:1:0"),
+        "{err}"
+    );
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn raw_records_carry_kind_group_and_cfg() {
+    let dir = unique_test_dir("source_raw_records");
+    let mut gated = located_fn_record("gated", "src/ffi.rs", 3);
+    gated.cfg = Some("unix".to_string());
+    let point = Record::new(
+        RecordKind::Struct,
+        "Point".to_string(),
+        "pub struct Point { pub x: i32 }".to_string(),
+        SourceLocation {
+            file: "src/lib.rs".to_string(),
+            line: 5,
+            ..SourceLocation::default()
+        },
+        None,
+    );
+    write_out_dir(
+        &dir,
+        &[
+            ("functions_1_1.jsonl", vec![gated]),
+            ("structs_1_1.jsonl", vec![point]),
+        ],
+    );
+    let source = Source::builder(&dir)
+        .enable_feature_filtering(None::<&str>)
+        .enable_target_filtering(None::<&str>)
+        .keep_raw_records(true)
+        .build();
+    let records: Vec<Record> = source.raw_records().unwrap().collect();
+    let summary: Vec<_> = records
+        .iter()
+        .map(|r| {
+            (
+                r.kind.clone(),
+                r.name.as_str(),
+                r.source_location.group.as_deref(),
+                r.cfg.as_deref(),
+                r.source_location.file.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (
+                RecordKind::Function,
+                "gated",
+                Some("functions"),
+                Some("unix"),
+                "src/ffi.rs"
+            ),
+            (
+                RecordKind::Struct,
+                "Point",
+                Some("structs"),
+                None,
+                "src/lib.rs"
+            ),
+        ]
+    );
+    assert!(records
+        .iter()
+        .all(|r| r.source_location.crate_name.as_deref() == Some("source_ffi")));
+
+    // Not kept unless asked for
+    assert!(unfiltered(&dir).raw_records().is_none());
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn try_items_all_reports_malformed_records() {
+    let dir = unique_test_dir("source_malformed_record");
+    let mut broken = located_fn_record("broken", "src/lib.rs", 4);
+    broken.content = "pub fn broken( {".to_string();
+    write_out_dir(
+        &dir,
+        &[(
+            "default_1_1.jsonl",
+            vec![
+                located_fn_record("fine", "src/lib.rs", 1),
+                broken,
+                located_fn_record("later", "src/lib.rs", 9),
+            ],
+        )],
+    );
+    let source = Source::builder(&dir)
+        .enable_feature_filtering(None::<&str>)
+        .enable_target_filtering(None::<&str>)
+        .keep_raw_records(true)
+        .build();
+    let entries: Vec<_> = source.try_items_all().collect();
+    assert_eq!(entries.len(), 3);
+    // Reported in record order, by source location
+    assert!(matches!(&entries[0], Ok((syn::Item::Fn(f), _)) if f.sig.ident == "fine"));
+    assert!(matches!(&entries[2], Ok((syn::Item::Fn(f), _)) if f.sig.ident == "later"));
+    let Err(error) = &entries[1] else {
+        panic!("expected a parse error, got {:?}", entries[1]);
+    };
+    assert_eq!(
+        error.data_file.as_deref(),
+        Some(dir.join("default_1_1.jsonl").as_path())
+    );
+    assert_eq!(error.name, "broken");
+    assert_eq!(error.content, "pub fn broken( {");
+    assert_eq!(error.location.file, "src/lib.rs");
+    assert_eq!(error.location.group.as_deref(), Some("default"));
+    assert!(
+        error
+            .to_string()
+            .starts_with("Failed to parse record `broken` at src/lib.rs:4:1"),
+        "{error}"
+    );
+
+    // The raw record is still reported as captured
+    assert_eq!(source.raw_records().unwrap().count(), 3);
+    // `items_all` unwraps, so the bad record panics there
+    let panicked = std::panic::catch_unwind(|| source.items_all().count()).unwrap_err();
+    let message = panicked.downcast_ref::<String>().unwrap();
+    assert!(message.contains("`broken`"), "{message}");
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn try_items_all_reports_malformed_lines() {
+    let dir = unique_test_dir("source_malformed_line");
+    write_out_dir(
+        &dir,
+        &[(
+            "default_1_1.jsonl",
+            vec![located_fn_record("fine", "src/lib.rs", 1)],
+        )],
+    );
+    let data_file = dir.join("default_1_1.jsonl");
+    let mut content = fs::read_to_string(&data_file).unwrap();
+    content.push_str("{\"kind\":\"function\",\"name\":\n");
+    fs::write(&data_file, content).unwrap();
+
+    // Neither building nor iterating with `try_items_all` panics
+    let source = Source::builder(&dir)
+        .enable_feature_filtering(None::<&str>)
+        .enable_target_filtering(None::<&str>)
+        .try_build()
+        .unwrap();
+    let entries: Vec<_> = source.try_items_all().collect();
+    assert_eq!(entries.len(), 2);
+    let Err(error) = &entries[0] else {
+        panic!("expected a parse error, got {:?}", entries[0]);
+    };
+    assert!(matches!(&entries[1], Ok((syn::Item::Fn(f), _)) if f.sig.ident == "fine"));
+    assert_eq!(error.data_file.as_deref(), Some(data_file.as_path()));
+    assert_eq!(error.location.group.as_deref(), Some("default"));
+    assert_eq!(error.content, "{\"kind\":\"function\",\"name\":");
+    assert!(
+        error.to_string().starts_with(&format!(
+            "Failed to read a record from {}: line 2:",
+            data_file.display()
+        )),
+        "{error}"
+    );
+    fs::remove_dir_all(&dir).ok();
+}
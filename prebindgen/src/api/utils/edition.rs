@@ -1,3 +1,9 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
 /// Rust edition for code generation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RustEdition {
@@ -15,6 +21,118 @@ impl RustEdition {
             RustEdition::Edition2024 => "2024",
         }
     }
+
+    /// Detect the edition of the crate whose build script is running
+    ///
+    /// Cargo doesn't pass the edition to build scripts, so it is read from
+    /// `package.edition` in `$CARGO_MANIFEST_DIR/Cargo.toml`, following
+    /// `edition.workspace = true` to the enclosing workspace manifest. A
+    /// manifest without an edition is 2015 for Cargo, which generates like
+    /// 2021 here; editions before 2024 all map to [`RustEdition::Edition2021`].
+    ///
+    /// When no manifest can be read (not in a build script, unreadable or
+    /// unparsable manifest), a `CARGO_PKG_RUST_VERSION` below 1.85 still
+    /// rules out the 2024 edition; otherwise the [`Default`] edition of the
+    /// compiler is returned.
+    pub fn from_cargo_env() -> Self {
+        let rust_version = env::var("CARGO_PKG_RUST_VERSION").ok();
+        env::var_os("CARGO_MANIFEST_DIR")
+            .and_then(|dir| manifest_edition(Path::new(&dir)))
+            .unwrap_or_else(|| Self::fallback(rust_version.as_deref()))
+    }
+
+    /// [`Self::from_cargo_env`], detected once per process: the edition
+    /// generated items (e.g. `#[no_mangle]`) are spelled for
+    pub(crate) fn detected() -> Self {
+        static DETECTED: OnceLock<RustEdition> = OnceLock::new();
+        *DETECTED.get_or_init(Self::from_cargo_env)
+    }
+
+    /// `#[no_mangle]` as this edition requires it: `#[unsafe(no_mangle)]`
+    /// from 2024 on
+    pub(crate) fn no_mangle_attr(&self) -> proc_macro2::TokenStream {
+        match self {
+            RustEdition::Edition2024 => quote::quote!(#[unsafe(no_mangle)]),
+            RustEdition::Edition2021 => quote::quote!(#[no_mangle]),
+        }
+    }
+
+    /// `#[export_name = "<symbol>"]` as this edition requires it:
+    /// `#[unsafe(export_name = "<symbol>")]` from 2024 on
+    #[cfg(feature = "unstable-cbindgen")]
+    pub(crate) fn export_name_attr(&self, symbol: &str) -> proc_macro2::TokenStream {
+        match self {
+            RustEdition::Edition2024 => quote::quote!(#[unsafe(export_name = #symbol)]),
+            RustEdition::Edition2021 => quote::quote!(#[export_name = #symbol]),
+        }
+    }
+
+    /// Edition named by a Cargo `edition` value
+    fn from_edition_str(edition: &str) -> Option<Self> {
+        let year: u32 = edition.trim().parse().ok()?;
+        Some(if year >= 2024 {
+            RustEdition::Edition2024
+        } else {
+            RustEdition::Edition2021
+        })
+    }
+
+    /// Edition when no manifest is available, from the `rust-version` hint
+    fn fallback(rust_version: Option<&str>) -> Self {
+        let below_1_85 = rust_version
+            .and_then(parse_major_minor)
+            .is_some_and(|v| v < (1, 85));
+        if below_1_85 {
+            RustEdition::Edition2021
+        } else {
+            Self::default()
+        }
+    }
+}
+
+/// `package.edition` of the manifest in `dir`, inherited from the workspace
+/// manifest of an ancestor directory when set to `{ workspace = true }`
+fn manifest_edition(dir: &Path) -> Option<RustEdition> {
+    let manifest = read_manifest(&dir.join("Cargo.toml"))?;
+    let edition = manifest.get("package")?.get("edition");
+    match edition {
+        None => Some(RustEdition::Edition2021),
+        Some(toml::Value::String(s)) => RustEdition::from_edition_str(s),
+        Some(inherited) if inherited.get("workspace").and_then(|w| w.as_bool()) == Some(true) => {
+            workspace_edition(dir)
+        }
+        Some(_) => None,
+    }
+}
+
+/// `workspace.package.edition` of the nearest workspace manifest at or
+/// above `dir`
+fn workspace_edition(dir: &Path) -> Option<RustEdition> {
+    dir.ancestors()
+        .map(|d| d.join("Cargo.toml"))
+        .filter_map(|path: PathBuf| read_manifest(&path))
+        .find_map(|manifest| manifest.get("workspace").cloned())
+        .and_then(|workspace| {
+            let edition = workspace
+                .get("package")?
+                .get("edition")?
+                .as_str()?
+                .to_owned();
+            RustEdition::from_edition_str(&edition)
+        })
+}
+
+fn read_manifest(path: &Path) -> Option<toml::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    toml::from_str(&content).ok()
+}
+
+/// `(major, minor)` of a `rust-version` such as `1.85` or `1.85.1`
+fn parse_major_minor(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |m| m.parse().ok())?;
+    Some((major, minor))
 }
 
 impl std::fmt::Display for RustEdition {
@@ -33,3 +151,6 @@ impl Default for RustEdition {
         }}
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,61 @@
+use std::fs;
+
+use super::*;
+use crate::api::test_util::unique_test_dir;
+
+#[test]
+fn edition_is_read_from_package() {
+    let dir = unique_test_dir("edition_package");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"a\"\nedition = \"2024\"\n",
+    )
+    .unwrap();
+    assert_eq!(manifest_edition(&dir), Some(RustEdition::Edition2024));
+
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"a\"\nedition = \"2018\"\n",
+    )
+    .unwrap();
+    assert_eq!(manifest_edition(&dir), Some(RustEdition::Edition2021));
+
+    // No edition key means Cargo's 2015 default
+    fs::write(dir.join("Cargo.toml"), "[package]\nname = \"a\"\n").unwrap();
+    assert_eq!(manifest_edition(&dir), Some(RustEdition::Edition2021));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn edition_is_inherited_from_workspace() {
+    let root = unique_test_dir("edition_workspace");
+    let member = root.join("member");
+    fs::create_dir_all(&member).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"member\"]\npackage.edition = \"2024\"\n",
+    )
+    .unwrap();
+    fs::write(
+        member.join("Cargo.toml"),
+        "[package]\nname = \"member\"\nedition = { workspace = true }\n",
+    )
+    .unwrap();
+    assert_eq!(manifest_edition(&member), Some(RustEdition::Edition2024));
+    fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn fallback_uses_rust_version_hint() {
+    assert_eq!(
+        RustEdition::fallback(Some("1.70")),
+        RustEdition::Edition2021
+    );
+    assert_eq!(
+        RustEdition::fallback(Some("1.85.1")),
+        RustEdition::default()
+    );
+    assert_eq!(RustEdition::fallback(None), RustEdition::default());
+    assert_eq!(manifest_edition(&unique_test_dir("edition_missing")), None);
+}
@@ -30,24 +30,43 @@ pub fn write_to_jsonl_file<P: AsRef<Path>, R: Borrow<Record>>(
 pub fn read_jsonl_file<P: AsRef<Path>>(
     file_path: P,
 ) -> Result<Vec<Record>, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string(&file_path)?;
     let mut records = Vec::new();
-
-    // Parse JSON-lines format: each line is a separate JSON object
-    for (line_num, line) in content.lines().enumerate() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue; // Skip empty lines
-        }
-
-        let record: Record = serde_json::from_str(line)
-            .map_err(|e| format!("{}:{}: {}", file_path.as_ref().display(), line_num + 1, e))?;
-
+    for line in read_jsonl_lines(&file_path)? {
+        let record = line
+            .record
+            .map_err(|e| format!("{}:{}: {}", file_path.as_ref().display(), line.number, e))?;
         records.push(record);
     }
-
     Ok(records)
 }
 
+/// A non-empty line of a JSON-lines file
+pub(crate) struct JsonlLine {
+    /// 1-based line number
+    pub number: usize,
+    /// The line as read, trimmed
+    pub text: String,
+    /// The record the line holds, or why it doesn't hold one
+    pub record: Result<Record, serde_json::Error>,
+}
+
+/// Read every non-empty line of a JSON-lines file, keeping the lines that
+/// don't decode to a record next to the ones that do
+pub(crate) fn read_jsonl_lines<P: AsRef<Path>>(file_path: P) -> std::io::Result<Vec<JsonlLine>> {
+    let content = fs::read_to_string(&file_path)?;
+    // Parse JSON-lines format: each line is a separate JSON object
+    Ok(content
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(number, line)| JsonlLine {
+            number,
+            text: line.to_string(),
+            record: serde_json::from_str(line),
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests;
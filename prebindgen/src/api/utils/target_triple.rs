@@ -16,6 +16,8 @@ pub struct TargetTriple {
     vendor: Option<String>,
     os: Option<String>,
     env: Option<String>,
+    pointer_width: Option<String>,
+    families: Vec<String>,
 }
 
 fn extract_cfg_condition(s: &str, name: &str) -> Option<String> {
@@ -61,6 +63,8 @@ impl TargetTriple {
         let mut vendor = None;
         let mut os = None;
         let mut env = None;
+        let mut pointer_width = None;
+        let mut families = Vec::new();
 
         for line in stdout.lines() {
             if let Some(a) = extract_cfg_condition(line, "target_arch") {
@@ -71,6 +75,10 @@ impl TargetTriple {
                 os = Some(o);
             } else if let Some(e) = extract_cfg_condition(line, "target_env") {
                 env = Some(e);
+            } else if let Some(w) = extract_cfg_condition(line, "target_pointer_width") {
+                pointer_width = Some(w);
+            } else if let Some(f) = extract_cfg_condition(line, "target_family") {
+                families.push(f);
             }
         }
 
@@ -80,6 +88,8 @@ impl TargetTriple {
             vendor,
             os,
             env,
+            pointer_width,
+            families,
         };
 
         // Store in cache for subsequent calls.
@@ -112,6 +122,16 @@ impl TargetTriple {
         self.env.as_deref()
     }
 
+    /// Get the pointer width as string used by Rust cfg target_pointer_width.
+    pub fn pointer_width(&self) -> Option<&str> {
+        self.pointer_width.as_deref()
+    }
+
+    /// Get the families as strings used by Rust cfg target_family (e.g. "unix").
+    pub fn families(&self) -> &[String] {
+        &self.families
+    }
+
     /// Build a cfg expression TokenStream like:
     /// all(target_arch = "aarch64", target_vendor = "apple", target_os = "macos", target_env = "gnu")
     /// Omits target_env when unknown/empty.
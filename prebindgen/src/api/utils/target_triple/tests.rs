@@ -74,3 +74,10 @@ fn armv7_unknown_linux_gnueabihf() {
         tt.env()
     );
 }
+
+#[test]
+fn pointer_width_and_family() {
+    let tt = TargetTriple::parse("x86_64-unknown-linux-gnu").unwrap();
+    assert_eq!(tt.pointer_width(), Some("64"));
+    assert_eq!(tt.families(), ["unix".to_string()]);
+}
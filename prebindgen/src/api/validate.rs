@@ -0,0 +1,59 @@
+use crate::api::{core::registry::Registry, record::SourceLocation, source::Source};
+
+/// A `#[prebindgen]` item that can't cross the FFI boundary, found by
+/// [`validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Where the offending item is defined in the source crate
+    pub location: SourceLocation,
+    /// What is wrong with it, naming the location
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Check every item of `source` for signatures no binding can be generated
+/// for, without generating anything
+///
+/// Runs the signature checks the registry performs when it scans a declared
+/// function — variadics, generic type and const parameters and `where`
+/// bounds, `self` receivers, non-identifier parameter patterns, `impl Trait`
+/// other than `impl Fn(...) + Send + Sync + 'static` — over all functions
+/// instead of only the declared ones, and reports every problem instead of
+/// the first. Whether a type has a C lowering depends on the language
+/// adapter and isn't checked here. Meant for a CI step that lints the FFI
+/// surface.
+///
+/// # Example
+///
+/// ```
+/// # prebindgen::Source::init_doctest_simulate();
+/// let source = prebindgen::Source::new("source_ffi");
+/// assert!(prebindgen::validate(&source).is_empty());
+/// ```
+pub fn validate(source: &Source) -> Vec<ValidationIssue> {
+    let mut scratch = Registry::<()>::default();
+    source
+        .items_all()
+        .flat_map(|(item, location)| {
+            let syn::Item::Fn(f) = &item else {
+                return Vec::new();
+            };
+            scratch
+                .scan_fn_signature(f, &location)
+                .into_iter()
+                .map(|error| ValidationIssue {
+                    location: location.clone(),
+                    message: error.to_string(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
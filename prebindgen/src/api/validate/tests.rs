@@ -0,0 +1,95 @@
+use std::fs;
+
+use super::*;
+use crate::api::{
+    record::{Record, RecordKind},
+    test_util::unique_test_dir,
+    utils::jsonl::write_to_jsonl_file,
+};
+
+fn fn_record(content: &str, line: usize) -> Record {
+    let item: syn::ItemFn = syn::parse_str(content).unwrap();
+    Record::new(
+        RecordKind::Function,
+        item.sig.ident.to_string(),
+        content.to_string(),
+        SourceLocation {
+            file: "src/lib.rs".to_string(),
+            line,
+            column: 1,
+            ..SourceLocation::default()
+        },
+        None,
+    )
+}
+
+#[test]
+fn validate_reports_every_unsupported_signature() {
+    let dir = unique_test_dir("validate_issues");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(crate::CRATE_NAME_FILE), "source_ffi").unwrap();
+    fs::write(dir.join(crate::FEATURES_FILE), "").unwrap();
+    let records = [
+        "pub fn ok(a: u32, on_event: impl Fn(u32) + Send + Sync + 'static) -> Option<u32> { None }",
+        "pub fn generic<'a, T: Copy, const N: usize>(v: &'a [T; N]) {}",
+        "pub fn pattern((a, b): (u32, u32)) -> impl Iterator<Item = u32> { [a, b].into_iter() }",
+        "pub fn nested(items: Vec<impl Send>) {}",
+        "pub fn method(&self) {}",
+        "pub fn bounded(x: u8) where u8: Copy {}",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(line, content)| fn_record(content, line + 1))
+    .collect::<Vec<_>>();
+    write_to_jsonl_file(dir.join("default_1_1.jsonl"), &records).unwrap();
+    let source = Source::builder(&dir)
+        .enable_feature_filtering(None::<&str>)
+        .enable_target_filtering(None::<&str>)
+        .build();
+
+    let issues: Vec<(usize, String)> = validate(&source)
+        .into_iter()
+        .map(|issue| (issue.location.line, issue.message))
+        .collect();
+    assert_eq!(
+        issues,
+        vec![
+            (
+                2,
+                "generic functions cannot be exported: `generic` has `T : Copy, const N : usize` \
+                 at src/lib.rs:2:1 — only lifetime parameters are allowed; export a non-generic \
+                 wrapper per instantiation instead"
+                    .to_string()
+            ),
+            (
+                3,
+                "non-ident parameter pattern is not supported at src/lib.rs:3:1".to_string()
+            ),
+            (
+                3,
+                "`impl Trait` is not allowed at src/lib.rs:3:1: `impl Iterator < Item = u32 >` \
+                 (only `impl Fn(...) + Send + Sync + 'static` is supported)"
+                    .to_string()
+            ),
+            (
+                4,
+                "`impl Trait` is not allowed at src/lib.rs:4:1: `impl Send` (only \
+                 `impl Fn(...) + Send + Sync + 'static` is supported)"
+                    .to_string()
+            ),
+            (
+                5,
+                "method receiver (`self`) parameters are not supported at src/lib.rs:5:1"
+                    .to_string()
+            ),
+            (
+                6,
+                "generic functions cannot be exported: `bounded` has `where u8 : Copy` at \
+                 src/lib.rs:6:1 — only lifetime parameters are allowed; export a non-generic \
+                 wrapper per instantiation instead"
+                    .to_string()
+            ),
+        ]
+    );
+    fs::remove_dir_all(&dir).ok();
+}
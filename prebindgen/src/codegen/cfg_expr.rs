@@ -4,6 +4,8 @@
 //! that include logical operators like `any`, `all`, and `not`, as well as simple
 //! feature checks.
 
+use quote::ToTokens;
+
 use crate::{codegen::CfgExprRules, SourceLocation};
 
 /// Represents a cfg expression that can be evaluated against a set of enabled/disabled features
@@ -20,6 +22,11 @@ pub enum CfgExpr {
     TargetOs(String),
     /// Target environment check: `target_env = "env"`
     TargetEnv(String),
+    /// Target pointer width check: `target_pointer_width = "64"`
+    TargetPointerWidth(String),
+    /// Target family check: `target_family = "unix"`, or the `unix` /
+    /// `windows` shorthands
+    TargetFamily(String),
 
     // Logical operators next
     /// Logical NOT: `not(expr)`
@@ -46,69 +53,121 @@ impl CfgExpr {
     /// Parse a cfg expression from a string representation
     pub fn parse_from_string(input: &str) -> Result<Self, String> {
         let input = input.trim();
-
-        // Handle logical expressions first (before simple features)
-        if let Some(inner) = strip_function_call(input, "not") {
-            let inner_expr = Self::parse_from_string(&inner)?;
-            return Ok(CfgExpr::Not(Box::new(inner_expr)));
-        }
-
-        if let Some(inner) = strip_function_call(input, "all") {
-            let exprs = parse_comma_separated(&inner)?;
-            return Ok(CfgExpr::All(exprs));
-        }
-
-        if let Some(inner) = strip_function_call(input, "any") {
-            let exprs = parse_comma_separated(&inner)?;
-            return Ok(CfgExpr::Any(exprs));
-        }
-
-        // Handle simple feature expressions
-        if let Some(feature_name) = extract_simple_feature(input) {
-            return Ok(CfgExpr::Feature(feature_name));
-        }
-
-        // Handle target_arch expressions
-        if let Some(arch) = extract_target_arch(input) {
-            return Ok(CfgExpr::TargetArch(arch));
-        }
-        // Handle target_vendor expressions
-        if let Some(vendor) = extract_target_vendor(input) {
-            return Ok(CfgExpr::TargetVendor(vendor));
+        match syn::parse_str::<syn::Meta>(input) {
+            Ok(meta) => Self::parse_from_meta(&meta),
+            // Not a single meta item: keep it verbatim
+            Err(_) => Ok(CfgExpr::Other(input.to_string())),
         }
+    }
 
-        // Handle target_os expressions
-        if let Some(os) = extract_target_os(input) {
-            return Ok(CfgExpr::TargetOs(os));
+    /// Parse a cfg predicate structurally from its `syn::Meta` form
+    fn parse_from_meta(meta: &syn::Meta) -> Result<Self, String> {
+        match meta {
+            syn::Meta::List(list) => {
+                let Some(op) = list.path.get_ident().map(|i| i.to_string()) else {
+                    return Ok(CfgExpr::Other(meta.to_token_stream().to_string()));
+                };
+                if !matches!(op.as_str(), "not" | "all" | "any") {
+                    return Ok(CfgExpr::Other(meta.to_token_stream().to_string()));
+                }
+                let args = list
+                    .parse_args_with(
+                        syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                    )
+                    .map_err(|e| format!("invalid `{op}(...)` cfg predicate: {e}"))?;
+                let mut exprs = args
+                    .iter()
+                    .map(Self::parse_from_meta)
+                    .collect::<Result<Vec<_>, _>>()?;
+                match op.as_str() {
+                    "not" => match (exprs.pop(), exprs.is_empty()) {
+                        (Some(inner), true) => Ok(CfgExpr::Not(Box::new(inner))),
+                        _ => Err(format!(
+                            "`not(...)` takes exactly one cfg predicate, got {}",
+                            args.len()
+                        )),
+                    },
+                    "all" => Ok(CfgExpr::All(exprs)),
+                    _ => Ok(CfgExpr::Any(exprs)),
+                }
+            }
+            syn::Meta::NameValue(nv) => {
+                let value = match &nv.value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }) => s.value(),
+                    _ => return Ok(CfgExpr::Other(meta.to_token_stream().to_string())),
+                };
+                let name = nv.path.get_ident().map(|i| i.to_string());
+                Ok(match name.as_deref() {
+                    Some("feature") => CfgExpr::Feature(value),
+                    Some("target_arch") => CfgExpr::TargetArch(value),
+                    Some("target_vendor") => CfgExpr::TargetVendor(value),
+                    Some("target_os") => CfgExpr::TargetOs(value),
+                    Some("target_env") => CfgExpr::TargetEnv(value),
+                    Some("target_pointer_width") => CfgExpr::TargetPointerWidth(value),
+                    Some("target_family") => CfgExpr::TargetFamily(value),
+                    _ => CfgExpr::Other(meta.to_token_stream().to_string()),
+                })
+            }
+            syn::Meta::Path(path) if path.is_ident("unix") || path.is_ident("windows") => {
+                Ok(CfgExpr::TargetFamily(path.to_token_stream().to_string()))
+            }
+            syn::Meta::Path(path) => Ok(CfgExpr::Other(path.to_token_stream().to_string())),
         }
+    }
 
-        // Handle target_env expressions
-        if let Some(env) = extract_target_env(input) {
-            return Ok(CfgExpr::TargetEnv(env));
+    /// Whether `self` and `other` can never hold at the same time
+    ///
+    /// Conservative: `true` only for `X` vs `not(X)` and for different values
+    /// of a single-valued target key (`target_os = "linux"` vs
+    /// `target_os = "windows"`), through `all`/`any` nesting. Target families
+    /// are not single-valued (`unix` and `wasm` can both be set), so different
+    /// families never exclude each other.
+    pub fn excludes(&self, other: &CfgExpr) -> bool {
+        match (self, other) {
+            (CfgExpr::Not(a), b) | (b, CfgExpr::Not(a)) if **a == *b => true,
+            (CfgExpr::All(xs), y) | (y, CfgExpr::All(xs)) => xs.iter().any(|x| x.excludes(y)),
+            (CfgExpr::Any(xs), y) | (y, CfgExpr::Any(xs)) => {
+                !xs.is_empty() && xs.iter().all(|x| x.excludes(y))
+            }
+            (CfgExpr::TargetArch(a), CfgExpr::TargetArch(b))
+            | (CfgExpr::TargetVendor(a), CfgExpr::TargetVendor(b))
+            | (CfgExpr::TargetOs(a), CfgExpr::TargetOs(b))
+            | (CfgExpr::TargetEnv(a), CfgExpr::TargetEnv(b))
+            | (CfgExpr::TargetPointerWidth(a), CfgExpr::TargetPointerWidth(b)) => a != b,
+            _ => false,
         }
-
-        // If we can't parse it, store it as "Other"
-        Ok(CfgExpr::Other(input.to_string()))
     }
 
     /// Process features according to the rules:
     /// - Features in enabled list: replaced with true and removed from expression
-    /// - Features in disabled list: replaced with false and removed from expression  
+    /// - Features in disabled list: replaced with false and removed from expression
     /// - Features in mapping list: renamed
-    /// - Any unmapped feature remaining: panic with "unmapped feature"
+    /// - Any unmapped feature remaining: kept as is if
+    ///   `rules.keep_unmapped_features`, otherwise pushed to `unmapped` with its
+    ///   source location and replaced with false
     /// - If architecture, os, target, env condition is specified, replace
     ///   matching condition with false and unmatching to true
     /// - If architecture, os, target, env condition is not specified, leave as is
+    /// - Bare predicates in `rules.forbidden_cfgs` (`test`) replaced with false
     pub fn apply_rules(
         &self,
         rules: &CfgExprRules,
         source_location: &SourceLocation,
+        unmapped: &mut Vec<(String, SourceLocation)>,
     ) -> Option<Self> {
         match self {
             CfgExpr::Feature(name) => {
                 if rules.enabled_features.contains(name) {
-                    // Feature is enabled - replace with true (remove from expression)
-                    None // This means "always true", caller should handle removal
+                    if rules.keep_enabled_features {
+                        // Feature is enabled but the cfg is kept for the destination crate
+                        Some(self.clone())
+                    } else {
+                        // Feature is enabled - replace with true (remove from expression)
+                        None // This means "always true", caller should handle removal
+                    }
                 } else if rules.disabled_features.contains(name) {
                     // Feature is disabled - replace with false (remove from expression)
                     Some(CfgExpr::False) // Explicit false value
@@ -117,10 +176,17 @@ impl CfgExpr {
                     Some(CfgExpr::Feature(new_name.clone()))
                 } else if rules.disable_unknown_features {
                     // Treat unknown feature as disabled
+                    if rules.report_unknown_features {
+                        unmapped.push((name.clone(), source_location.clone()));
+                    }
                     Some(CfgExpr::False)
+                } else if rules.keep_unmapped_features {
+                    // Leave the unmapped feature for the destination crate's cfg
+                    Some(self.clone())
                 } else {
-                    // Unmapped feature - panic with source location information
-                    panic!("unmapped feature: {name} (at {source_location})");
+                    // Unmapped feature - report it with its source location
+                    unmapped.push((name.clone(), source_location.clone()));
+                    Some(CfgExpr::False)
                 }
             }
             CfgExpr::TargetVendor(val) => {
@@ -148,7 +214,7 @@ impl CfgExpr {
             CfgExpr::All(exprs) => {
                 let mut processed_exprs = Vec::new();
                 for expr in exprs {
-                    match expr.apply_rules(rules, source_location) {
+                    match expr.apply_rules(rules, source_location, unmapped) {
                         Some(CfgExpr::False) => {
                             // If any expression in All is false, the whole All is false
                             return Some(CfgExpr::False);
@@ -169,7 +235,7 @@ impl CfgExpr {
                 let mut processed_exprs = Vec::new();
                 let mut has_true = false;
                 for expr in exprs {
-                    match expr.apply_rules(rules, source_location) {
+                    match expr.apply_rules(rules, source_location, unmapped) {
                         Some(CfgExpr::False) => {
                             // False expressions in Any can be omitted
                         }
@@ -191,7 +257,7 @@ impl CfgExpr {
                 }
             }
             CfgExpr::Not(expr) => {
-                match expr.apply_rules(rules, source_location) {
+                match expr.apply_rules(rules, source_location, unmapped) {
                     Some(CfgExpr::False) => None, // not(false) = true
                     Some(processed) => Some(CfgExpr::Not(Box::new(processed))),
                     None => Some(CfgExpr::False), // not(true) = false
@@ -219,6 +285,27 @@ impl CfgExpr {
                     Some(self.clone())
                 }
             }
+            CfgExpr::TargetPointerWidth(val) => {
+                if let Some(sel) = rules.enabled_target_pointer_width.as_ref() {
+                    if val == sel {
+                        None
+                    } else {
+                        Some(CfgExpr::False)
+                    }
+                } else {
+                    Some(self.clone())
+                }
+            }
+            CfgExpr::TargetFamily(val) => {
+                if rules.enabled_target_families.is_empty() {
+                    Some(self.clone())
+                } else if rules.enabled_target_families.contains(val) {
+                    None
+                } else {
+                    Some(CfgExpr::False)
+                }
+            }
+            CfgExpr::Other(name) if rules.forbidden_cfgs.contains(name) => Some(CfgExpr::False),
             CfgExpr::Other(_) => Some(self.clone()),
             CfgExpr::False => Some(CfgExpr::False),
         }
@@ -242,6 +329,12 @@ impl CfgExpr {
             CfgExpr::TargetEnv(env) => {
                 quote::quote! { target_env = #env }
             }
+            CfgExpr::TargetPointerWidth(width) => {
+                quote::quote! { target_pointer_width = #width }
+            }
+            CfgExpr::TargetFamily(family) => {
+                quote::quote! { target_family = #family }
+            }
             CfgExpr::All(exprs) => {
                 let tokens: Vec<_> = exprs.iter().map(|e| e.to_tokens()).collect();
                 quote::quote! { all(#(#tokens),*) }
@@ -272,121 +365,17 @@ impl CfgExpr {
     }
 }
 
-/// Extract a simple feature name from expressions like `feature = "name"`
-fn extract_simple_feature(input: &str) -> Option<String> {
-    use regex::Regex;
-    let feature_regex = Regex::new(r#"feature\s*=\s*"([^"]+)""#).unwrap();
-
-    feature_regex
-        .captures(input)
-        .map(|captures| captures[1].to_string())
-}
-
-/// Extract target architecture from expressions like `target_arch = "x86_64"`
-fn extract_target_arch(input: &str) -> Option<String> {
-    use regex::Regex;
-    let arch_regex = Regex::new(r#"target_arch\s*=\s*"([^"]+)""#).unwrap();
-
-    arch_regex
-        .captures(input)
-        .map(|captures| captures[1].to_string())
-}
-
-/// Extract target vendor from expressions like `target_vendor = "apple"`
-fn extract_target_vendor(input: &str) -> Option<String> {
-    use regex::Regex;
-    let re = Regex::new(r#"target_vendor\s*=\s*\"([^\"]+)\""#).unwrap();
-    re.captures(input).map(|c| c[1].to_string())
-}
-
-/// Extract target OS from expressions like `target_os = "macos"`
-fn extract_target_os(input: &str) -> Option<String> {
-    use regex::Regex;
-    let re = Regex::new(r#"target_os\s*=\s*\"([^\"]+)\""#).unwrap();
-    re.captures(input).map(|c| c[1].to_string())
-}
-
-/// Extract target env from expressions like `target_env = "gnu"`
-fn extract_target_env(input: &str) -> Option<String> {
-    use regex::Regex;
-    let re = Regex::new(r#"target_env\s*=\s*\"([^\"]+)\""#).unwrap();
-    re.captures(input).map(|c| c[1].to_string())
-}
-
-/// Strip a function call wrapper, returning the inner content
-/// For example: `not(feature = "test")` -> `feature = "test"`
-fn strip_function_call(input: &str, function_name: &str) -> Option<String> {
-    let input = input.trim();
-
-    // Handle function calls with or without spaces: "not(" and "not ("
-    let pattern1 = format!("{function_name}(");
-    let pattern2 = format!("{function_name} (");
-
-    if (input.starts_with(&pattern1) || input.starts_with(&pattern2)) && input.ends_with(')') {
-        let start = if input.starts_with(&pattern1) {
-            pattern1.len()
-        } else {
-            pattern2.len()
-        };
-        let end = input.len() - 1;
-
-        if start < end {
-            Some(input[start..end].to_string())
-        } else {
-            Some(String::new())
-        }
-    } else {
-        None
-    }
-}
-
-/// Parse comma-separated expressions
-fn parse_comma_separated(input: &str) -> Result<Vec<CfgExpr>, String> {
-    let mut exprs = Vec::new();
-    let mut current = String::new();
-    let mut paren_depth = 0;
-    let mut in_quotes = false;
-    let mut escape_next = false;
-
-    for ch in input.chars() {
-        if escape_next {
-            current.push(ch);
-            escape_next = false;
-            continue;
-        }
-
-        match ch {
-            '\\' => {
-                escape_next = true;
-                current.push(ch);
-            }
-            '"' => {
-                in_quotes = !in_quotes;
-                current.push(ch);
-            }
-            '(' if !in_quotes => {
-                paren_depth += 1;
-                current.push(ch);
-            }
-            ')' if !in_quotes => {
-                paren_depth -= 1;
-                current.push(ch);
-            }
-            ',' if !in_quotes && paren_depth == 0 => {
-                if !current.trim().is_empty() {
-                    exprs.push(CfgExpr::parse_from_string(current.trim())?);
-                }
-                current.clear();
-            }
-            _ => {
-                current.push(ch);
-            }
-        }
-    }
-
-    if !current.trim().is_empty() {
-        exprs.push(CfgExpr::parse_from_string(current.trim())?);
-    }
-
-    Ok(exprs)
+/// Whether items carrying `a` and `b` can never be compiled together: some
+/// `#[cfg]` of one excludes some `#[cfg]` of the other.
+pub(crate) fn cfgs_exclude(a: &[syn::Attribute], b: &[syn::Attribute]) -> bool {
+    let cfgs = |attrs: &[syn::Attribute]| -> Vec<CfgExpr> {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .filter_map(|attr| attr.meta.require_list().ok())
+            .filter_map(|list| CfgExpr::parse_from_tokens(&list.tokens).ok())
+            .collect()
+    };
+    let b = cfgs(b);
+    cfgs(a).iter().any(|x| b.iter().any(|y| x.excludes(y)))
 }
@@ -13,6 +13,15 @@ pub struct CfgExprRules {
     pub feature_mappings: HashMap<String, String>,
     /// If true, unknown features are treated as disabled (skipped) instead of causing an error
     pub disable_unknown_features: bool,
+    /// If true, unknown features treated as disabled are still reported as
+    /// unmapped
+    pub report_unknown_features: bool,
+    /// If true, unmapped features are left in the cfg expression unchanged
+    /// instead of being reported
+    pub keep_unmapped_features: bool,
+    /// If true, enabled features are left in the cfg expression instead of
+    /// being replaced with true
+    pub keep_enabled_features: bool,
     /// If Some, replace matching target architecture to true and any other to false
     pub enabled_target_arch: Option<String>,
     /// If Some, replace matching target vendor to true and any other to false
@@ -21,6 +30,13 @@ pub struct CfgExprRules {
     pub enabled_target_os: Option<String>,
     /// If Some, replace matching target environment to true and any other to false
     pub enabled_target_env: Option<String>,
+    /// If Some, replace matching target pointer width to true and any other to false
+    pub enabled_target_pointer_width: Option<String>,
+    /// If not empty, replace listed target families to true and any other to false
+    pub enabled_target_families: HashSet<String>,
+    /// Bare cfg predicates (`test`, `debug_assertions`) replaced to false, so
+    /// the code they guard is dropped
+    pub forbidden_cfgs: HashSet<String>,
 }
 
 impl CfgExprRules {
@@ -34,6 +50,9 @@ impl CfgExprRules {
             || self.enabled_target_vendor.is_some()
             || self.enabled_target_os.is_some()
             || self.enabled_target_env.is_some()
+            || self.enabled_target_pointer_width.is_some()
+            || !self.enabled_target_families.is_empty()
+            || !self.forbidden_cfgs.is_empty()
     }
 }
 
@@ -5,6 +5,7 @@
 //! - Removing code blocks guarded by disabled features
 //! - Removing cfg attributes for enabled features (keeping the code)
 //! - Replacing feature names according to the mapping
+//! - Resolving `#[cfg_attr(...)]` conditions the same way
 
 use roxygen::roxygen;
 
@@ -26,34 +27,45 @@ pub(crate) fn process_item_features(
     rules: &CfgExprRules,
     /// Source location information for error reporting
     source_location: &SourceLocation,
+    /// Collects features no rule maps, with the location they appear at
+    unmapped: &mut Vec<(String, SourceLocation)>,
 ) -> bool {
     let attrs = match item {
         syn::Item::Fn(f) => {
             // Process function parameters recursively (so `#[cfg(...)]` on an
             // individual parameter is honored, just like struct fields / enum
             // variants) — avoids needing two whole-function variants.
-            process_fn_inputs(&mut f.sig.inputs, rules, source_location);
+            process_fn_inputs(&mut f.sig.inputs, rules, source_location, unmapped);
             &mut f.attrs
         }
         syn::Item::Struct(s) => {
             // Process struct fields recursively
-            process_struct_fields(&mut s.fields, rules, source_location);
+            process_struct_fields(&mut s.fields, rules, source_location, unmapped);
             &mut s.attrs
         }
         syn::Item::Enum(e) => {
             // Process enum variants recursively
-            process_enum_variants(&mut e.variants, rules, source_location);
+            process_enum_variants(&mut e.variants, rules, source_location, unmapped);
             &mut e.attrs
         }
         syn::Item::Union(u) => {
             // Process union fields recursively
-            process_union_fields(&mut u.fields, rules, source_location);
+            process_union_fields(&mut u.fields, rules, source_location, unmapped);
             &mut u.attrs
         }
         syn::Item::Type(t) => &mut t.attrs,
         syn::Item::Const(c) => &mut c.attrs,
         syn::Item::Static(s) => &mut s.attrs,
-        syn::Item::Mod(m) => &mut m.attrs,
+        syn::Item::Mod(m) => {
+            // Process inline module items recursively, dropping the ones
+            // guarded by disabled features
+            if let Some((_, items)) = &mut m.content {
+                items.retain_mut(|item| {
+                    process_item_features(item, rules, source_location, unmapped)
+                });
+            }
+            &mut m.attrs
+        }
         syn::Item::Use(u) => &mut u.attrs,
         syn::Item::Impl(i) => &mut i.attrs,
         syn::Item::Trait(t) => &mut t.attrs,
@@ -61,7 +73,7 @@ pub(crate) fn process_item_features(
     };
 
     // Use the centralized attribute processing function
-    process_attributes(attrs, rules, source_location)
+    process_attributes(attrs, rules, source_location, unmapped)
 }
 
 /// Process struct fields for feature flags
@@ -69,6 +81,7 @@ fn process_struct_fields(
     fields: &mut syn::Fields,
     rules: &CfgExprRules,
     source_location: &SourceLocation,
+    unmapped: &mut Vec<(String, SourceLocation)>,
 ) {
     match fields {
         syn::Fields::Named(fields_named) => {
@@ -76,7 +89,7 @@ fn process_struct_fields(
             let mut new_fields = syn::punctuated::Punctuated::new();
             for field in fields_named.named.pairs() {
                 let mut field = field.into_value().clone();
-                if process_attributes(&mut field.attrs, rules, source_location) {
+                if process_attributes(&mut field.attrs, rules, source_location, unmapped) {
                     new_fields.push(field);
                 }
             }
@@ -87,7 +100,7 @@ fn process_struct_fields(
             let mut new_fields = syn::punctuated::Punctuated::new();
             for field in fields_unnamed.unnamed.pairs() {
                 let mut field = field.into_value().clone();
-                if process_attributes(&mut field.attrs, rules, source_location) {
+                if process_attributes(&mut field.attrs, rules, source_location, unmapped) {
                     new_fields.push(field);
                 }
             }
@@ -109,13 +122,18 @@ fn process_fn_inputs(
     inputs: &mut syn::punctuated::Punctuated<syn::FnArg, syn::Token![,]>,
     rules: &CfgExprRules,
     source_location: &SourceLocation,
+    unmapped: &mut Vec<(String, SourceLocation)>,
 ) {
     let mut new_inputs = syn::punctuated::Punctuated::new();
     for input_pair in inputs.pairs() {
         let mut input = input_pair.into_value().clone();
         let keep = match &mut input {
-            syn::FnArg::Typed(pt) => process_attributes(&mut pt.attrs, rules, source_location),
-            syn::FnArg::Receiver(r) => process_attributes(&mut r.attrs, rules, source_location),
+            syn::FnArg::Typed(pt) => {
+                process_attributes(&mut pt.attrs, rules, source_location, unmapped)
+            }
+            syn::FnArg::Receiver(r) => {
+                process_attributes(&mut r.attrs, rules, source_location, unmapped)
+            }
         };
         if keep {
             new_inputs.push(input);
@@ -129,17 +147,18 @@ fn process_enum_variants(
     variants: &mut syn::punctuated::Punctuated<syn::Variant, syn::Token![,]>,
     rules: &CfgExprRules,
     source_location: &SourceLocation,
+    unmapped: &mut Vec<(String, SourceLocation)>,
 ) {
     // Manual filtering since Punctuated doesn't have retain_mut
     let mut new_variants = syn::punctuated::Punctuated::new();
     for variant_pair in variants.pairs() {
         let mut variant = variant_pair.into_value().clone();
         // Process variant attributes
-        let keep_variant = process_attributes(&mut variant.attrs, rules, source_location);
+        let keep_variant = process_attributes(&mut variant.attrs, rules, source_location, unmapped);
 
         if keep_variant {
             // Process variant fields if it's kept
-            process_struct_fields(&mut variant.fields, rules, source_location);
+            process_struct_fields(&mut variant.fields, rules, source_location, unmapped);
             new_variants.push(variant);
         }
     }
@@ -151,12 +170,13 @@ fn process_union_fields(
     fields: &mut syn::FieldsNamed,
     rules: &CfgExprRules,
     source_location: &SourceLocation,
+    unmapped: &mut Vec<(String, SourceLocation)>,
 ) {
     // Manual filtering since Punctuated doesn't have retain_mut
     let mut new_fields = syn::punctuated::Punctuated::new();
     for field_pair in fields.named.pairs() {
         let mut field = field_pair.into_value().clone();
-        if process_attributes(&mut field.attrs, rules, source_location) {
+        if process_attributes(&mut field.attrs, rules, source_location, unmapped) {
             new_fields.push(field);
         }
     }
@@ -164,15 +184,25 @@ fn process_union_fields(
 }
 
 /// Process attributes for feature flags and return whether the item should be kept
+///
+/// Every `#[cfg]` is evaluated: the item is kept only if none of them is
+/// false (Rust treats several `#[cfg]`s as their conjunction), the ones that
+/// hold are removed and the rest keep their processed expression.
+///
+/// A `#[cfg_attr(condition, attrs..)]` is resolved the same way as a `#[cfg]`:
+/// when the condition holds its attributes are applied unconditionally, when
+/// it is false the whole `cfg_attr` is dropped, and otherwise the remaining
+/// condition (with mapped feature names) is kept.
 fn process_attributes(
     attrs: &mut Vec<syn::Attribute>,
     rules: &CfgExprRules,
     source_location: &SourceLocation,
+    unmapped: &mut Vec<(String, SourceLocation)>,
 ) -> bool {
     let mut keep_item = true;
-    let mut remove_attrs = Vec::new();
+    let mut processed = Vec::with_capacity(attrs.len());
 
-    for (i, attr) in attrs.iter_mut().enumerate() {
+    for mut attr in std::mem::take(attrs) {
         // Check if this is a cfg attribute
         if attr.path().is_ident("cfg") {
             // Parse the meta to extract cfg information
@@ -181,13 +211,16 @@ fn process_attributes(
                 match CfgExpr::parse_from_tokens(&meta_list.tokens) {
                     Ok(cfg_expr) => {
                         // Apply strict feature processing
-                        match cfg_expr.apply_rules(rules, source_location) {
+                        match cfg_expr.apply_rules(rules, source_location, unmapped) {
                             Some(processed_expr) => {
                                 // Check if the processed expression is CfgExpr::False
                                 if matches!(processed_expr, CfgExpr::False) {
-                                    // Expression evaluates to false, exclude this item
+                                    // Expression evaluates to false, exclude this item.
+                                    // Several `#[cfg]`s are a conjunction; the rest are
+                                    // still processed, so unmapped features are reported
+                                    // regardless of the attribute order
                                     keep_item = false;
-                                    break;
+                                    continue;
                                 } else {
                                     // Expression still exists after processing, update the cfg attribute
                                     let new_tokens = processed_expr.to_tokens();
@@ -199,7 +232,7 @@ fn process_attributes(
                             }
                             None => {
                                 // Expression evaluates to true, remove the cfg attribute
-                                remove_attrs.push(i);
+                                continue;
                             }
                         }
                     }
@@ -209,16 +242,68 @@ fn process_attributes(
                     }
                 }
             }
+        } else if attr.path().is_ident("cfg_attr") {
+            if let Some((cfg_expr, metas)) = split_cfg_attr(&attr.meta) {
+                match cfg_expr.apply_rules(rules, source_location, unmapped) {
+                    // Condition is false, the conditional attributes never apply
+                    Some(CfgExpr::False) => continue,
+                    // Condition still open, keep it with the processed expression
+                    Some(processed_expr) => {
+                        let new_tokens = processed_expr.to_tokens();
+                        attr.meta = syn::parse_quote! {
+                            cfg_attr(#new_tokens, #(#metas),*)
+                        };
+                    }
+                    // Condition holds, apply the inner attributes unconditionally
+                    None => {
+                        for meta in metas {
+                            let mut inner = attr.clone();
+                            inner.meta = meta;
+                            processed.push(inner);
+                        }
+                        continue;
+                    }
+                }
+            }
+            // Unparseable `cfg_attr` is left as-is, like an unparseable `cfg`
         }
+        processed.push(attr);
     }
 
-    // Remove attributes that should be removed (in reverse order to maintain indices)
-    for &i in remove_attrs.iter().rev() {
-        attrs.remove(i);
-    }
-
+    *attrs = processed;
     keep_item
 }
 
+/// Split a `cfg_attr(condition, attr, ..)` meta into its parsed condition and
+/// the conditional attributes. `None` if it doesn't have that shape.
+fn split_cfg_attr(meta: &syn::Meta) -> Option<(CfgExpr, Vec<syn::Meta>)> {
+    let syn::Meta::List(meta_list) = meta else {
+        return None;
+    };
+    let mut parts: Vec<proc_macro2::TokenStream> = vec![proc_macro2::TokenStream::new()];
+    for tt in meta_list.tokens.clone() {
+        match &tt {
+            proc_macro2::TokenTree::Punct(p) if p.as_char() == ',' => {
+                parts.push(proc_macro2::TokenStream::new())
+            }
+            _ => parts.last_mut()?.extend([tt]),
+        }
+    }
+    // A trailing comma leaves an empty last part
+    if parts.last().is_some_and(|part| part.is_empty()) {
+        parts.pop();
+    }
+    let mut parts = parts.into_iter();
+    let cfg_expr = CfgExpr::parse_from_tokens(&parts.next()?).ok()?;
+    let metas = parts
+        .map(syn::parse2::<syn::Meta>)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    if metas.is_empty() {
+        return None;
+    }
+    Some((cfg_expr, metas))
+}
+
 #[cfg(test)]
 mod tests;
@@ -27,7 +27,8 @@ fn cfg_on_fn_parameter_is_filtered() {
     assert!(process_item_features(
         &mut item,
         &rules(&[], &["unstable"]),
-        &src
+        &src,
+        &mut Vec::new()
     ));
     let s = item.to_token_stream().to_string();
     assert!(s.contains("a : i32"), "{s}");
@@ -39,7 +40,8 @@ fn cfg_on_fn_parameter_is_filtered() {
     assert!(process_item_features(
         &mut item,
         &rules(&["unstable"], &[]),
-        &src
+        &src,
+        &mut Vec::new()
     ));
     let s = item.to_token_stream().to_string();
     assert!(s.contains("a : i32"), "{s}");
@@ -60,7 +62,8 @@ fn fn_parameters_without_cfg_are_preserved() {
     assert!(process_item_features(
         &mut item,
         &rules(&[], &["unstable"]),
-        &src
+        &src,
+        &mut Vec::new()
     ));
     let s = item.to_token_stream().to_string();
     assert!(
@@ -68,3 +71,164 @@ fn fn_parameters_without_cfg_are_preserved() {
         "{s}"
     );
 }
+
+/// A `#[cfg_attr]` gated by an enabled feature applies its attribute
+/// unconditionally; a disabled one is dropped; a mapped one is renamed.
+#[test]
+fn cfg_attr_is_resolved() {
+    let src = SourceLocation::default();
+    let make = || -> syn::Item {
+        syn::parse_quote! {
+            #[cfg_attr(feature = "ffi", repr(C), derive(Clone))]
+            pub struct S { pub a: i32 }
+        }
+    };
+
+    let mut item = make();
+    assert!(process_item_features(
+        &mut item,
+        &rules(&["ffi"], &[]),
+        &src,
+        &mut Vec::new()
+    ));
+    let s = item.to_token_stream().to_string();
+    assert!(
+        s.contains("# [repr (C)] # [derive (Clone)] pub struct S"),
+        "{s}"
+    );
+    assert!(!s.contains("cfg_attr"), "{s}");
+
+    let mut item = make();
+    assert!(process_item_features(
+        &mut item,
+        &rules(&[], &["ffi"]),
+        &src,
+        &mut Vec::new()
+    ));
+    let s = item.to_token_stream().to_string();
+    assert!(!s.contains("repr"), "{s}");
+    assert!(!s.contains("cfg_attr"), "{s}");
+
+    let mut item = make();
+    let mapped = CfgExprRules {
+        feature_mappings: [("ffi".to_string(), "c_api".to_string())].into(),
+        ..Default::default()
+    };
+    assert!(process_item_features(
+        &mut item,
+        &mapped,
+        &src,
+        &mut Vec::new()
+    ));
+    let s = item.to_token_stream().to_string();
+    assert!(
+        s.contains("# [cfg_attr (feature = \"c_api\" , repr (C) , derive (Clone))]"),
+        "{s}"
+    );
+}
+
+/// Two `#[cfg]`s on one item are a conjunction: each is evaluated, the item
+/// is dropped if either is false, and the survivors are removed or renamed.
+#[test]
+fn multiple_cfgs_are_all_processed() {
+    let src = SourceLocation::default();
+    let make = || -> syn::Item {
+        syn::parse_quote! {
+            #[cfg(feature = "a")]
+            #[cfg(target_arch = "x86_64")]
+            pub fn f() {}
+        }
+    };
+    let on_arch = |arch: &str| CfgExprRules {
+        enabled_features: ["a".to_string()].into(),
+        enabled_target_arch: Some(arch.to_string()),
+        ..Default::default()
+    };
+
+    let mut item = make();
+    assert!(process_item_features(
+        &mut item,
+        &on_arch("x86_64"),
+        &src,
+        &mut Vec::new()
+    ));
+    let s = item.to_token_stream().to_string();
+    assert!(!s.contains("cfg"), "{s}");
+
+    let mut item = make();
+    assert!(!process_item_features(
+        &mut item,
+        &on_arch("aarch64"),
+        &src,
+        &mut Vec::new()
+    ));
+
+    // Mapping the feature doesn't stop the target cfg from being kept.
+    let mut item = make();
+    let mapped = CfgExprRules {
+        feature_mappings: [("a".to_string(), "b".to_string())].into(),
+        ..Default::default()
+    };
+    assert!(process_item_features(
+        &mut item,
+        &mapped,
+        &src,
+        &mut Vec::new()
+    ));
+    let s = item.to_token_stream().to_string();
+    assert!(
+        s.contains("# [cfg (feature = \"b\")] # [cfg (target_arch = \"x86_64\")]"),
+        "{s}"
+    );
+
+    // A false first cfg doesn't hide an unmapped feature in the second.
+    let mut item: syn::Item = syn::parse_quote! {
+        #[cfg(target_arch = "aarch64")]
+        #[cfg(feature = "unknown")]
+        pub fn g() {}
+    };
+    let mut unmapped = Vec::new();
+    assert!(!process_item_features(
+        &mut item,
+        &on_arch("x86_64"),
+        &src,
+        &mut unmapped
+    ));
+    assert_eq!(
+        unmapped
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>(),
+        ["unknown"]
+    );
+}
+
+/// Items of an inline module are filtered like top-level ones: a disabled
+/// child is dropped, an enabled one kept with its cfg stripped.
+#[test]
+fn cfg_on_module_items_is_filtered() {
+    let src = SourceLocation::default();
+    let mut item: syn::Item = syn::parse_quote! {
+        pub mod geometry {
+            #[cfg(feature = "unstable")]
+            pub fn unstable_area() -> u32 { 0 }
+            #[cfg(feature = "stable")]
+            pub struct Size { pub w: u32 }
+            pub mod nested {
+                #[cfg(feature = "unstable")]
+                pub const DEPTH: u32 = 1;
+            }
+        }
+    };
+    assert!(process_item_features(
+        &mut item,
+        &rules(&["stable"], &["unstable"]),
+        &src,
+        &mut Vec::new()
+    ));
+    let s = item.to_token_stream().to_string();
+    assert!(!s.contains("unstable_area"), "{s}");
+    assert!(s.contains("pub struct Size"), "{s}");
+    assert!(!s.contains("DEPTH"), "{s}");
+    assert!(!s.contains("cfg"), "{s}");
+}
@@ -34,21 +34,33 @@ fn test_target_filters_processing() {
     // With no selection, keep predicates as-is
     let expr = CfgExpr::TargetOs("macos".into());
     assert_eq!(
-        expr.apply_rules(&CfgExprRules::default(), &src),
+        expr.apply_rules(&CfgExprRules::default(), &src, &mut Vec::new()),
         Some(CfgExpr::TargetOs("macos".into()))
     );
 
     // No selection for arch/vendor/env should also keep predicates as-is
     assert_eq!(
-        CfgExpr::TargetArch("x86_64".into()).apply_rules(&CfgExprRules::default(), &src),
+        CfgExpr::TargetArch("x86_64".into()).apply_rules(
+            &CfgExprRules::default(),
+            &src,
+            &mut Vec::new()
+        ),
         Some(CfgExpr::TargetArch("x86_64".into()))
     );
     assert_eq!(
-        CfgExpr::TargetVendor("apple".into()).apply_rules(&CfgExprRules::default(), &src),
+        CfgExpr::TargetVendor("apple".into()).apply_rules(
+            &CfgExprRules::default(),
+            &src,
+            &mut Vec::new()
+        ),
         Some(CfgExpr::TargetVendor("apple".into()))
     );
     assert_eq!(
-        CfgExpr::TargetEnv("gnu".into()).apply_rules(&CfgExprRules::default(), &src),
+        CfgExpr::TargetEnv("gnu".into()).apply_rules(
+            &CfgExprRules::default(),
+            &src,
+            &mut Vec::new()
+        ),
         Some(CfgExpr::TargetEnv("gnu".into()))
     );
 
@@ -60,6 +72,7 @@ fn test_target_filters_processing() {
                 ..Default::default()
             },
             &src,
+            &mut Vec::new()
         ),
         None
     );
@@ -72,6 +85,7 @@ fn test_target_filters_processing() {
                 ..Default::default()
             },
             &src,
+            &mut Vec::new()
         ),
         Some(CfgExpr::False)
     );
@@ -84,6 +98,7 @@ fn test_target_filters_processing() {
                 ..Default::default()
             },
             &src,
+            &mut Vec::new()
         ),
         None
     );
@@ -94,6 +109,7 @@ fn test_target_filters_processing() {
                 ..Default::default()
             },
             &src,
+            &mut Vec::new()
         ),
         Some(CfgExpr::False)
     );
@@ -106,6 +122,7 @@ fn test_target_filters_processing() {
                 ..Default::default()
             },
             &src,
+            &mut Vec::new()
         ),
         None
     );
@@ -117,6 +134,7 @@ fn test_target_filters_processing() {
                 ..Default::default()
             },
             &src,
+            &mut Vec::new()
         ),
         Some(CfgExpr::False)
     );
@@ -127,6 +145,7 @@ fn test_target_filters_processing() {
                 ..Default::default()
             },
             &src,
+            &mut Vec::new()
         ),
         None
     );
@@ -138,6 +157,7 @@ fn test_target_filters_processing() {
                 ..Default::default()
             },
             &src,
+            &mut Vec::new()
         ),
         Some(CfgExpr::False)
     );
@@ -190,7 +210,8 @@ fn test_strict_feature_processing() {
                 enabled_features: vec!["feature1".to_string()].into_iter().collect(),
                 ..Default::default()
             },
-            &SourceLocation::default()
+            &SourceLocation::default(),
+            &mut Vec::new()
         ),
         None
     );
@@ -203,7 +224,8 @@ fn test_strict_feature_processing() {
                 disabled_features: vec!["feature2".to_string()].into_iter().collect(),
                 ..Default::default()
             },
-            &SourceLocation::default()
+            &SourceLocation::default(),
+            &mut Vec::new()
         ),
         Some(CfgExpr::False)
     );
@@ -218,7 +240,8 @@ fn test_strict_feature_processing() {
                     .collect(),
                 ..Default::default()
             },
-            &SourceLocation::default()
+            &SourceLocation::default(),
+            &mut Vec::new()
         ),
         Some(CfgExpr::Feature("new_feature".to_string()))
     );
@@ -235,7 +258,8 @@ fn test_strict_feature_processing() {
                 disabled_features: vec!["feature2".to_string()].into_iter().collect(),
                 ..Default::default()
             },
-            &SourceLocation::default()
+            &SourceLocation::default(),
+            &mut Vec::new()
         ),
         None
     );
@@ -252,7 +276,8 @@ fn test_strict_feature_processing() {
                 disabled_features: vec!["feature2".to_string()].into_iter().collect(),
                 ..Default::default()
             },
-            &SourceLocation::default()
+            &SourceLocation::default(),
+            &mut Vec::new()
         ),
         Some(CfgExpr::False)
     );
@@ -265,7 +290,8 @@ fn test_strict_feature_processing() {
                 disabled_features: vec!["feature2".to_string()].into_iter().collect(),
                 ..Default::default()
             },
-            &SourceLocation::default()
+            &SourceLocation::default(),
+            &mut Vec::new()
         ),
         None
     );
@@ -278,33 +304,179 @@ fn test_strict_feature_processing() {
                 enabled_features: vec!["feature1".to_string()].into_iter().collect(),
                 ..Default::default()
             },
-            &SourceLocation::default()
+            &SourceLocation::default(),
+            &mut Vec::new()
         ),
         Some(CfgExpr::False)
     );
 }
 
 #[test]
-#[should_panic(expected = "unmapped feature: unknown")]
-fn test_strict_feature_processing_unmapped_panic() {
-    // Test unmapped feature - should panic
+fn test_strict_feature_processing_unmapped_is_collected() {
+    // Unmapped feature: reported with its location, evaluates to false
+    let location = SourceLocation {
+        file: "src/lib.rs".to_string(),
+        line: 12,
+        column: 1,
+        crate_name: None,
+        group: None,
+        module_path: None,
+        source_name: None,
+    };
+    let mut unmapped = Vec::new();
     let expr = CfgExpr::Feature("unknown".to_string());
-    expr.apply_rules(&CfgExprRules::default(), &SourceLocation::default());
+    assert_eq!(
+        expr.apply_rules(&CfgExprRules::default(), &location, &mut unmapped),
+        Some(CfgExpr::False)
+    );
+    assert_eq!(unmapped, vec![("unknown".to_string(), location)]);
 }
 
 #[test]
-#[should_panic(expected = "unmapped feature: unknown")]
-fn test_strict_feature_processing_unmapped_in_any_panic() {
-    // Test unmapped feature in any() - should panic
+fn test_strict_feature_processing_unmapped_in_any_is_collected() {
+    // Unmapped feature in any() is still reported even though any() is true
+    let mut unmapped = Vec::new();
     let expr = CfgExpr::Any(vec![
         CfgExpr::Feature("feature1".to_string()),
         CfgExpr::Feature("unknown".to_string()),
     ]);
-    expr.apply_rules(
-        &CfgExprRules {
-            enabled_features: vec!["feature1".to_string()].into_iter().collect(),
-            ..Default::default()
-        },
-        &SourceLocation::default(),
+    assert_eq!(
+        expr.apply_rules(
+            &CfgExprRules {
+                enabled_features: vec!["feature1".to_string()].into_iter().collect(),
+                ..Default::default()
+            },
+            &SourceLocation::default(),
+            &mut unmapped,
+        ),
+        None
+    );
+    assert_eq!(unmapped.len(), 1);
+    assert_eq!(unmapped[0].0, "unknown");
+}
+
+#[test]
+fn test_unmapped_feature_kept_when_requested() {
+    let mut unmapped = Vec::new();
+    let expr = CfgExpr::Feature("unknown".to_string());
+    assert_eq!(
+        expr.apply_rules(
+            &CfgExprRules {
+                keep_unmapped_features: true,
+                ..Default::default()
+            },
+            &SourceLocation::default(),
+            &mut unmapped,
+        ),
+        Some(expr.clone())
+    );
+    assert!(unmapped.is_empty());
+}
+
+#[test]
+fn test_structural_parse_of_quoted_values() {
+    // Commas and escaped quotes inside a string literal belong to the value
+    let expr = CfgExpr::parse_from_string(r#"any(feature = "a,b", feature = "c\"d")"#).unwrap();
+    assert_eq!(
+        expr,
+        CfgExpr::Any(vec![
+            CfgExpr::Feature("a,b".to_string()),
+            CfgExpr::Feature("c\"d".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_structural_parse_keeps_unknown_predicates() {
+    let tokens: proc_macro2::TokenStream = quote::quote!(all(
+        debug_assertions,
+        not(target_os = "macos"),
+        panic = "abort"
+    ));
+    let expr = CfgExpr::parse_from_tokens(&tokens).unwrap();
+    assert_eq!(
+        expr,
+        CfgExpr::All(vec![
+            CfgExpr::Other("debug_assertions".to_string()),
+            CfgExpr::Not(Box::new(CfgExpr::TargetOs("macos".to_string()))),
+            CfgExpr::Other("panic = \"abort\"".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_structural_parse_rejects_malformed_not() {
+    assert!(CfgExpr::parse_from_string(r#"not(feature = "a", feature = "b")"#).is_err());
+    assert!(CfgExpr::parse_from_string("not()").is_err());
+}
+
+#[test]
+fn test_pointer_width_and_family() {
+    let expr = CfgExpr::parse_from_string(
+        r#"any(all(unix, target_pointer_width = "64"), windows, target_family = "wasm")"#,
+    )
+    .unwrap();
+    assert_eq!(
+        expr,
+        CfgExpr::Any(vec![
+            CfgExpr::All(vec![
+                CfgExpr::TargetFamily("unix".to_string()),
+                CfgExpr::TargetPointerWidth("64".to_string()),
+            ]),
+            CfgExpr::TargetFamily("windows".to_string()),
+            CfgExpr::TargetFamily("wasm".to_string()),
+        ])
+    );
+
+    // Shorthands are written back in their long form
+    let reparsed = CfgExpr::parse_from_tokens(&expr.to_tokens()).unwrap();
+    assert_eq!(reparsed, expr);
+    assert_eq!(
+        CfgExpr::TargetFamily("unix".to_string())
+            .to_tokens()
+            .to_string(),
+        r#"target_family = "unix""#
+    );
+
+    let rules = CfgExprRules {
+        enabled_target_pointer_width: Some("64".into()),
+        enabled_target_families: ["unix".to_string()].into_iter().collect(),
+        ..Default::default()
+    };
+    let src = SourceLocation::default();
+    assert_eq!(expr.apply_rules(&rules, &src, &mut Vec::new()), None);
+    assert_eq!(
+        CfgExpr::TargetPointerWidth("32".into()).apply_rules(&rules, &src, &mut Vec::new()),
+        Some(CfgExpr::False)
+    );
+    assert_eq!(
+        CfgExpr::TargetFamily("windows".into()).apply_rules(&rules, &src, &mut Vec::new()),
+        Some(CfgExpr::False)
+    );
+
+    // Without a selection the predicates are kept as-is
+    assert_eq!(
+        CfgExpr::TargetFamily("windows".into()).apply_rules(
+            &CfgExprRules::default(),
+            &src,
+            &mut Vec::new()
+        ),
+        Some(CfgExpr::TargetFamily("windows".into()))
+    );
+}
+
+#[cfg(feature = "unstable-cbindgen")]
+#[test]
+fn test_excludes() {
+    let parse = |s: &str| CfgExpr::parse_from_string(s).unwrap();
+    assert!(parse("unix").excludes(&parse("not(unix)")));
+    assert!(parse("target_os = \"linux\"").excludes(&parse("target_os = \"windows\"")));
+    assert!(
+        parse("all(feature = \"a\", target_arch = \"x86_64\")").excludes(&parse(
+            "any(target_arch = \"arm\", target_arch = \"aarch64\")"
+        ))
     );
+    assert!(!parse("unix").excludes(&parse("windows")));
+    assert!(!parse("feature = \"a\"").excludes(&parse("feature = \"b\"")));
+    assert!(!parse("target_os = \"linux\"").excludes(&parse("target_os = \"linux\"")));
 }
@@ -156,6 +156,9 @@
 /// File name for storing the crate name
 const CRATE_NAME_FILE: &str = "crate_name.txt";
 
+/// File name for storing the crate version
+const CRATE_VERSION_FILE: &str = "crate_version.txt";
+
 /// File name for storing enabled Cargo features collected in build.rs
 const FEATURES_FILE: &str = "features.txt";
 
@@ -171,8 +174,9 @@ pub use crate::api::{
         is_feature_enabled,
     },
     record::SourceLocation,
-    source::Source,
+    source::{Source, SourceError},
     utils::{edition::RustEdition, target_triple::TargetTriple},
+    validate::{validate, ValidationIssue},
 };
 
 /// Not part of the public API — referenced by the [`ident!`] macro expansion
@@ -288,9 +292,9 @@ macro_rules! ident {
 /// with the `unstable-cbindgen` feature.
 pub mod core {
     pub use crate::api::core::{
-        ConverterImpl, Direction, DomainScalar, Generation, Gravestone, NicheSlot, Niches,
-        Prebindgen, Registry, RepresentationDomain, ScalarValue, ScanError, Stage, Transmute,
-        TypeEntry, TypeKey, WriteRustError,
+        ConverterImpl, Direction, DomainScalar, Generation, GenerationSummary, Gravestone,
+        NicheSlot, Niches, Prebindgen, Registry, RepresentationDomain, ScalarValue, ScanError,
+        SkippedItem, Stage, Transmute, TypeEntry, TypeKey, WriteRustError,
     };
 }
 
@@ -328,13 +332,49 @@ pub mod lang {
     };
 }
 
+/// Per-item transformations of the `(syn::Item, SourceLocation)` stream, for
+/// use with `Iterator::map` between [`Source`] and the final [`collect::Destination`].
+pub mod map {
+    pub use crate::api::map::{
+        layout_assert::LayoutAsserts, rename_type::RenameType, strip_derive::StripDerives,
+        strip_macro::StripMacros, struct_align::StructAlign,
+    };
+
+    /// Size and alignment assertions for copied `#[repr]` types.
+    pub mod layout_assert {
+        pub use crate::api::map::layout_assert::{Builder, LayoutAsserts};
+    }
+
+    /// Consistent renaming of types and of every reference to them.
+    pub mod rename_type {
+        pub use crate::api::map::rename_type::{Builder, RenameType};
+    }
+
+    /// Removal of `#[derive(...)]` entries binding generators can't handle.
+    pub mod strip_derive {
+        pub use crate::api::map::strip_derive::{Builder, StripDerives};
+    }
+
+    /// Removal of attribute macros binding generators can't expand.
+    pub mod strip_macro {
+        pub use crate::api::map::strip_macro::{Builder, StripMacros};
+    }
+
+    /// Forced `#[repr(align(N))]` on chosen structs.
+    pub mod struct_align {
+        pub use crate::api::map::struct_align::{Builder, StructAlign};
+    }
+}
+
+/// Collection of the final Rust items into formatted source files.
+pub mod collect {
+    pub use crate::api::collect::destination::Destination;
+}
+
 pub mod utils {
     #[doc(hidden)]
     pub use crate::api::utils::jsonl::{read_jsonl_file, write_to_jsonl_file};
     pub use crate::api::utils::target_triple::TargetTriple;
 }
 
-#[doc(hidden)]
-pub use crate::api::record::Record;
-#[doc(hidden)]
-pub use crate::api::record::RecordKind;
+pub use crate::api::record::{ParseError, Record, RecordKind};